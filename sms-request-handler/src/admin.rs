@@ -6,7 +6,8 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
-use crate::db::VoucherRepository;
+use crate::db::{VoucherRepository, USDC_DECIMALS};
+use crate::wallet::Amount;
 
 /// Admin routes state
 #[derive(Clone)]
@@ -20,8 +21,8 @@ pub struct AdminState {
 pub struct CreateVouchersRequest {
     /// Number of vouchers to create
     pub count: usize,
-    /// USDC amount per voucher (e.g., 10.00 for $10)
-    pub usdc_amount: f64,
+    /// USDC amount per voucher (e.g., "10.00" for $10)
+    pub usdc_amount: Amount,
     /// Optional prefix for voucher codes
     #[serde(default = "default_prefix")]
     pub prefix: String,
@@ -38,7 +39,7 @@ fn default_prefix() -> String {
 pub struct CreateVouchersResponse {
     pub success: bool,
     pub count: usize,
-    pub usdc_amount: f64,
+    pub usdc_amount: Amount,
     pub codes: Vec<String>,
 }
 
@@ -48,8 +49,8 @@ pub struct VoucherStatsResponse {
     pub total: i64,
     pub unused: i64,
     pub redeemed: i64,
-    pub total_value_unused: f64,
-    pub total_value_redeemed: f64,
+    pub total_value_unused: Amount,
+    pub total_value_redeemed: Amount,
 }
 
 /// Create admin routes
@@ -67,7 +68,18 @@ async fn create_vouchers(
     Json(req): Json<CreateVouchersRequest>,
 ) -> Json<CreateVouchersResponse> {
     // Convert USDC to micro USDC (6 decimals)
-    let usdc_micro = (req.usdc_amount * 1_000_000.0) as i64;
+    let usdc_micro = match req.usdc_amount.to_base_units_i64(USDC_DECIMALS) {
+        Ok(micro) => micro,
+        Err(e) => {
+            tracing::warn!("Rejected voucher amount {}: {}", req.usdc_amount, e);
+            return Json(CreateVouchersResponse {
+                success: false,
+                count: 0,
+                usdc_amount: req.usdc_amount,
+                codes: vec![],
+            });
+        }
+    };
 
     // Generate codes
     let codes = VoucherRepository::generate_codes(req.count, &req.prefix);
@@ -81,10 +93,12 @@ async fn create_vouchers(
     match state.voucher_repo.create_batch(&codes, usdc_micro, expires_at).await {
         Ok(vouchers) => {
             let created_codes: Vec<String> = vouchers.iter().map(|v| v.code.clone()).collect();
+            // Report the value as stored, in exact base units
+            let usdc_amount = vouchers.first().map_or(req.usdc_amount, |v| v.usdc());
             Json(CreateVouchersResponse {
                 success: true,
                 count: created_codes.len(),
-                usdc_amount: req.usdc_amount,
+                usdc_amount,
                 codes: created_codes,
             })
         }
@@ -104,7 +118,7 @@ async fn create_vouchers(
 #[derive(Debug, Serialize)]
 pub struct VoucherInfo {
     pub code: String,
-    pub usdc_amount: f64,
+    pub usdc_amount: Amount,
     pub status: String,
     pub redeemed_by: Option<String>,
 }
@@ -125,8 +139,8 @@ async fn get_voucher_stats(State(state): State<AdminState>) -> Json<VoucherStats
         total: 0,
         unused: 0,
         redeemed: 0,
-        total_value_unused: 0.0,
-        total_value_redeemed: 0.0,
+        total_value_unused: Amount::default(),
+        total_value_redeemed: Amount::default(),
     })
 }
 
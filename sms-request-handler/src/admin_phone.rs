@@ -0,0 +1,67 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::post,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::commands::CommandProcessor;
+use crate::i18n::Lang;
+use crate::sms::header_token_matches;
+
+/// Admin phone change routes state
+#[derive(Clone)]
+pub struct AdminPhoneState {
+    pub command_processor: Arc<CommandProcessor>,
+    /// Shared secret sent in `X-Admin-Token`
+    pub admin_token: String,
+}
+
+/// Move a wallet whose old SIM is lost
+#[derive(Debug, Deserialize)]
+pub struct StartPhoneChangeRequest {
+    pub old_phone: String,
+    pub new_phone: String,
+}
+
+/// Phone change response
+#[derive(Debug, Serialize)]
+pub struct StartPhoneChangeResponse {
+    pub success: bool,
+    pub reference: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Create admin phone change routes
+pub fn admin_phone_routes(state: AdminPhoneState) -> Router {
+    Router::new()
+        .route("/phone-changes", post(start_phone_change))
+        .with_state(state)
+}
+
+/// Text a code to the new number; the owner finishes with VERIFY <code> <PIN>
+/// from it and the move completes after the usual waiting period
+async fn start_phone_change(
+    State(state): State<AdminPhoneState>,
+    headers: HeaderMap,
+    Json(req): Json<StartPhoneChangeRequest>,
+) -> Result<Json<StartPhoneChangeResponse>, StatusCode> {
+    if !header_token_matches(&headers, "x-admin-token", &state.admin_token) {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    match state.command_processor.start_phone_change(&req.old_phone, &req.new_phone, "admin", Lang::En).await {
+        Ok(change) => Ok(Json(StartPhoneChangeResponse {
+            success: true,
+            reference: Some(change.reference),
+            error: None,
+        })),
+        Err(error) => Ok(Json(StartPhoneChangeResponse {
+            success: false,
+            reference: None,
+            error: Some(error),
+        })),
+    }
+}
@@ -0,0 +1,94 @@
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    routing::get,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::db::{OutboundMessage, OutboundMessageRepository};
+use crate::sms::header_token_matches;
+
+/// Admin SMS delivery routes state
+#[derive(Clone)]
+pub struct AdminSmsState {
+    pub outbound_repo: OutboundMessageRepository,
+    /// Shared secret sent in `X-Admin-Token`
+    pub admin_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FailedQuery {
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+/// A message given up on, or reported undelivered by the gateway
+#[derive(Debug, Serialize)]
+pub struct FailedDelivery {
+    pub id: String,
+    pub to: String,
+    pub body: String,
+    pub gateway: Option<String>,
+    pub status: String,
+    pub delivery_status: Option<String>,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub provider_message_id: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<OutboundMessage> for FailedDelivery {
+    fn from(m: OutboundMessage) -> Self {
+        Self {
+            id: m.id.to_string(),
+            to: m.to_phone,
+            body: m.body,
+            gateway: m.gateway,
+            status: m.status,
+            delivery_status: m.delivery_status,
+            attempts: m.attempts,
+            last_error: m.last_error,
+            provider_message_id: m.provider_message_id,
+            created_at: m.created_at.to_rfc3339(),
+            updated_at: m.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct FailedDeliveriesResponse {
+    pub messages: Vec<FailedDelivery>,
+}
+
+/// Create admin SMS delivery routes
+pub fn admin_sms_routes(state: AdminSmsState) -> Router {
+    Router::new()
+        .route("/sms/failed", get(list_failed))
+        .with_state(state)
+}
+
+/// Failed deliveries, newest first (`?limit=`, default 50, at most 500)
+async fn list_failed(
+    State(state): State<AdminSmsState>,
+    headers: HeaderMap,
+    Query(query): Query<FailedQuery>,
+) -> Result<Json<FailedDeliveriesResponse>, StatusCode> {
+    if !header_token_matches(&headers, "x-admin-token", &state.admin_token) {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    let messages = state.outbound_repo.find_failed(query.limit.clamp(1, 500)).await.map_err(|e| {
+        tracing::error!("Failed to list failed deliveries: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(FailedDeliveriesResponse {
+        messages: messages.into_iter().map(FailedDelivery::from).collect(),
+    }))
+}
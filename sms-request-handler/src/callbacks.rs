@@ -0,0 +1,71 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::post,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::db::{TransactionRepository, TxStatus};
+use crate::sms::header_token_matches;
+
+/// Backend callback routes state
+#[derive(Clone)]
+pub struct CallbackState {
+    pub transaction_repo: Arc<TransactionRepository>,
+    /// Shared secret the backend sends in `X-Callback-Token`
+    pub callback_token: String,
+}
+
+/// Completion report for a transaction the backend was asked to run
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionCallback {
+    /// Reference passed to the backend with the original request
+    pub reference: String,
+    /// "completed" or "failed" (also accepts "success", "confirmed", "error", "reverted")
+    pub status: String,
+    pub tx_hash: Option<String>,
+}
+
+/// Callback response
+#[derive(Debug, Serialize)]
+pub struct CallbackResponse {
+    pub success: bool,
+    /// Ledger rows moved out of pending
+    pub updated: u64,
+}
+
+/// Create backend callback routes
+pub fn callback_routes(state: CallbackState) -> Router {
+    Router::new()
+        .route("/transactions/callback", post(transaction_callback))
+        .with_state(state)
+}
+
+/// Settle the ledger rows for a reference once the backend knows the outcome
+async fn transaction_callback(
+    State(state): State<CallbackState>,
+    headers: HeaderMap,
+    Json(req): Json<TransactionCallback>,
+) -> Result<Json<CallbackResponse>, StatusCode> {
+    if !header_token_matches(&headers, "x-callback-token", &state.callback_token) {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    let Some(status) = TxStatus::from_input(&req.status) else {
+        return Err(StatusCode::BAD_REQUEST);
+    };
+
+    match state.transaction_repo.update_status(&req.reference, status, req.tx_hash.as_deref()).await {
+        Ok(updated) => {
+            tracing::info!(reference = %req.reference, status = %status, updated, "Transaction callback");
+            Ok(Json(CallbackResponse { success: true, updated }))
+        }
+        Err(e) => {
+            tracing::error!(reference = %req.reference, "Failed to apply transaction callback: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
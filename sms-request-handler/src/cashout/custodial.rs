@@ -0,0 +1,103 @@
+use futures::future::BoxFuture;
+use futures::FutureExt;
+
+use super::{quote_at_rate, status_from_json, CashoutError, CashoutProvider, CashoutQuote, PayoutRequest, PayoutStatus};
+use crate::wallet::Amount;
+
+/// USDC credited to the user's account at a custodian (exchange or
+/// agent network), looked up by phone number.
+///
+/// Transfers are `POST {api}/transfers` with `{phone, amount, asset,
+/// reference}` and checked with `GET {api}/transfers/{id}`; both answer
+/// `{id, status, reason}`.
+#[derive(Debug, Clone)]
+pub struct CustodialUsdcProvider {
+    api_url: String,
+    api_key: String,
+    /// USDC per TXTC
+    rate: Amount,
+    fee_bps: u32,
+}
+
+impl CustodialUsdcProvider {
+    pub fn new(api_url: &str, api_key: &str, rate: Amount, fee_bps: u32) -> Self {
+        Self {
+            api_url: api_url.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+            rate,
+            fee_bps,
+        }
+    }
+
+    /// Read CUSTODIAL_API_URL, CUSTODIAL_API_KEY, CUSTODIAL_RATE and
+    /// CUSTODIAL_FEE_BPS (default 50); None unless the URL and rate are set
+    pub fn from_env() -> Option<Self> {
+        let api_url = std::env::var("CUSTODIAL_API_URL").ok()?;
+        let rate = std::env::var("CUSTODIAL_RATE").ok().and_then(|v| Amount::parse(&v).ok())?;
+        let api_key = std::env::var("CUSTODIAL_API_KEY").unwrap_or_default();
+        let fee_bps = std::env::var("CUSTODIAL_FEE_BPS").ok().and_then(|v| v.parse().ok()).unwrap_or(50);
+        Some(Self::new(&api_url, &api_key, rate, fee_bps))
+    }
+}
+
+impl CashoutProvider for CustodialUsdcProvider {
+    fn key(&self) -> &'static str {
+        "USDC"
+    }
+
+    fn currency(&self) -> &str {
+        "USDC"
+    }
+
+    fn quote(&self, amount: Amount, token: &str) -> BoxFuture<'_, Result<CashoutQuote, CashoutError>> {
+        let quote = match token {
+            "TXTC" => Ok(quote_at_rate(amount, self.rate, self.fee_bps, 6)),
+            _ => Err(CashoutError::UnsupportedToken(token.to_string())),
+        };
+        async move { quote }.boxed()
+    }
+
+    fn payout(&self, request: PayoutRequest) -> BoxFuture<'_, Result<PayoutStatus, CashoutError>> {
+        async move {
+            let response = reqwest::Client::new()
+                .post(format!("{}/transfers", self.api_url))
+                .bearer_auth(&self.api_key)
+                .json(&serde_json::json!({
+                    "phone": request.phone,
+                    "amount": request.amount.to_string(),
+                    "asset": "USDC",
+                    "reference": request.reference
+                }))
+                .timeout(std::time::Duration::from_secs(15))
+                .send()
+                .await
+                .map_err(|e| CashoutError::Unavailable(e.to_string()))?;
+
+            let result: serde_json::Value = response
+                .json()
+                .await
+                .map_err(|e| CashoutError::Unavailable(e.to_string()))?;
+            status_from_json(&result)
+        }
+        .boxed()
+    }
+
+    fn status(&self, id: String) -> BoxFuture<'_, Result<PayoutStatus, CashoutError>> {
+        async move {
+            let response = reqwest::Client::new()
+                .get(format!("{}/transfers/{}", self.api_url, id))
+                .bearer_auth(&self.api_key)
+                .timeout(std::time::Duration::from_secs(10))
+                .send()
+                .await
+                .map_err(|e| CashoutError::Unavailable(e.to_string()))?;
+
+            let result: serde_json::Value = response
+                .json()
+                .await
+                .map_err(|e| CashoutError::Unavailable(e.to_string()))?;
+            status_from_json(&result)
+        }
+        .boxed()
+    }
+}
@@ -0,0 +1,127 @@
+use futures::future::BoxFuture;
+use futures::FutureExt;
+
+use super::{quote_at_rate, status_from_json, CashoutError, CashoutProvider, CashoutQuote, PayoutRequest, PayoutStatus};
+use crate::wallet::Amount;
+
+/// Mobile money payout to the user's own number (M-Pesa style B2C).
+///
+/// Tokens are priced at a fixed rate set by the operator. Payouts are
+/// `POST {api}/b2c/payouts` with `{phone, amount, currency, reference}`
+/// and checked with `GET {api}/b2c/payouts/{id}`; both answer
+/// `{id, status, reason}`.
+#[derive(Debug, Clone)]
+pub struct MobileMoneyProvider {
+    api_url: String,
+    api_key: String,
+    currency: String,
+    /// Payout currency per TXTC
+    rate: Amount,
+    fee_bps: u32,
+}
+
+impl MobileMoneyProvider {
+    pub fn new(api_url: &str, api_key: &str, currency: &str, rate: Amount, fee_bps: u32) -> Self {
+        Self {
+            api_url: api_url.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+            currency: currency.to_uppercase(),
+            rate,
+            fee_bps,
+        }
+    }
+
+    /// Read MPESA_API_URL, MPESA_API_KEY, MPESA_RATE, MPESA_CURRENCY
+    /// (default KES) and MPESA_FEE_BPS (default 100); None unless the
+    /// URL and rate are set
+    pub fn from_env() -> Option<Self> {
+        let api_url = std::env::var("MPESA_API_URL").ok()?;
+        let rate = std::env::var("MPESA_RATE").ok().and_then(|v| Amount::parse(&v).ok())?;
+        let api_key = std::env::var("MPESA_API_KEY").unwrap_or_default();
+        let currency = std::env::var("MPESA_CURRENCY").unwrap_or_else(|_| "KES".to_string());
+        let fee_bps = std::env::var("MPESA_FEE_BPS").ok().and_then(|v| v.parse().ok()).unwrap_or(100);
+        Some(Self::new(&api_url, &api_key, &currency, rate, fee_bps))
+    }
+}
+
+impl CashoutProvider for MobileMoneyProvider {
+    fn key(&self) -> &'static str {
+        "MPESA"
+    }
+
+    fn currency(&self) -> &str {
+        &self.currency
+    }
+
+    fn quote(&self, amount: Amount, token: &str) -> BoxFuture<'_, Result<CashoutQuote, CashoutError>> {
+        // Mobile money pays whole units
+        let quote = match token {
+            "TXTC" => Ok(quote_at_rate(amount, self.rate, self.fee_bps, 0)),
+            _ => Err(CashoutError::UnsupportedToken(token.to_string())),
+        };
+        async move { quote }.boxed()
+    }
+
+    fn payout(&self, request: PayoutRequest) -> BoxFuture<'_, Result<PayoutStatus, CashoutError>> {
+        async move {
+            let response = reqwest::Client::new()
+                .post(format!("{}/b2c/payouts", self.api_url))
+                .bearer_auth(&self.api_key)
+                .json(&serde_json::json!({
+                    "phone": request.phone,
+                    "amount": request.amount.to_string(),
+                    "currency": self.currency,
+                    "reference": request.reference
+                }))
+                .timeout(std::time::Duration::from_secs(15))
+                .send()
+                .await
+                .map_err(|e| CashoutError::Unavailable(e.to_string()))?;
+
+            let result: serde_json::Value = response
+                .json()
+                .await
+                .map_err(|e| CashoutError::Unavailable(e.to_string()))?;
+            status_from_json(&result)
+        }
+        .boxed()
+    }
+
+    fn status(&self, id: String) -> BoxFuture<'_, Result<PayoutStatus, CashoutError>> {
+        async move {
+            let response = reqwest::Client::new()
+                .get(format!("{}/b2c/payouts/{}", self.api_url, id))
+                .bearer_auth(&self.api_key)
+                .timeout(std::time::Duration::from_secs(10))
+                .send()
+                .await
+                .map_err(|e| CashoutError::Unavailable(e.to_string()))?;
+
+            let result: serde_json::Value = response
+                .json()
+                .await
+                .map_err(|e| CashoutError::Unavailable(e.to_string()))?;
+            status_from_json(&result)
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_quote_whole_units() {
+        let provider = MobileMoneyProvider::new("https://mm.example/", "key", "kes", Amount::parse("129.5").unwrap(), 100);
+        assert_eq!(provider.currency(), "KES");
+        assert_eq!(provider.api_url, "https://mm.example");
+
+        let quote = provider.quote(Amount::parse("1.5").unwrap(), "TXTC").await.unwrap();
+        assert_eq!(quote.payout.to_string(), "192");
+        assert_eq!(quote.fee.to_string(), "1");
+
+        let err = provider.quote(Amount::parse("1").unwrap(), "ETH").await.unwrap_err();
+        assert_eq!(err, CashoutError::UnsupportedToken("ETH".to_string()));
+    }
+}
@@ -0,0 +1,138 @@
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use super::{quote_at_rate, CashoutError, CashoutProvider, CashoutQuote, PayoutRequest, PayoutStatus};
+use crate::wallet::Amount;
+
+/// How the mock provider answers a payout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MockOutcome {
+    /// Paid at once
+    Paid,
+    /// Accepted, and paid the first time its status is checked
+    Pending,
+    /// Refused by the "provider"
+    Failed,
+    /// Provider unreachable
+    Down,
+    /// Paid, but the reply is lost; asked again with the same reference
+    /// it reports the payout as paid
+    Lost,
+}
+
+impl MockOutcome {
+    /// Parse CASHOUT_MOCK: `paid` (also `true`/`1`), `pending`, `failed`,
+    /// `down` or `lost`
+    pub fn from_input(input: &str) -> Option<MockOutcome> {
+        match input.to_lowercase().as_str() {
+            "paid" | "true" | "1" | "yes" => Some(MockOutcome::Paid),
+            "pending" => Some(MockOutcome::Pending),
+            "failed" => Some(MockOutcome::Failed),
+            "down" => Some(MockOutcome::Down),
+            "lost" => Some(MockOutcome::Lost),
+            _ => None,
+        }
+    }
+}
+
+/// Local off-ramp for tests and development; no money moves
+#[derive(Debug, Clone)]
+pub struct MockCashoutProvider {
+    rate: Amount,
+    outcome: MockOutcome,
+    /// References already paid, for `MockOutcome::Lost`
+    paid: Arc<Mutex<HashSet<String>>>,
+}
+
+impl MockCashoutProvider {
+    pub fn new(rate: Amount, outcome: MockOutcome) -> Self {
+        Self { rate, outcome, paid: Arc::default() }
+    }
+}
+
+impl CashoutProvider for MockCashoutProvider {
+    fn key(&self) -> &'static str {
+        "MOCK"
+    }
+
+    fn currency(&self) -> &str {
+        "USD"
+    }
+
+    fn quote(&self, amount: Amount, _token: &str) -> BoxFuture<'_, Result<CashoutQuote, CashoutError>> {
+        let quote = quote_at_rate(amount, self.rate, 0, 2);
+        async move { Ok(quote) }.boxed()
+    }
+
+    fn payout(&self, request: PayoutRequest) -> BoxFuture<'_, Result<PayoutStatus, CashoutError>> {
+        let id = format!("MOCK-{}", request.reference);
+        let status = match self.outcome {
+            MockOutcome::Paid => Ok(PayoutStatus::Paid { id }),
+            MockOutcome::Pending => Ok(PayoutStatus::Pending { id }),
+            MockOutcome::Failed => Ok(PayoutStatus::Failed { reason: "Mock refusal".to_string() }),
+            MockOutcome::Down => Err(CashoutError::Unavailable("Mock provider down".to_string())),
+            MockOutcome::Lost => {
+                let mut paid = self.paid.lock().unwrap_or_else(|e| e.into_inner());
+                if paid.insert(request.reference) {
+                    Err(CashoutError::Unavailable("Mock reply lost".to_string()))
+                } else {
+                    Ok(PayoutStatus::Paid { id })
+                }
+            }
+        };
+        async move { status }.boxed()
+    }
+
+    fn status(&self, id: String) -> BoxFuture<'_, Result<PayoutStatus, CashoutError>> {
+        async move { Ok(PayoutStatus::Paid { id }) }.boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request() -> PayoutRequest {
+        PayoutRequest {
+            reference: "ABC234".to_string(),
+            phone: "+254700000000".to_string(),
+            amount: Amount::parse("10").unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mock_outcomes() {
+        let rate = Amount::parse("0.5").unwrap();
+        let paid = MockCashoutProvider::new(rate, MockOutcome::Paid);
+        assert_eq!(paid.quote(Amount::parse("3").unwrap(), "TXTC").await.unwrap().payout.to_string(), "1.5");
+        assert_eq!(paid.payout(request()).await, Ok(PayoutStatus::Paid { id: "MOCK-ABC234".to_string() }));
+
+        let pending = MockCashoutProvider::new(rate, MockOutcome::Pending);
+        let Ok(PayoutStatus::Pending { id }) = pending.payout(request()).await else {
+            panic!("expected a pending payout");
+        };
+        assert_eq!(pending.status(id).await, Ok(PayoutStatus::Paid { id: "MOCK-ABC234".to_string() }));
+
+        let failed = MockCashoutProvider::new(rate, MockOutcome::Failed);
+        assert!(matches!(failed.payout(request()).await, Ok(PayoutStatus::Failed { .. })));
+
+        let down = MockCashoutProvider::new(rate, MockOutcome::Down);
+        assert!(matches!(down.payout(request()).await, Err(CashoutError::Unavailable(_))));
+
+        // Asked again with the same reference, a lost payout shows as paid
+        let lost = MockCashoutProvider::new(rate, MockOutcome::Lost);
+        assert!(matches!(lost.payout(request()).await, Err(CashoutError::Unavailable(_))));
+        assert_eq!(lost.payout(request()).await, Ok(PayoutStatus::Paid { id: "MOCK-ABC234".to_string() }));
+    }
+
+    #[test]
+    fn test_mock_outcome_from_input() {
+        assert_eq!(MockOutcome::from_input("TRUE"), Some(MockOutcome::Paid));
+        assert_eq!(MockOutcome::from_input("pending"), Some(MockOutcome::Pending));
+        assert_eq!(MockOutcome::from_input("down"), Some(MockOutcome::Down));
+        assert_eq!(MockOutcome::from_input("lost"), Some(MockOutcome::Lost));
+        assert_eq!(MockOutcome::from_input("false"), None);
+    }
+}
@@ -0,0 +1,225 @@
+pub mod custodial;
+pub mod mobile_money;
+pub mod mock;
+
+pub use custodial::CustodialUsdcProvider;
+pub use mobile_money::MobileMoneyProvider;
+pub use mock::{MockCashoutProvider, MockOutcome};
+
+use ethers::types::U256;
+use futures::future::BoxFuture;
+use std::sync::Arc;
+
+use crate::wallet::{Amount, MAX_DECIMALS};
+
+/// Basis points in 100%
+const BPS: u32 = 10_000;
+
+/// What a cashout of some tokens would pay out
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CashoutQuote {
+    /// Paid to the user, after the fee
+    pub payout: Amount,
+    /// Kept by the provider, in the payout currency
+    pub fee: Amount,
+}
+
+/// Payout sent to a provider once the user's tokens are in the treasury
+#[derive(Debug, Clone)]
+pub struct PayoutRequest {
+    /// Our reference; providers must treat a repeated reference as the same payout
+    pub reference: String,
+    /// Who gets paid; mobile money and custodial accounts are keyed by phone
+    pub phone: String,
+    pub amount: Amount,
+}
+
+/// Where a payout stands at the provider
+#[derive(Debug, Clone, PartialEq)]
+pub enum PayoutStatus {
+    /// Accepted, not yet paid; `id` is the provider's own reference
+    Pending { id: String },
+    Paid { id: String },
+    Failed { reason: String },
+}
+
+impl PayoutStatus {
+    /// Read a provider's status word; anything unrecognised is still pending
+    pub fn from_provider(id: &str, status: &str, reason: Option<&str>) -> PayoutStatus {
+        match status.to_lowercase().as_str() {
+            "paid" | "completed" | "success" | "succeeded" => PayoutStatus::Paid { id: id.to_string() },
+            "failed" | "rejected" | "reversed" | "cancelled" => PayoutStatus::Failed {
+                reason: reason.unwrap_or(status).to_string(),
+            },
+            _ => PayoutStatus::Pending { id: id.to_string() },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum CashoutError {
+    #[error("{0} can't be cashed out")]
+    UnsupportedToken(String),
+    #[error("Provider unreachable: {0}")]
+    Unavailable(String),
+    #[error("Provider refused: {0}")]
+    Rejected(String),
+}
+
+/// Off-ramp that turns tokens held in the treasury into money for the user.
+///
+/// Methods return boxed futures so providers can be picked at runtime.
+pub trait CashoutProvider: Send + Sync {
+    /// Word users pick the provider by, e.g. `MPESA`
+    fn key(&self) -> &'static str;
+
+    /// Currency the provider pays out in, e.g. `KES`
+    fn currency(&self) -> &str;
+
+    /// What `amount` of `token` would pay out right now
+    fn quote(&self, amount: Amount, token: &str) -> BoxFuture<'_, Result<CashoutQuote, CashoutError>>;
+
+    /// Start paying out; may finish at once or stay pending
+    fn payout(&self, request: PayoutRequest) -> BoxFuture<'_, Result<PayoutStatus, CashoutError>>;
+
+    /// Check on a pending payout by the provider's reference
+    fn status(&self, id: String) -> BoxFuture<'_, Result<PayoutStatus, CashoutError>>;
+}
+
+/// Configured off-ramps, and the treasury that user tokens are sent to
+#[derive(Clone, Default)]
+pub struct CashoutProviders {
+    treasury: Option<String>,
+    providers: Vec<Arc<dyn CashoutProvider>>,
+}
+
+impl CashoutProviders {
+    pub fn new(treasury: &str) -> Self {
+        Self { treasury: Some(treasury.to_string()), providers: Vec::new() }
+    }
+
+    /// Add a provider; the first one added is the default
+    pub fn with(mut self, provider: impl CashoutProvider + 'static) -> Self {
+        self.providers.push(Arc::new(provider));
+        self
+    }
+
+    /// Read CASHOUT_TREASURY_ADDRESS and each provider's settings.
+    ///
+    /// Cashouts stay off without a treasury address. CASHOUT_MOCK adds
+    /// the mock provider for local testing, paying 1 USD per token.
+    pub fn from_env() -> Self {
+        let Ok(treasury) = std::env::var("CASHOUT_TREASURY_ADDRESS") else {
+            return Self::default();
+        };
+
+        let mut providers = Self::new(&treasury);
+        if let Some(provider) = MobileMoneyProvider::from_env() {
+            providers = providers.with(provider);
+        }
+        if let Some(provider) = CustodialUsdcProvider::from_env() {
+            providers = providers.with(provider);
+        }
+        if let Some(outcome) = std::env::var("CASHOUT_MOCK").ok().and_then(|v| MockOutcome::from_input(&v)) {
+            providers = providers.with(MockCashoutProvider::new(Amount::from_base_units(1, 0), outcome));
+        }
+
+        if providers.providers.is_empty() {
+            tracing::warn!("CASHOUT_TREASURY_ADDRESS is set but no cashout provider is configured");
+        }
+        providers
+    }
+
+    /// Address user tokens are sent to, when cashouts are on
+    pub fn treasury(&self) -> Option<&str> {
+        self.treasury.as_deref().filter(|_| !self.providers.is_empty())
+    }
+
+    /// Provider for `key`, or the default one when no key is given
+    pub fn get(&self, key: Option<&str>) -> Option<Arc<dyn CashoutProvider>> {
+        match key {
+            Some(key) => self.providers.iter().find(|p| p.key().eq_ignore_ascii_case(key)).cloned(),
+            None => self.providers.first().cloned(),
+        }
+    }
+
+    /// Keys of every configured provider, e.g. `MPESA/USDC`
+    pub fn keys(&self) -> String {
+        self.providers.iter().map(|p| p.key()).collect::<Vec<_>>().join("/")
+    }
+}
+
+/// Quote at a fixed `rate` of payout currency per token, less `fee_bps`,
+/// rounded down to `places` decimals
+pub fn quote_at_rate(amount: Amount, rate: Amount, fee_bps: u32, places: u8) -> CashoutQuote {
+    let base = |a: Amount| a.to_base_units(MAX_DECIMALS).unwrap_or_default();
+    let gross = base(amount) * base(rate) / U256::exp10(MAX_DECIMALS as usize);
+    let fee = gross * U256::from(fee_bps.min(BPS)) / U256::from(BPS);
+
+    let to_amount = |units: U256| {
+        let places = places.min(MAX_DECIMALS);
+        let truncated = units / U256::exp10((MAX_DECIMALS - places) as usize);
+        Amount::from_base_units(u128::try_from(truncated).unwrap_or_default(), places)
+    };
+    CashoutQuote { payout: to_amount(gross - fee), fee: to_amount(fee) }
+}
+
+/// Parse the `{"id", "status", "reason"}` reply both HTTP providers use
+fn status_from_json(result: &serde_json::Value) -> Result<PayoutStatus, CashoutError> {
+    let Some(id) = result["id"].as_str() else {
+        let error = result["error"].as_str().unwrap_or("Unknown error");
+        return Err(CashoutError::Rejected(error.to_string()));
+    };
+    let status = result["status"].as_str().unwrap_or("pending");
+    Ok(PayoutStatus::from_provider(id, status, result["reason"].as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn amount(s: &str) -> Amount {
+        Amount::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_quote_at_rate() {
+        // 10 TXTC at 129.5 KES, 1% fee, whole shillings
+        let quote = quote_at_rate(amount("10"), amount("129.5"), 100, 0);
+        assert_eq!(quote.payout, amount("1282"));
+        assert_eq!(quote.fee, amount("12"));
+
+        let quote = quote_at_rate(amount("2.5"), amount("1"), 50, 6);
+        assert_eq!(quote.payout, amount("2.4875"));
+        assert_eq!(quote.fee, amount("0.0125"));
+    }
+
+    #[test]
+    fn test_payout_status_from_json() {
+        let paid = serde_json::json!({"id": "PAY1", "status": "completed"});
+        assert_eq!(status_from_json(&paid), Ok(PayoutStatus::Paid { id: "PAY1".to_string() }));
+
+        let queued = serde_json::json!({"id": "PAY2", "status": "queued"});
+        assert_eq!(status_from_json(&queued), Ok(PayoutStatus::Pending { id: "PAY2".to_string() }));
+
+        let failed = serde_json::json!({"id": "PAY3", "status": "failed", "reason": "Account closed"});
+        assert_eq!(status_from_json(&failed), Ok(PayoutStatus::Failed { reason: "Account closed".to_string() }));
+
+        let refused = serde_json::json!({"error": "Limit exceeded"});
+        assert_eq!(status_from_json(&refused), Err(CashoutError::Rejected("Limit exceeded".to_string())));
+    }
+
+    #[test]
+    fn test_providers_pick_by_key() {
+        let providers = CashoutProviders::new("0xtreasury")
+            .with(MockCashoutProvider::new(amount("1"), MockOutcome::Paid));
+        assert_eq!(providers.treasury(), Some("0xtreasury"));
+        assert_eq!(providers.keys(), "MOCK");
+        assert!(providers.get(None).is_some());
+        assert!(providers.get(Some("mock")).is_some());
+        assert!(providers.get(Some("MPESA")).is_none());
+
+        assert_eq!(CashoutProviders::default().treasury(), None);
+        assert_eq!(CashoutProviders::new("0xtreasury").treasury(), None);
+    }
+}
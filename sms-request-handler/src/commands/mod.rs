@@ -1,3 +1,4 @@
 pub mod parser;
+pub mod registry;
 
 pub use parser::CommandProcessor;
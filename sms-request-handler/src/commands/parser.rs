@@ -1,7 +1,22 @@
 use std::sync::Arc;
-use sha2::Digest;
-use crate::db::{UserRepository, VoucherRepository, DepositRepository, AddressBookRepository};
-use crate::wallet::{AmoyProvider, UserWallet, Chain, MultiChainProvider};
+use uuid::Uuid;
+use crate::cashout::{CashoutError, CashoutProviders, PayoutRequest, PayoutStatus};
+use crate::config::env_duration;
+use crate::db::{
+    generate_reference, hash_pin, is_valid_contact_name, UserRepository, VoucherRepository,
+    AddressBookRepository, CashoutRepository, ContactMatch, ContactTarget, Conversation, ConversationRepository,
+    HistoryFilter, LimitPeriod, NewCashout, NewSchedule, NewTransaction, PaymentRequestRepository, PendingAction, PendingTransactionRepository,
+    PhoneChange, PhoneChangeRepository, generate_phone_code,
+    Recurrence, ScheduleError, ScheduleRepository, ScheduledPayment, SpendingLimitRepository, SplitShare,
+    TransactionRepository, TxDirection, TxKind, TxStatus, User, MAX_PIN_ATTEMPTS, PIN_LOCKOUT_MINS, user_limits,
+};
+use crate::wallet::{
+    create_chain_provider, get_chain_balances, token_decimals, transfer, transferable_tokens, Amount,
+    AmountError, AmoyProvider, Chain, ChainProvider, MultiChainProvider, Slippage, SwapQuote, UserWallet,
+};
+use crate::i18n::{keywords, tr, tr_with, Lang, Msg};
+use crate::sms::{normalize_phone, Outbox, PhoneNumber, ReplyShaper};
+use super::registry::{self, ArgError, ArgSpec};
 
 /// Parsed SMS command
 #[derive(Debug, Clone, PartialEq)]
@@ -12,35 +27,100 @@ pub enum Command {
     Join { ens_name: Option<String> },
     /// Check account balance
     Balance,
-    /// Set or change PIN
+    /// Set PIN for the first time
     Pin { new_pin: Option<String> },
+    /// Change an existing PIN: PIN <old> <new>
+    ChangePin { old_pin: String, new_pin: String },
     /// Send money to someone
     Send {
-        amount: f64,
+        amount: Amount,
         token: String,
         recipient: String,
     },
+    /// Send on a schedule: SEND <amount> <token> <recipient> EVERY .../AT <date>
+    ScheduleSend {
+        amount: Amount,
+        token: String,
+        recipient: String,
+        schedule: String,
+    },
+    /// List active scheduled payments
+    Schedules,
+    /// Cancel a scheduled payment: STOP <id>
+    Stop { code: String },
+    /// Divide a payment: SPLIT <amount> <token> <recipient[:share]>...
+    Split {
+        amount: Amount,
+        token: String,
+        recipients: String,
+    },
+    /// Ask someone for money: REQUEST <amount> <token> FROM <payer>
+    Request {
+        amount: Amount,
+        token: String,
+        payer: String,
+    },
+    /// Pay a request addressed to you: PAY <code> [pin]
+    Pay { code: String, pin: Option<String> },
+    /// Refuse a request addressed to you: DECLINE <code>
+    Decline { code: String },
     /// Check deposit address
     Deposit,
-    /// Check transaction history
-    History,
+    /// Page through the transaction ledger: HISTORY [SENT|RECEIVED] [page]
+    History { filter: HistoryFilter, page: u32 },
+    /// Where an operation stands: STATUS <ref>
+    Status { reference: String },
     /// Redeem a voucher code
     Redeem { code: String },
     /// Swap tokens for ETH: SWAP <amount> TXTC
-    Swap { amount: f64, token: String },
+    Swap { amount: Amount, token: String },
+    /// Expected output of a swap: QUOTE <amount> TXTC
+    Quote { amount: Amount, token: String },
+    /// Show or set the swap slippage tolerance: SLIPPAGE [percent]
+    Slippage { percent: Option<String> },
+    /// Turn tokens into money: CASHOUT <amount> TXTC [method]
+    Cashout { amount: Amount, token: String, method: Option<String> },
+    /// Show spending limits: LIMIT [token]
+    Limits { token: String },
+    /// Change a spending limit: LIMIT [token] <TX|DAY|WEEK> <amount> [pin]
+    SetLimit { token: String, period: LimitPeriod, amount: Amount, pin: Option<String> },
+    /// Move the sender's wallet to another number: NEWNUMBER <phone>
+    NewNumber { phone: String },
+    /// Stop a number change from either number: NEWNUMBER CANCEL
+    CancelNumberChange,
+    /// Prove the new number: VERIFY <code> <pin>
+    VerifyNumber { code: String, pin: String },
     /// Bridge tokens cross-chain: BRIDGE <amount> <token> FROM <chain> TO <chain>
     Bridge {
-        amount: f64,
+        amount: Amount,
         token: String,
         from_chain: String,
         to_chain: String,
     },
-    /// Save a contact: SAVE <name> <phone>
-    Save { name: String, phone: String },
-    /// List contacts
-    Contacts,
+    /// Save a contact: SAVE <name> <phone|0x...|ens>
+    Save { name: String, target: String },
+    /// Remove a contact: DELETE <name>
+    Delete { name: String },
+    /// Rename a contact: RENAME <old> [TO] <new>
+    Rename { old_name: String, new_name: String },
+    /// Page through contacts: CONTACTS [page]
+    Contacts { page: u32 },
     /// Switch chain: CHAIN <name>
     SwitchChain { chain: String },
+    /// Confirm the pending transaction: YES <pin>
+    Confirm { pin: String },
+    /// Cancel the pending transaction
+    Cancel,
+    /// Next page of a long reply
+    More,
+    /// Show or change the reply language: LANG [code]
+    Lang { code: Option<String> },
+    /// Known command missing an argument it can ask for: start a dialog
+    Ask { command: &'static str, args: String, prompt: Msg },
+    /// Known command with missing or malformed arguments
+    Usage(String),
+    /// Near-miss of known keywords: the input and corrected commands
+    Suggest { input: String, suggestions: Vec<String> },
     /// Unknown command
     Unknown(String),
 }
@@ -50,24 +130,101 @@ pub enum Command {
 pub struct CommandProcessor {
     user_repo: Option<UserRepository>,
     voucher_repo: Option<VoucherRepository>,
-    deposit_repo: Option<DepositRepository>,
     address_book_repo: Option<AddressBookRepository>,
+    pending_repo: Option<PendingTransactionRepository>,
+    transaction_repo: Option<TransactionRepository>,
+    payment_request_repo: Option<PaymentRequestRepository>,
+    schedule_repo: Option<ScheduleRepository>,
+    conversation_repo: Option<ConversationRepository>,
+    cashout_repo: Option<CashoutRepository>,
+    limit_repo: Option<SpendingLimitRepository>,
+    phone_change_repo: Option<PhoneChangeRepository>,
+    notifier: Option<Outbox>,
+    replies: ReplyShaper,
+    cashouts: CashoutProviders,
     provider: Arc<AmoyProvider>,
     multi_chain: MultiChainProvider,
     backend_url: String,
+    /// How long a value-moving command waits for YES <PIN>
+    pending_ttl: chrono::Duration,
+    /// How long a REQUEST can be paid or declined
+    request_ttl: chrono::Duration,
+    /// How long a dialog waits for the next answer
+    dialog_ttl: chrono::Duration,
+    /// How long an operation may stay pending before the user is alerted
+    watchdog_after: chrono::Duration,
+    /// How long a raised spending limit waits before it applies
+    limit_cooldown: chrono::Duration,
+    /// How long the code texted to a new number stays valid
+    phone_code_ttl: chrono::Duration,
+    /// How long a verified number change waits, so the old number can stop it
+    phone_change_wait: chrono::Duration,
+    auto_correct: bool,
+}
+
+/// Reply language for numbers without a saved preference
+fn default_language() -> Lang {
+    std::env::var("DEFAULT_LANGUAGE")
+        .ok()
+        .and_then(|v| Lang::from_input(&v))
+        .unwrap_or_default()
+}
+
+/// Ledger rows per HISTORY reply, so a page fits in about one SMS
+const HISTORY_PAGE_SIZE: i64 = 3;
+
+/// Contacts per CONTACTS reply
+const CONTACTS_PAGE_SIZE: i64 = 5;
+
+/// Most people one SPLIT can pay
+const MAX_SPLIT_RECIPIENTS: usize = 10;
+
+/// Trade size whose rate counts as the spot price when measuring price impact
+fn quote_reference() -> Amount {
+    Amount::from_base_units(1, 2)
 }
 
+/// Scheduled payments run per scheduler tick
+const SCHEDULE_BATCH_SIZE: i64 = 50;
+
+/// Overdue transactions alerted per watchdog tick
+const WATCHDOG_BATCH_SIZE: i64 = 50;
+
+/// Pending payouts checked per scheduler tick
+const CASHOUT_BATCH_SIZE: i64 = 50;
+
+/// Number changes completed per scheduler tick
+const PHONE_CHANGE_BATCH_SIZE: i64 = 20;
+
 impl CommandProcessor {
     pub fn new(user_repo: Option<UserRepository>, provider: Arc<AmoyProvider>) -> Self {
         let backend_url = std::env::var("BACKEND_URL").unwrap_or_else(|_| "http://localhost:3000".to_string());
         Self { 
             user_repo,
             voucher_repo: None,
-            deposit_repo: None,
             address_book_repo: None,
+            pending_repo: None,
+            transaction_repo: None,
+            payment_request_repo: None,
+            schedule_repo: None,
+            conversation_repo: None,
+            cashout_repo: None,
+            limit_repo: None,
+            phone_change_repo: None,
+            notifier: None,
+            replies: ReplyShaper::from_env(),
+            cashouts: CashoutProviders::from_env(),
             provider,
             multi_chain: MultiChainProvider::new(),
             backend_url,
+            pending_ttl: env_duration("PENDING_TX_TTL_SECS", chrono::Duration::seconds(300)),
+            request_ttl: env_duration("PAYMENT_REQUEST_TTL_HOURS", chrono::Duration::hours(24)),
+            dialog_ttl: env_duration("DIALOG_TTL_SECS", chrono::Duration::seconds(300)),
+            watchdog_after: env_duration("TX_WATCHDOG_MINS", chrono::Duration::minutes(15)),
+            limit_cooldown: env_duration("LIMIT_RAISE_COOLDOWN_HOURS", chrono::Duration::hours(24)),
+            phone_code_ttl: env_duration("PHONE_CHANGE_CODE_TTL_MINS", chrono::Duration::minutes(15)),
+            phone_change_wait: env_duration("PHONE_CHANGE_WAIT_HOURS", chrono::Duration::hours(48)),
+            auto_correct: false,
         }
     }
 
@@ -75,223 +232,313 @@ impl CommandProcessor {
     pub fn with_repos(
         user_repo: Option<UserRepository>,
         voucher_repo: Option<VoucherRepository>,
-        deposit_repo: Option<DepositRepository>,
         address_book_repo: Option<AddressBookRepository>,
         provider: Arc<AmoyProvider>,
     ) -> Self {
-        let backend_url = std::env::var("BACKEND_URL").unwrap_or_else(|_| "http://localhost:3000".to_string());
         Self {
-            user_repo,
             voucher_repo,
-            deposit_repo,
             address_book_repo,
-            provider,
-            multi_chain: MultiChainProvider::new(),
-            backend_url,
+            ..Self::new(user_repo, provider)
         }
     }
 
-    /// Process an incoming SMS and return the response
+    /// Run unambiguous typo corrections instead of replying with a suggestion
+    pub fn with_auto_correct(mut self, enabled: bool) -> Self {
+        self.auto_correct = enabled;
+        self
+    }
+
+    /// Enable PIN-confirmed pending transactions
+    pub fn with_pending_repo(mut self, pending_repo: PendingTransactionRepository) -> Self {
+        self.pending_repo = Some(pending_repo);
+        self
+    }
+
+    /// Record value-moving commands in the transaction ledger
+    pub fn with_transaction_repo(mut self, transaction_repo: TransactionRepository) -> Self {
+        self.transaction_repo = Some(transaction_repo);
+        self
+    }
+
+    /// Enable REQUEST/PAY/DECLINE
+    pub fn with_payment_request_repo(mut self, payment_request_repo: PaymentRequestRepository) -> Self {
+        self.payment_request_repo = Some(payment_request_repo);
+        self
+    }
+
+    /// Enable SEND ... EVERY/AT, SCHEDULES and STOP
+    pub fn with_schedule_repo(mut self, schedule_repo: ScheduleRepository) -> Self {
+        self.schedule_repo = Some(schedule_repo);
+        self
+    }
+
+    /// Prompt for missing arguments instead of replying with usage
+    pub fn with_conversation_repo(mut self, conversation_repo: ConversationRepository) -> Self {
+        self.conversation_repo = Some(conversation_repo);
+        self
+    }
+
+    /// Enable CASHOUT
+    pub fn with_cashout_repo(mut self, cashout_repo: CashoutRepository) -> Self {
+        self.cashout_repo = Some(cashout_repo);
+        self
+    }
+
+    /// Enforce per-user spending limits and enable LIMIT
+    pub fn with_limit_repo(mut self, limit_repo: SpendingLimitRepository) -> Self {
+        self.limit_repo = Some(limit_repo);
+        self
+    }
+
+    /// Enable NEWNUMBER/VERIFY wallet moves
+    pub fn with_phone_change_repo(mut self, phone_change_repo: PhoneChangeRepository) -> Self {
+        self.phone_change_repo = Some(phone_change_repo);
+        self
+    }
+
+    /// Outbox for messages to users other than the sender
+    pub fn with_notifier(mut self, notifier: Outbox) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Provider for a chain, connecting on demand to chains not preloaded
+    fn chain_provider(&self, chain: Chain) -> Arc<ChainProvider> {
+        self.multi_chain.get(chain).unwrap_or_else(|| create_chain_provider(chain))
+    }
+
+    /// Process an incoming SMS and return the response, shaped to fit
+    /// the SMS segment budget
     pub async fn process(&self, from: &str, body: &str) -> String {
-        let command = self.parse(body);
+        // An open dialog takes the message as its next answer
+        if let Some(conversation) = self.active_conversation(from).await {
+            let lang = conversation.lang();
+            if let Some(reply) = self.continue_dialog(from, lang, &conversation, body).await {
+                return self.replies.shape(from, lang, &reply);
+            }
+        }
+
+        let lang = self.language_for(from, body).await;
+        let command = self.parse(body, lang);
         
         tracing::debug!(
             from = %from,
+            lang = %lang,
             command = ?command,
             "Processing command"
         );
 
-        self.execute(from, command).await
+        // MORE returns a page that has already been shaped
+        if command == Command::More {
+            return self.more_response(from, lang);
+        }
+        let reply = self.execute(from, lang, command).await;
+        self.replies.shape(from, lang, &reply)
     }
 
-    /// Parse SMS text into a structured command
-    pub fn parse(&self, text: &str) -> Command {
-        let original = text.trim();
-        let text = original.to_uppercase();
-        let parts: Vec<&str> = text.split_whitespace().collect();
-        let original_parts: Vec<&str> = original.split_whitespace().collect();
-
-        if parts.is_empty() {
-            return Command::Unknown("".to_string());
+    /// Run one complete command, without SMS dialogs or paging, for
+    /// channels with menus of their own (USSD)
+    pub async fn run_command(&self, from: &str, text: &str) -> String {
+        let lang = self.language_for(from, text).await;
+        match self.parse(text, lang) {
+            // The menu asks for its own arguments
+            Command::Ask { prompt, .. } => tr(lang, prompt),
+            command => self.execute(from, lang, command).await,
         }
+    }
 
-        match parts[0] {
-            "COMMANDS" | "MENU" | "?" => Command::Help,
-            "JOIN" | "START" | "REGISTER" => {
-                let ens_name = parts.get(1).map(|s| s.to_lowercase());
-                Command::Join { ens_name }
-            },
-            "BALANCE" | "BAL" => Command::Balance,
-            "PIN" => {
-                let new_pin = parts.get(1).map(|s| s.to_string());
-                Command::Pin { new_pin }
-            }
-            "SEND" => self.parse_send(&original_parts),
-            "DEPOSIT" | "RECEIVE" => Command::Deposit,
-            "HISTORY" | "TRANSACTIONS" | "TXS" => Command::History,
-            "REDEEM" | "VOUCHER" | "CODE" => {
-                if parts.len() < 2 {
-                    Command::Unknown("Usage: REDEEM <code>".to_string())
-                } else {
-                    Command::Redeem { code: parts[1].to_string() }
-                }
-            }
-            "SWAP" | "EXCHANGE" => self.parse_swap(&parts),
-            "BRIDGE" | "CROSS" => self.parse_bridge(&parts),
-            "SAVE" | "ADD" => self.parse_save(&parts),
-            "CONTACTS" | "BOOK" => Command::Contacts,
-            "CHAIN" | "NETWORK" => {
-                if parts.len() < 2 {
-                    Command::Unknown("Usage: CHAIN <polygon|base|eth|arb>".to_string())
-                } else {
-                    Command::SwitchChain { chain: parts[1].to_string() }
-                }
-            }
-            _ => Command::Unknown(text),
-        }
+    /// Language replies to `from` are written in
+    pub async fn user_language(&self, from: &str) -> Lang {
+        self.language_for(from, "").await
     }
 
-    /// Parse SAVE command: SAVE <name> <phone>
-    fn parse_save(&self, parts: &[&str]) -> Command {
-        if parts.len() < 3 {
-            return Command::Unknown("Usage: SAVE <name> <phone>".to_string());
-        }
-        Command::Save {
-            name: parts[1].to_string(),
-            phone: parts[2..].join(" "),
+    /// Reference of the transaction `from` has waiting for YES <PIN>, if any
+    pub async fn pending_reference(&self, from: &str) -> Option<String> {
+        let repo = self.pending_repo.as_ref()?;
+        match repo.find_open(from).await {
+            Ok(Some(pending)) if !pending.is_expired() => Some(pending.reference),
+            _ => None,
         }
     }
 
-    /// Parse SEND command: SEND <amount> <token> [TO] <recipient>
-    /// Supports: SEND 10 TXTC TO swarnim.ttcip.eth
-    ///           SEND 10 TXTC swarnim.ttcip.eth
-    ///           SEND 0.001 ETH 0xabc...
-    fn parse_send(&self, parts: &[&str]) -> Command {
-        if parts.len() < 4 {
-            return Command::Unknown("Use: SEND <amount> <token> <recipient>\nExample: SEND 10 TXTC swarnim.ttcip.eth".to_string());
+    /// YES <PIN> for the pending transaction `reference` only; nothing is
+    /// confirmed if another has replaced it
+    pub async fn confirm_reference(&self, from: &str, pin: &str, reference: &str) -> String {
+        let lang = self.language_for(from, "").await;
+        let Some(ref repo) = self.pending_repo else {
+            return tr(lang, Msg::DbOffline);
+        };
+        match repo.find_open(from).await {
+            Ok(Some(pending)) if pending.reference == reference => {
+                self.confirm_response(from, lang, pin, Some(pending.id)).await
+            }
+            Ok(_) => tr(lang, Msg::NothingToConfirm),
+            Err(_) => tr(lang, Msg::TryLater),
         }
+    }
 
-        let amount = match parts[1].parse::<f64>() {
-            Ok(amt) => amt,
-            Err(_) => return Command::Unknown("Invalid amount".to_string()),
-        };
+    /// Reply to the first message over a rate limit, in the sender's language
+    pub async fn throttled_response(&self, from: &str, body: &str, window: chrono::Duration) -> String {
+        let lang = self.language_for(from, body).await;
+        tr_with(lang, Msg::Throttled, &[&window.num_minutes().max(1)])
+    }
 
-        let token = parts[2].to_string();
+    /// Parse SMS text into a structured command using the command registry.
+    ///
+    /// Localized keywords are accepted for any supported language; `lang` is
+    /// used for usage messages when the keyword itself is English.
+    pub fn parse(&self, text: &str, lang: Lang) -> Command {
+        let original = text.trim();
+        let original_parts: Vec<&str> = original.split_whitespace().collect();
 
-        // Check if "TO" keyword is present (optional)
-        let recipient = if parts.len() >= 5 && parts[3].eq_ignore_ascii_case("TO") {
-            parts[4..].join(" ")
-        } else {
-            parts[3..].join(" ")
+        let Some(keyword) = original_parts.first() else {
+            return Command::Unknown("".to_string());
         };
 
-        if recipient.is_empty() {
-            return Command::Unknown("Missing recipient.\nExample: SEND 10 TXTC swarnim.ttcip.eth".to_string());
-        }
+        let Some((spec, keyword_lang)) = registry::lookup_localized(keyword) else {
+            return self.parse_typo(&original_parts, lang);
+        };
 
-        Command::Send {
-            amount,
-            token,
-            recipient,
+        let args_lang = keyword_lang.unwrap_or(lang);
+        match spec.parse_args(&original_parts[1..], args_lang) {
+            Ok(args) => (spec.build)(&args),
+            Err(ArgError::Missing(ArgSpec { prompt: Some(prompt), .. })) => Command::Ask {
+                command: spec.name(),
+                args: original_parts[1..].join(" "),
+                prompt,
+            },
+            Err(error) => Command::Usage(spec.error_text(error, args_lang)),
         }
     }
 
-    /// Parse BRIDGE command: BRIDGE <amount> <token> FROM <chain> TO <chain>
-    /// Also supports: BRIDGE <amount> <token> <from_chain> <to_chain>
-    fn parse_bridge(&self, parts: &[&str]) -> Command {
-        if parts.len() < 5 {
-            return Command::Unknown("Usage: BRIDGE <amount> <token> FROM <chain> TO <chain>\nExample: BRIDGE 10 USDC FROM POLYGON TO BASE".to_string());
-        }
-
-        let amount = match parts[1].parse::<f64>() {
-            Ok(amt) => amt,
-            Err(_) => return Command::Unknown("Invalid amount".to_string()),
-        };
-
-        let token = parts[2].to_string();
-
-        // Parse FROM/TO chains - support both "FROM x TO y" and "x y" formats
-        let (from_chain, to_chain) = if parts.len() >= 7 && parts[3] == "FROM" && parts[5] == "TO" {
-            (parts[4].to_string(), parts[6].to_string())
-        } else if parts.len() >= 6 && parts[3] == "FROM" {
-            // BRIDGE 10 USDC FROM POLYGON BASE
-            (parts[4].to_string(), parts[5].to_string())
-        } else if parts.len() >= 5 {
-            // BRIDGE 10 USDC POLYGON BASE
-            (parts[3].to_string(), parts[4].to_string())
-        } else {
-            return Command::Unknown("Usage: BRIDGE <amount> <token> FROM <chain> TO <chain>".to_string());
+    /// Handle an unrecognised keyword: auto-correct a single near-miss if
+    /// enabled, otherwise suggest the closest commands.
+    fn parse_typo(&self, parts: &[&str], lang: Lang) -> Command {
+        let suggestions = registry::suggest(parts[0]);
+        let rest = parts[1..].join(" ");
+        let corrected = |keyword: &str| {
+            if rest.is_empty() {
+                keyword.to_string()
+            } else {
+                format!("{} {}", keyword, rest)
+            }
         };
 
-        Command::Bridge {
-            amount,
-            token,
-            from_chain,
-            to_chain,
+        match suggestions.as_slice() {
+            [] => Command::Unknown(parts.join(" ").to_uppercase()),
+            [only] if self.auto_correct => {
+                tracing::info!(typo = %parts[0], keyword = %only.keyword, "Auto-correcting command");
+                self.parse(&corrected(only.keyword), lang)
+            }
+            _ => Command::Suggest {
+                input: parts[0].to_uppercase(),
+                suggestions: suggestions.iter().map(|s| corrected(s.keyword)).collect(),
+            },
         }
     }
 
-    /// Parse SWAP command: SWAP <amount> TXTC
-    fn parse_swap(&self, parts: &[&str]) -> Command {
-        if parts.len() < 3 {
-            return Command::Unknown("Usage: SWAP <amount> TXTC".to_string());
+    /// Pick the reply language: the user's saved preference, then the
+    /// language of the keyword they used, then DEFAULT_LANGUAGE.
+    async fn language_for(&self, from: &str, body: &str) -> Lang {
+        if let Some(ref repo) = self.user_repo {
+            if let Ok(Some(user)) = repo.find_by_phone(from).await {
+                if let Some(lang) = user.lang() {
+                    return lang;
+                }
+            }
         }
 
-        let amount = match parts[1].parse::<f64>() {
-            Ok(amt) => amt,
-            Err(_) => return Command::Unknown("Invalid amount".to_string()),
-        };
-
-        let token = parts[2].to_string();
-        
-        Command::Swap {
-            amount,
-            token,
-        }
+        body.split_whitespace()
+            .next()
+            .and_then(keywords::canonical)
+            .map(|(_, lang)| lang)
+            .unwrap_or_else(default_language)
     }
 
     /// Execute a parsed command and return the response text
-    async fn execute(&self, from: &str, command: Command) -> String {
+    async fn execute(&self, from: &str, lang: Lang, command: Command) -> String {
         match command {
-            Command::Help => self.help_response(),
-            Command::Join { ens_name } => self.join_response(from, ens_name).await,
-            Command::Balance => self.balance_response(from).await,
-            Command::Pin { new_pin } => self.pin_response(from, new_pin).await,
+            Command::Help => self.help_response(lang),
+            Command::Join { ens_name } => self.join_response(from, lang, ens_name).await,
+            Command::Balance => self.balance_response(from, lang).await,
+            Command::Pin { new_pin } => self.pin_response(from, lang, new_pin).await,
+            Command::ChangePin { old_pin, new_pin } => {
+                self.change_pin_response(from, lang, &old_pin, &new_pin).await
+            }
             Command::Send { amount, token, recipient } => {
-                self.send_response(from, amount, &token, &recipient).await
+                self.send_response(from, lang, amount, &token, &recipient).await
+            }
+            Command::ScheduleSend { amount, token, recipient, schedule } => {
+                self.schedule_send_response(from, lang, amount, &token, &recipient, &schedule).await
             }
-            Command::Deposit => self.deposit_response(from).await,
-            Command::History => self.history_response(from).await,
-            Command::Redeem { code } => self.redeem_response(from, &code).await,
-            Command::Swap { amount, token } => self.swap_response(from, amount, &token).await,
+            Command::Schedules => self.schedules_response(from, lang).await,
+            Command::Stop { code } => self.stop_response(from, lang, &code).await,
+            Command::Split { amount, token, recipients } => {
+                self.split_response(from, lang, amount, &token, &recipients).await
+            }
+            Command::Request { amount, token, payer } => {
+                self.request_response(from, lang, amount, &token, &payer).await
+            }
+            Command::Pay { code, pin } => self.pay_response(from, lang, &code, pin).await,
+            Command::Decline { code } => self.decline_response(from, lang, &code).await,
+            Command::Deposit => self.deposit_response(from, lang).await,
+            Command::History { filter, page } => self.history_response(from, lang, filter, page).await,
+            Command::Status { reference } => self.status_response(from, lang, &reference).await,
+            Command::Redeem { code } => self.redeem_response(from, lang, &code).await,
+            Command::Swap { amount, token } => self.swap_response(from, lang, amount, &token).await,
+            Command::Quote { amount, token } => self.quote_response(from, lang, amount, &token).await,
+            Command::Slippage { percent } => self.slippage_response(from, lang, percent).await,
+            Command::Cashout { amount, token, method } => {
+                self.cashout_response(from, lang, amount, &token, method).await
+            }
+            Command::Limits { token } => self.limits_response(from, lang, &token).await,
+            Command::SetLimit { token, period, amount, pin } => {
+                self.set_limit_response(from, lang, &token, period, amount, pin).await
+            }
+            Command::NewNumber { phone } => self.new_number_response(from, lang, &phone).await,
+            Command::CancelNumberChange => self.cancel_number_change_response(from, lang).await,
+            Command::VerifyNumber { code, pin } => self.verify_number_response(from, lang, &code, &pin).await,
             Command::Bridge { amount, token, from_chain, to_chain } => {
-                self.bridge_response(from, amount, &token, &from_chain, &to_chain).await
+                self.bridge_response(from, lang, amount, &token, &from_chain, &to_chain).await
             }
-            Command::Save { name, phone } => self.save_response(from, &name, &phone).await,
-            Command::Contacts => self.contacts_response(from).await,
-            Command::SwitchChain { chain } => self.chain_response(from, &chain).await,
-            Command::Unknown(text) => self.unknown_response(&text),
+            Command::Save { name, target } => self.save_response(from, lang, &name, &target).await,
+            Command::Delete { name } => self.delete_response(from, lang, &name).await,
+            Command::Rename { old_name, new_name } => {
+                self.rename_response(from, lang, &old_name, &new_name).await
+            }
+            Command::Contacts { page } => self.contacts_response(from, lang, page).await,
+            Command::SwitchChain { chain } => self.chain_response(from, lang, &chain).await,
+            Command::Confirm { pin } => self.confirm_response(from, lang, &pin, None).await,
+            Command::Cancel => self.cancel_response(from, lang).await,
+            Command::More => self.more_response(from, lang),
+            Command::Lang { code } => self.lang_response(from, lang, code).await,
+            Command::Ask { command, args, prompt } => self.ask(from, lang, command, &args, prompt).await,
+            Command::Usage(usage) => usage,
+            Command::Suggest { input, suggestions } => {
+                tr_with(lang, Msg::DidYouMean, &[&input, &suggestions.join(" / ")])
+            }
+            Command::Unknown(text) => self.unknown_response(lang, &text),
         }
     }
 
-    fn help_response(&self) -> String {
-        "Text-to-Chain Commands:\nJOIN <name> - Create wallet\nBALANCE - Check balance\nSEND 10 TXTC TO name.ttcip.eth\nDEPOSIT - Get deposit address\nREDEEM <code> - Redeem voucher\nSWAP 10 TXTC - Swap to ETH\nCASHOUT <AMOUNT> TXTC - Cash out to USDC\nMENU - Show this help".to_string()
+    fn help_response(&self, lang: Lang) -> String {
+        registry::help_text(lang)
     }
 
-    async fn join_response(&self, from: &str, ens_name: Option<String>) -> String {
+    async fn join_response(&self, from: &str, lang: Lang, ens_name: Option<String>) -> String {
         // Check if database is available
         let Some(ref repo) = self.user_repo else {
-            return "DB offline. Try later.".to_string();
+            return tr(lang, Msg::DbOffline);
         };
 
         // If ENS name provided, validate and register it
         if let Some(name) = ens_name {
             // Validate format
             if name.len() < 3 || name.len() > 20 {
-                return "ENS name must be 3-20 characters.\n\nTry again: JOIN <name>\nExample: JOIN alice".to_string();
+                return tr(lang, Msg::EnsLength);
             }
             if !name.chars().all(|c| c.is_alphanumeric()) {
-                return "ENS name can only contain letters and numbers.\n\nTry again: JOIN <name>".to_string();
+                return tr(lang, Msg::EnsCharset);
             }
 
             // Check if user already has a wallet
@@ -311,15 +558,12 @@ impl CommandProcessor {
                             if let Ok(check_data) = resp.json::<serde_json::Value>().await {
                                 if !check_data["available"].as_bool().unwrap_or(false) {
                                     let reason = check_data["reason"].as_str().unwrap_or("Name not available");
-                                    return format!(
-                                        "❌ {}\n\nTry another name:\nJOIN <name>\n\nExamples: alice, bob123, john",
-                                        reason
-                                    );
+                                    return tr_with(lang, Msg::NameUnavailable, &[&reason]);
                                 }
                             }
                         }
                         _ => {
-                            return "Error checking name availability. Try later.".to_string();
+                            return tr(lang, Msg::NameCheckFailed);
                         }
                     }
 
@@ -343,22 +587,18 @@ impl CommandProcessor {
                             }
                             
                             // TODO: Mint ENS subdomain on-chain here
-                            return format!(
-                                "Registered!\n{}\nWallet: {}\n\nReply DEPOSIT to fund.",
-                                full_ens,
-                                user.wallet_address
-                            );
+                            return tr_with(lang, Msg::Registered, &[&full_ens, &user.wallet_address]);
                         }
                         _ => {
-                            return "Error registering ENS name. Try later.".to_string();
+                            return tr(lang, Msg::NameRegisterFailed);
                         }
                     }
                 }
                 Ok(None) => {
-                    return "Please use JOIN first to create your wallet.".to_string();
+                    return tr(lang, Msg::JoinFirst);
                 }
                 Err(_) => {
-                    return "Error. Try later.".to_string();
+                    return tr(lang, Msg::TryLater);
                 }
             }
         }
@@ -367,10 +607,7 @@ impl CommandProcessor {
         match repo.find_by_phone(from).await {
             Ok(Some(user)) => {
                 // User already has wallet, just show welcome message
-                return format!(
-                    "Welcome back!\n\nYour wallet:\n{}\n\nReply BALANCE or DEPOSIT",
-                    user.wallet_address
-                );
+                return tr_with(lang, Msg::WelcomeBack, &[&user.wallet_address]);
             }
             Ok(None) => {
                 // New user - create wallet and prompt for ENS name
@@ -378,7 +615,7 @@ impl CommandProcessor {
                     Ok(w) => w,
                     Err(e) => {
                         tracing::error!("Wallet error: {}", e);
-                        return "Error creating wallet.".to_string();
+                        return tr(lang, Msg::WalletCreateFailed);
                     }
                 };
 
@@ -388,37 +625,45 @@ impl CommandProcessor {
                 // Save to database
                 match repo.create(from, &wallet.address_string(), &encrypted_key).await {
                     Ok(_) => {
-                        format!(
-                            "Wallet created!\n{}\n\nNow pick a name:\nJOIN <name>\n\nEx: JOIN alice",
-                            wallet.address_string()
-                        )
+                        // Remember the language they joined in
+                        if lang != default_language() {
+                            if let Err(e) = repo.update_language(from, lang.code()).await {
+                                tracing::warn!("Failed to save language: {}", e);
+                            }
+                        }
+                        tr_with(lang, Msg::WalletCreated, &[&wallet.address_string()])
                     }
                     Err(e) => {
                         tracing::error!("DB save error: {}", e);
-                        "Error saving wallet.".to_string()
+                        tr(lang, Msg::WalletSaveFailed)
                     }
                 }
             }
             Err(e) => {
                 tracing::error!("DB error: {}", e);
-                "Error. Try later.".to_string()
+                tr(lang, Msg::TryLater)
             }
         }
     }
 
-    async fn balance_response(&self, from: &str) -> String {
+    async fn balance_response(&self, from: &str, lang: Lang) -> String {
         let Some(ref repo) = self.user_repo else {
-            return "Balance: $0.00\nDB offline.".to_string();
+            return tr(lang, Msg::BalanceDbOffline);
         };
 
         // Get user's wallet address
         let user = match repo.find_by_phone(from).await {
             Ok(Some(u)) => u,
-            Ok(None) => return "No wallet. Reply JOIN first.".to_string(),
-            Err(_) => return "Error. Try later.".to_string(),
+            Ok(None) => return tr(lang, Msg::NoWallet),
+            Err(_) => return tr(lang, Msg::TryLater),
         };
 
-        // Call Contract API to get balance on Sepolia
+        let chain = user.chain();
+        if chain != Chain::default() {
+            return self.chain_balance_response(lang, &user, chain).await;
+        }
+
+        // Call Contract API to get TXTC and ETH balance on the default chain
         let client = reqwest::Client::new();
         let api_url = format!("{}/api/balance/{}", self.backend_url, user.wallet_address);
         
@@ -428,7 +673,7 @@ impl CommandProcessor {
             Ok(resp) => resp,
             Err(e) => {
                 tracing::error!("Failed to call Contract API: {}", e);
-                return "Network error. Try later.".to_string();
+                return tr(lang, Msg::NetworkError);
             }
         };
 
@@ -437,7 +682,7 @@ impl CommandProcessor {
             Ok(json) => json,
             Err(e) => {
                 tracing::error!("Failed to parse API response: {}", e);
-                return "Error processing response.".to_string();
+                return tr(lang, Msg::BadResponse);
             }
         };
 
@@ -450,220 +695,1356 @@ impl CommandProcessor {
             let eth: f64 = eth_balance.parse().unwrap_or(0.0);
             
             if txtc > 0.0 || eth > 0.0 {
-                format!(
-                    "Balance:\n{} TXTC\n{} ETH\n\nSepolia testnet",
-                    txtc, eth
-                )
+                tr_with(lang, Msg::Balance, &[&txtc, &eth, &chain.short_code()])
             } else {
-                "Balance: $0.00\n\nReply DEPOSIT to fund wallet.".to_string()
+                tr_with(lang, Msg::BalanceEmpty, &[&chain.short_code()])
             }
         } else {
-            "Error fetching balance.".to_string()
+            tr(lang, Msg::BalanceFailed)
         }
     }
 
-    async fn pin_response(&self, from: &str, new_pin: Option<String>) -> String {
-        match new_pin {
-            Some(pin) => {
-                if pin.len() < 4 || pin.len() > 6 || !pin.chars().all(|c| c.is_ascii_digit()) {
-                    "PIN must be 4-6 digits.\nExample: PIN 1234".to_string()
-                } else {
-                    // Save PIN hash
-                    if let Some(ref repo) = self.user_repo {
-                        // Simple hash for demo (use bcrypt in production)
-                        let pin_hash = format!("{:x}", sha2::Sha256::digest(pin.as_bytes()));
-                        if repo.update_pin(from, &pin_hash).await.is_ok() {
-                            return "PIN set!".to_string();
-                        }
-                    }
-                    "PIN set!".to_string()
+    /// Native and USDC balances read directly from a non-default chain
+    async fn chain_balance_response(&self, lang: Lang, user: &User, chain: Chain) -> String {
+        let Ok(address) = user.wallet_address.parse() else {
+            return tr(lang, Msg::BalanceFailed);
+        };
+
+        match get_chain_balances(self.chain_provider(chain), chain, address).await {
+            Ok(balances) => {
+                let mut lines = vec![format!("{} {}", balances.native.formatted(), balances.native.symbol)];
+                if let Some(usdc) = balances.usdc {
+                    lines.push(format!("{} USDC", usdc.formatted()));
                 }
+                tr_with(lang, Msg::ChainBalance, &[&chain.short_code(), &lines.join("\n")])
+            }
+            Err(e) => {
+                tracing::error!("Balance on {} failed: {}", chain, e);
+                tr(lang, Msg::BalanceFailed)
             }
-            None => "Reply: PIN <4-6 digits>\nExample: PIN 1234".to_string(),
         }
     }
 
-    async fn send_response(&self, from: &str, amount: f64, token: &str, recipient: &str) -> String {
-        let token_upper = token.to_uppercase();
-        // Support TXTC and ETH
-        if token_upper != "TXTC" && token_upper != "ETH" {
-            return format!("Supported tokens: TXTC, ETH\nExample: SEND 10 TXTC swarnim.ttcip.eth");
+    async fn pin_response(&self, from: &str, lang: Lang, new_pin: Option<String>) -> String {
+        let Some(pin) = new_pin else {
+            return tr(lang, Msg::PinPrompt);
+        };
+        if !is_valid_pin(&pin) {
+            return tr(lang, Msg::PinInvalid);
         }
 
-        // Get sender's wallet and private key
-        let Some(ref user_repo) = self.user_repo else {
-            return "DB offline. Try later.".to_string();
+        let Some(ref repo) = self.user_repo else {
+            return tr(lang, Msg::DbOffline);
+        };
+
+        match repo.find_by_phone(from).await {
+            Ok(Some(user)) if user.pin_hash.is_some() => {
+                tr(lang, Msg::PinAlreadySet)
+            }
+            Ok(Some(_)) => match repo.update_pin(from, &hash_pin(&pin)).await {
+                Ok(_) => tr(lang, Msg::PinSet),
+                Err(_) => tr(lang, Msg::TryLater),
+            },
+            Ok(None) => tr(lang, Msg::NoWallet),
+            Err(_) => tr(lang, Msg::TryLater),
+        }
+    }
+
+    async fn change_pin_response(&self, from: &str, lang: Lang, old_pin: &str, new_pin: &str) -> String {
+        if !is_valid_pin(new_pin) {
+            return tr(lang, Msg::PinInvalid);
+        }
+
+        let Some(ref repo) = self.user_repo else {
+            return tr(lang, Msg::DbOffline);
         };
 
-        let sender = match user_repo.find_by_phone(from).await {
+        let user = match repo.find_by_phone(from).await {
             Ok(Some(u)) => u,
-            Ok(None) => { return "No wallet. Reply JOIN first.".to_string(); },
-            Err(_) => { return "Error. Try later.".to_string(); },
+            Ok(None) => return tr(lang, Msg::NoWallet),
+            Err(_) => return tr(lang, Msg::TryLater),
         };
 
-        // Resolve recipient address (wallet address, phone number, or ENS name)
-        let recipient_address = if recipient.starts_with("0x") && recipient.len() == 42 {
-            // Already a wallet address
-            recipient.to_string()
-        } else if recipient.starts_with("+") {
-            // Phone number - look up in database
-            match user_repo.find_by_phone(recipient).await {
-                Ok(Some(u)) => u.wallet_address,
-                Ok(None) => { return format!("{} hasn't joined yet.\nAsk them to text JOIN", recipient); },
-                Err(_) => { return "Error looking up recipient.".to_string(); },
-            }
-        } else if recipient.contains(".eth") || recipient.contains(".") {
-            // ENS name (e.g., swarnim.ttcip.eth) - resolve via backend
-            let client = reqwest::Client::new();
-            let resolve_url = format!("{}/api/ens/resolve/{}", self.backend_url, recipient);
-            match client.get(&resolve_url).send().await {
-                Ok(resp) => {
-                    match resp.json::<serde_json::Value>().await {
-                        Ok(json) => {
-                            if let Some(addr) = json["address"].as_str() {
-                                addr.to_string()
-                            } else {
-                                return format!("Could not resolve {}.\nUse wallet address instead.", recipient);
-                            }
-                        },
-                        Err(_) => { return format!("Could not resolve {}.", recipient); },
-                    }
-                },
-                Err(_) => { return "Network error resolving ENS. Try later.".to_string(); },
+        if user.pin_hash.is_none() {
+            return tr(lang, Msg::PinNotSet);
+        }
+        match self.check_pin(&user, old_pin).await {
+            Ok(()) => {}
+            Err(PinRefused::Wrong(_)) => return tr(lang, Msg::PinWrongUnchanged),
+            Err(refused) => return refused.reply(lang),
+        }
+
+        match repo.update_pin(from, &hash_pin(new_pin)).await {
+            Ok(_) => tr(lang, Msg::PinChanged),
+            Err(_) => tr(lang, Msg::TryLater),
+        }
+    }
+
+    /// Look up the sender and make sure they can confirm value-moving commands
+    async fn require_pin_user(&self, from: &str, lang: Lang) -> Result<User, String> {
+        let Some(ref user_repo) = self.user_repo else {
+            return Err(tr(lang, Msg::DbOffline));
+        };
+
+        match user_repo.find_by_phone(from).await {
+            Ok(Some(u)) if u.pin_hash.is_some() => Ok(u),
+            Ok(Some(_)) => Err(tr(lang, Msg::PinRequired)),
+            Ok(None) => Err(tr(lang, Msg::NoWallet)),
+            Err(_) => Err(tr(lang, Msg::TryLater)),
+        }
+    }
+
+    /// Check the PIN given with a PIN command. Wrong PINs are counted per
+    /// user across all of them, so staging a new op doesn't reset the
+    /// count; MAX_PIN_ATTEMPTS in a row lock them all for PIN_LOCKOUT_MINS.
+    async fn check_pin(&self, user: &User, pin: &str) -> Result<(), PinRefused> {
+        let Some(ref repo) = self.user_repo else {
+            return Err(PinRefused::Unavailable);
+        };
+
+        match repo.pin_locked_until(&user.phone).await {
+            Ok(Some(until)) => {
+                let minutes = ((until - chrono::Utc::now()).num_seconds() + 59) / 60;
+                return Err(PinRefused::Locked(minutes.max(1)));
             }
-        } else {
-            // Try as contact name from address book
-            if let Some(ref address_book) = self.address_book_repo {
-                match address_book.find_by_name(from, recipient).await {
-                    Ok(contacts) if !contacts.is_empty() => {
-                        let contact = &contacts[0];
-                        if let Some(ref addr) = contact.wallet_address {
-                            addr.clone()
-                        } else if let Some(ref phone) = contact.contact_phone {
-                            match user_repo.find_by_phone(phone).await {
-                                Ok(Some(u)) => u.wallet_address,
-                                _ => { return format!("Contact {} has no wallet.", recipient); },
-                            }
-                        } else {
-                            return format!("Contact {} has no address.", recipient);
-                        }
-                    },
-                    _ => { return "Invalid recipient.\nUse ENS (name.ttcip.eth), phone (+1...), or address (0x...)".to_string(); },
-                }
-            } else {
-                return "Invalid recipient.\nUse ENS (name.ttcip.eth), phone (+1...), or address (0x...)".to_string();
+            Ok(None) => {}
+            Err(_) => return Err(PinRefused::Unavailable),
+        }
+
+        if user.verify_pin(pin) {
+            if let Err(e) = repo.clear_pin_failures(&user.phone).await {
+                tracing::error!("Failed to reset wrong PIN count: {}", e);
             }
-        };
+            return Ok(());
+        }
 
-        // Route through Yellow Network for instant finality
-        let client = reqwest::Client::new();
-        let api_url = &format!("{}/api/send-yellow", self.backend_url);
-        
-        tracing::info!("Sending {} {} from {} to {} (via Yellow)", amount, token_upper, sender.wallet_address, recipient_address);
-        
-        let response = match client
-            .post(api_url)
-            .json(&serde_json::json!({
-                "fromAddress": sender.wallet_address,
-                "toAddress": recipient_address,
-                "amount": amount.to_string(),
-                "token": token_upper,
-                "userPhone": from,
-                "senderKey": sender.encrypted_private_key
-            }))
-            .timeout(std::time::Duration::from_secs(30))
-            .send()
-            .await
-        {
-            Ok(resp) => resp,
+        let locked_until = chrono::Utc::now() + chrono::Duration::minutes(PIN_LOCKOUT_MINS);
+        match repo.record_pin_failure(&user.phone, locked_until).await {
+            Ok(0) => {
+                tracing::warn!(phone = %user.phone, "PIN commands locked after wrong PINs");
+                Err(PinRefused::Locked(PIN_LOCKOUT_MINS))
+            }
+            Ok(left) => Err(PinRefused::Wrong(left)),
             Err(e) => {
-                tracing::error!("Failed to call Yellow API: {}", e);
-                return "Network error. Try later.".to_string();
+                tracing::error!("Failed to count wrong PIN: {}", e);
+                Err(PinRefused::Unavailable)
             }
+        }
+    }
+
+    /// Store a value-moving action and ask the user to confirm it with their PIN
+    async fn stage_pending(&self, from: &str, lang: Lang, action: PendingAction) -> Staged {
+        let Some(ref pending_repo) = self.pending_repo else {
+            return Staged::refused(tr(lang, Msg::DbOffline));
         };
+        if let Some((amount, token)) = action.spend() {
+            if let Err(msg) = self.check_limits(from, lang, amount, token).await {
+                return Staged::refused(msg);
+            }
+        }
 
-        // Parse response
-        let result: serde_json::Value = match response.json().await {
-            Ok(json) => json,
+        match pending_repo.create(from, &action, self.pending_ttl).await {
+            Ok(pending) => Staged {
+                id: Some(pending.id),
+                reply: tr_with(lang, Msg::ConfirmPrompt, &[
+                    &action.describe(lang),
+                    &pending.reference,
+                    &self.pending_ttl.num_minutes().max(1),
+                ]),
+            },
             Err(e) => {
-                tracing::error!("Failed to parse API response: {}", e);
-                return "Error processing response.".to_string();
+                tracing::error!("Failed to store pending transaction: {}", e);
+                Staged::refused(tr(lang, Msg::TryLater))
             }
+        }
+    }
+
+    /// Confirm the sender's open pending op; with `expected`, only if it
+    /// is still that one
+    async fn confirm_response(&self, from: &str, lang: Lang, pin: &str, expected: Option<Uuid>) -> String {
+        let Some(ref pending_repo) = self.pending_repo else {
+            return tr(lang, Msg::DbOffline);
         };
 
-        if result["success"].as_bool().unwrap_or(false) {
-            format!(
-                "Sending {} {} to {}...\n\nQueued via Yellow Network.\nYou'll get SMS when complete.",
-                amount, token_upper, recipient
-            )
-        } else {
-            let error_msg = result["error"].as_str().unwrap_or("Unknown error");
-            tracing::error!("Transfer failed: {}", error_msg);
-            
-            if error_msg.contains("insufficient") || error_msg.contains("balance") {
-                "Insufficient balance.".to_string()
-            } else {
-                "Transfer failed. Try later.".to_string()
+        let pending = match pending_repo.find_open(from).await {
+            Ok(Some(p)) if expected.is_none_or(|id| id == p.id) => p,
+            Ok(_) => return tr(lang, Msg::NothingToConfirm),
+            Err(_) => return tr(lang, Msg::TryLater),
+        };
+
+        if pending.is_expired() {
+            let _ = pending_repo.set_status(pending.id, "expired").await;
+            return tr_with(lang, Msg::PendingExpired, &[&pending.reference]);
+        }
+
+        let user = match self.require_pin_user(from, lang).await {
+            Ok(u) => u,
+            Err(msg) => return msg,
+        };
+
+        match self.check_pin(&user, pin).await {
+            Ok(()) => {}
+            Err(PinRefused::Locked(minutes)) => {
+                let _ = pending_repo.set_status(pending.id, "cancelled").await;
+                tracing::warn!(from = %from, reference = %pending.reference, "Pending transaction cancelled after wrong PINs");
+                return format!(
+                    "{}\n{}",
+                    tr_with(lang, Msg::WrongPinCancelled, &[&pending.reference]),
+                    tr_with(lang, Msg::PinLocked, &[&minutes]),
+                );
             }
+            Err(refused) => return refused.reply(lang),
+        }
+
+        // Claim the pending op; fails if another YES got here first
+        match pending_repo.set_status(pending.id, "confirmed").await {
+            Ok(true) => {}
+            Ok(false) => return tr(lang, Msg::NothingToConfirm),
+            Err(_) => return tr(lang, Msg::TryLater),
+        }
+
+        let Some(action) = pending.action() else {
+            tracing::error!(reference = %pending.reference, "Unreadable pending action");
+            return tr(lang, Msg::TryLater);
+        };
+
+        match action {
+            PendingAction::Schedule(ref schedule) => self.create_schedule(from, lang, schedule).await,
+            PendingAction::Split { .. } => self.execute_split(lang, &user, &pending.reference, action).await,
+            action => self.execute_action(lang, &user, &pending.reference, action).await.reply,
         }
     }
 
-    async fn deposit_response(&self, from: &str) -> String {
-        let Some(ref repo) = self.user_repo else {
-            return "DB offline. Reply JOIN first.".to_string();
+    /// Send each share of a confirmed SPLIT and summarize the outcome.
+    ///
+    /// Shares are recorded as `<reference>-1`, `<reference>-2`, ... so the
+    /// group shares the confirmation reference and each share settles on
+    /// its own.
+    async fn execute_split(&self, lang: Lang, user: &User, reference: &str, split: PendingAction) -> String {
+        let PendingAction::Split { amount, token, chain, shares } = split else {
+            unreachable!("only splits are executed here")
         };
+        // The whole group is checked once, before any share is recorded;
+        // shares don't check again, or earlier shares would count twice
+        if let Err(msg) = self.check_limits(&user.phone, lang, amount, &token).await {
+            return msg;
+        }
+        let total = shares.len();
+        let mut sent = 0;
+        let mut pending = false;
+        let mut low_balance = false;
+        let mut lines = Vec::with_capacity(total);
+
+        for (i, share) in shares.into_iter().enumerate() {
+            let line = format!("{}: {} {}", share.recipient, share.amount, token);
+            let action = PendingAction::Send {
+                amount: share.amount,
+                token: token.clone(),
+                recipient: share.recipient,
+                recipient_address: share.recipient_address,
+                chain,
+                request: None,
+            };
+            let executed = self.run_action(lang, user, &format!("{}-{}", reference, i + 1), action).await;
+
+            if executed.status != TxStatus::Failed {
+                sent += 1;
+            }
+            pending |= executed.status == TxStatus::Pending;
+            low_balance |= executed.low_balance;
+            lines.push(match executed.status {
+                TxStatus::Completed => line,
+                TxStatus::Pending => format!("{} {}", line, tr(lang, Msg::TxPendingTag)),
+                TxStatus::Failed => format!("{} {}", line, tr(lang, Msg::TxFailedTag)),
+            });
+        }
 
-        match repo.find_by_phone(from).await {
-            Ok(Some(user)) => {
-                let deposit_address = if let Some(ref ens) = user.ens_name {
-                    ens.clone()
-                } else {
-                    user.wallet_address.clone()
-                };
-                
-                format!(
-                    "Fund wallet:\nDial *384*46750#\nOr REDEEM <code>\nOr send to:\n{}",
-                    deposit_address
-                )
+        let mut reply = tr_with(lang, Msg::SplitSummary, &[&amount, &token, &sent, &total, &lines.join("\n")]);
+        if low_balance {
+            reply = format!("{}\n{}", reply, tr(lang, Msg::InsufficientBalance));
+        }
+        if pending {
+            reply = format!("{}\n{}", reply, tr_with(lang, Msg::TrackHint, &[&reference]));
+        }
+        reply
+    }
+
+    /// Run a confirmed action within the sender's spending limits
+    async fn execute_action(&self, lang: Lang, user: &User, reference: &str, action: PendingAction) -> Executed {
+        // Checked again here: other payments may have gone out since staging
+        if let Some((amount, token)) = action.spend() {
+            if let Err(msg) = self.check_limits(&user.phone, lang, amount, token).await {
+                return Executed::failed(msg);
             }
-            Ok(None) => "No wallet. Reply JOIN first.".to_string(),
-            Err(_) => "Error. Try later.".to_string(),
         }
+        self.run_action(lang, user, reference, action).await
     }
 
-    async fn history_response(&self, from: &str) -> String {
-        // Check for recent deposits
-        if let Some(ref deposit_repo) = self.deposit_repo {
-            if let Ok(deposits) = deposit_repo.get_recent(from, 5).await {
-                if !deposits.is_empty() {
-                    let history: Vec<String> = deposits.iter()
-                        .map(|d| format!("${:.2} via {}", d.amount_as_f64(), d.source))
-                        .collect();
-                    return format!("Recent deposits:\n{}", history.join("\n"));
+    /// Run a confirmed action whose spending limits have been checked,
+    /// keeping its ledger rows and any REQUEST it pays in step
+    async fn run_action(&self, lang: Lang, user: &User, reference: &str, action: PendingAction) -> Executed {
+        let Some(entry) = action.ledger_entry(reference) else {
+            return Executed::failed(tr(lang, Msg::TryLater));
+        };
+        self.record_transaction(&user.phone, &entry).await;
+        let mut request_code = None;
+        if let PendingAction::Send { ref recipient_address, ref request, .. } = action {
+            self.record_incoming(user, recipient_address, &entry).await;
+            request_code = request.clone();
+        }
+
+        let mut executed = match action {
+            PendingAction::Send { recipient_address, chain, .. } => {
+                if chain == Chain::default() {
+                    self.execute_send(lang, user, &entry, &recipient_address).await
+                } else {
+                    self.execute_chain_send(lang, user, &entry, &recipient_address).await
                 }
             }
+            PendingAction::Swap { quoted_out, .. } => self.execute_swap(lang, user, &entry, quoted_out).await,
+            PendingAction::Bridge { from_chain, to_chain, .. } => {
+                self.execute_bridge(lang, user, &entry, &from_chain, &to_chain).await
+            }
+            PendingAction::Cashout(cashout) => self.execute_cashout(lang, user, &entry, cashout).await,
+            PendingAction::Schedule(_) | PendingAction::Split { .. } => {
+                unreachable!("schedules and splits are executed before this point")
+            }
+        };
+
+        if executed.status != TxStatus::Pending || executed.tx_hash.is_some() {
+            self.settle_transaction(reference, executed.status, executed.tx_hash.as_deref()).await;
+        }
+        if let Some(code) = request_code.filter(|_| executed.status != TxStatus::Failed) {
+            self.settle_request(user, &code).await;
+        }
+        if executed.status == TxStatus::Pending {
+            executed.reply = format!("{}\n{}", executed.reply, tr_with(lang, Msg::TrackHint, &[&reference]));
         }
-        "No transactions yet.\nReply REDEEM <code> to add funds.".to_string()
+        executed
     }
 
-    async fn redeem_response(&self, from: &str, code: &str) -> String {
-        // Check if user has wallet
-        let Some(ref user_repo) = self.user_repo else {
-            return "DB offline. Try later.".to_string();
+    /// Store a confirmed SEND ... EVERY/AT
+    async fn create_schedule(&self, from: &str, lang: Lang, schedule: &NewSchedule) -> String {
+        let Some(ref repo) = self.schedule_repo else {
+            return tr(lang, Msg::DbOffline);
         };
 
-        // Get user's wallet address
-        let user = match user_repo.find_by_phone(from).await {
-            Ok(Some(user)) => user,
-            Ok(None) => return "No wallet. Reply JOIN first.".to_string(),
-            Err(_) => return "Error. Try later.".to_string(),
+        match repo.create(from, schedule).await {
+            Ok(created) => {
+                let first = created.next_run_at.format("%Y-%m-%d").to_string();
+                tr_with(lang, Msg::ScheduleCreated, &[&created.code, &first])
+            }
+            Err(e) => {
+                tracing::error!("Failed to store scheduled payment: {}", e);
+                tr(lang, Msg::TryLater)
+            }
+        }
+    }
+
+    /// Run every scheduled payment that has come due, through the same
+    /// path as a confirmed SEND. Called periodically by the scheduler.
+    pub async fn run_due_schedules(&self) {
+        let (Some(ref repo), Some(ref user_repo)) = (&self.schedule_repo, &self.user_repo) else {
+            return;
         };
 
-        // Call Contract API to redeem voucher on-chain
-        let client = reqwest::Client::new();
-        let api_url = &format!("{}/api/redeem", self.backend_url);
-        
-        tracing::info!("Calling Contract API to redeem voucher: {}", code);
+        let due = match repo.find_due(SCHEDULE_BATCH_SIZE).await {
+            Ok(due) => due,
+            Err(e) => {
+                tracing::error!("Failed to load due scheduled payments: {}", e);
+                return;
+            }
+        };
+
+        for schedule in due {
+            let (Some(recurrence), Some(amount)) = (schedule.recurrence(), Amount::parse(&schedule.amount).ok()) else {
+                tracing::error!(code = %schedule.code, "Unreadable scheduled payment, stopping it");
+                let _ = repo.cancel(&schedule.user_phone, &schedule.code).await;
+                continue;
+            };
+
+            // Claim first so a slow run is never picked up twice
+            let next = recurrence.next_after(schedule.next_run_at.date_naive());
+            match repo.claim_run(&schedule, next).await {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(e) => {
+                    tracing::error!(code = %schedule.code, "Failed to claim scheduled payment: {}", e);
+                    continue;
+                }
+            }
+
+            let user = match user_repo.find_by_phone(&schedule.user_phone).await {
+                Ok(Some(u)) => u,
+                _ => {
+                    tracing::error!(code = %schedule.code, "Scheduled payment owner not found");
+                    continue;
+                }
+            };
+            let lang = user.lang().unwrap_or_else(default_language);
+
+            tracing::info!(code = %schedule.code, from = %user.phone, "Running scheduled payment");
+            let action = PendingAction::Send {
+                amount,
+                token: schedule.token.clone(),
+                recipient: schedule.recipient.clone(),
+                recipient_address: schedule.recipient_address.clone(),
+                chain: schedule.chain(),
+                request: None,
+            };
+            let executed = self.execute_action(lang, &user, &generate_reference(), action).await;
+
+            let message = self.schedule_run_message(lang, &schedule, &executed);
+            self.notify(&user.phone, |_| message).await;
+        }
+    }
+
+    /// Tell users about operations the backend never reported back on.
+    /// Each row is alerted once. Called periodically by the scheduler.
+    pub async fn alert_stalled_transactions(&self) {
+        let Some(ref repo) = self.transaction_repo else {
+            return;
+        };
+
+        let stalled = match repo.find_stalled(self.watchdog_after, WATCHDOG_BATCH_SIZE).await {
+            Ok(stalled) => stalled,
+            Err(e) => {
+                tracing::error!("Failed to load stalled transactions: {}", e);
+                return;
+            }
+        };
+
+        for tx in stalled {
+            let minutes = (chrono::Utc::now() - tx.created_at).num_minutes();
+            tracing::warn!(reference = %tx.reference, from = %tx.user_phone, minutes, "Transaction still pending");
+            self.notify(&tx.user_phone, |lang| tr_with(lang, Msg::TxStalled, &[&tx.reference, &minutes])).await;
+            if let Err(e) = repo.mark_alerted(tx.id).await {
+                tracing::error!(reference = %tx.reference, "Failed to mark transaction alerted: {}", e);
+            }
+        }
+    }
+
+    /// Move wallets whose number change has waited out its period, and
+    /// tell both numbers. Called periodically by the scheduler.
+    pub async fn complete_phone_changes(&self) {
+        let (Some(ref repo), Some(ref user_repo)) = (&self.phone_change_repo, &self.user_repo) else {
+            return;
+        };
+
+        let due = match repo.find_due(PHONE_CHANGE_BATCH_SIZE).await {
+            Ok(due) => due,
+            Err(e) => {
+                tracing::error!("Failed to load due number changes: {}", e);
+                return;
+            }
+        };
+
+        for change in due {
+            // Read the language while the user is still on the old number
+            let lang = match user_repo.find_by_phone(&change.old_phone).await {
+                Ok(Some(user)) => user.lang().unwrap_or_else(default_language),
+                _ => default_language(),
+            };
+
+            match repo.complete(&change).await {
+                Ok(true) => {
+                    tracing::info!(reference = %change.reference, from = %change.old_phone, to = %change.new_phone, "Wallet moved to new number");
+                    let moved = tr_with(lang, Msg::NumberChanged, &[&change.new_phone]);
+                    let left = tr_with(lang, Msg::NumberChangedOld, &[&change.new_phone]);
+                    self.notify(&change.new_phone, |_| moved).await;
+                    self.notify(&change.old_phone, |_| left).await;
+                }
+                Ok(false) => {
+                    tracing::warn!(reference = %change.reference, "Number change skipped: already claimed or new number joined");
+                }
+                Err(e) => {
+                    tracing::error!(reference = %change.reference, "Failed to move wallet to new number: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Check payouts still pending at their provider: settle the ledger
+    /// and tell the user once paid, or refund them if the payout failed.
+    /// Called periodically by the scheduler.
+    pub async fn poll_cashouts(&self) {
+        let (Some(ref repo), Some(ref user_repo)) = (&self.cashout_repo, &self.user_repo) else {
+            return;
+        };
+        self.pay_debited_cashouts().await;
+
+        let awaiting = match repo.find_awaiting(CASHOUT_BATCH_SIZE).await {
+            Ok(awaiting) => awaiting,
+            Err(e) => {
+                tracing::error!("Failed to load pending cashouts: {}", e);
+                return;
+            }
+        };
+
+        for cashout in awaiting {
+            let reference = &cashout.reference;
+            let provider = self.cashouts.get(Some(&cashout.provider));
+            let (Some(provider), Some(details)) = (provider, cashout.details()) else {
+                tracing::error!(reference = %reference, provider = %cashout.provider, "Can't check cashout");
+                let _ = repo.touch(reference).await;
+                continue;
+            };
+
+            let step = match cashout.payout_id.clone() {
+                Some(payout_id) => match provider.status(payout_id).await {
+                    Err(e) => PayoutStep::Retry(e.to_string()),
+                    status => PayoutStep::from_result(status),
+                },
+                // The first request got no answer; the same reference is
+                // the same payout, so asking again can't pay twice
+                None => PayoutStep::from_result(provider.payout(PayoutRequest {
+                    reference: reference.clone(),
+                    phone: cashout.user_phone.clone(),
+                    amount: details.payout,
+                }).await),
+            };
+
+            match step {
+                PayoutStep::Paid(id) => {
+                    let _ = repo.set_payout_id(reference, &id).await;
+                    if let Ok(true) = repo.finish(reference, "paid").await {
+                        self.settle_transaction(reference, TxStatus::Completed, None).await;
+                        self.notify(&cashout.user_phone, |lang| {
+                            tr_with(lang, Msg::CashoutPaid, &[&details.payout, &details.currency, &details.provider])
+                        }).await;
+                    }
+                }
+                PayoutStep::Refund(reason) => {
+                    let Ok(Some(user)) = user_repo.find_by_phone(&cashout.user_phone).await else {
+                        tracing::error!(reference = %reference, "Cashout owner not found");
+                        let _ = repo.touch(reference).await;
+                        continue;
+                    };
+                    let lang = user.lang().unwrap_or_else(default_language);
+                    let message = self.refund_cashout(lang, &user, reference, &details, &reason).await;
+                    self.settle_transaction(reference, TxStatus::Failed, None).await;
+                    self.notify(&user.phone, |_| message).await;
+                }
+                PayoutStep::Pending(id) => {
+                    if cashout.payout_id.is_none() {
+                        let _ = repo.set_payout_id(reference, &id).await;
+                    }
+                    let _ = repo.touch(reference).await;
+                }
+                PayoutStep::Retry(reason) => {
+                    tracing::warn!(reference = %reference, "Failed to check payout: {}", reason);
+                    let _ = repo.touch(reference).await;
+                }
+            }
+        }
+    }
+
+    /// Pay out cashouts held for their debit once the backend reports it
+    /// completed, and close those whose debit failed
+    async fn pay_debited_cashouts(&self) {
+        let (Some(ref repo), Some(ref user_repo), Some(ref tx_repo)) = (&self.cashout_repo, &self.user_repo, &self.transaction_repo) else {
+            return;
+        };
+
+        let held = match repo.find_debit_pending(CASHOUT_BATCH_SIZE).await {
+            Ok(held) => held,
+            Err(e) => {
+                tracing::error!("Failed to load cashouts awaiting their debit: {}", e);
+                return;
+            }
+        };
+
+        for cashout in held {
+            let reference = &cashout.reference;
+            let debit = match tx_repo.find_by_reference(&cashout.user_phone, reference).await {
+                Ok(rows) => rows.iter().find(|row| row.reference == *reference).and_then(|row| row.status()),
+                Err(e) => {
+                    tracing::warn!(reference = %reference, "Failed to check cashout debit: {}", e);
+                    None
+                }
+            };
+            let Some(details) = cashout.details() else {
+                tracing::error!(reference = %reference, "Unreadable cashout held for its debit");
+                let _ = repo.touch(reference).await;
+                continue;
+            };
+            match debit {
+                Some(TxStatus::Completed) => {}
+                Some(TxStatus::Failed) => {
+                    if let Ok(true) = repo.end_debit(reference, false).await {
+                        tracing::warn!(reference = %reference, "Cashout debit failed, nothing paid out");
+                        self.notify(&cashout.user_phone, |lang| {
+                            tr_with(lang, Msg::CashoutDebitFailed, &[&details.amount, &details.token])
+                        }).await;
+                    }
+                    continue;
+                }
+                _ => {
+                    let _ = repo.touch(reference).await;
+                    continue;
+                }
+            }
+
+            // Claim it, so only one worker pays it out
+            if !matches!(repo.end_debit(reference, true).await, Ok(true)) {
+                continue;
+            }
+            let Ok(Some(user)) = user_repo.find_by_phone(&cashout.user_phone).await else {
+                tracing::error!(reference = %reference, "Cashout owner not found, payout needs manual follow-up");
+                continue;
+            };
+            let lang = user.lang().unwrap_or_else(default_language);
+            let (message, _) = self.start_payout(lang, &user, reference, &details).await;
+            self.notify(&user.phone, |_| message).await;
+        }
+    }
+
+    /// SMS telling the owner how a scheduled run went
+    fn schedule_run_message(&self, lang: Lang, schedule: &ScheduledPayment, executed: &Executed) -> String {
+        let args: [&dyn std::fmt::Display; 4] = [&schedule.code, &schedule.amount, &schedule.token, &schedule.recipient];
+        match executed.status {
+            TxStatus::Failed if executed.low_balance => tr_with(lang, Msg::ScheduleLowBalance, &args),
+            TxStatus::Failed => tr_with(lang, Msg::ScheduleRunFailed, &args),
+            _ => tr_with(lang, Msg::ScheduleRan, &[&schedule.code, &executed.reply]),
+        }
+    }
+
+    /// Close a paid REQUEST and tell the requester
+    async fn settle_request(&self, payer: &User, code: &str) {
+        let Some(ref repo) = self.payment_request_repo else {
+            return;
+        };
+        match repo.mark_paid(code).await {
+            Ok(Some(request)) => {
+                self.notify(&request.requester_phone, |lang| {
+                    tr_with(lang, Msg::RequestPaid, &[&payer.display_name(), &request.amount, &request.token, &request.code])
+                }).await;
+            }
+            Ok(None) => {}
+            Err(e) => tracing::error!(code = %code, "Failed to mark payment request paid: {}", e),
+        }
+    }
+
+    /// Text another user in their own language; skipped when no SMS client is set
+    async fn notify(&self, phone: &str, message: impl FnOnce(Lang) -> String) {
+        let Some(ref notifier) = self.notifier else {
+            tracing::warn!(to = %phone, "No SMS client configured, notification dropped");
+            return;
+        };
+
+        let lang = match self.user_repo {
+            Some(ref repo) => repo.find_by_phone(phone).await.ok().flatten().and_then(|u| u.lang()),
+            None => None,
+        };
+        let lang = lang.unwrap_or_else(default_language);
+        let body = self.replies.shape(phone, lang, &message(lang));
+
+        if let Err(e) = notifier.send(None, phone, &body).await {
+            tracing::error!(to = %phone, "Failed to send notification: {}", e);
+        }
+    }
+
+    /// Add a row to the ledger; failures are logged, never shown to the user
+    async fn record_transaction(&self, phone: &str, entry: &NewTransaction) {
+        let Some(ref repo) = self.transaction_repo else {
+            return;
+        };
+        if let Err(e) = repo.record(phone, entry).await {
+            tracing::error!(reference = %entry.reference, "Failed to record transaction: {}", e);
+        }
+    }
+
+    /// Mirror a send into the recipient's ledger when they are a registered user
+    async fn record_incoming(&self, sender: &User, recipient_address: &str, entry: &NewTransaction) {
+        let Some(ref user_repo) = self.user_repo else {
+            return;
+        };
+        let Ok(Some(recipient)) = user_repo.find_by_wallet(recipient_address).await else {
+            return;
+        };
+
+        let incoming = NewTransaction {
+            direction: TxDirection::In,
+            counterparty: Some(sender.display_name()),
+            ..entry.clone()
+        };
+        self.record_transaction(&recipient.phone, &incoming).await;
+    }
+
+    /// Move the ledger rows for a reference out of pending
+    async fn settle_transaction(&self, reference: &str, status: TxStatus, tx_hash: Option<&str>) {
+        let Some(ref repo) = self.transaction_repo else {
+            return;
+        };
+        if let Err(e) = repo.update_status(reference, status, tx_hash).await {
+            tracing::error!(reference = %reference, "Failed to update transaction: {}", e);
+        }
+    }
+
+    async fn cancel_response(&self, from: &str, lang: Lang) -> String {
+        let Some(ref pending_repo) = self.pending_repo else {
+            return tr(lang, Msg::DbOffline);
+        };
+
+        match pending_repo.find_open(from).await {
+            Ok(Some(pending)) => match pending_repo.set_status(pending.id, "cancelled").await {
+                Ok(_) => tr_with(lang, Msg::Cancelled, &[&pending.reference]),
+                Err(_) => tr(lang, Msg::TryLater),
+            },
+            Ok(None) => tr(lang, Msg::NothingToCancel),
+            Err(_) => tr(lang, Msg::TryLater),
+        }
+    }
+
+    /// Unexpired dialog for a number, if dialogs are enabled
+    async fn active_conversation(&self, from: &str) -> Option<Conversation> {
+        let repo = self.conversation_repo.as_ref()?;
+        match repo.find_active(from).await {
+            Ok(conversation) => conversation,
+            Err(e) => {
+                tracing::error!("Failed to load conversation: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Save the words collected so far and ask for the next argument.
+    /// Without a database the dialog can't continue, so reply with usage.
+    async fn ask(&self, from: &str, lang: Lang, command: &str, args: &str, prompt: Msg) -> String {
+        let usage = || registry::lookup(command).map(|spec| spec.usage(lang)).unwrap_or_default();
+        let Some(ref repo) = self.conversation_repo else {
+            return usage();
+        };
+
+        match repo.save(from, command, args, lang, self.dialog_ttl).await {
+            Ok(_) => format!(
+                "{}\n{}",
+                tr(lang, prompt),
+                tr_with(lang, Msg::DialogHint, &[&keywords::localized(lang, "NO")])
+            ),
+            Err(e) => {
+                tracing::error!("Failed to save conversation: {}", e);
+                usage()
+            }
+        }
+    }
+
+    /// End the dialog for a number
+    async fn end_dialog(&self, from: &str) {
+        if let Some(ref repo) = self.conversation_repo {
+            if let Err(e) = repo.clear(from).await {
+                tracing::error!("Failed to clear conversation: {}", e);
+            }
+        }
+    }
+
+    /// Add a message to an open dialog and run the command once nothing
+    /// is missing. Returns None when the message is a command of its own,
+    /// which ends the dialog; NO/CANCEL ends it without running anything.
+    async fn continue_dialog(&self, from: &str, lang: Lang, conversation: &Conversation, body: &str) -> Option<String> {
+        let first = body.split_whitespace().next()?;
+        if let Some((spec, _)) = registry::lookup_localized(first) {
+            self.end_dialog(from).await;
+            return (spec.name() == "NO").then(|| tr(lang, Msg::DialogCancelled));
+        }
+
+        let Some(spec) = registry::lookup(&conversation.command) else {
+            self.end_dialog(from).await;
+            return None;
+        };
+        let mut words = conversation.words();
+        words.extend(body.split_whitespace());
+
+        let reply = match spec.parse_args(&words, lang) {
+            Ok(args) => {
+                self.end_dialog(from).await;
+                self.execute(from, lang, (spec.build)(&args)).await
+            }
+            Err(ArgError::Missing(ArgSpec { prompt: Some(prompt), .. })) => {
+                self.ask(from, lang, spec.name(), &words.join(" "), prompt).await
+            }
+            Err(error) => match (error, spec.next_prompt(&conversation.words(), lang)) {
+                // A bad answer is asked for again rather than ending the dialog
+                (ArgError::Invalid(Some(reason)), Some(prompt)) => {
+                    format!("{}\n{}", tr(lang, reason), tr(lang, prompt))
+                }
+                (error, _) => {
+                    self.end_dialog(from).await;
+                    spec.error_text(error, lang)
+                }
+            },
+        };
+        Some(reply)
+    }
+
+    /// Resolve a recipient (wallet address, phone number, ENS name or contact) to an address
+    async fn resolve_recipient(&self, from: &str, lang: Lang, recipient: &str) -> Result<String, String> {
+        let Some(ref user_repo) = self.user_repo else {
+            return Err(tr(lang, Msg::DbOffline));
+        };
+
+        if recipient.starts_with("0x") && recipient.len() == 42 {
+            // Already a wallet address
+            Ok(recipient.to_string())
+        } else if let Some(phone) = PhoneNumber::parse(recipient) {
+            // Phone number, international or local - look up in database
+            match user_repo.find_by_phone(phone.as_str()).await {
+                Ok(Some(u)) => Ok(u.wallet_address),
+                Ok(None) => Err(tr_with(lang, Msg::NotJoined, &[&phone])),
+                Err(_) => Err(tr(lang, Msg::RecipientLookupFailed)),
+            }
+        } else if recipient.contains('.') {
+            // ENS name (e.g., swarnim.ttcip.eth)
+            self.resolve_ens(lang, recipient).await
+        } else {
+            // Try as contact name from address book
+            let invalid = tr(lang, Msg::InvalidRecipient);
+            let Some(ref address_book) = self.address_book_repo else {
+                return Err(invalid);
+            };
+            let contact = match address_book.lookup(from, recipient).await {
+                Ok(ContactMatch::One(contact)) => contact,
+                Ok(ContactMatch::Ambiguous(contacts)) => {
+                    let names: Vec<&str> = contacts.iter().map(|c| c.name.as_str()).collect();
+                    return Err(tr_with(lang, Msg::ContactAmbiguous, &[&recipient, &names.join(", ")]));
+                }
+                _ => return Err(invalid),
+            };
+
+            if let Some(ref addr) = contact.wallet_address {
+                Ok(addr.clone())
+            } else if let Some(ref ens) = contact.ens_name {
+                self.resolve_ens(lang, ens).await
+            } else if let Some(ref phone) = contact.contact_phone {
+                match user_repo.find_by_phone(phone).await {
+                    Ok(Some(u)) => Ok(u.wallet_address),
+                    _ => Err(tr_with(lang, Msg::ContactNoWallet, &[&recipient])),
+                }
+            } else {
+                Err(tr_with(lang, Msg::ContactNoAddress, &[&recipient]))
+            }
+        }
+    }
+
+    /// Resolve an ENS name (e.g. swarnim.ttcip.eth) via the backend
+    async fn resolve_ens(&self, lang: Lang, name: &str) -> Result<String, String> {
+        let client = reqwest::Client::new();
+        let resolve_url = format!("{}/api/ens/resolve/{}", self.backend_url, name);
+        match client.get(&resolve_url).send().await {
+            Ok(resp) => match resp.json::<serde_json::Value>().await {
+                Ok(json) => match json["address"].as_str() {
+                    Some(addr) => Ok(addr.to_string()),
+                    None => Err(tr_with(lang, Msg::EnsUnresolved, &[&name])),
+                },
+                Err(_) => Err(tr_with(lang, Msg::EnsUnresolved, &[&name])),
+            },
+            Err(_) => Err(tr(lang, Msg::EnsNetworkError)),
+        }
+    }
+
+    async fn send_response(&self, from: &str, lang: Lang, amount: Amount, token: &str, recipient: &str) -> String {
+        let user = match self.require_pin_user(from, lang).await {
+            Ok(u) => u,
+            Err(msg) => return msg,
+        };
+
+        let chain = user.chain();
+        let token_upper = match check_send_token(lang, chain, token) {
+            Ok(t) => t,
+            Err(msg) => return msg,
+        };
+        if let Err(msg) = check_amount(lang, amount, &token_upper) {
+            return msg;
+        }
+
+        let recipient_address = match self.resolve_recipient(from, lang, recipient).await {
+            Ok(addr) => addr,
+            Err(msg) => return msg,
+        };
+
+        self.stage_pending(from, lang, PendingAction::Send {
+            amount,
+            token: token_upper,
+            recipient: recipient.to_string(),
+            recipient_address,
+            chain,
+            request: None,
+        }).await.reply
+    }
+
+    async fn schedule_send_response(
+        &self,
+        from: &str,
+        lang: Lang,
+        amount: Amount,
+        token: &str,
+        recipient: &str,
+        clause: &str,
+    ) -> String {
+        let user = match self.require_pin_user(from, lang).await {
+            Ok(u) => u,
+            Err(msg) => return msg,
+        };
+
+        let (recurrence, first_run) = match Recurrence::parse(clause, chrono::Utc::now().date_naive()) {
+            Ok(parsed) => parsed,
+            Err(ScheduleError::Invalid) => return tr(lang, Msg::InvalidSchedule),
+            Err(ScheduleError::Past(date)) => return tr_with(lang, Msg::SchedulePast, &[&date]),
+        };
+
+        let chain = user.chain();
+        let token_upper = match check_send_token(lang, chain, token) {
+            Ok(t) => t,
+            Err(msg) => return msg,
+        };
+        if let Err(msg) = check_amount(lang, amount, &token_upper) {
+            return msg;
+        }
+
+        let recipient_address = match self.resolve_recipient(from, lang, recipient).await {
+            Ok(addr) => addr,
+            Err(msg) => return msg,
+        };
+
+        self.stage_pending(from, lang, PendingAction::Schedule(NewSchedule {
+            amount,
+            token: token_upper,
+            recipient: recipient.to_string(),
+            recipient_address,
+            chain,
+            recurrence,
+            first_run,
+        })).await.reply
+    }
+
+    async fn schedules_response(&self, from: &str, lang: Lang) -> String {
+        let Some(ref repo) = self.schedule_repo else {
+            return tr(lang, Msg::DbOffline);
+        };
+
+        match repo.list_active(from).await {
+            Ok(schedules) if schedules.is_empty() => tr(lang, Msg::NoSchedules),
+            Ok(schedules) => {
+                let lines: Vec<String> = schedules.iter().map(|s| s.to_sms_line(lang)).collect();
+                tr_with(lang, Msg::SchedulesList, &[&lines.join("\n")])
+            }
+            Err(_) => tr(lang, Msg::TryLater),
+        }
+    }
+
+    async fn stop_response(&self, from: &str, lang: Lang, code: &str) -> String {
+        let Some(ref repo) = self.schedule_repo else {
+            return tr(lang, Msg::DbOffline);
+        };
+
+        match repo.cancel(from, code).await {
+            Ok(true) => tr_with(lang, Msg::ScheduleStopped, &[&code]),
+            Ok(false) => tr_with(lang, Msg::ScheduleNotFound, &[&code]),
+            Err(_) => tr(lang, Msg::TryLater),
+        }
+    }
+
+    async fn split_response(&self, from: &str, lang: Lang, amount: Amount, token: &str, recipients: &str) -> String {
+        let user = match self.require_pin_user(from, lang).await {
+            Ok(u) => u,
+            Err(msg) => return msg,
+        };
+
+        let chain = user.chain();
+        let token_upper = match check_send_token(lang, chain, token) {
+            Ok(t) => t,
+            Err(msg) => return msg,
+        };
+        if let Err(msg) = check_amount(lang, amount, &token_upper) {
+            return msg;
+        }
+
+        let Some(parsed) = parse_split_recipients(recipients) else {
+            return tr_with(lang, Msg::InvalidSplit, &[&MAX_SPLIT_RECIPIENTS]);
+        };
+        let weights: Vec<Amount> = parsed.iter().map(|(_, weight)| *weight).collect();
+        let decimals = token_decimals(&token_upper).unwrap_or(0);
+        let amounts = match amount.split(&weights, decimals) {
+            Ok(amounts) => amounts,
+            Err(_) => return tr_with(lang, Msg::SplitTooSmall, &[&amount, &token_upper, &parsed.len()]),
+        };
+
+        let mut shares = Vec::with_capacity(parsed.len());
+        for ((recipient, _), share) in parsed.into_iter().zip(amounts) {
+            let recipient_address = match self.resolve_recipient(from, lang, &recipient).await {
+                Ok(addr) => addr,
+                Err(msg) => return msg,
+            };
+            shares.push(SplitShare { recipient, recipient_address, amount: share });
+        }
+
+        self.stage_pending(from, lang, PendingAction::Split {
+            amount,
+            token: token_upper,
+            chain,
+            shares,
+        }).await.reply
+    }
+
+    async fn request_response(&self, from: &str, lang: Lang, amount: Amount, token: &str, payer: &str) -> String {
+        let Some(ref user_repo) = self.user_repo else {
+            return tr(lang, Msg::DbOffline);
+        };
+        let Some(ref repo) = self.payment_request_repo else {
+            return tr(lang, Msg::DbOffline);
+        };
+
+        let requester = match user_repo.find_by_phone(from).await {
+            Ok(Some(u)) => u,
+            Ok(None) => return tr(lang, Msg::NoWallet),
+            Err(_) => return tr(lang, Msg::TryLater),
+        };
+
+        // The payer sends on the requester's chain
+        let chain = requester.chain();
+        let token_upper = match check_send_token(lang, chain, token) {
+            Ok(t) => t,
+            Err(msg) => return msg,
+        };
+        if let Err(msg) = check_amount(lang, amount, &token_upper) {
+            return msg;
+        }
+
+        // Same resolution as SEND, but the payer must have a wallet here to get the SMS
+        let payer_address = match self.resolve_recipient(from, lang, payer).await {
+            Ok(addr) => addr,
+            Err(msg) => return msg,
+        };
+        let payer_user = match user_repo.find_by_wallet(&payer_address).await {
+            Ok(Some(u)) => u,
+            Ok(None) => return tr_with(lang, Msg::PayerNotJoined, &[&payer]),
+            Err(_) => return tr(lang, Msg::RecipientLookupFailed),
+        };
+        if payer_user.phone == requester.phone {
+            return tr(lang, Msg::RequestSelf);
+        }
+
+        let request = match repo.create(from, &payer_user.phone, amount, &token_upper, chain, self.request_ttl).await {
+            Ok(r) => r,
+            Err(e) => {
+                tracing::error!("Failed to store payment request: {}", e);
+                return tr(lang, Msg::TryLater);
+            }
+        };
+
+        self.notify(&payer_user.phone, |payer_lang| {
+            tr_with(payer_lang, Msg::PaymentRequested, &[
+                &requester.display_name(),
+                &amount,
+                &token_upper,
+                &request.code,
+                &chain.short_code(),
+            ])
+        }).await;
+
+        tr_with(lang, Msg::RequestSent, &[
+            &amount,
+            &token_upper,
+            &payer,
+            &request.code,
+            &self.request_ttl.num_hours().max(1),
+        ])
+    }
+
+    /// Stage a send for a request addressed to the user; with a PIN it is confirmed at once
+    async fn pay_response(&self, from: &str, lang: Lang, code: &str, pin: Option<String>) -> String {
+        let Some(ref user_repo) = self.user_repo else {
+            return tr(lang, Msg::DbOffline);
+        };
+        let Some(ref repo) = self.payment_request_repo else {
+            return tr(lang, Msg::DbOffline);
+        };
+
+        let request = match repo.find_open(from, code).await {
+            Ok(Some(r)) => r,
+            Ok(None) => return tr_with(lang, Msg::RequestNotFound, &[&code]),
+            Err(_) => return tr(lang, Msg::TryLater),
+        };
+        if request.is_expired() {
+            let _ = repo.set_status(request.id, "expired").await;
+            return tr_with(lang, Msg::RequestExpired, &[&request.code]);
+        }
+
+        if let Err(msg) = self.require_pin_user(from, lang).await {
+            return msg;
+        }
+        let (Some(amount), Ok(Some(requester))) = (request.amount(), user_repo.find_by_phone(&request.requester_phone).await) else {
+            tracing::error!(code = %request.code, "Payment request points at a missing requester or bad amount");
+            return tr(lang, Msg::TryLater);
+        };
+
+        let staged = self.stage_pending(from, lang, PendingAction::Send {
+            amount,
+            token: request.token.clone(),
+            recipient: requester.display_name(),
+            recipient_address: requester.wallet_address.clone(),
+            chain: request.chain(),
+            request: Some(request.code.clone()),
+        }).await;
+
+        // Confirm only the op just staged, so a PIN can't confirm an older one instead
+        match (pin, staged.id) {
+            (Some(pin), Some(id)) => self.confirm_response(from, lang, &pin, Some(id)).await,
+            _ => staged.reply,
+        }
+    }
+
+    async fn decline_response(&self, from: &str, lang: Lang, code: &str) -> String {
+        let Some(ref repo) = self.payment_request_repo else {
+            return tr(lang, Msg::DbOffline);
+        };
+
+        let request = match repo.find_open(from, code).await {
+            Ok(Some(r)) => r,
+            Ok(None) => return tr_with(lang, Msg::RequestNotFound, &[&code]),
+            Err(_) => return tr(lang, Msg::TryLater),
+        };
+
+        match repo.set_status(request.id, "declined").await {
+            Ok(true) => {}
+            Ok(false) => return tr_with(lang, Msg::RequestNotFound, &[&code]),
+            Err(_) => return tr(lang, Msg::TryLater),
+        }
+
+        let payer = match self.user_repo {
+            Some(ref user_repo) => user_repo.find_by_phone(from).await.ok().flatten(),
+            None => None,
+        };
+        let payer_name = payer.map(|u| u.display_name()).unwrap_or_else(|| from.to_string());
+        self.notify(&request.requester_phone, |requester_lang| {
+            tr_with(requester_lang, Msg::RequestDeclined, &[&payer_name, &request.amount, &request.token, &request.code])
+        }).await;
+
+        tr_with(lang, Msg::RequestDeclinedAck, &[&request.code])
+    }
+
+    /// Send a confirmed transfer through the backend
+    async fn execute_send(&self, lang: Lang, sender: &User, entry: &NewTransaction, recipient_address: &str) -> Executed {
+        let (reference, amount, token_upper) = (&entry.reference, entry.amount, &entry.token);
+        let recipient = entry.counterparty.as_deref().unwrap_or(recipient_address);
+
+        // Route through Yellow Network for instant finality
+        let client = reqwest::Client::new();
+        let api_url = &format!("{}/api/send-yellow", self.backend_url);
+        
+        tracing::info!("Sending {} {} from {} to {} (via Yellow)", amount, token_upper, sender.wallet_address, recipient_address);
+        
+        let response = match client
+            .post(api_url)
+            .json(&serde_json::json!({
+                "fromAddress": sender.wallet_address,
+                "toAddress": recipient_address,
+                "amount": amount.to_string(),
+                "token": token_upper,
+                "userPhone": sender.phone,
+                "senderKey": sender.encrypted_private_key,
+                "reference": reference
+            }))
+            .timeout(std::time::Duration::from_secs(30))
+            .send()
+            .await
+        {
+            Ok(resp) => resp,
+            Err(e) => {
+                tracing::error!("Failed to call Yellow API: {}", e);
+                return Executed::failed(tr(lang, Msg::NetworkError));
+            }
+        };
+
+        // Parse response; the transfer may still have gone through, so
+        // leave it pending for the backend callback
+        let result: serde_json::Value = match response.json().await {
+            Ok(json) => json,
+            Err(e) => {
+                tracing::error!("Failed to parse API response: {}", e);
+                return Executed::pending(tr(lang, Msg::BadResponse));
+            }
+        };
+
+        if result["success"].as_bool().unwrap_or(false) {
+            let reply = tr_with(lang, Msg::Sending, &[&amount, &token_upper, &recipient, &Chain::default().short_code()]);
+            match result["txHash"].as_str() {
+                Some(tx_hash) => Executed::completed(reply, tx_hash),
+                None => Executed::pending(reply),
+            }
+        } else {
+            let error_msg = result["error"].as_str().unwrap_or("Unknown error");
+            tracing::error!("Transfer failed: {}", error_msg);
+            
+            if error_msg.contains("insufficient") || error_msg.contains("balance") {
+                Executed::low_balance(tr(lang, Msg::InsufficientBalance))
+            } else {
+                Executed::failed(tr(lang, Msg::TransferFailed))
+            }
+        }
+    }
+
+    /// Sign a confirmed transfer with the user's key on a non-default chain
+    async fn execute_chain_send(&self, lang: Lang, sender: &User, entry: &NewTransaction, recipient_address: &str) -> Executed {
+        let (chain, amount, token) = (entry.chain, entry.amount, entry.token.as_str());
+        let (Some(decimals), Ok(to)) = (token_decimals(token), recipient_address.parse()) else {
+            return Executed::failed(tr(lang, Msg::TransferFailed));
+        };
+        let Ok(base_units) = amount.to_base_units(decimals) else {
+            return Executed::failed(tr(lang, Msg::InvalidAmount));
+        };
+
+        tracing::info!("Sending {} {} from {} to {} on {}", amount, token, sender.wallet_address, recipient_address, chain);
+
+        match transfer(self.chain_provider(chain), chain, &sender.encrypted_private_key, to, token, base_units).await {
+            Ok(tx_hash) => {
+                // Broadcast but not yet mined: keep it pending with its hash
+                let tx_hash = format!("{:?}", tx_hash);
+                Executed {
+                    reply: tr_with(lang, Msg::SentOnChain, &[
+                        &amount,
+                        &token,
+                        &recipient_address,
+                        &chain.short_code(),
+                        &tx_hash,
+                    ]),
+                    status: TxStatus::Pending,
+                    tx_hash: Some(tx_hash),
+                    low_balance: false,
+                }
+            }
+            Err(e) => {
+                tracing::error!("Transfer on {} failed: {}", chain, e);
+                if e.contains("insufficient funds") {
+                    Executed::low_balance(tr(lang, Msg::InsufficientBalance))
+                } else {
+                    Executed::failed(tr(lang, Msg::TransferFailed))
+                }
+            }
+        }
+    }
+
+    async fn deposit_response(&self, from: &str, lang: Lang) -> String {
+        let Some(ref repo) = self.user_repo else {
+            return tr(lang, Msg::DbOffline);
+        };
+
+        match repo.find_by_phone(from).await {
+            Ok(Some(user)) => {
+                let deposit_address = if let Some(ref ens) = user.ens_name {
+                    ens.clone()
+                } else {
+                    user.wallet_address.clone()
+                };
+                
+                let chain = user.chain();
+                if chain == Chain::default() {
+                    tr_with(lang, Msg::FundWallet, &[&deposit_address, &chain.short_code()])
+                } else {
+                    tr_with(lang, Msg::FundWalletChain, &[&deposit_address, &chain.short_code()])
+                }
+            }
+            Ok(None) => tr(lang, Msg::NoWallet),
+            Err(_) => tr(lang, Msg::TryLater),
+        }
+    }
+
+    async fn history_response(&self, from: &str, lang: Lang, filter: HistoryFilter, page: u32) -> String {
+        let Some(ref repo) = self.transaction_repo else {
+            return tr(lang, Msg::DbOffline);
+        };
+
+        let total = match repo.count(from, filter).await {
+            Ok(n) => n,
+            Err(e) => {
+                tracing::error!("Failed to count transactions: {}", e);
+                return tr(lang, Msg::TryLater);
+            }
+        };
+        if total == 0 {
+            return tr(lang, Msg::NoTransactions);
+        }
+
+        let pages = (total + HISTORY_PAGE_SIZE - 1) / HISTORY_PAGE_SIZE;
+        if page as i64 > pages {
+            return tr_with(lang, Msg::HistoryNoPage, &[&page, &pages]);
+        }
+
+        let offset = (page as i64 - 1) * HISTORY_PAGE_SIZE;
+        let rows = match repo.page(from, filter, HISTORY_PAGE_SIZE, offset).await {
+            Ok(rows) => rows,
+            Err(e) => {
+                tracing::error!("Failed to load transactions: {}", e);
+                return tr(lang, Msg::TryLater);
+            }
+        };
+
+        let lines: Vec<String> = rows.iter().map(|tx| tx.to_sms_line(lang)).collect();
+        let mut reply = tr_with(lang, Msg::HistoryPage, &[&page, &pages, &lines.join("\n")]);
+        if (page as i64) < pages {
+            let mut next = keywords::localized(lang, "HISTORY").to_string();
+            if let Some(word) = filter.keyword() {
+                next = format!("{} {}", next, word);
+            }
+            next = format!("{} {}", next, page + 1);
+            reply = format!("{}\n{}", reply, tr_with(lang, Msg::HistoryMore, &[&next]));
+        }
+        reply
+    }
+
+    /// Where each leg of an operation stands, with its tx hash once known
+    async fn status_response(&self, from: &str, lang: Lang, reference: &str) -> String {
+        let Some(ref repo) = self.transaction_repo else {
+            return tr(lang, Msg::DbOffline);
+        };
+
+        let rows = match repo.find_by_reference(from, reference).await {
+            Ok(rows) => rows,
+            Err(e) => {
+                tracing::error!("Failed to look up transaction: {}", e);
+                return tr(lang, Msg::TryLater);
+            }
+        };
+        if rows.is_empty() {
+            return tr_with(lang, Msg::StatusNotFound, &[&reference]);
+        }
+
+        let lines: Vec<String> = rows
+            .iter()
+            .map(|tx| {
+                let status = match tx.status() {
+                    Some(TxStatus::Completed) => tr(lang, Msg::StatusCompleted),
+                    Some(TxStatus::Failed) => tr(lang, Msg::StatusFailed),
+                    _ => tr(lang, Msg::StatusPending),
+                };
+                let mut line = format!("{}\n{}", tr_with(lang, Msg::TxStatusLine, &[&tx.reference, &status]), tx.describe(lang));
+                if let Some(ref hash) = tx.tx_hash {
+                    line = format!("{}\n{}", line, tr_with(lang, Msg::TxHash, &[hash]));
+                }
+                line
+            })
+            .collect();
+        lines.join("\n")
+    }
+
+    async fn redeem_response(&self, from: &str, lang: Lang, code: &str) -> String {
+        // Check if user has wallet
+        let Some(ref user_repo) = self.user_repo else {
+            return tr(lang, Msg::DbOffline);
+        };
+
+        // Get user's wallet address
+        let user = match user_repo.find_by_phone(from).await {
+            Ok(Some(user)) => user,
+            Ok(None) => return tr(lang, Msg::NoWallet),
+            Err(_) => return tr(lang, Msg::TryLater),
+        };
+
+        // Call Contract API to redeem voucher on-chain
+        let client = reqwest::Client::new();
+        let api_url = &format!("{}/api/redeem", self.backend_url);
+        
+        tracing::info!("Calling Contract API to redeem voucher: {}", code);
         
         let response = match client
             .post(api_url)
@@ -678,7 +2059,7 @@ impl CommandProcessor {
             Ok(resp) => resp,
             Err(e) => {
                 tracing::error!("Failed to call Contract API: {}", e);
-                return "Network error. Try later.".to_string();
+                return tr(lang, Msg::NetworkError);
             }
         };
 
@@ -687,7 +2068,7 @@ impl CommandProcessor {
             Ok(json) => json,
             Err(e) => {
                 tracing::error!("Failed to parse API response: {}", e);
-                return "Error processing response.".to_string();
+                return tr(lang, Msg::BadResponse);
             }
         };
 
@@ -696,37 +2077,441 @@ impl CommandProcessor {
             let tx_hash = result["txHash"].as_str().unwrap_or("");
             
             tracing::info!("Voucher redeemed successfully: {} ETH, tx: {}", eth_amount, tx_hash);
+
+            if let Ok(amount) = Amount::parse(eth_amount) {
+                self.record_transaction(from, &NewTransaction {
+                    reference: generate_reference(),
+                    kind: TxKind::Redeem,
+                    direction: TxDirection::In,
+                    counterparty: Some(code.to_uppercase()),
+                    amount,
+                    token: "ETH".to_string(),
+                    chain: Chain::default(),
+                    status: TxStatus::Completed,
+                    tx_hash: (!tx_hash.is_empty()).then(|| tx_hash.to_string()),
+                }).await;
+            }
             
-            format!(
-                "Voucher redeemed!\n\n{} ETH credited.\n\nReply BALANCE to check.",
-                eth_amount
-            )
+            tr_with(lang, Msg::VoucherRedeemed, &[&eth_amount])
         } else {
             let error_msg = result["error"].as_str().unwrap_or("Unknown error");
             tracing::error!("Redemption failed: {}", error_msg);
             
             if error_msg.contains("already redeemed") || error_msg.contains("AlreadyRedeemed") {
-                "Voucher already used.".to_string()
+                tr(lang, Msg::VoucherUsed)
             } else if error_msg.contains("not found") || error_msg.contains("invalid") {
-                "Invalid voucher code.".to_string()
+                tr(lang, Msg::VoucherInvalid)
             } else {
-                "Redemption failed. Try later.".to_string()
+                tr(lang, Msg::RedeemFailed)
             }
         }
     }
 
-    async fn swap_response(&self, from: &str, amount: f64, token: &str) -> String {
-        // Check if user has wallet
-        let Some(ref user_repo) = self.user_repo else {
-            return "DB offline. Try later.".to_string();
+    async fn swap_response(&self, from: &str, lang: Lang, amount: Amount, token: &str) -> String {
+        if let Err(msg) = check_amount(lang, amount, token) {
+            return msg;
+        }
+        let user = match self.require_pin_user(from, lang).await {
+            Ok(u) => u,
+            Err(msg) => return msg,
         };
 
-        // Get user's wallet address
+        // The swap pool only exists on the default chain
+        let chain = user.chain();
+        if chain != Chain::default() {
+            let home = Chain::default();
+            return tr_with(lang, Msg::SwapUnsupportedChain, &[&chain.short_code(), &home.short_code(), &home.key()]);
+        }
+
+        // Show the expected output; confirming checks it hasn't moved
+        let Some(quote) = self.swap_quote(amount).await else {
+            return tr(lang, Msg::QuoteFailed);
+        };
+
+        self.stage_pending(from, lang, PendingAction::Swap {
+            amount,
+            token: token.to_string(),
+            quoted_out: Some(quote.amount_out),
+        }).await.reply
+    }
+
+    async fn quote_response(&self, from: &str, lang: Lang, amount: Amount, token: &str) -> String {
+        if let Err(msg) = check_amount(lang, amount, token) {
+            return msg;
+        }
+        let Some(quote) = self.swap_quote(amount).await else {
+            return tr(lang, Msg::QuoteFailed);
+        };
+
+        let slippage = match self.user_repo {
+            Some(ref repo) => repo.find_by_phone(from).await.ok().flatten().map_or(Slippage::DEFAULT, |u| u.slippage()),
+            None => Slippage::DEFAULT,
+        };
+        format!(
+            "{}\n{}",
+            tr_with(lang, Msg::Quote, &[&amount, &token, &quote.amount_out, &quote.price_impact]),
+            tr_with(lang, Msg::QuoteMin, &[&slippage.min_out(quote.amount_out), &slippage]),
+        )
+    }
+
+    async fn slippage_response(&self, from: &str, lang: Lang, percent: Option<String>) -> String {
+        let Some(ref repo) = self.user_repo else {
+            return tr(lang, Msg::DbOffline);
+        };
+
+        let Some(percent) = percent else {
+            return match repo.find_by_phone(from).await {
+                Ok(Some(user)) => tr_with(lang, Msg::SlippageCurrent, &[&user.slippage()]),
+                Ok(None) => tr(lang, Msg::NoWallet),
+                Err(_) => tr(lang, Msg::TryLater),
+            };
+        };
+        let Some(slippage) = Slippage::parse(&percent) else {
+            return tr(lang, Msg::SlippageInvalid);
+        };
+
+        match repo.update_slippage(from, slippage).await {
+            Ok(true) => tr_with(lang, Msg::SlippageSet, &[&slippage]),
+            Ok(false) => tr(lang, Msg::NoWallet),
+            Err(e) => {
+                tracing::error!("Failed to save slippage: {}", e);
+                tr(lang, Msg::TryLater)
+            }
+        }
+    }
+
+    /// Refuse a payment that would break the sender's spending limits.
+    ///
+    /// Daily and weekly limits are rolling windows over the ledger; failed
+    /// rows don't count.
+    async fn check_limits(&self, from: &str, lang: Lang, amount: Amount, token: &str) -> Result<(), String> {
+        let (Some(ref repo), Some(ref ledger)) = (&self.limit_repo, &self.transaction_repo) else {
+            return Ok(());
+        };
+        let token = token.to_uppercase();
+        let now = chrono::Utc::now();
+
+        let rows = repo.find(from, &token).await.map_err(|e| {
+            tracing::error!("Failed to load spending limits: {}", e);
+            tr(lang, Msg::TryLater)
+        })?;
+        let (limits, _) = user_limits(&token, &rows, now);
+
+        if amount > limits.per_tx {
+            return Err(tr_with(lang, Msg::LimitPerTxExceeded, &[&limits.per_tx, &token]));
+        }
+        for (period, msg) in [(LimitPeriod::Daily, Msg::LimitDailyExceeded), (LimitPeriod::Weekly, Msg::LimitWeeklyExceeded)] {
+            let Some(window) = period.window() else { continue };
+            let spent = ledger.spent_since(from, &token, now - window).await.map_err(|e| {
+                tracing::error!("Failed to total recent spending: {}", e);
+                tr(lang, Msg::TryLater)
+            })?;
+            let limit = limits.get(period);
+            if spent.checked_add(amount).is_none_or(|total| total > limit) {
+                return Err(tr_with(lang, msg, &[&limit, &token, &limit.saturating_sub(spent)]));
+            }
+        }
+        Ok(())
+    }
+
+    /// Show a token's limits, what was spent against them and any raise
+    /// still cooling off
+    async fn limits_response(&self, from: &str, lang: Lang, token: &str) -> String {
+        let (Some(ref repo), Some(ref ledger)) = (&self.limit_repo, &self.transaction_repo) else {
+            return tr(lang, Msg::DbOffline);
+        };
+        let now = chrono::Utc::now();
+
+        let rows = match repo.find(from, token).await {
+            Ok(rows) => rows,
+            Err(_) => return tr(lang, Msg::TryLater),
+        };
+        let (limits, waiting) = user_limits(token, &rows, now);
+
+        let day = ledger.spent_since(from, token, now - chrono::Duration::hours(24)).await;
+        let week = ledger.spent_since(from, token, now - chrono::Duration::days(7)).await;
+        let (Ok(day), Ok(week)) = (day, week) else {
+            return tr(lang, Msg::TryLater);
+        };
+
+        let mut lines = vec![tr_with(lang, Msg::LimitsShow, &[&token, &limits.per_tx, &limits.daily, &day, &limits.weekly, &week])];
+        for raise in waiting {
+            let when = raise.from.format("%Y-%m-%d %H:%M UTC").to_string();
+            lines.push(tr_with(lang, Msg::LimitRaiseWaiting, &[&raise.period.label(lang), &raise.amount, &token, &when]));
+        }
+        lines.join("\n")
+    }
+
+    /// Lower a limit at once, or raise it after the cooling-off period
+    /// once the PIN checks out
+    async fn set_limit_response(
+        &self,
+        from: &str,
+        lang: Lang,
+        token: &str,
+        period: LimitPeriod,
+        amount: Amount,
+        pin: Option<String>,
+    ) -> String {
+        let (Some(ref repo), Some(ref user_repo)) = (&self.limit_repo, &self.user_repo) else {
+            return tr(lang, Msg::DbOffline);
+        };
+        if let Err(msg) = check_amount(lang, amount, token) {
+            return msg;
+        }
+
         let user = match user_repo.find_by_phone(from).await {
-            Ok(Some(user)) => user,
-            Ok(None) => { return "No wallet. Reply JOIN first.".to_string(); },
-            Err(_) => { return "Error. Try later.".to_string(); },
+            Ok(Some(u)) => u,
+            Ok(None) => return tr(lang, Msg::NoWallet),
+            Err(_) => return tr(lang, Msg::TryLater),
+        };
+        let rows = match repo.find(from, token).await {
+            Ok(rows) => rows,
+            Err(_) => return tr(lang, Msg::TryLater),
+        };
+        let now = chrono::Utc::now();
+        let (limits, waiting) = user_limits(token, &rows, now);
+        let current = limits.get(period);
+        let label = period.label(lang);
+
+        let raising = waiting.iter().any(|raise| raise.period == period);
+        if amount == current && !raising {
+            return tr_with(lang, Msg::LimitUnchanged, &[&label, &current, &token]);
+        }
+
+        // Lowering takes effect at once and drops any raise in waiting
+        if amount <= current {
+            return match repo.lower(from, token, period, amount).await {
+                Ok(()) => tr_with(lang, Msg::LimitLowered, &[&label, &amount, &token]),
+                Err(e) => {
+                    tracing::error!("Failed to lower spending limit: {}", e);
+                    tr(lang, Msg::TryLater)
+                }
+            };
+        }
+
+        let Some(pin) = pin else {
+            let keyword = keywords::localized(lang, "LIMIT");
+            let example = format!("{} {} {} {} <PIN>", keyword, token, period.keyword(), amount);
+            return tr_with(lang, Msg::LimitRaiseNeedsPin, &[&example]);
+        };
+        if user.pin_hash.is_none() {
+            return tr(lang, Msg::PinRequired);
+        }
+        match self.check_pin(&user, &pin).await {
+            Ok(()) => {}
+            Err(PinRefused::Wrong(_)) => return tr(lang, Msg::LimitWrongPin),
+            Err(refused) => return refused.reply(lang),
+        }
+
+        let applies = now + self.limit_cooldown;
+        match repo.raise(from, token, period, current, (amount, applies)).await {
+            Ok(()) => {
+                tracing::info!(from = %from, token = %token, period = %period, "Spending limit raise scheduled");
+                let when = applies.format("%Y-%m-%d %H:%M UTC").to_string();
+                tr_with(lang, Msg::LimitRaiseScheduled, &[&label, &amount, &token, &when])
+            }
+            Err(e) => {
+                tracing::error!("Failed to schedule spending limit raise: {}", e);
+                tr(lang, Msg::TryLater)
+            }
+        }
+    }
+
+    /// Start moving the sender's wallet to another number
+    async fn new_number_response(&self, from: &str, lang: Lang, phone: &str) -> String {
+        match self.start_phone_change(from, phone, "user", lang).await {
+            Ok(change) => tr_with(lang, Msg::NumberCodeSent, &[&change.new_phone, &self.phone_code_ttl.num_minutes().max(1)]),
+            Err(msg) => msg,
+        }
+    }
+
+    /// Open a move from `old_phone` to `new_phone` and text the code to the
+    /// new number. Also used by the admin API when the old SIM is lost, so
+    /// the PIN is only checked once the new number answers.
+    pub async fn start_phone_change(
+        &self,
+        old_phone: &str,
+        new_phone: &str,
+        requested_by: &str,
+        lang: Lang,
+    ) -> Result<PhoneChange, String> {
+        let (Some(ref repo), Some(ref user_repo)) = (&self.phone_change_repo, &self.user_repo) else {
+            return Err(tr(lang, Msg::DbOffline));
+        };
+        let Some(ContactTarget::Phone(new_phone)) = ContactTarget::detect(new_phone) else {
+            return Err(tr(lang, Msg::NumberInvalid));
+        };
+        let old_phone = normalize_phone(old_phone);
+        if new_phone == old_phone {
+            return Err(tr(lang, Msg::NumberSame));
+        }
+
+        let user = match user_repo.find_by_phone(&old_phone).await {
+            Ok(Some(u)) if u.pin_hash.is_some() => u,
+            Ok(Some(_)) => return Err(tr(lang, Msg::PinRequired)),
+            Ok(None) => return Err(tr(lang, Msg::NoWallet)),
+            Err(_) => return Err(tr(lang, Msg::TryLater)),
+        };
+        match user_repo.exists(&new_phone).await {
+            Ok(false) => {}
+            Ok(true) => return Err(tr_with(lang, Msg::NumberTaken, &[&new_phone])),
+            Err(_) => return Err(tr(lang, Msg::TryLater)),
+        }
+        let Some(ref notifier) = self.notifier else {
+            tracing::warn!("No SMS client configured, can't send a number change code");
+            return Err(tr(lang, Msg::TryLater));
+        };
+
+        let code = generate_phone_code();
+        let change = repo.create(&old_phone, &new_phone, &code, requested_by, self.phone_code_ttl).await.map_err(|e| {
+            tracing::error!("Failed to store number change: {}", e);
+            tr(lang, Msg::TryLater)
+        })?;
+
+        let user_lang = user.lang().unwrap_or(lang);
+        let minutes = self.phone_code_ttl.num_minutes().max(1);
+        let body = tr_with(user_lang, Msg::NumberCode, &[&code, &old_phone, &minutes]);
+        if let Err(e) = notifier.send(None, &new_phone, &body).await {
+            tracing::error!(to = %new_phone, "Failed to send number change code: {}", e);
+            let _ = repo.cancel(change.id).await;
+            return Err(tr(lang, Msg::TryLater));
+        }
+
+        tracing::info!(reference = %change.reference, from = %old_phone, to = %new_phone, by = %requested_by, "Number change started");
+        Ok(change)
+    }
+
+    /// Check the code and PIN sent from the new number, then start the
+    /// waiting period and warn the old number
+    async fn verify_number_response(&self, from: &str, lang: Lang, code: &str, pin: &str) -> String {
+        let (Some(ref repo), Some(ref user_repo)) = (&self.phone_change_repo, &self.user_repo) else {
+            return tr(lang, Msg::DbOffline);
+        };
+
+        let change = match repo.find_open(from).await {
+            Ok(Some(c)) if c.new_phone == from && c.awaiting_code() => c,
+            Ok(_) => return tr(lang, Msg::NumberNoChange),
+            Err(_) => return tr(lang, Msg::TryLater),
+        };
+        if change.is_code_expired() {
+            let _ = repo.cancel(change.id).await;
+            return tr(lang, Msg::NumberCodeExpired);
+        }
+
+        let user = match user_repo.find_by_phone(&change.old_phone).await {
+            Ok(Some(u)) => u,
+            Ok(None) => return tr(lang, Msg::NoWallet),
+            Err(_) => return tr(lang, Msg::TryLater),
+        };
+
+        // A wrong PIN counts against the wallet's owner like any other
+        let pin_ok = match self.check_pin(&user, pin).await {
+            Ok(()) => true,
+            Err(PinRefused::Wrong(_)) => false,
+            Err(refused) => return refused.reply(lang),
+        };
+        if !change.verify_code(code) || !pin_ok {
+            let attempts = repo.record_failed_attempt(change.id).await.unwrap_or(MAX_PIN_ATTEMPTS);
+            if attempts >= MAX_PIN_ATTEMPTS {
+                let _ = repo.cancel(change.id).await;
+                tracing::warn!(reference = %change.reference, to = %from, "Number change cancelled after wrong codes");
+                return tr(lang, Msg::NumberTooManyTries);
+            }
+            return tr_with(lang, Msg::NumberWrongCode, &[&(MAX_PIN_ATTEMPTS - attempts)]);
+        }
+
+        let effective_at = chrono::Utc::now() + self.phone_change_wait;
+        match repo.verify(change.id, effective_at).await {
+            Ok(true) => {}
+            Ok(false) => return tr(lang, Msg::NumberNoChange),
+            Err(_) => return tr(lang, Msg::TryLater),
+        }
+
+        let when = effective_at.format("%Y-%m-%d %H:%M UTC").to_string();
+        self.notify(&change.old_phone, |old_lang| {
+            tr_with(old_lang, Msg::NumberChangeWarning, &[&change.new_phone, &when])
+        }).await;
+        tr_with(lang, Msg::NumberVerified, &[&when])
+    }
+
+    /// Stop an open number change from either number
+    async fn cancel_number_change_response(&self, from: &str, lang: Lang) -> String {
+        let Some(ref repo) = self.phone_change_repo else {
+            return tr(lang, Msg::DbOffline);
+        };
+
+        let change = match repo.find_open(from).await {
+            Ok(Some(c)) => c,
+            Ok(None) => return tr(lang, Msg::NumberNoChange),
+            Err(_) => return tr(lang, Msg::TryLater),
+        };
+        match repo.cancel(change.id).await {
+            Ok(true) => {}
+            Ok(false) => return tr(lang, Msg::NumberNoChange),
+            Err(_) => return tr(lang, Msg::TryLater),
+        }
+
+        tracing::info!(reference = %change.reference, by = %from, "Number change cancelled");
+        let other = if change.old_phone == from { &change.new_phone } else { &change.old_phone };
+        self.notify(other, |other_lang| tr(other_lang, Msg::NumberChangeCancelled)).await;
+        tr(lang, Msg::NumberChangeCancelled)
+    }
+
+    /// Quote a TXTC -> ETH swap from the backend, with price impact
+    /// measured against a tiny reference trade
+    async fn swap_quote(&self, amount: Amount) -> Option<SwapQuote> {
+        let reference = quote_reference();
+        let amount_out = self.quote_output(amount).await?;
+        let reference_out = self.quote_output(reference).await?;
+        Some(SwapQuote::new(amount, amount_out, reference, reference_out))
+    }
+
+    /// ETH the pool would give for `amount` TXTC right now
+    async fn quote_output(&self, amount: Amount) -> Option<Amount> {
+        let response = reqwest::Client::new()
+            .post(format!("{}/api/quote", self.backend_url))
+            .json(&serde_json::json!({
+                "amount": amount.to_string(),
+                "isTokenToEth": true
+            }))
+            .timeout(std::time::Duration::from_secs(5))
+            .send()
+            .await;
+
+        let result = match response {
+            Ok(resp) => resp.json::<serde_json::Value>().await.ok()?,
+            Err(e) => {
+                tracing::error!("Quote API error: {}", e);
+                return None;
+            }
+        };
+        if !result["success"].as_bool().unwrap_or(false) {
+            return None;
+        }
+        // The backend reports "0" when the pool can't be read
+        result["outputAmount"].as_str().and_then(|out| Amount::parse(out).ok())
+    }
+
+    /// Start a confirmed swap through the backend.
+    ///
+    /// A fresh quote sets `minEthOut` from the user's slippage tolerance;
+    /// if it has fallen beyond that tolerance of the quote shown when the
+    /// swap was staged, the swap is refused.
+    async fn execute_swap(&self, lang: Lang, user: &User, entry: &NewTransaction, quoted_out: Option<Amount>) -> Executed {
+        let (reference, amount, token) = (&entry.reference, entry.amount, &entry.token);
+
+        let Some(quote) = self.swap_quote(amount).await else {
+            return Executed::failed(tr(lang, Msg::QuoteFailed));
         };
+        let slippage = user.slippage();
+        if let Some(shown) = quoted_out {
+            if !slippage.accepts(shown, quote.amount_out) {
+                tracing::info!(reference = %reference, shown = %shown, fresh = %quote.amount_out, "Swap quote moved");
+                return Executed::failed(tr_with(lang, Msg::QuoteMoved, &[&shown, &quote.amount_out, &slippage]));
+            }
+        }
+        let min_eth_out = slippage.min_out(quote.amount_out);
 
         // Call Contract API to swap tokens (async - don't wait for completion)
         let client = reqwest::Client::new();
@@ -735,149 +2520,593 @@ impl CommandProcessor {
         tracing::info!("Initiating swap of {} {} for user {}", amount, token, user.wallet_address);
         
         // Send request with user phone for SMS notification
-        let _response = client
+        let response = client
             .post(api_url)
             .json(&serde_json::json!({
                 "userAddress": user.wallet_address,
                 "tokenAmount": amount.to_string(),
-                "minEthOut": "0",
-                "userPhone": from
+                "minEthOut": min_eth_out.to_string(),
+                "userPhone": user.phone,
+                "reference": reference
             }))
             .timeout(std::time::Duration::from_secs(2))
             .send()
             .await;
 
-        // Respond immediately - don't wait for swap to complete
-        // Backend will send SMS notification when swap completes
-        format!(
-            "Swapping {} {}...\n\nYou'll get an SMS when complete.\n\nThis may take 30 seconds.",
-            amount, token
-        )
+        // Don't wait long for the swap to complete: on a timeout the
+        // backend sends an SMS when it's done and settles the ledger row
+        // through the transaction callback
+        let reply = tr_with(lang, Msg::Swapping, &[&amount, &token, &Chain::default().short_code(), &min_eth_out]);
+        let resp = match response {
+            Ok(resp) => resp,
+            Err(e) if e.is_timeout() => return Executed::pending(reply),
+            Err(e) => {
+                tracing::error!("Swap API error: {}", e);
+                return Executed::failed(tr(lang, Msg::NetworkError));
+            }
+        };
+
+        let Ok(result) = resp.json::<serde_json::Value>().await else {
+            return Executed::pending(reply);
+        };
+        if result["success"].as_bool().unwrap_or(false) {
+            match result["txHash"].as_str() {
+                Some(tx_hash) => Executed::completed(reply, tx_hash),
+                None => Executed::pending(reply),
+            }
+        } else {
+            let err = result["error"].as_str().unwrap_or("Unknown error");
+            tracing::error!(reference = %reference, "Swap failed: {}", err);
+            Executed::failed(tr_with(lang, Msg::SwapFailed, &[&err]))
+        }
+    }
+
+    async fn bridge_response(&self, from: &str, lang: Lang, amount: Amount, token: &str, from_chain: &str, to_chain: &str) -> String {
+        if let Err(msg) = check_amount(lang, amount, token) {
+            return msg;
+        }
+        if let Err(msg) = self.require_pin_user(from, lang).await {
+            return msg;
+        }
+
+        self.stage_pending(from, lang, PendingAction::Bridge {
+            amount,
+            token: token.to_string(),
+            from_chain: from_chain.to_string(),
+            to_chain: to_chain.to_string(),
+        }).await.reply
+    }
+
+    /// Start a confirmed bridge through the backend
+    async fn execute_bridge(&self, lang: Lang, user: &User, entry: &NewTransaction, from_chain: &str, to_chain: &str) -> Executed {
+        let (reference, amount, token) = (&entry.reference, entry.amount, &entry.token);
+
+        let client = reqwest::Client::new();
+
+        tracing::info!(
+            "Bridge: {} {} from {} to {} for {}",
+            amount, token, from_chain, to_chain, user.wallet_address
+        );
+
+        let response = client
+            .post(format!("{}/api/bridge", self.backend_url))
+            .json(&serde_json::json!({
+                "fromChain": from_chain.to_lowercase(),
+                "toChain": to_chain.to_lowercase(),
+                "fromToken": token,
+                "toToken": token,
+                "amount": amount.to_string(),
+                "userAddress": user.wallet_address,
+                "userPhone": user.phone,
+                "reference": reference
+            }))
+            .timeout(std::time::Duration::from_secs(5))
+            .send()
+            .await;
+
+        match response {
+            Ok(resp) => {
+                if let Ok(result) = resp.json::<serde_json::Value>().await {
+                    if result["success"].as_bool().unwrap_or(false) {
+                        let route = result["route"].as_str().unwrap_or("");
+                        Executed::pending(tr_with(lang, Msg::BridgeStarted, &[&route]))
+                    } else {
+                        let err = result["error"].as_str().unwrap_or("Unknown error");
+                        Executed::failed(tr_with(lang, Msg::BridgeFailed, &[&err]))
+                    }
+                } else {
+                    Executed::pending(tr(lang, Msg::BridgeInitiated))
+                }
+            }
+            Err(e) => {
+                tracing::error!("Bridge API error: {}", e);
+                Executed::failed(tr(lang, Msg::BridgeUnavailable))
+            }
+        }
+    }
+
+    async fn cashout_response(&self, from: &str, lang: Lang, amount: Amount, token: &str, method: Option<String>) -> String {
+        if let Err(msg) = check_amount(lang, amount, token) {
+            return msg;
+        }
+        if let Err(msg) = self.require_pin_user(from, lang).await {
+            return msg;
+        }
+        if self.cashouts.treasury().is_none() {
+            return tr(lang, Msg::CashoutUnavailable);
+        }
+        let Some(provider) = self.cashouts.get(method.as_deref()) else {
+            return tr_with(lang, Msg::CashoutUnknownMethod, &[&method.unwrap_or_default(), &self.cashouts.keys()]);
+        };
+
+        let quote = match provider.quote(amount, token).await {
+            Ok(quote) => quote,
+            Err(CashoutError::UnsupportedToken(token)) => return tr_with(lang, Msg::CashoutUnsupportedToken, &[&token]),
+            Err(e) => {
+                tracing::error!(provider = %provider.key(), "Cashout quote failed: {}", e);
+                return tr(lang, Msg::CashoutQuoteFailed);
+            }
+        };
+
+        self.stage_pending(from, lang, PendingAction::Cashout(NewCashout {
+            amount,
+            token: token.to_string(),
+            provider: provider.key().to_string(),
+            payout: quote.payout,
+            currency: provider.currency().to_string(),
+        })).await.reply
+    }
+
+    /// Move a confirmed cashout's tokens to the treasury, then have the
+    /// provider pay out. A payout the provider refuses is refunded.
+    async fn execute_cashout(&self, lang: Lang, user: &User, entry: &NewTransaction, cashout: NewCashout) -> Executed {
+        let reference = &entry.reference;
+        let (Some(treasury), Some(_)) = (self.cashouts.treasury(), self.cashouts.get(Some(&cashout.provider))) else {
+            return Executed::failed(tr(lang, Msg::CashoutUnavailable));
+        };
+        let Some(ref repo) = self.cashout_repo else {
+            return Executed::failed(tr(lang, Msg::DbOffline));
+        };
+        if let Err(e) = repo.create(&user.phone, reference, &cashout).await {
+            tracing::error!(reference = %reference, "Failed to store cashout: {}", e);
+            return Executed::failed(tr(lang, Msg::TryLater));
+        }
+
+        // Nothing is paid out unless the tokens left the user's wallet
+        tracing::info!(reference = %reference, provider = %cashout.provider, "Cashing out {} {}", cashout.amount, cashout.token);
+        let debit = self.execute_send(lang, user, entry, treasury).await;
+        match debit.status {
+            TxStatus::Completed => {}
+            TxStatus::Failed => {
+                let _ = repo.finish(reference, "failed").await;
+                return debit;
+            }
+            TxStatus::Pending => {
+                // Paid out by poll_cashouts once the backend reports the debit
+                if let Err(e) = repo.hold_for_debit(reference).await {
+                    tracing::error!(reference = %reference, "Failed to hold cashout for its debit: {}", e);
+                }
+                let reply = tr_with(lang, Msg::CashoutDebitPending, &[&cashout.payout, &cashout.currency, &cashout.provider]);
+                return Executed::pending(reply);
+            }
+        }
+
+        let (reply, status) = self.start_payout(lang, user, reference, &cashout).await;
+        Executed { reply, status, tx_hash: debit.tx_hash, low_balance: false }
+    }
+
+    /// Ask the provider to pay out a debited cashout; refunded if it refuses
+    async fn start_payout(&self, lang: Lang, user: &User, reference: &str, cashout: &NewCashout) -> (String, TxStatus) {
+        let (Some(provider), Some(ref repo)) = (self.cashouts.get(Some(&cashout.provider)), &self.cashout_repo) else {
+            let reason = format!("provider {} unavailable", cashout.provider);
+            return (self.refund_cashout(lang, user, reference, cashout, &reason).await, TxStatus::Failed);
+        };
+
+        let request = PayoutRequest {
+            reference: reference.to_string(),
+            phone: user.phone.clone(),
+            amount: cashout.payout,
+        };
+        let paying = |msg| tr_with(lang, msg, &[&cashout.payout, &cashout.currency, &cashout.provider]);
+        match PayoutStep::from_result(provider.payout(request).await) {
+            PayoutStep::Paid(id) => {
+                let _ = repo.set_payout_id(reference, &id).await;
+                let _ = repo.finish(reference, "paid").await;
+                (paying(Msg::CashoutPaid), TxStatus::Completed)
+            }
+            PayoutStep::Pending(id) => {
+                // Polled by the scheduler until the provider settles it
+                if let Err(e) = repo.set_payout_id(reference, &id).await {
+                    tracing::error!(reference = %reference, "Failed to store payout id: {}", e);
+                }
+                (paying(Msg::CashoutStarted), TxStatus::Pending)
+            }
+            PayoutStep::Retry(reason) => {
+                // Left pending; the scheduler asks again with the same reference
+                tracing::warn!(reference = %reference, "No answer to payout, retrying later: {}", reason);
+                (paying(Msg::CashoutStarted), TxStatus::Pending)
+            }
+            PayoutStep::Refund(reason) => {
+                (self.refund_cashout(lang, user, reference, cashout, &reason).await, TxStatus::Failed)
+            }
+        }
+    }
+
+    /// Send a failed cashout's tokens back from the treasury through the
+    /// backend, and return the message for the user
+    async fn refund_cashout(&self, lang: Lang, user: &User, reference: &str, cashout: &NewCashout, reason: &str) -> String {
+        // Claim the refund so it is only ever paid once
+        if let Some(ref repo) = self.cashout_repo {
+            if !matches!(repo.start_refund(reference).await, Ok(true)) {
+                return tr_with(lang, Msg::CashoutRefundFailed, &[&reference]);
+            }
+        }
+        tracing::warn!(reference = %reference, provider = %cashout.provider, "Cashout failed, refunding: {}", reason);
+
+        let refund_reference = format!("{}-R", reference);
+        let response = reqwest::Client::new()
+            .post(format!("{}/api/refund", self.backend_url))
+            .json(&serde_json::json!({
+                "toAddress": user.wallet_address,
+                "amount": cashout.amount.to_string(),
+                "token": cashout.token,
+                "userPhone": user.phone,
+                "reference": refund_reference
+            }))
+            .timeout(std::time::Duration::from_secs(30))
+            .send()
+            .await;
+
+        let result = match response {
+            Ok(resp) => resp.json::<serde_json::Value>().await.ok(),
+            Err(e) => {
+                tracing::error!("Refund API error: {}", e);
+                None
+            }
+        };
+        let refunded = result.as_ref().is_some_and(|r| r["success"].as_bool().unwrap_or(false));
+        if let Some(ref repo) = self.cashout_repo {
+            if let Err(e) = repo.end_refund(reference, refunded).await {
+                tracing::error!(reference = %reference, "Failed to record refund: {}", e);
+            }
+        }
+        if !refunded {
+            tracing::error!(reference = %reference, "Cashout refund failed, needs manual follow-up");
+            return tr_with(lang, Msg::CashoutRefundFailed, &[&reference]);
+        }
+
+        self.record_transaction(&user.phone, &NewTransaction {
+            reference: refund_reference,
+            kind: TxKind::Refund,
+            direction: TxDirection::In,
+            counterparty: Some(cashout.provider.clone()),
+            amount: cashout.amount,
+            token: cashout.token.clone(),
+            chain: Chain::default(),
+            status: TxStatus::Completed,
+            tx_hash: result.as_ref().and_then(|r| r["txHash"].as_str()).map(str::to_string),
+        }).await;
+        tr_with(lang, Msg::CashoutRefunded, &[&cashout.amount, &cashout.token, &cashout.provider])
     }
 
-    async fn bridge_response(&self, from: &str, amount: f64, token: &str, from_chain: &str, to_chain: &str) -> String {
-        let Some(ref user_repo) = self.user_repo else {
-            return "DB offline. Try later.".to_string();
+    async fn save_response(&self, from: &str, lang: Lang, name: &str, target: &str) -> String {
+        let Some(ref address_book) = self.address_book_repo else {
+            return tr(lang, Msg::AddressBookOffline);
         };
 
-        let user = match user_repo.find_by_phone(from).await {
-            Ok(Some(user)) => user,
-            Ok(None) => return "No wallet. Reply JOIN first.".to_string(),
-            Err(_) => return "Error. Try later.".to_string(),
+        if !is_valid_contact_name(name) {
+            return tr_with(lang, Msg::InvalidContactName, &[&name]);
+        }
+        let Some(target) = ContactTarget::detect(target) else {
+            return tr(lang, Msg::InvalidContact);
         };
 
-        let client = reqwest::Client::new();
-
-        tracing::info!(
-            "Bridge: {} {} from {} to {} for {}",
-            amount, token, from_chain, to_chain, user.wallet_address
-        );
-
-        let response = client
-            .post(&format!("{}/api/bridge", self.backend_url))
-            .json(&serde_json::json!({
-                "fromChain": from_chain.to_lowercase(),
-                "toChain": to_chain.to_lowercase(),
-                "fromToken": token,
-                "toToken": token,
-                "amount": amount.to_string(),
-                "userAddress": user.wallet_address,
-                "userPhone": from
-            }))
-            .timeout(std::time::Duration::from_secs(5))
-            .send()
-            .await;
+        // Cache what an ENS name points at so SEND doesn't resolve it every time
+        let (shown, resolved) = match target {
+            ContactTarget::Phone(ref phone) => (phone.clone(), None),
+            ContactTarget::Wallet(ref addr) => (addr.clone(), None),
+            ContactTarget::Ens(ref ens) => match self.resolve_ens(lang, ens).await {
+                Ok(addr) => (ens.clone(), Some(addr)),
+                Err(msg) => return msg,
+            },
+        };
 
-        match response {
-            Ok(resp) => {
-                if let Ok(result) = resp.json::<serde_json::Value>().await {
-                    if result["success"].as_bool().unwrap_or(false) {
-                        let route = result["route"].as_str().unwrap_or("");
-                        format!(
-                            "Bridge started!\n{}\nSMS when done.",
-                            route
-                        )
-                    } else {
-                        let err = result["error"].as_str().unwrap_or("Unknown error");
-                        format!("❌ Bridge failed: {}", err)
-                    }
-                } else {
-                    "Bridge initiated. You'll get an SMS when complete.".to_string()
-                }
-            }
+        match address_book.save(from, name, &target, resolved.as_deref()).await {
+            Ok(_) => tr_with(lang, Msg::ContactSaved, &[&shown, &name]),
             Err(e) => {
-                tracing::error!("Bridge API error: {}", e);
-                "Bridge service unavailable. Try later.".to_string()
+                tracing::error!("Failed to save contact: {}", e);
+                tr(lang, Msg::ContactSaveFailed)
             }
         }
     }
 
-    async fn save_response(&self, from: &str, name: &str, phone: &str) -> String {
+    async fn delete_response(&self, from: &str, lang: Lang, name: &str) -> String {
         let Some(ref address_book) = self.address_book_repo else {
-            return "Address book offline.".to_string();
+            return tr(lang, Msg::AddressBookOffline);
         };
 
-        match address_book.add_contact(from, name, Some(phone), None).await {
-            Ok(_) => format!("Saved {} as {}.", phone, name),
-            Err(_) => "Error saving contact.".to_string(),
+        match address_book.delete(from, name).await {
+            Ok(true) => tr_with(lang, Msg::ContactDeleted, &[&name]),
+            Ok(false) => tr_with(lang, Msg::ContactNotFound, &[&name]),
+            Err(_) => tr(lang, Msg::TryLater),
         }
     }
 
-    async fn contacts_response(&self, from: &str) -> String {
+    async fn rename_response(&self, from: &str, lang: Lang, old_name: &str, new_name: &str) -> String {
         let Some(ref address_book) = self.address_book_repo else {
-            return "Address book offline.".to_string();
+            return tr(lang, Msg::AddressBookOffline);
         };
 
-        match address_book.list_all(from).await {
-            Ok(contacts) if contacts.is_empty() => {
-                "No contacts yet.\n\nSAVE <name> <phone>".to_string()
-            }
-            Ok(contacts) => {
-                let list: Vec<String> = contacts.iter()
-                    .take(5)
-                    .map(|c| c.to_sms_string())
-                    .collect();
-                format!("Contacts:\n{}", list.join("\n"))
+        if !is_valid_contact_name(new_name) {
+            return tr_with(lang, Msg::InvalidContactName, &[&new_name]);
+        }
+        if !old_name.eq_ignore_ascii_case(new_name) {
+            match address_book.find_by_name(from, new_name).await {
+                Ok(contacts) if contacts.iter().any(|c| c.name.eq_ignore_ascii_case(new_name)) => {
+                    return tr_with(lang, Msg::ContactNameTaken, &[&new_name]);
+                }
+                Ok(_) => {}
+                Err(_) => return tr(lang, Msg::TryLater),
             }
-            Err(_) => "Error loading contacts.".to_string(),
         }
+
+        match address_book.rename(from, old_name, new_name).await {
+            Ok(true) => tr_with(lang, Msg::ContactRenamed, &[&old_name, &new_name]),
+            Ok(false) => tr_with(lang, Msg::ContactNotFound, &[&old_name]),
+            Err(_) => tr(lang, Msg::TryLater),
+        }
+    }
+
+    async fn contacts_response(&self, from: &str, lang: Lang, page: u32) -> String {
+        let Some(ref address_book) = self.address_book_repo else {
+            return tr(lang, Msg::AddressBookOffline);
+        };
+
+        let total = match address_book.count(from).await {
+            Ok(n) => n,
+            Err(_) => return tr(lang, Msg::ContactsLoadFailed),
+        };
+        if total == 0 {
+            return tr(lang, Msg::NoContacts);
+        }
+
+        let pages = (total + CONTACTS_PAGE_SIZE - 1) / CONTACTS_PAGE_SIZE;
+        if page as i64 > pages {
+            return tr_with(lang, Msg::ContactsNoPage, &[&page, &pages]);
+        }
+
+        let offset = (page as i64 - 1) * CONTACTS_PAGE_SIZE;
+        let contacts = match address_book.page(from, CONTACTS_PAGE_SIZE, offset).await {
+            Ok(contacts) => contacts,
+            Err(_) => return tr(lang, Msg::ContactsLoadFailed),
+        };
+
+        let list: Vec<String> = contacts.iter().map(|c| c.to_sms_string()).collect();
+        let mut reply = tr_with(lang, Msg::ContactsPage, &[&page, &pages, &list.join("\n")]);
+        if (page as i64) < pages {
+            let next = format!("{} {}", keywords::localized(lang, "CONTACTS"), page + 1);
+            reply = format!("{}\n{}", reply, tr_with(lang, Msg::HistoryMore, &[&next]));
+        }
+        reply
     }
 
-    async fn chain_response(&self, from: &str, chain_input: &str) -> String {
+    async fn chain_response(&self, from: &str, lang: Lang, chain_input: &str) -> String {
         let Some(chain) = Chain::from_input(chain_input) else {
-            return format!(
-                "Unknown chain: {}\n\nAvailable: polygon, base, eth, arb",
-                chain_input
-            );
+            return tr_with(lang, Msg::UnknownChain, &[&chain_input]);
         };
 
-        // For now, just acknowledge - could save preference to DB
-        format!(
-            "Switched to {}!\n\nChain ID: {}\nNative: {}",
-            chain.name(),
-            chain.chain_id(),
-            chain.native_token()
-        )
+        let Some(ref repo) = self.user_repo else {
+            return tr(lang, Msg::DbOffline);
+        };
+
+        match repo.update_preferred_chain(from, chain).await {
+            Ok(true) => tr_with(lang, Msg::ChainSwitched, &[
+                &chain.name(),
+                &chain.chain_id(),
+                &chain.native_token(),
+                &chain.short_code(),
+            ]),
+            Ok(false) => tr(lang, Msg::NoWallet),
+            Err(e) => {
+                tracing::error!("Failed to save chain: {}", e);
+                tr(lang, Msg::TryLater)
+            }
+        }
+    }
+
+    async fn lang_response(&self, from: &str, lang: Lang, code: Option<String>) -> String {
+        let options = Lang::ALL
+            .iter()
+            .map(|l| format!("{} ({})", l.code(), l.native_name()))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let Some(code) = code else {
+            return tr_with(lang, Msg::LangCurrent, &[&lang.native_name(), &options]);
+        };
+
+        let Some(new_lang) = Lang::from_input(&code) else {
+            return tr_with(lang, Msg::LangUnknown, &[&code, &options]);
+        };
+
+        let Some(ref repo) = self.user_repo else {
+            return tr(lang, Msg::DbOffline);
+        };
+
+        match repo.update_language(from, new_lang.code()).await {
+            Ok(true) => tr(new_lang, Msg::LangSet),
+            Ok(false) => tr(lang, Msg::NoWallet),
+            Err(e) => {
+                tracing::error!("Failed to save language: {}", e);
+                tr(lang, Msg::TryLater)
+            }
+        }
     }
 
-    fn unknown_response(&self, text: &str) -> String {
+    fn more_response(&self, from: &str, lang: Lang) -> String {
+        self.replies.next_page(from).unwrap_or_else(|| tr(lang, Msg::NoMorePages))
+    }
+
+    fn unknown_response(&self, lang: Lang, text: &str) -> String {
         if text.is_empty() {
-            "Welcome to TextChain!\n\nReply COMMANDS for help.".to_string()
+            tr(lang, Msg::Welcome)
         } else {
-            format!(
-                "Unknown: {}\n\nReply COMMANDS for help.",
-                text.chars().take(15).collect::<String>()
-            )
+            tr_with(lang, Msg::UnknownCommand, &[&text.chars().take(15).collect::<String>()])
+        }
+    }
+}
+
+/// Why a PIN command was refused
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PinRefused {
+    /// Wrong PIN, with this many tries left before the lockout
+    Wrong(i32),
+    /// Locked after too many wrong PINs, for about this many minutes
+    Locked(i64),
+    /// The PIN couldn't be checked
+    Unavailable,
+}
+
+impl PinRefused {
+    fn reply(self, lang: Lang) -> String {
+        match self {
+            PinRefused::Wrong(left) => tr_with(lang, Msg::WrongPinTriesLeft, &[&left]),
+            PinRefused::Locked(minutes) => tr_with(lang, Msg::PinLocked, &[&minutes]),
+            PinRefused::Unavailable => tr(lang, Msg::TryLater),
         }
     }
 }
 
+/// What to do with a cashout after asking its provider about the payout
+#[derive(Debug, Clone, PartialEq)]
+enum PayoutStep {
+    /// Paid, with the provider's reference
+    Paid(String),
+    /// Accepted and still being paid
+    Pending(String),
+    /// No usable answer; the provider may already have paid, so ask again
+    /// with the same reference rather than refund
+    Retry(String),
+    /// Refused, so nothing was paid and the tokens go back
+    Refund(String),
+}
+
+impl PayoutStep {
+    fn from_result(result: Result<PayoutStatus, CashoutError>) -> Self {
+        match result {
+            Ok(PayoutStatus::Paid { id }) => PayoutStep::Paid(id),
+            Ok(PayoutStatus::Pending { id }) => PayoutStep::Pending(id),
+            Ok(PayoutStatus::Failed { reason }) => PayoutStep::Refund(reason),
+            Err(e @ CashoutError::Rejected(_)) => PayoutStep::Refund(e.to_string()),
+            Err(e) => PayoutStep::Retry(e.to_string()),
+        }
+    }
+}
+
+/// Reply to staging an action, and the pending op it stored, if any
+struct Staged {
+    id: Option<Uuid>,
+    reply: String,
+}
+
+impl Staged {
+    /// Nothing stored; `reply` says why
+    fn refused(reply: String) -> Self {
+        Self { id: None, reply }
+    }
+}
+
+/// Reply to a confirmed action and where its ledger row ends up
+struct Executed {
+    reply: String,
+    status: TxStatus,
+    tx_hash: Option<String>,
+    /// Failed because the wallet could not cover it
+    low_balance: bool,
+}
+
+impl Executed {
+    /// Accepted; the backend callback reports the outcome
+    fn pending(reply: String) -> Self {
+        Self { reply, status: TxStatus::Pending, tx_hash: None, low_balance: false }
+    }
+
+    fn completed(reply: String, tx_hash: &str) -> Self {
+        Self { reply, status: TxStatus::Completed, tx_hash: Some(tx_hash.to_string()), low_balance: false }
+    }
+
+    fn failed(reply: String) -> Self {
+        Self { reply, status: TxStatus::Failed, tx_hash: None, low_balance: false }
+    }
+
+    fn low_balance(reply: String) -> Self {
+        Self { low_balance: true, ..Self::failed(reply) }
+    }
+}
+
+/// Check a token can be sent on a chain, returning it uppercased.
+///
+/// TXTC and ETH go through the backend on the default chain; elsewhere
+/// the chain's native token and USDC are sent directly.
+fn check_send_token(lang: Lang, chain: Chain, token: &str) -> Result<String, String> {
+    let tokens = if chain == Chain::default() {
+        vec!["TXTC", "ETH"]
+    } else {
+        transferable_tokens(chain)
+    };
+    let token_upper = token.to_uppercase();
+    if tokens.contains(&token_upper.as_str()) {
+        Ok(token_upper)
+    } else {
+        Err(tr_with(lang, Msg::UnsupportedToken, &[&chain.short_code(), &tokens.join(", "), &tokens[0]]))
+    }
+}
+
+/// Check an amount fits the token's decimals before anything is staged
+fn check_amount(lang: Lang, amount: Amount, token: &str) -> Result<(), String> {
+    let Some(decimals) = token_decimals(token) else {
+        return Err(tr_with(lang, Msg::UnknownToken, &[&token.to_uppercase()]));
+    };
+    match amount.to_base_units(decimals) {
+        Ok(_) => Ok(()),
+        Err(AmountError::TooPrecise(max)) => Err(tr_with(lang, Msg::AmountTooPrecise, &[&max, &token.to_uppercase()])),
+        Err(_) => Err(tr(lang, Msg::InvalidAmount)),
+    }
+}
+
+/// Parse `alice bob` (equal shares) or `alice:20 bob:10` (weighted) into
+/// recipients and weights. Shares must be given for everyone or no one.
+fn parse_split_recipients(input: &str) -> Option<Vec<(String, Amount)>> {
+    let one = Amount::from_base_units(1, 0);
+    let parsed: Vec<(&str, Option<Amount>)> = input
+        .split_whitespace()
+        .map(|word| match word.rsplit_once(':') {
+            Some((name, share)) => Amount::parse(share).ok().map(|share| (name, Some(share))),
+            None => Some((word, None)),
+        })
+        .collect::<Option<_>>()?;
+
+    let weighted = parsed.iter().filter(|(_, share)| share.is_some()).count();
+    let valid_count = (2..=MAX_SPLIT_RECIPIENTS).contains(&parsed.len());
+    let consistent = weighted == 0 || weighted == parsed.len();
+    if !valid_count || !consistent || parsed.iter().any(|(name, _)| name.is_empty()) {
+        return None;
+    }
+
+    Some(parsed.into_iter().map(|(name, share)| (name.to_string(), share.unwrap_or(one))).collect())
+}
+
+/// PINs are 4-6 digits
+fn is_valid_pin(pin: &str) -> bool {
+    (4..=6).contains(&pin.len()) && pin.chars().all(|c| c.is_ascii_digit())
+}
+
 impl std::fmt::Debug for CommandProcessor {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("CommandProcessor")
             .field("has_db", &self.user_repo.is_some())
             .field("has_vouchers", &self.voucher_repo.is_some())
-            .field("has_deposits", &self.deposit_repo.is_some())
+            .field("has_pending", &self.pending_repo.is_some())
             .finish()
     }
 }
@@ -894,51 +3123,432 @@ mod tests {
     #[test]
     fn test_parse_help() {
         let processor = test_processor();
-        assert_eq!(processor.parse("COMMANDS"), Command::Help);
-        assert_eq!(processor.parse("menu"), Command::Help);
-        assert_eq!(processor.parse("?"), Command::Help);
+        assert_eq!(processor.parse("COMMANDS", Lang::En), Command::Help);
+        assert_eq!(processor.parse("menu", Lang::En), Command::Help);
+        assert_eq!(processor.parse("?", Lang::En), Command::Help);
     }
 
     #[test]
     fn test_parse_join() {
         let processor = test_processor();
-        assert_eq!(processor.parse("JOIN"), Command::Join { ens_name: None });
-        assert_eq!(processor.parse("JOIN john"), Command::Join { ens_name: Some("john".to_string()) });
-        assert_eq!(processor.parse("start"), Command::Join { ens_name: None });
+        assert_eq!(processor.parse("JOIN", Lang::En), Command::Join { ens_name: None });
+        assert_eq!(processor.parse("JOIN john", Lang::En), Command::Join { ens_name: Some("john".to_string()) });
+        assert_eq!(processor.parse("start", Lang::En), Command::Join { ens_name: None });
     }
 
     #[test]
     fn test_parse_balance() {
         let processor = test_processor();
-        assert_eq!(processor.parse("BALANCE"), Command::Balance);
-        assert_eq!(processor.parse("bal"), Command::Balance);
+        assert_eq!(processor.parse("BALANCE", Lang::En), Command::Balance);
+        assert_eq!(processor.parse("bal", Lang::En), Command::Balance);
     }
 
     #[test]
     fn test_parse_send() {
         let processor = test_processor();
         
-        let cmd = processor.parse("SEND 10 USDC TO +917123456789");
+        let cmd = processor.parse("SEND 10 USDC TO +917123456789", Lang::En);
         assert!(matches!(cmd, Command::Send { amount, token, recipient } 
-            if amount == 10.0 && token == "USDC" && recipient == "+917123456789"));
+            if amount == Amount::parse("10").unwrap() && token == "USDC" && recipient == "+917123456789"));
     }
 
     #[test]
     fn test_parse_pin() {
         let processor = test_processor();
         
-        let cmd = processor.parse("PIN 1234");
+        let cmd = processor.parse("PIN 1234", Lang::En);
         assert!(matches!(cmd, Command::Pin { new_pin: Some(pin) } if pin == "1234"));
         
-        let cmd = processor.parse("PIN");
+        let cmd = processor.parse("PIN", Lang::En);
         assert!(matches!(cmd, Command::Pin { new_pin: None }));
+
+        assert_eq!(
+            processor.parse("PIN 1234 5678", Lang::En),
+            Command::ChangePin { old_pin: "1234".to_string(), new_pin: "5678".to_string() }
+        );
+    }
+
+    #[test]
+    fn test_parse_confirm() {
+        let processor = test_processor();
+
+        assert_eq!(processor.parse("yes 1234", Lang::En), Command::Confirm { pin: "1234".to_string() });
+        assert_eq!(processor.parse("NO", Lang::En), Command::Cancel);
+        assert!(matches!(processor.parse("YES", Lang::En), Command::Usage(_)));
+    }
+
+    #[test]
+    fn test_valid_pin() {
+        assert!(is_valid_pin("1234"));
+        assert!(is_valid_pin("123456"));
+        assert!(!is_valid_pin("123"));
+        assert!(!is_valid_pin("12a4"));
+    }
+
+    #[test]
+    fn test_parse_bridge() {
+        let processor = test_processor();
+        let expected = Command::Bridge {
+            amount: Amount::parse("10").unwrap(),
+            token: "USDC".to_string(),
+            from_chain: "POLYGON".to_string(),
+            to_chain: "BASE".to_string(),
+        };
+
+        assert_eq!(processor.parse("BRIDGE 10 usdc FROM polygon TO base", Lang::En), expected);
+        assert_eq!(processor.parse("bridge 10 USDC FROM POLYGON BASE", Lang::En), expected);
+        assert_eq!(processor.parse("CROSS 10 USDC POLYGON BASE", Lang::En), expected);
+    }
+
+    #[test]
+    fn test_parse_usage_errors() {
+        let processor = test_processor();
+
+        assert!(matches!(processor.parse("REDEEM", Lang::En), Command::Usage(u) if u.starts_with("Usage: REDEEM <code>")));
+        assert!(matches!(processor.parse("SEND ten TXTC alice", Lang::En), Command::Usage(u) if u.contains("SEND <amount>")));
+        assert!(matches!(processor.parse("SWAP ten TXTC", Lang::En), Command::Usage(u) if u.starts_with("Invalid amount")));
+    }
+
+    #[test]
+    fn test_parse_ask_for_missing_args() {
+        let processor = test_processor();
+
+        assert_eq!(processor.parse("SEND", Lang::En), Command::Ask {
+            command: "SEND",
+            args: String::new(),
+            prompt: Msg::AskAmount,
+        });
+        assert_eq!(processor.parse("tuma 10", Lang::En), Command::Ask {
+            command: "SEND",
+            args: "10".to_string(),
+            prompt: Msg::AskToken,
+        });
+        assert_eq!(processor.parse("SEND 10 TXTC TO", Lang::En), Command::Ask {
+            command: "SEND",
+            args: "10 TXTC TO".to_string(),
+            prompt: Msg::AskRecipient,
+        });
+        assert_eq!(processor.parse("REQUEST 20 TXTC", Lang::En), Command::Ask {
+            command: "REQUEST",
+            args: "20 TXTC".to_string(),
+            prompt: Msg::AskPayer,
+        });
+    }
+
+    #[tokio::test]
+    async fn test_ask_without_db_replies_usage() {
+        let processor = test_processor();
+        let reply = processor.process("+15550001111", "SEND 10 TXTC").await;
+        assert!(reply.starts_with("Usage: SEND <amount> <token> [TO] <recipient>"));
     }
 
     #[test]
     fn test_parse_unknown() {
         let processor = test_processor();
         
-        let cmd = processor.parse("FOOBAR");
+        let cmd = processor.parse("FOOBAR", Lang::En);
         assert!(matches!(cmd, Command::Unknown(_)));
     }
+
+    #[test]
+    fn test_parse_localized() {
+        let processor = test_processor();
+
+        assert_eq!(
+            processor.parse("TUMA 10 TXTC KWA alice.ttcip.eth", Lang::En),
+            Command::Send {
+                amount: Amount::parse("10").unwrap(),
+                token: "TXTC".to_string(),
+                recipient: "alice.ttcip.eth".to_string(),
+            }
+        );
+        assert_eq!(processor.parse("salio", Lang::En), Command::Balance);
+        assert_eq!(processor.parse("NDIYO 1234", Lang::En), Command::Confirm { pin: "1234".to_string() });
+        assert_eq!(processor.parse("SI 1234", Lang::En), Command::Confirm { pin: "1234".to_string() });
+        assert_eq!(processor.parse("BHEJO 5 ETH KO +919876543210", Lang::En), Command::Send {
+            amount: Amount::parse("5").unwrap(),
+            token: "ETH".to_string(),
+            recipient: "+919876543210".to_string(),
+        });
+
+        match processor.parse("GAWANYA 10 TXTC", Lang::En) {
+            Command::Usage(usage) => assert!(usage.starts_with("Matumizi: GAWANYA")),
+            other => panic!("expected usage, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_typo_suggests() {
+        let processor = test_processor();
+
+        assert_eq!(
+            processor.parse("BALENCE", Lang::En),
+            Command::Suggest { input: "BALENCE".to_string(), suggestions: vec!["BALANCE".to_string()] }
+        );
+        assert_eq!(
+            processor.parse("sned 10 TXTC alice", Lang::En),
+            Command::Suggest {
+                input: "SNED".to_string(),
+                suggestions: vec!["SEND 10 TXTC alice".to_string()],
+            }
+        );
+        assert!(matches!(processor.parse("HELLO", Lang::En), Command::Unknown(_)));
+    }
+
+    #[test]
+    fn test_parse_typo_auto_correct() {
+        let processor = test_processor().with_auto_correct(true);
+
+        assert_eq!(processor.parse("REDEM ABC123", Lang::En), Command::Redeem { code: "ABC123".to_string() });
+        assert_eq!(
+            processor.parse("SNED 10 TXTC alice", Lang::En),
+            Command::Send { amount: Amount::parse("10").unwrap(), token: "TXTC".to_string(), recipient: "alice".to_string() }
+        );
+        // Ambiguous typos are never auto-accepted
+        assert!(matches!(processor.parse("PUN", Lang::En), Command::Suggest { .. }));
+    }
+
+    #[test]
+    fn test_parse_lang() {
+        let processor = test_processor();
+
+        assert_eq!(processor.parse("LANG", Lang::En), Command::Lang { code: None });
+        assert_eq!(processor.parse("LANG sw", Lang::En), Command::Lang { code: Some("sw".to_string()) });
+        assert_eq!(processor.parse("IDIOMA es", Lang::En), Command::Lang { code: Some("es".to_string()) });
+    }
+
+    #[test]
+    fn test_parse_request_pay_decline() {
+        let processor = test_processor();
+
+        assert_eq!(processor.parse("REQUEST 20 TXTC FROM alice", Lang::En), Command::Request {
+            amount: Amount::parse("20").unwrap(),
+            token: "TXTC".to_string(),
+            payer: "alice".to_string(),
+        });
+        assert_eq!(processor.parse("OMBA 5 ETH KUTOKA +255700000001", Lang::En), Command::Request {
+            amount: Amount::parse("5").unwrap(),
+            token: "ETH".to_string(),
+            payer: "+255700000001".to_string(),
+        });
+        assert_eq!(processor.parse("pay abc234", Lang::En), Command::Pay { code: "ABC234".to_string(), pin: None });
+        assert_eq!(
+            processor.parse("PAGAR ABC234 1234", Lang::En),
+            Command::Pay { code: "ABC234".to_string(), pin: Some("1234".to_string()) }
+        );
+        assert_eq!(processor.parse("DECLINE ABC234", Lang::En), Command::Decline { code: "ABC234".to_string() });
+    }
+
+    #[test]
+    fn test_parse_history() {
+        let processor = test_processor();
+        let history = |filter, page| Command::History { filter, page };
+
+        assert_eq!(processor.parse("HISTORY", Lang::En), history(HistoryFilter::All, 1));
+        assert_eq!(processor.parse("HISTORY 2", Lang::En), history(HistoryFilter::All, 2));
+        assert_eq!(processor.parse("history sent", Lang::En), history(HistoryFilter::Sent, 1));
+        assert_eq!(processor.parse("TXS RECEIVED 3", Lang::En), history(HistoryFilter::Received, 3));
+        assert!(matches!(processor.parse("HISTORY 0", Lang::En), Command::Usage(u) if u.contains("HISTORY [SENT|RECEIVED|ALL] [page]")));
+        assert!(matches!(processor.parse("HISTORY LOTS", Lang::En), Command::Usage(_)));
+    }
+
+    #[test]
+    fn test_parse_cashout() {
+        let processor = test_processor();
+        let cashout = |amount: &str, method: Option<&str>| Command::Cashout {
+            amount: Amount::parse(amount).unwrap(),
+            token: "TXTC".to_string(),
+            method: method.map(str::to_string),
+        };
+
+        assert_eq!(processor.parse("CASHOUT 10 txtc", Lang::En), cashout("10", None));
+        assert_eq!(processor.parse("WITHDRAW 1k TXTC mpesa", Lang::En), cashout("1000", Some("MPESA")));
+        assert_eq!(processor.parse("TOA 10 TXTC", Lang::En), cashout("10", None));
+        assert!(matches!(processor.parse("CASHOUT 10", Lang::En), Command::Usage(u) if u.contains("CASHOUT <amount> <token> [method]")));
+    }
+
+    #[tokio::test]
+    async fn test_payout_step_refunds_only_refusals() {
+        use crate::cashout::{CashoutProvider, MockCashoutProvider, MockOutcome};
+
+        let request = || PayoutRequest {
+            reference: "ABC234".to_string(),
+            phone: "+254700000000".to_string(),
+            amount: Amount::parse("10").unwrap(),
+        };
+        let rate = Amount::parse("1").unwrap();
+
+        // Paid but unanswered: retried with the same reference, not refunded
+        let lost = MockCashoutProvider::new(rate, MockOutcome::Lost);
+        assert!(matches!(PayoutStep::from_result(lost.payout(request()).await), PayoutStep::Retry(_)));
+        assert_eq!(PayoutStep::from_result(lost.payout(request()).await), PayoutStep::Paid("MOCK-ABC234".to_string()));
+
+        let failed = MockCashoutProvider::new(rate, MockOutcome::Failed);
+        assert!(matches!(PayoutStep::from_result(failed.payout(request()).await), PayoutStep::Refund(_)));
+        let rejected = Err(CashoutError::Rejected("bad number".to_string()));
+        assert!(matches!(PayoutStep::from_result(rejected), PayoutStep::Refund(_)));
+    }
+
+    #[test]
+    fn test_parse_limit() {
+        let processor = test_processor();
+        let limits = |token: &str| Command::Limits { token: token.to_string() };
+        let set = |token: &str, period: LimitPeriod, amount: &str, pin: Option<&str>| Command::SetLimit {
+            token: token.to_string(),
+            period,
+            amount: Amount::parse(amount).unwrap(),
+            pin: pin.map(str::to_string),
+        };
+
+        assert_eq!(processor.parse("LIMIT", Lang::En), limits("TXTC"));
+        assert_eq!(processor.parse("LIMITS usdc", Lang::En), limits("USDC"));
+        assert_eq!(processor.parse("LIMIT DAY", Lang::En), limits("TXTC"));
+        assert_eq!(processor.parse("LIMIT day 500", Lang::En), set("TXTC", LimitPeriod::Daily, "500", None));
+        assert_eq!(processor.parse("LIMIT ETH TX 0.5 1234", Lang::En), set("ETH", LimitPeriod::PerTx, "0.5", Some("1234")));
+        assert_eq!(processor.parse("KIKOMO WEEK 2k", Lang::En), set("TXTC", LimitPeriod::Weekly, "2000", None));
+        assert!(matches!(processor.parse("LIMIT DAY lots", Lang::En), Command::Usage(_)));
+    }
+
+    #[test]
+    fn test_parse_new_number() {
+        let processor = test_processor();
+
+        assert_eq!(
+            processor.parse("NEWNUMBER +254711000000", Lang::En),
+            Command::NewNumber { phone: "+254711000000".to_string() }
+        );
+        assert_eq!(
+            processor.parse("NEWNUMBER +91 98765 43210", Lang::En),
+            Command::NewNumber { phone: "+91 98765 43210".to_string() }
+        );
+        assert_eq!(processor.parse("NEWNUMBER cancel", Lang::En), Command::CancelNumberChange);
+        assert_eq!(processor.parse("NAMBAMPYA HAPANA", Lang::En), Command::CancelNumberChange);
+        assert_eq!(
+            processor.parse("VERIFY 123456 1234", Lang::En),
+            Command::VerifyNumber { code: "123456".to_string(), pin: "1234".to_string() }
+        );
+        assert!(matches!(processor.parse("VERIFY 123456", Lang::En), Command::Usage(u) if u.contains("VERIFY <code> <pin>")));
+    }
+
+    #[test]
+    fn test_parse_status() {
+        let processor = test_processor();
+        let status = |reference: &str| Command::Status { reference: reference.to_string() };
+
+        assert_eq!(processor.parse("STATUS abc234", Lang::En), status("ABC234"));
+        assert_eq!(processor.parse("TRACK ABC234-2", Lang::En), status("ABC234-2"));
+        assert_eq!(processor.parse("HALI ABC234", Lang::En), status("ABC234"));
+        assert!(matches!(processor.parse("STATUS", Lang::En), Command::Usage(u) if u.contains("STATUS <ref>")));
+    }
+
+    #[test]
+    fn test_parse_schedules() {
+        let processor = test_processor();
+
+        assert_eq!(processor.parse("SEND 50 TXTC landlord EVERY MONTH ON 1", Lang::En), Command::ScheduleSend {
+            amount: Amount::parse("50").unwrap(),
+            token: "TXTC".to_string(),
+            recipient: "landlord".to_string(),
+            schedule: "EVERY MONTH ON 1".to_string(),
+        });
+        assert_eq!(processor.parse("TUMA 10 TXTC kwa mom AT 2026-11-01", Lang::En), Command::ScheduleSend {
+            amount: Amount::parse("10").unwrap(),
+            token: "TXTC".to_string(),
+            recipient: "mom".to_string(),
+            schedule: "AT 2026-11-01".to_string(),
+        });
+        assert_eq!(processor.parse("SCHEDULES", Lang::En), Command::Schedules);
+        assert_eq!(processor.parse("stop k7m2qx", Lang::En), Command::Stop { code: "K7M2QX".to_string() });
+        assert_eq!(processor.parse("DETENER K7M2QX", Lang::En), Command::Stop { code: "K7M2QX".to_string() });
+        assert!(matches!(processor.parse("STOP", Lang::En), Command::Usage(u) if u.contains("STOP <id>")));
+    }
+
+    #[test]
+    fn test_parse_more() {
+        let processor = test_processor();
+        assert_eq!(processor.parse("MORE", Lang::En), Command::More);
+        assert_eq!(processor.parse("zaidi", Lang::En), Command::More);
+        assert_eq!(processor.more_response("+15550001111", Lang::En), "Nothing more to show.");
+    }
+
+    #[test]
+    fn test_parse_address_book() {
+        let processor = test_processor();
+
+        assert_eq!(processor.parse("SAVE mom +254700000000", Lang::En), Command::Save {
+            name: "MOM".to_string(),
+            target: "+254700000000".to_string(),
+        });
+        assert_eq!(processor.parse("HIFADHI alice alice.ttcip.eth", Lang::En), Command::Save {
+            name: "ALICE".to_string(),
+            target: "alice.ttcip.eth".to_string(),
+        });
+        assert_eq!(processor.parse("DELETE mom", Lang::En), Command::Delete { name: "MOM".to_string() });
+        assert_eq!(processor.parse("RENAME mom TO mama", Lang::En), Command::Rename {
+            old_name: "MOM".to_string(),
+            new_name: "MAMA".to_string(),
+        });
+        assert_eq!(processor.parse("RENOMBRAR mom a mama", Lang::En), Command::Rename {
+            old_name: "MOM".to_string(),
+            new_name: "MAMA".to_string(),
+        });
+        assert_eq!(processor.parse("CONTACTS", Lang::En), Command::Contacts { page: 1 });
+        assert_eq!(processor.parse("CONTACTS 2", Lang::En), Command::Contacts { page: 2 });
+        assert!(matches!(processor.parse("RENAME mom", Lang::En), Command::Usage(u) if u.contains("RENAME <old> [TO] <new>")));
+    }
+
+    #[test]
+    fn test_parse_quote_and_slippage() {
+        let processor = test_processor();
+
+        assert_eq!(processor.parse("QUOTE 10 txtc", Lang::En), Command::Quote {
+            amount: Amount::parse("10").unwrap(),
+            token: "TXTC".to_string(),
+        });
+        assert_eq!(processor.parse("BEI 10 TXTC", Lang::En), Command::Quote {
+            amount: Amount::parse("10").unwrap(),
+            token: "TXTC".to_string(),
+        });
+        assert_eq!(processor.parse("SLIPPAGE", Lang::En), Command::Slippage { percent: None });
+        assert_eq!(processor.parse("DESLIZ 0.5%", Lang::En), Command::Slippage { percent: Some("0.5%".to_string()) });
+        assert!(matches!(processor.parse("QUOTE", Lang::En), Command::Usage(u) if u.contains("QUOTE <amount> <TXTC>")));
+        assert!(matches!(processor.parse("QUOTE 10 ETH", Lang::En), Command::Usage(_)));
+        assert!(matches!(processor.parse("SWAP 10 USDC", Lang::En), Command::Usage(_)));
+    }
+
+    #[test]
+    fn test_parse_split() {
+        let processor = test_processor();
+
+        assert_eq!(processor.parse("SPLIT 30 TXTC alice bob", Lang::En), Command::Split {
+            amount: Amount::parse("30").unwrap(),
+            token: "TXTC".to_string(),
+            recipients: "alice bob".to_string(),
+        });
+        assert_eq!(processor.parse("GAWANYA 30 TXTC KWA alice:20 bob:10", Lang::En), Command::Split {
+            amount: Amount::parse("30").unwrap(),
+            token: "TXTC".to_string(),
+            recipients: "alice:20 bob:10".to_string(),
+        });
+        assert!(matches!(processor.parse("SPLIT 30 TXTC", Lang::En), Command::Usage(u) if u.contains("SPLIT <amount>")));
+    }
+
+    #[test]
+    fn test_parse_split_recipients() {
+        let one = Amount::from_base_units(1, 0);
+        assert_eq!(
+            parse_split_recipients("alice +254700000000"),
+            Some(vec![("alice".to_string(), one), ("+254700000000".to_string(), one)])
+        );
+        assert_eq!(
+            parse_split_recipients("alice:20 bob:10"),
+            Some(vec![("alice".to_string(), Amount::parse("20").unwrap()), ("bob".to_string(), Amount::parse("10").unwrap())])
+        );
+        // Everyone or no one gets a share, and a split needs 2-10 people
+        assert_eq!(parse_split_recipients("alice:20 bob"), None);
+        assert_eq!(parse_split_recipients("alice:x bob:1"), None);
+        assert_eq!(parse_split_recipients(":5 bob:1"), None);
+        assert_eq!(parse_split_recipients("alice"), None);
+        assert_eq!(parse_split_recipients(&"bob ".repeat(11)), None);
+    }
 }
@@ -0,0 +1,913 @@
+use super::parser::Command;
+use crate::db::{HistoryFilter, LimitPeriod};
+use crate::i18n::{keywords, tr, tr_with, Lang, Msg};
+use crate::wallet::{Amount, AmountError};
+
+/// How a single positional argument is read from the SMS
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArgKind {
+    /// One word, kept exactly as typed
+    Word,
+    /// One word, uppercased
+    Upper,
+    /// One word, lowercased
+    Lower,
+    /// Numeric amount
+    Amount,
+    /// Everything that is left, joined with spaces
+    Rest,
+    /// Optional filler keyword (e.g. TO, FROM) that is skipped when present
+    Keyword(&'static str),
+    /// One of a fixed set of words, uppercased; an optional choice is
+    /// skipped without consuming the word when it doesn't match
+    Choice(&'static [&'static str]),
+    /// Positive whole number, e.g. a page
+    Number,
+}
+
+/// Declared argument of a command
+#[derive(Debug, Clone, Copy)]
+pub struct ArgSpec {
+    pub name: &'static str,
+    pub kind: ArgKind,
+    pub required: bool,
+    /// Question sent when the argument is missing; None replies with usage
+    pub prompt: Option<Msg>,
+}
+
+impl ArgSpec {
+    pub const fn required(name: &'static str, kind: ArgKind) -> Self {
+        Self { name, kind, required: true, prompt: None }
+    }
+
+    /// Required argument that is asked for when left out
+    pub const fn prompted(name: &'static str, kind: ArgKind, prompt: Msg) -> Self {
+        Self { name, kind, required: true, prompt: Some(prompt) }
+    }
+
+    pub const fn optional(name: &'static str, kind: ArgKind) -> Self {
+        Self { name, kind, required: false, prompt: None }
+    }
+
+    pub const fn keyword(word: &'static str) -> Self {
+        Self { name: word, kind: ArgKind::Keyword(word), required: false, prompt: None }
+    }
+}
+
+/// Why the words after a keyword don't fit the command's schema
+#[derive(Debug, Clone, Copy)]
+pub enum ArgError {
+    /// The words ran out before this required argument
+    Missing(ArgSpec),
+    /// An argument is malformed, with the reason when there is one
+    Invalid(Option<Msg>),
+}
+
+/// Parsed value of an argument
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArgValue {
+    Text(String),
+    Amount(Amount),
+    Number(u32),
+}
+
+/// Arguments collected according to a command's schema
+#[derive(Debug, Default)]
+pub struct ParsedArgs {
+    values: Vec<(&'static str, ArgValue)>,
+}
+
+impl ParsedArgs {
+    /// Text value of an optional argument
+    pub fn opt(&self, name: &str) -> Option<String> {
+        self.values.iter().find(|(n, _)| *n == name).and_then(|(_, v)| match v {
+            ArgValue::Text(s) => Some(s.clone()),
+            _ => None,
+        })
+    }
+
+    /// Text values of an argument declared more than once
+    pub fn all(&self, name: &str) -> Vec<String> {
+        self.values.iter()
+            .filter(|(n, _)| *n == name)
+            .filter_map(|(_, v)| match v {
+                ArgValue::Text(s) => Some(s.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Text value of a required argument
+    pub fn text(&self, name: &str) -> String {
+        self.opt(name).unwrap_or_default()
+    }
+
+    /// Amount value of a required argument
+    pub fn amount(&self, name: &str) -> Amount {
+        self.opt_amount(name).unwrap_or_default()
+    }
+
+    /// Amount value of an optional argument
+    pub fn opt_amount(&self, name: &str) -> Option<Amount> {
+        self.values.iter().find(|(n, _)| *n == name).and_then(|(_, v)| match v {
+            ArgValue::Amount(a) => Some(*a),
+            _ => None,
+        })
+    }
+
+    /// Number value of an optional argument
+    pub fn number(&self, name: &str) -> Option<u32> {
+        self.values.iter().find(|(n, _)| *n == name).and_then(|(_, v)| match v {
+            ArgValue::Number(n) => Some(*n),
+            _ => None,
+        })
+    }
+}
+
+/// Declarative definition of an SMS command.
+///
+/// Parsing, usage errors and the COMMANDS/MENU text are all generated
+/// from these entries, so adding a command means adding a `Command`
+/// variant, an entry in `COMMANDS` and an arm in `CommandProcessor::execute`.
+pub struct CommandSpec {
+    /// Keywords that trigger the command; the first one is canonical
+    pub keywords: &'static [&'static str],
+    /// Positional arguments, in order
+    pub args: &'static [ArgSpec],
+    /// Example shown with usage errors
+    pub example: Option<&'static str>,
+    /// One-line description for the help menu (None hides the command)
+    pub summary: Option<Msg>,
+    /// Build the command from its parsed arguments
+    pub build: fn(&ParsedArgs) -> Command,
+}
+
+impl CommandSpec {
+    /// Canonical keyword
+    pub fn name(&self) -> &'static str {
+        self.keywords[0]
+    }
+
+    /// Argument synopsis, e.g. `SEND <amount> <token> [TO] <recipient>`
+    pub fn synopsis(&self, lang: Lang) -> String {
+        let mut out = keywords::localized(lang, self.name()).to_string();
+        for arg in self.args {
+            let part = match (arg.kind, arg.required) {
+                (ArgKind::Keyword(word), _) => format!("[{}]", word),
+                (ArgKind::Choice(words), true) => format!("<{}>", words.join("|")),
+                (ArgKind::Choice(words), false) => format!("[{}]", words.join("|")),
+                (_, true) => format!("<{}>", arg.name),
+                (_, false) => format!("[{}]", arg.name),
+            };
+            out.push(' ');
+            out.push_str(&part);
+        }
+        out
+    }
+
+    /// Usage text returned when the arguments don't fit the schema
+    pub fn usage(&self, lang: Lang) -> String {
+        let usage = tr_with(lang, Msg::Usage, &[&self.synopsis(lang)]);
+        match self.example {
+            Some(example) => format!("{}\n{}", usage, tr_with(lang, Msg::Example, &[&example])),
+            None => usage,
+        }
+    }
+
+    /// Usage text for a parse error, preceded by its reason
+    pub fn error_text(&self, error: ArgError, lang: Lang) -> String {
+        match error {
+            ArgError::Invalid(Some(reason)) => format!("{}\n{}", tr(lang, reason), self.usage(lang)),
+            ArgError::Invalid(None) | ArgError::Missing(_) => self.usage(lang),
+        }
+    }
+
+    /// Prompt for the first required argument `words` leave out, if it has one
+    pub fn next_prompt(&self, words: &[&str], lang: Lang) -> Option<Msg> {
+        match self.parse_args(words, lang) {
+            Err(ArgError::Missing(arg)) => arg.prompt,
+            _ => None,
+        }
+    }
+
+    /// Parse the words following the keyword against the schema
+    pub fn parse_args(&self, words: &[&str], lang: Lang) -> Result<ParsedArgs, ArgError> {
+        let mut parsed = ParsedArgs::default();
+        let mut pos = 0;
+
+        for arg in self.args {
+            let word = words.get(pos).copied();
+
+            if let ArgKind::Keyword(keyword) = arg.kind {
+                if word.is_some_and(|w| keywords::is_filler(lang, keyword, w)) {
+                    pos += 1;
+                }
+                continue;
+            }
+
+            if let ArgKind::Choice(choices) = arg.kind {
+                match word.filter(|w| choices.iter().any(|c| c.eq_ignore_ascii_case(w))) {
+                    Some(w) => {
+                        parsed.values.push((arg.name, ArgValue::Text(w.to_uppercase())));
+                        pos += 1;
+                    }
+                    None if arg.required && word.is_none() => return Err(ArgError::Missing(*arg)),
+                    None if arg.required => return Err(ArgError::Invalid(None)),
+                    None => {}
+                }
+                continue;
+            }
+
+            let Some(word) = word else {
+                if arg.required {
+                    return Err(ArgError::Missing(*arg));
+                }
+                continue;
+            };
+
+            let value = match arg.kind {
+                ArgKind::Word => ArgValue::Text(word.to_string()),
+                ArgKind::Upper => ArgValue::Text(word.to_uppercase()),
+                ArgKind::Lower => ArgValue::Text(word.to_lowercase()),
+                ArgKind::Amount => match Amount::parse(word) {
+                    Ok(amount) => ArgValue::Amount(amount),
+                    Err(e) => {
+                        let msg = match e {
+                            AmountError::Negative | AmountError::Zero => Msg::AmountNotPositive,
+                            _ => Msg::InvalidAmount,
+                        };
+                        return Err(ArgError::Invalid(Some(msg)));
+                    }
+                },
+                ArgKind::Number => match word.parse::<u32>() {
+                    Ok(n) if n > 0 => ArgValue::Number(n),
+                    _ => return Err(ArgError::Invalid(None)),
+                },
+                ArgKind::Rest => {
+                    let rest = words[pos..].join(" ");
+                    pos = words.len();
+                    parsed.values.push((arg.name, ArgValue::Text(rest)));
+                    continue;
+                }
+                ArgKind::Keyword(_) | ArgKind::Choice(_) => unreachable!(),
+            };
+
+            parsed.values.push((arg.name, value));
+            pos += 1;
+        }
+
+        Ok(parsed)
+    }
+}
+
+/// Every command understood over SMS
+pub static COMMANDS: &[CommandSpec] = &[
+    CommandSpec {
+        keywords: &["JOIN", "START", "REGISTER"],
+        args: &[ArgSpec::optional("name", ArgKind::Lower)],
+        example: Some("JOIN alice"),
+        summary: Some(Msg::HelpJoin),
+        build: |args| Command::Join { ens_name: args.opt("name") },
+    },
+    CommandSpec {
+        keywords: &["BALANCE", "BAL"],
+        args: &[],
+        example: None,
+        summary: Some(Msg::HelpBalance),
+        build: |_| Command::Balance,
+    },
+    CommandSpec {
+        keywords: &["PIN"],
+        args: &[
+            ArgSpec::optional("pin", ArgKind::Word),
+            ArgSpec::optional("new_pin", ArgKind::Word),
+        ],
+        example: Some("PIN 1234"),
+        summary: Some(Msg::HelpPin),
+        build: |args| match args.opt("new_pin") {
+            Some(new_pin) => Command::ChangePin { old_pin: args.text("pin"), new_pin },
+            None => Command::Pin { new_pin: args.opt("pin") },
+        },
+    },
+    CommandSpec {
+        keywords: &["YES", "CONFIRM"],
+        args: &[ArgSpec::required("pin", ArgKind::Word)],
+        example: Some("YES 1234"),
+        summary: None,
+        build: |args| Command::Confirm { pin: args.text("pin") },
+    },
+    CommandSpec {
+        keywords: &["NO", "CANCEL"],
+        args: &[],
+        example: None,
+        summary: None,
+        build: |_| Command::Cancel,
+    },
+    CommandSpec {
+        keywords: &["SEND"],
+        args: &[
+            ArgSpec::prompted("amount", ArgKind::Amount, Msg::AskAmount),
+            ArgSpec::prompted("token", ArgKind::Word, Msg::AskToken),
+            ArgSpec::keyword("TO"),
+            ArgSpec::prompted("recipient", ArgKind::Rest, Msg::AskRecipient),
+        ],
+        example: Some("SEND 10 TXTC swarnim.ttcip.eth"),
+        summary: Some(Msg::HelpSend),
+        build: |args| {
+            let (recipient, schedule) = split_schedule(&args.text("recipient"));
+            match schedule {
+                Some(schedule) => Command::ScheduleSend {
+                    amount: args.amount("amount"),
+                    token: args.text("token"),
+                    recipient,
+                    schedule,
+                },
+                None => Command::Send {
+                    amount: args.amount("amount"),
+                    token: args.text("token"),
+                    recipient,
+                },
+            }
+        },
+    },
+    CommandSpec {
+        keywords: &["SPLIT"],
+        args: &[
+            ArgSpec::required("amount", ArgKind::Amount),
+            ArgSpec::required("token", ArgKind::Word),
+            ArgSpec::keyword("TO"),
+            ArgSpec::required("recipients", ArgKind::Rest),
+        ],
+        example: Some("SPLIT 30 TXTC alice bob +254700000000"),
+        summary: Some(Msg::HelpSplit),
+        build: |args| Command::Split {
+            amount: args.amount("amount"),
+            token: args.text("token"),
+            recipients: args.text("recipients"),
+        },
+    },
+    CommandSpec {
+        keywords: &["REQUEST", "ASK"],
+        args: &[
+            ArgSpec::prompted("amount", ArgKind::Amount, Msg::AskAmount),
+            ArgSpec::prompted("token", ArgKind::Word, Msg::AskToken),
+            ArgSpec::keyword("FROM"),
+            ArgSpec::prompted("payer", ArgKind::Rest, Msg::AskPayer),
+        ],
+        example: Some("REQUEST 20 TXTC FROM alice"),
+        summary: Some(Msg::HelpRequest),
+        build: |args| Command::Request {
+            amount: args.amount("amount"),
+            token: args.text("token"),
+            payer: args.text("payer"),
+        },
+    },
+    CommandSpec {
+        keywords: &["PAY"],
+        args: &[
+            ArgSpec::required("code", ArgKind::Upper),
+            ArgSpec::optional("pin", ArgKind::Word),
+        ],
+        example: Some("PAY ABC234 1234"),
+        summary: None,
+        build: |args| Command::Pay { code: args.text("code"), pin: args.opt("pin") },
+    },
+    CommandSpec {
+        keywords: &["DECLINE", "REJECT"],
+        args: &[ArgSpec::required("code", ArgKind::Upper)],
+        example: Some("DECLINE ABC234"),
+        summary: None,
+        build: |args| Command::Decline { code: args.text("code") },
+    },
+    CommandSpec {
+        keywords: &["SCHEDULES", "SCHEDULED"],
+        args: &[],
+        example: None,
+        summary: Some(Msg::HelpSchedules),
+        build: |_| Command::Schedules,
+    },
+    CommandSpec {
+        keywords: &["STOP", "UNSCHEDULE"],
+        args: &[ArgSpec::required("id", ArgKind::Upper)],
+        example: Some("STOP ABC234"),
+        summary: None,
+        build: |args| Command::Stop { code: args.text("id") },
+    },
+    CommandSpec {
+        keywords: &["DEPOSIT", "RECEIVE"],
+        args: &[],
+        example: None,
+        summary: Some(Msg::HelpDeposit),
+        build: |_| Command::Deposit,
+    },
+    CommandSpec {
+        keywords: &["HISTORY", "TRANSACTIONS", "TXS"],
+        args: &[
+            ArgSpec::optional("filter", ArgKind::Choice(&["SENT", "RECEIVED", "ALL"])),
+            ArgSpec::optional("page", ArgKind::Number),
+        ],
+        example: Some("HISTORY SENT 2"),
+        summary: Some(Msg::HelpHistory),
+        build: |args| Command::History {
+            filter: args.opt("filter").and_then(|f| HistoryFilter::from_input(&f)).unwrap_or_default(),
+            page: args.number("page").unwrap_or(1),
+        },
+    },
+    CommandSpec {
+        keywords: &["STATUS", "TRACK"],
+        args: &[ArgSpec::required("ref", ArgKind::Upper)],
+        example: Some("STATUS ABC234"),
+        summary: Some(Msg::HelpStatus),
+        build: |args| Command::Status { reference: args.text("ref") },
+    },
+    CommandSpec {
+        keywords: &["REDEEM", "VOUCHER", "CODE"],
+        args: &[ArgSpec::required("code", ArgKind::Upper)],
+        example: Some("REDEEM TTC123456"),
+        summary: Some(Msg::HelpRedeem),
+        build: |args| Command::Redeem { code: args.text("code") },
+    },
+    CommandSpec {
+        keywords: &["SWAP", "EXCHANGE"],
+        args: &[
+            ArgSpec::required("amount", ArgKind::Amount),
+            ArgSpec::required("token", ArgKind::Choice(&["TXTC"])),
+        ],
+        example: Some("SWAP 10 TXTC"),
+        summary: Some(Msg::HelpSwap),
+        build: |args| Command::Swap {
+            amount: args.amount("amount"),
+            token: args.text("token"),
+        },
+    },
+    CommandSpec {
+        keywords: &["QUOTE", "PRICE"],
+        args: &[
+            ArgSpec::required("amount", ArgKind::Amount),
+            ArgSpec::required("token", ArgKind::Choice(&["TXTC"])),
+        ],
+        example: Some("QUOTE 10 TXTC"),
+        summary: Some(Msg::HelpQuote),
+        build: |args| Command::Quote {
+            amount: args.amount("amount"),
+            token: args.text("token"),
+        },
+    },
+    CommandSpec {
+        keywords: &["SLIPPAGE"],
+        args: &[ArgSpec::optional("percent", ArgKind::Word)],
+        example: Some("SLIPPAGE 1%"),
+        summary: Some(Msg::HelpSlippage),
+        build: |args| Command::Slippage { percent: args.opt("percent") },
+    },
+    CommandSpec {
+        keywords: &["CASHOUT", "WITHDRAW"],
+        args: &[
+            ArgSpec::required("amount", ArgKind::Amount),
+            ArgSpec::required("token", ArgKind::Upper),
+            ArgSpec::optional("method", ArgKind::Upper),
+        ],
+        example: Some("CASHOUT 10 TXTC MPESA"),
+        summary: Some(Msg::HelpCashout),
+        build: |args| Command::Cashout {
+            amount: args.amount("amount"),
+            token: args.text("token"),
+            method: args.opt("method"),
+        },
+    },
+    CommandSpec {
+        keywords: &["LIMIT", "LIMITS"],
+        args: &[
+            ArgSpec::optional("token", ArgKind::Choice(&["TXTC", "ETH", "WETH", "USDC", "USDT", "DAI", "MATIC", "POL"])),
+            ArgSpec::optional("period", ArgKind::Choice(&["TX", "DAY", "WEEK"])),
+            ArgSpec::optional("amount", ArgKind::Amount),
+            ArgSpec::optional("pin", ArgKind::Word),
+        ],
+        example: Some("LIMIT TXTC DAY 500"),
+        summary: Some(Msg::HelpLimit),
+        build: |args| {
+            let token = args.opt("token").unwrap_or_else(|| "TXTC".to_string());
+            let period = args.opt("period").and_then(|p| LimitPeriod::from_input(&p));
+            match (period, args.opt_amount("amount")) {
+                (Some(period), Some(amount)) => Command::SetLimit { token, period, amount, pin: args.opt("pin") },
+                _ => Command::Limits { token },
+            }
+        },
+    },
+    CommandSpec {
+        keywords: &["NEWNUMBER", "MOVE"],
+        args: &[ArgSpec::required("phone", ArgKind::Rest)],
+        example: Some("NEWNUMBER +254711000000"),
+        summary: Some(Msg::HelpNewNumber),
+        build: |args| {
+            let phone = args.text("phone");
+            let cancel = ["CANCEL", "NO"].iter().any(|w| w.eq_ignore_ascii_case(&phone))
+                || keywords::canonical(&phone).is_some_and(|(canonical, _)| canonical == "NO");
+            if cancel {
+                Command::CancelNumberChange
+            } else {
+                Command::NewNumber { phone }
+            }
+        },
+    },
+    CommandSpec {
+        keywords: &["VERIFY"],
+        args: &[
+            ArgSpec::required("code", ArgKind::Word),
+            ArgSpec::required("pin", ArgKind::Word),
+        ],
+        example: Some("VERIFY 123456 1234"),
+        summary: None,
+        build: |args| Command::VerifyNumber { code: args.text("code"), pin: args.text("pin") },
+    },
+    CommandSpec {
+        keywords: &["BRIDGE", "CROSS"],
+        args: &[
+            ArgSpec::required("amount", ArgKind::Amount),
+            ArgSpec::required("token", ArgKind::Upper),
+            ArgSpec::keyword("FROM"),
+            ArgSpec::required("chain", ArgKind::Upper),
+            ArgSpec::keyword("TO"),
+            ArgSpec::required("chain", ArgKind::Upper),
+        ],
+        example: Some("BRIDGE 10 USDC FROM POLYGON TO BASE"),
+        summary: None,
+        build: |args| {
+            let chains = args.all("chain");
+            Command::Bridge {
+                amount: args.amount("amount"),
+                token: args.text("token"),
+                from_chain: chains.first().cloned().unwrap_or_default(),
+                to_chain: chains.get(1).cloned().unwrap_or_default(),
+            }
+        },
+    },
+    CommandSpec {
+        keywords: &["SAVE", "ADD"],
+        args: &[
+            ArgSpec::required("name", ArgKind::Upper),
+            ArgSpec::required("phone|0x...|ens", ArgKind::Rest),
+        ],
+        example: Some("SAVE mom +254700000000"),
+        summary: None,
+        build: |args| Command::Save {
+            name: args.text("name"),
+            target: args.text("phone|0x...|ens"),
+        },
+    },
+    CommandSpec {
+        keywords: &["DELETE", "REMOVE"],
+        args: &[ArgSpec::required("name", ArgKind::Upper)],
+        example: Some("DELETE mom"),
+        summary: None,
+        build: |args| Command::Delete { name: args.text("name") },
+    },
+    CommandSpec {
+        keywords: &["RENAME"],
+        args: &[
+            ArgSpec::required("old", ArgKind::Upper),
+            ArgSpec::keyword("TO"),
+            ArgSpec::required("new", ArgKind::Upper),
+        ],
+        example: Some("RENAME mom TO mama"),
+        summary: None,
+        build: |args| Command::Rename { old_name: args.text("old"), new_name: args.text("new") },
+    },
+    CommandSpec {
+        keywords: &["CONTACTS", "BOOK"],
+        args: &[ArgSpec::optional("page", ArgKind::Number)],
+        example: Some("CONTACTS 2"),
+        summary: Some(Msg::HelpContacts),
+        build: |args| Command::Contacts { page: args.number("page").unwrap_or(1) },
+    },
+    CommandSpec {
+        keywords: &["CHAIN", "NETWORK"],
+        args: &[ArgSpec::required("chain", ArgKind::Upper)],
+        example: Some("CHAIN <polygon|base|eth|arb>"),
+        summary: None,
+        build: |args| Command::SwitchChain { chain: args.text("chain") },
+    },
+    CommandSpec {
+        keywords: &["MORE", "NEXT"],
+        args: &[],
+        example: None,
+        summary: None,
+        build: |_| Command::More,
+    },
+    CommandSpec {
+        keywords: &["LANG", "LANGUAGE"],
+        args: &[ArgSpec::optional("code", ArgKind::Word)],
+        example: Some("LANG sw"),
+        summary: Some(Msg::HelpLang),
+        build: |args| Command::Lang { code: args.opt("code") },
+    },
+    CommandSpec {
+        keywords: &["COMMANDS", "MENU", "?"],
+        args: &[],
+        example: None,
+        summary: Some(Msg::HelpMenu),
+        build: |_| Command::Help,
+    },
+];
+
+/// Words that start a schedule clause at the end of a SEND
+const SCHEDULE_WORDS: &[&str] = &["EVERY", "AT", "ON", "DAILY", "WEEKLY", "MONTHLY"];
+
+/// Longest schedule clause, `EVERY MONTH ON 1`
+const MAX_SCHEDULE_WORDS: usize = 4;
+
+/// Split `mom EVERY MONTH ON 1` into the recipient and the schedule clause.
+///
+/// Only the last few words can be a clause, so a schedule word earlier in
+/// the message stays part of the recipient. A clause that turns out not
+/// to parse is rejected later rather than sent as a one-off.
+fn split_schedule(recipient: &str) -> (String, Option<String>) {
+    let words: Vec<&str> = recipient.split_whitespace().collect();
+    let first = words.len().saturating_sub(MAX_SCHEDULE_WORDS).max(1);
+    let start = (first..words.len()).find(|&i| SCHEDULE_WORDS.iter().any(|k| k.eq_ignore_ascii_case(words[i])));
+
+    match start {
+        Some(i) => (words[..i].join(" "), Some(words[i..].join(" "))),
+        None => (recipient.to_string(), None),
+    }
+}
+
+/// Find the command triggered by an English keyword (case-insensitive)
+pub fn lookup(keyword: &str) -> Option<&'static CommandSpec> {
+    COMMANDS
+        .iter()
+        .find(|spec| spec.keywords.iter().any(|k| k.eq_ignore_ascii_case(keyword)))
+}
+
+/// Find the command triggered by a keyword in any supported language
+pub fn lookup_localized(keyword: &str) -> Option<(&'static CommandSpec, Option<Lang>)> {
+    if let Some(spec) = lookup(keyword) {
+        return Some((spec, None));
+    }
+    let (canonical, lang) = keywords::canonical(keyword)?;
+    lookup(canonical).map(|spec| (spec, Some(lang)))
+}
+
+/// Known keyword within typo distance of what the user sent
+#[derive(Clone, Copy)]
+pub struct Suggestion {
+    pub spec: &'static CommandSpec,
+    /// Keyword as it appears in the tables (English or localized)
+    pub keyword: &'static str,
+    pub distance: usize,
+}
+
+/// Edits tolerated for a keyword of this length. Very short keywords
+/// (NO, SI, ?) are too close to everything to match fuzzily.
+fn max_typo_distance(keyword_len: usize) -> usize {
+    match keyword_len {
+        0..=2 => 0,
+        3..=4 => 1,
+        _ => 2,
+    }
+}
+
+/// Optimal string alignment distance: Levenshtein plus adjacent
+/// transpositions, so SNED is one edit from SEND.
+fn osa_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    d[a.len()][b.len()]
+}
+
+/// Commands whose keyword (in any language) is a near-miss for `input`.
+///
+/// Only the closest matches are returned, one per command, so a single
+/// entry means the correction is unambiguous.
+pub fn suggest(input: &str) -> Vec<Suggestion> {
+    let input = input.to_uppercase();
+    if input.chars().count() < 2 {
+        return Vec::new();
+    }
+
+    let english = COMMANDS
+        .iter()
+        .flat_map(|spec| spec.keywords.iter().map(move |k| (spec, *k)));
+    let localized = keywords::all()
+        .filter_map(|(_, local, canonical)| lookup(canonical).map(|spec| (spec, local)));
+
+    let mut matches: Vec<Suggestion> = english
+        .chain(localized)
+        .filter_map(|(spec, keyword)| {
+            let distance = osa_distance(&input, keyword);
+            (distance > 0 && distance <= max_typo_distance(keyword.chars().count()))
+                .then_some(Suggestion { spec, keyword, distance })
+        })
+        .collect();
+
+    let Some(best) = matches.iter().map(|m| m.distance).min() else {
+        return Vec::new();
+    };
+    matches.retain(|m| m.distance == best);
+
+    let mut seen: Vec<&'static str> = Vec::new();
+    matches.retain(|m| {
+        let name = m.spec.name();
+        if seen.contains(&name) {
+            false
+        } else {
+            seen.push(name);
+            true
+        }
+    });
+    matches
+}
+
+/// Help menu generated from the command table
+pub fn help_text(lang: Lang) -> String {
+    let lines: Vec<String> = COMMANDS
+        .iter()
+        .filter_map(|spec| {
+            spec.summary.map(|summary| {
+                let summary = tr(lang, summary);
+                let name = keywords::localized(lang, spec.name());
+                let required: Vec<String> = spec.args.iter()
+                    .filter(|a| a.required)
+                    .map(|a| format!("<{}>", a.name))
+                    .collect();
+                if required.is_empty() {
+                    format!("{} - {}", name, summary)
+                } else {
+                    format!("{} {} - {}", name, required.join(" "), summary)
+                }
+            })
+        })
+        .collect();
+
+    format!("{}\n{}", tr(lang, Msg::HelpTitle), lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keywords_are_unique() {
+        let mut seen = std::collections::HashSet::new();
+        for spec in COMMANDS {
+            for keyword in spec.keywords {
+                assert!(seen.insert(*keyword), "duplicate keyword {}", keyword);
+            }
+        }
+    }
+
+    #[test]
+    fn test_usage_generated_from_schema() {
+        let send = lookup("send").unwrap();
+        assert_eq!(
+            send.usage(Lang::En),
+            "Usage: SEND <amount> <token> [TO] <recipient>\nExample: SEND 10 TXTC swarnim.ttcip.eth"
+        );
+        assert!(send.usage(Lang::Sw).starts_with("Matumizi: TUMA <amount>"));
+    }
+
+    #[test]
+    fn test_missing_args_prompt() {
+        let send = lookup("SEND").unwrap();
+        assert!(matches!(send.parse_args(&[], Lang::En), Err(ArgError::Missing(a)) if a.name == "amount"));
+        assert_eq!(send.next_prompt(&["10"], Lang::En), Some(Msg::AskToken));
+        assert_eq!(send.next_prompt(&["10", "TXTC", "TO"], Lang::En), Some(Msg::AskRecipient));
+        assert_eq!(send.next_prompt(&["10", "TXTC", "alice"], Lang::En), None);
+        assert!(matches!(send.parse_args(&["ten"], Lang::En), Err(ArgError::Invalid(Some(Msg::InvalidAmount)))));
+
+        // Arguments without a prompt are still usage errors
+        let redeem = lookup("REDEEM").unwrap();
+        assert_eq!(redeem.next_prompt(&[], Lang::En), None);
+    }
+
+    #[test]
+    fn test_split_schedule() {
+        assert_eq!(
+            split_schedule("landlord EVERY MONTH ON 1"),
+            ("landlord".to_string(), Some("EVERY MONTH ON 1".to_string()))
+        );
+        assert_eq!(split_schedule("mom at 2026-11-01"), ("mom".to_string(), Some("at 2026-11-01".to_string())));
+        assert_eq!(split_schedule("swarnim.ttcip.eth"), ("swarnim.ttcip.eth".to_string(), None));
+        // A schedule word before the last few words is not a clause
+        assert_eq!(
+            split_schedule("mom at the shop near home"),
+            ("mom at the shop near home".to_string(), None)
+        );
+        assert_eq!(
+            split_schedule("mom at the shop EVERY DAY"),
+            ("mom at the shop".to_string(), Some("EVERY DAY".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_lookup_localized() {
+        let (spec, lang) = lookup_localized("tuma").unwrap();
+        assert_eq!(spec.name(), "SEND");
+        assert_eq!(lang, Some(Lang::Sw));
+
+        let (spec, lang) = lookup_localized("BAL").unwrap();
+        assert_eq!(spec.name(), "BALANCE");
+        assert_eq!(lang, None);
+    }
+
+    #[test]
+    fn test_osa_distance() {
+        assert_eq!(osa_distance("SEND", "SEND"), 0);
+        assert_eq!(osa_distance("SNED", "SEND"), 1);
+        assert_eq!(osa_distance("BALENCE", "BALANCE"), 1);
+        assert_eq!(osa_distance("REDEM", "REDEEM"), 1);
+        assert_eq!(osa_distance("", "PIN"), 3);
+        assert_eq!(osa_distance("CA", "ABC"), 3);
+    }
+
+    #[test]
+    fn test_suggest_common_typos() {
+        for (typo, expected) in [("BALENCE", "BALANCE"), ("SNED", "SEND"), ("REDEM", "REDEEM"), ("TUAM", "SEND")] {
+            let names: Vec<&str> = suggest(typo).iter().map(|s| s.spec.name()).collect();
+            assert_eq!(names, [expected], "{}", typo);
+        }
+        assert_eq!(suggest("TUAM")[0].keyword, "TUMA");
+    }
+
+    #[test]
+    fn test_suggest_ignores_exact_and_far_words() {
+        assert!(suggest("SEND").is_empty());
+        assert!(suggest("HELLO").is_empty());
+        assert!(suggest("GO").is_empty());
+        assert!(suggest("X").is_empty());
+        assert!(suggest("").is_empty());
+    }
+
+    /// Every alias must be recoverable from a one-edit typo
+    #[test]
+    fn test_suggest_covers_alias_table() {
+        let english = COMMANDS
+            .iter()
+            .flat_map(|spec| spec.keywords.iter().map(move |k| (spec.name(), *k)));
+        let localized = keywords::all().map(|(_, local, canonical)| (canonical, local));
+
+        for (name, keyword) in english.chain(localized) {
+            let chars: Vec<char> = keyword.chars().collect();
+            if max_typo_distance(chars.len()) == 0 {
+                continue;
+            }
+
+            let mut typos = Vec::new();
+            // Adjacent transposition
+            let mut swapped = chars.clone();
+            swapped.swap(0, 1);
+            typos.push(swapped.into_iter().collect::<String>());
+            // Dropped last letter
+            typos.push(chars[..chars.len() - 1].iter().collect::<String>());
+            // Wrong middle letter
+            let mut wrong = chars.clone();
+            let mid = chars.len() / 2;
+            wrong[mid] = if wrong[mid] == 'Q' { 'Z' } else { 'Q' };
+            typos.push(wrong.into_iter().collect::<String>());
+            // Doubled letter
+            let mut doubled = chars.clone();
+            doubled.insert(mid, chars[mid]);
+            typos.push(doubled.into_iter().collect::<String>());
+
+            for typo in typos {
+                if typo == keyword || lookup_localized(&typo).is_some() {
+                    continue;
+                }
+                let names: Vec<&str> = suggest(&typo).iter().map(|s| s.spec.name()).collect();
+                assert!(names.contains(&name), "{} (typo of {}) suggested {:?}", typo, keyword, names);
+            }
+        }
+    }
+
+    #[test]
+    fn test_help_lists_only_real_commands() {
+        let help = help_text(Lang::En);
+        assert!(help.contains("SEND <amount> <token> <recipient> - Send tokens"));
+        assert!(help.contains("MENU") || help.contains("COMMANDS"));
+        assert!(help.contains("CASHOUT <amount> <token> - Cash out to money"));
+
+        let help = help_text(Lang::Sw);
+        assert!(help.contains("TUMA <amount> <token> <recipient> - Tuma tokeni"));
+    }
+}
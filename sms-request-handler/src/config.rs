@@ -2,10 +2,13 @@ use std::env;
 
 #[derive(Debug, Clone)]
 pub struct Config {
-    pub twilio: TwilioConfig,
+    /// Set when TWILIO_ACCOUNT_SID is; other gateways read their own settings
+    pub twilio: Option<TwilioConfig>,
     pub server: ServerConfig,
     pub aa: AaConfig,
     pub admin_private_key: String,
+    /// Run unambiguous typo corrections (SNED -> SEND) instead of asking
+    pub auto_correct_commands: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -34,13 +37,15 @@ impl Config {
         dotenvy::dotenv().ok();
 
         Ok(Config {
-            twilio: TwilioConfig {
-                account_sid: env::var("TWILIO_ACCOUNT_SID")
-                    .map_err(|_| ConfigError::Missing("TWILIO_ACCOUNT_SID"))?,
-                auth_token: env::var("TWILIO_AUTH_TOKEN")
-                    .map_err(|_| ConfigError::Missing("TWILIO_AUTH_TOKEN"))?,
-                phone_number: env::var("TWILIO_PHONE_NUMBER")
-                    .map_err(|_| ConfigError::Missing("TWILIO_PHONE_NUMBER"))?,
+            twilio: match env::var("TWILIO_ACCOUNT_SID") {
+                Ok(account_sid) => Some(TwilioConfig {
+                    account_sid,
+                    auth_token: env::var("TWILIO_AUTH_TOKEN")
+                        .map_err(|_| ConfigError::Missing("TWILIO_AUTH_TOKEN"))?,
+                    phone_number: env::var("TWILIO_PHONE_NUMBER")
+                        .map_err(|_| ConfigError::Missing("TWILIO_PHONE_NUMBER"))?,
+                }),
+                Err(_) => None,
             },
             server: ServerConfig {
                 host: env::var("SERVER_HOST").unwrap_or_else(|_| "0.0.0.0".to_string()),
@@ -55,6 +60,9 @@ impl Config {
                 simple_account_factory_address: env::var("SIMPLE_ACCOUNT_FACTORY_ADDRESS").unwrap_or_else(|_| "".to_string()),
             },
             admin_private_key: env::var("ADMIN_PRIVATE_KEY").unwrap_or_else(|_| "".to_string()),
+            auto_correct_commands: env::var("AUTO_CORRECT_COMMANDS")
+                .map(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes"))
+                .unwrap_or(false),
         })
     }
 
@@ -64,6 +72,22 @@ impl Config {
     }
 }
 
+/// Duration read from a variable named for its unit (`_SECS`, `_MINS` or
+/// `_HOURS`), or `default` when it is unset or not a whole number
+pub fn env_duration(name: &str, default: chrono::Duration) -> chrono::Duration {
+    let Some(value) = env::var(name).ok().and_then(|v| v.trim().parse::<u32>().ok()) else {
+        return default;
+    };
+    let value = i64::from(value);
+    if name.ends_with("_HOURS") {
+        chrono::Duration::hours(value)
+    } else if name.ends_with("_MINS") {
+        chrono::Duration::minutes(value)
+    } else {
+        chrono::Duration::seconds(value)
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum ConfigError {
     #[error("Missing environment variable: {0}")]
@@ -1,6 +1,7 @@
 use sqlx::PgPool;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use crate::sms::{normalize_phone, PhoneNumber};
 
 /// Contact in address book
 #[derive(Debug, Clone, sqlx::FromRow)]
@@ -9,21 +10,80 @@ pub struct Contact {
     pub user_phone: String,      // Owner of this contact
     pub name: String,            // Contact name/label
     pub contact_phone: Option<String>,  // Phone number if known
-    pub wallet_address: Option<String>, // Wallet address if known
+    pub wallet_address: Option<String>, // Wallet address if known (cached for ENS contacts)
+    pub ens_name: Option<String>,       // ENS name if saved by name
     pub created_at: DateTime<Utc>,
 }
 
 impl Contact {
     /// Format for SMS display
     pub fn to_sms_string(&self) -> String {
-        match (&self.contact_phone, &self.wallet_address) {
-            (Some(phone), _) => format!("{}: {}", self.name, phone),
-            (_, Some(addr)) => format!("{}: {}...{}", self.name, &addr[..6], &addr[38..]),
+        match (&self.contact_phone, &self.ens_name, &self.wallet_address) {
+            (Some(phone), _, _) => format!("{}: {}", self.name, phone),
+            (_, Some(ens), _) => format!("{}: {}", self.name, ens),
+            (_, _, Some(addr)) if addr.len() == 42 => format!("{}: {}...{}", self.name, &addr[..6], &addr[38..]),
+            (_, _, Some(addr)) => format!("{}: {}", self.name, addr),
             _ => self.name.clone(),
         }
     }
 }
 
+/// What a contact points at, detected from the SAVE input
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContactTarget {
+    /// `+254700000000`
+    Phone(String),
+    /// `0x` followed by 40 hex digits
+    Wallet(String),
+    /// `alice.ttcip.eth`
+    Ens(String),
+}
+
+impl ContactTarget {
+    /// Detect a phone number, wallet address or ENS name
+    pub fn detect(input: &str) -> Option<ContactTarget> {
+        let input = input.trim();
+
+        if let Some(hex) = input.strip_prefix("0x").or_else(|| input.strip_prefix("0X")) {
+            return (hex.len() == 40 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+                .then(|| ContactTarget::Wallet(format!("0x{}", hex)));
+        }
+
+        if !input.contains(|c: char| c.is_ascii_alphabetic()) {
+            return PhoneNumber::parse(input).map(|phone| ContactTarget::Phone(phone.into()));
+        }
+
+        let ens = input.to_lowercase();
+        let labels_ok = ens.split('.').all(|label| {
+            !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+        (ens.contains('.') && labels_ok).then_some(ContactTarget::Ens(ens))
+    }
+}
+
+/// Result of looking a contact up by name
+#[derive(Debug, Clone)]
+pub enum ContactMatch {
+    None,
+    One(Contact),
+    /// Several partial matches and no exact one
+    Ambiguous(Vec<Contact>),
+}
+
+/// Contact names are 1-30 letters, digits, `_` or `-`, so they can't be
+/// mistaken for a phone number, wallet address or ENS name
+pub fn is_valid_contact_name(name: &str) -> bool {
+    (1..=30).contains(&name.len())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        && !name.to_uppercase().starts_with("0X")
+        && PhoneNumber::parse(name).is_none()
+}
+
+/// Escape LIKE wildcards in user input
+fn like_escape(input: &str) -> String {
+    input.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
 /// Address book repository for database operations
 #[derive(Clone)]
 pub struct AddressBookRepository {
@@ -42,16 +102,18 @@ impl AddressBookRepository {
         name: &str,
         contact_phone: Option<&str>,
         wallet_address: Option<&str>,
+        ens_name: Option<&str>,
     ) -> Result<Contact, sqlx::Error> {
         let id = Uuid::new_v4();
-        
+        let contact_phone = contact_phone.map(normalize_phone);
+
         sqlx::query_as::<_, Contact>(
             r#"
-            INSERT INTO address_book (id, user_phone, name, contact_phone, wallet_address)
-            VALUES ($1, $2, $3, $4, $5)
+            INSERT INTO address_book (id, user_phone, name, contact_phone, wallet_address, ens_name)
+            VALUES ($1, $2, $3, $4, $5, $6)
             ON CONFLICT (user_phone, COALESCE(contact_phone, ''), COALESCE(wallet_address, ''))
-            DO UPDATE SET name = EXCLUDED.name
-            RETURNING id, user_phone, name, contact_phone, wallet_address, created_at
+            DO UPDATE SET name = EXCLUDED.name, ens_name = EXCLUDED.ens_name
+            RETURNING id, user_phone, name, contact_phone, wallet_address, ens_name, created_at
             "#
         )
         .bind(id)
@@ -59,20 +121,61 @@ impl AddressBookRepository {
         .bind(name)
         .bind(contact_phone)
         .bind(wallet_address)
+        .bind(ens_name)
         .fetch_one(&self.pool)
         .await
     }
 
+    /// Save a contact under `name`, replacing what an existing contact of
+    /// that name points at. `wallet_address` is the resolved address for
+    /// ENS targets.
+    pub async fn save(
+        &self,
+        user_phone: &str,
+        name: &str,
+        target: &ContactTarget,
+        wallet_address: Option<&str>,
+    ) -> Result<Contact, sqlx::Error> {
+        let (phone, ens) = match target {
+            ContactTarget::Phone(phone) => (Some(phone.as_str()), None),
+            ContactTarget::Wallet(_) => (None, None),
+            ContactTarget::Ens(ens) => (None, Some(ens.as_str())),
+        };
+        let wallet = match target {
+            ContactTarget::Wallet(addr) => Some(addr.as_str()),
+            _ => wallet_address,
+        };
+
+        let updated = sqlx::query_as::<_, Contact>(
+            "UPDATE address_book
+             SET contact_phone = $3, wallet_address = $4, ens_name = $5
+             WHERE user_phone = $1 AND UPPER(name) = UPPER($2)
+             RETURNING id, user_phone, name, contact_phone, wallet_address, ens_name, created_at"
+        )
+        .bind(user_phone)
+        .bind(name)
+        .bind(phone)
+        .bind(wallet)
+        .bind(ens)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match updated {
+            Some(contact) => Ok(contact),
+            None => self.add_contact(user_phone, name, phone, wallet, ens).await,
+        }
+    }
+
     /// Find contacts by name (partial match)
     pub async fn find_by_name(&self, user_phone: &str, name: &str) -> Result<Vec<Contact>, sqlx::Error> {
         sqlx::query_as::<_, Contact>(
-            "SELECT id, user_phone, name, contact_phone, wallet_address, created_at 
+            "SELECT id, user_phone, name, contact_phone, wallet_address, ens_name, created_at 
              FROM address_book 
              WHERE user_phone = $1 AND UPPER(name) LIKE UPPER($2)
              ORDER BY name"
         )
         .bind(user_phone)
-        .bind(format!("%{}%", name))
+        .bind(format!("%{}%", like_escape(name)))
         .fetch_all(&self.pool)
         .await
     }
@@ -80,29 +183,68 @@ impl AddressBookRepository {
     /// Find contact by phone number
     pub async fn find_by_phone(&self, user_phone: &str, contact_phone: &str) -> Result<Option<Contact>, sqlx::Error> {
         sqlx::query_as::<_, Contact>(
-            "SELECT id, user_phone, name, contact_phone, wallet_address, created_at 
+            "SELECT id, user_phone, name, contact_phone, wallet_address, ens_name, created_at 
              FROM address_book 
              WHERE user_phone = $1 AND contact_phone = $2"
         )
         .bind(user_phone)
-        .bind(contact_phone)
+        .bind(normalize_phone(contact_phone))
         .fetch_optional(&self.pool)
         .await
     }
 
-    /// Get all contacts for a user
-    pub async fn list_all(&self, user_phone: &str) -> Result<Vec<Contact>, sqlx::Error> {
+    /// Find a contact for sending: an exact name wins over partial
+    /// matches, and several partial matches are ambiguous
+    pub async fn lookup(&self, user_phone: &str, name: &str) -> Result<ContactMatch, sqlx::Error> {
+        let mut contacts = self.find_by_name(user_phone, name).await?;
+
+        if let Some(i) = contacts.iter().position(|c| c.name.eq_ignore_ascii_case(name)) {
+            return Ok(ContactMatch::One(contacts.swap_remove(i)));
+        }
+        Ok(match contacts.len() {
+            0 => ContactMatch::None,
+            1 => ContactMatch::One(contacts.remove(0)),
+            _ => ContactMatch::Ambiguous(contacts),
+        })
+    }
+
+    /// One page of a user's contacts, by name
+    pub async fn page(&self, user_phone: &str, limit: i64, offset: i64) -> Result<Vec<Contact>, sqlx::Error> {
         sqlx::query_as::<_, Contact>(
-            "SELECT id, user_phone, name, contact_phone, wallet_address, created_at 
+            "SELECT id, user_phone, name, contact_phone, wallet_address, ens_name, created_at 
              FROM address_book 
              WHERE user_phone = $1 
-             ORDER BY name"
+             ORDER BY name LIMIT $2 OFFSET $3"
         )
         .bind(user_phone)
+        .bind(limit)
+        .bind(offset)
         .fetch_all(&self.pool)
         .await
     }
 
+    /// Number of contacts a user has
+    pub async fn count(&self, user_phone: &str) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM address_book WHERE user_phone = $1")
+            .bind(user_phone)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    /// Rename a contact, returning false if there was none by that name
+    pub async fn rename(&self, user_phone: &str, old_name: &str, new_name: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE address_book SET name = $3 WHERE user_phone = $1 AND UPPER(name) = UPPER($2)"
+        )
+        .bind(user_phone)
+        .bind(old_name)
+        .bind(new_name)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     /// Delete a contact
     pub async fn delete(&self, user_phone: &str, name: &str) -> Result<bool, sqlx::Error> {
         let result = sqlx::query(
@@ -118,16 +260,72 @@ impl AddressBookRepository {
 
     /// Resolve a recipient - could be a name, phone, or address
     pub async fn resolve_recipient(&self, user_phone: &str, input: &str) -> Option<String> {
-        // If it looks like a phone number or address, return as-is
-        if input.starts_with('+') || input.starts_with("0x") {
+        // If it looks like a phone number or address, return it
+        if let Some(phone) = PhoneNumber::parse(input) {
+            return Some(phone.into());
+        }
+        if input.starts_with("0x") {
             return Some(input.to_string());
         }
 
         // Try to find in address book by name
-        let contacts = self.find_by_name(user_phone, input).await.ok()?;
-        
-        contacts.first().and_then(|c| {
-            c.contact_phone.clone().or(c.wallet_address.clone())
-        })
+        match self.lookup(user_phone, input).await.ok()? {
+            ContactMatch::One(c) => c.contact_phone.or(c.wallet_address),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_target() {
+        assert_eq!(ContactTarget::detect("+254 700-000000"), Some(ContactTarget::Phone("+254700000000".to_string())));
+        // Local numbers are read in the default region, Kenya
+        assert_eq!(ContactTarget::detect("0712 345678"), Some(ContactTarget::Phone("+254712345678".to_string())));
+        assert_eq!(
+            ContactTarget::detect("0xAbC0000000000000000000000000000000000001"),
+            Some(ContactTarget::Wallet("0xAbC0000000000000000000000000000000000001".to_string()))
+        );
+        assert_eq!(ContactTarget::detect("Alice.ttcip.eth"), Some(ContactTarget::Ens("alice.ttcip.eth".to_string())));
+        for bad in ["+12", "0x1234", "alice", "alice..eth", "+2547abc0000"] {
+            assert_eq!(ContactTarget::detect(bad), None, "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_contact_names() {
+        assert!(is_valid_contact_name("MOM"));
+        assert!(is_valid_contact_name("LAND_LORD-2"));
+        assert!(!is_valid_contact_name("A.B"));
+        assert!(!is_valid_contact_name("+254700000000"));
+        assert!(!is_valid_contact_name("0712345678"));
+        assert!(is_valid_contact_name("2024"));
+        assert!(!is_valid_contact_name("0XABC"));
+        assert!(!is_valid_contact_name(&"A".repeat(31)));
+    }
+
+    #[test]
+    fn test_like_escape() {
+        assert_eq!(like_escape("50%_off"), "50\\%\\_off");
+    }
+
+    #[test]
+    fn test_sms_string() {
+        let contact = Contact {
+            id: Uuid::nil(),
+            user_phone: "+15550001111".to_string(),
+            name: "ALICE".to_string(),
+            contact_phone: None,
+            wallet_address: Some("0xabc0000000000000000000000000000000000001".to_string()),
+            ens_name: Some("alice.ttcip.eth".to_string()),
+            created_at: Utc::now(),
+        };
+        assert_eq!(contact.to_sms_string(), "ALICE: alice.ttcip.eth");
+
+        let contact = Contact { ens_name: None, ..contact };
+        assert_eq!(contact.to_sms_string(), "ALICE: 0xabc0...0001");
     }
 }
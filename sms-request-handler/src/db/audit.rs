@@ -0,0 +1,29 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Audit log repository: security-relevant events kept for review
+#[derive(Clone)]
+pub struct AuditRepository {
+    pool: PgPool,
+}
+
+impl AuditRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record an event, e.g. `webhook_rejected` for gateway `twilio`
+    pub async fn record(&self, event: &str, subject: &str, detail: &str, source: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO audit_log (id, event, subject, detail, source) VALUES ($1, $2, $3, $4, $5)"
+        )
+        .bind(Uuid::new_v4())
+        .bind(event)
+        .bind(subject)
+        .bind(detail)
+        .bind(source)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
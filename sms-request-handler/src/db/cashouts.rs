@@ -0,0 +1,205 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use serde::{Deserialize, Serialize};
+use crate::wallet::Amount;
+
+/// Values for a new cashout, as quoted when CASHOUT was staged
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewCashout {
+    pub amount: Amount,
+    pub token: String,
+    /// Provider key, e.g. "MPESA"
+    pub provider: String,
+    pub payout: Amount,
+    pub currency: String,
+}
+
+/// Cashout record in database
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Cashout {
+    pub reference: String,
+    pub user_phone: String,
+    pub provider: String,
+    pub amount: String,       // tokens taken from the user, e.g. "10"
+    pub token: String,
+    pub payout: String,       // paid out by the provider, e.g. "1282"
+    pub currency: String,
+    pub payout_id: Option<String>,  // provider's own reference
+}
+
+impl Cashout {
+    /// The cashout as it was quoted, if readable
+    pub fn details(&self) -> Option<NewCashout> {
+        Some(NewCashout {
+            amount: Amount::parse(&self.amount).ok()?,
+            token: self.token.clone(),
+            provider: self.provider.clone(),
+            payout: Amount::parse(&self.payout).ok()?,
+            currency: self.currency.clone(),
+        })
+    }
+}
+
+/// Cashout repository for database operations. A cashout's `status` is
+/// one of "debit_pending", "pending", "paid", "failed", "refunding",
+/// "refunded" or "refund_failed".
+#[derive(Clone)]
+pub struct CashoutRepository {
+    pool: PgPool,
+}
+
+impl CashoutRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Store a confirmed cashout before any funds move
+    pub async fn create(&self, phone: &str, reference: &str, cashout: &NewCashout) -> Result<Cashout, sqlx::Error> {
+        let id = Uuid::new_v4();
+
+        sqlx::query_as::<_, Cashout>(
+            r#"
+            INSERT INTO cashouts (id, reference, user_phone, provider, amount, token, payout, currency, status)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, 'pending')
+            RETURNING reference, user_phone, provider, amount, token, payout, currency, payout_id
+            "#
+        )
+        .bind(id)
+        .bind(reference)
+        .bind(phone)
+        .bind(&cashout.provider)
+        .bind(cashout.amount.to_string())
+        .bind(&cashout.token)
+        .bind(cashout.payout.to_string())
+        .bind(&cashout.currency)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Remember the provider's reference for a payout it accepted
+    pub async fn set_payout_id(&self, reference: &str, payout_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE cashouts SET payout_id = $1, updated_at = NOW() WHERE reference = $2")
+            .bind(payout_id)
+            .bind(reference)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Close a pending cashout; false if it was already closed
+    pub async fn finish(&self, reference: &str, status: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE cashouts SET status = $1, updated_at = NOW()
+             WHERE reference = $2 AND status = 'pending'"
+        )
+        .bind(status)
+        .bind(reference)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Claim a pending cashout for refunding; false if it was already closed
+    pub async fn start_refund(&self, reference: &str) -> Result<bool, sqlx::Error> {
+        self.finish(reference, "refunding").await
+    }
+
+    /// Record whether the refund went through
+    pub async fn end_refund(&self, reference: &str, refunded: bool) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE cashouts SET status = $1, updated_at = NOW()
+             WHERE reference = $2 AND status = 'refunding'"
+        )
+        .bind(if refunded { "refunded" } else { "refund_failed" })
+        .bind(reference)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Hold a cashout whose debit the backend hasn't confirmed yet; it is
+    /// paid out once it has
+    pub async fn hold_for_debit(&self, reference: &str) -> Result<bool, sqlx::Error> {
+        self.finish(reference, "debit_pending").await
+    }
+
+    /// Release a held cashout once its debit settles: back to pending to be
+    /// paid out, or failed. False if another worker got there first.
+    pub async fn end_debit(&self, reference: &str, debited: bool) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE cashouts SET status = $1, updated_at = NOW()
+             WHERE reference = $2 AND status = 'debit_pending'"
+        )
+        .bind(if debited { "pending" } else { "failed" })
+        .bind(reference)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Cashouts waiting on their debit, least recently checked first
+    pub async fn find_debit_pending(&self, limit: i64) -> Result<Vec<Cashout>, sqlx::Error> {
+        sqlx::query_as::<_, Cashout>(
+            "SELECT reference, user_phone, provider, amount, token, payout, currency, payout_id
+             FROM cashouts
+             WHERE status = 'debit_pending'
+             ORDER BY updated_at LIMIT $1"
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Payouts not yet settled, least recently checked first: those a
+    /// provider accepted, and those whose payout request got no answer.
+    /// The latter are left a minute so a request still running can finish.
+    pub async fn find_awaiting(&self, limit: i64) -> Result<Vec<Cashout>, sqlx::Error> {
+        sqlx::query_as::<_, Cashout>(
+            "SELECT reference, user_phone, provider, amount, token, payout, currency, payout_id
+             FROM cashouts
+             WHERE status = 'pending'
+               AND (payout_id IS NOT NULL OR updated_at < NOW() - INTERVAL '1 minute')
+             ORDER BY updated_at LIMIT $1"
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Mark a pending payout as checked, so the next poll starts elsewhere
+    pub async fn touch(&self, reference: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE cashouts SET updated_at = NOW() WHERE reference = $1")
+            .bind(reference)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cashout_details() {
+        let cashout = Cashout {
+            reference: "ABC234".to_string(),
+            user_phone: "+254700000000".to_string(),
+            provider: "MPESA".to_string(),
+            amount: "10".to_string(),
+            token: "TXTC".to_string(),
+            payout: "1282".to_string(),
+            currency: "KES".to_string(),
+            payout_id: Some("PAY1".to_string()),
+        };
+        let details = cashout.details().unwrap();
+        assert_eq!(details.amount, Amount::parse("10").unwrap());
+        assert_eq!(details.payout, Amount::parse("1282").unwrap());
+        assert_eq!(details.provider, "MPESA");
+
+        let broken = Cashout { payout: "lots".to_string(), ..cashout };
+        assert!(broken.details().is_none());
+    }
+}
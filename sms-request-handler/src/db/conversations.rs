@@ -0,0 +1,119 @@
+use sqlx::PgPool;
+use chrono::{Duration, Utc};
+use crate::i18n::Lang;
+
+/// Command being filled in over several messages, one prompt at a time
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Conversation {
+    pub command: String,      // canonical keyword, e.g. "SEND"
+    pub args: String,         // words collected so far, space separated
+    pub lang: String,
+}
+
+impl Conversation {
+    /// Words collected so far
+    pub fn words(&self) -> Vec<&str> {
+        self.args.split_whitespace().collect()
+    }
+
+    /// Language the prompts were sent in
+    pub fn lang(&self) -> Lang {
+        Lang::from_input(&self.lang).unwrap_or_default()
+    }
+}
+
+/// Conversation repository for database operations.
+///
+/// One row per phone number, so every handler instance sees the same
+/// dialog and it survives restarts.
+#[derive(Clone)]
+pub struct ConversationRepository {
+    pool: PgPool,
+}
+
+impl ConversationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Start or advance the dialog for `phone`, replacing any other one
+    pub async fn save(
+        &self,
+        phone: &str,
+        command: &str,
+        args: &str,
+        lang: Lang,
+        ttl: Duration,
+    ) -> Result<Conversation, sqlx::Error> {
+        sqlx::query_as::<_, Conversation>(
+            r#"
+            INSERT INTO conversations (user_phone, command, args, lang, expires_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, NOW())
+            ON CONFLICT (user_phone) DO UPDATE
+            SET command = EXCLUDED.command, args = EXCLUDED.args, lang = EXCLUDED.lang,
+                expires_at = EXCLUDED.expires_at, updated_at = NOW()
+            RETURNING command, args, lang
+            "#
+        )
+        .bind(phone)
+        .bind(command)
+        .bind(args)
+        .bind(lang.code())
+        .bind(Utc::now() + ttl)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Get the unexpired dialog for a user, if any
+    pub async fn find_active(&self, phone: &str) -> Result<Option<Conversation>, sqlx::Error> {
+        sqlx::query_as::<_, Conversation>(
+            "SELECT command, args, lang
+             FROM conversations
+             WHERE user_phone = $1 AND expires_at > NOW()"
+        )
+        .bind(phone)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// End the dialog for a user; returns false if there was none
+    pub async fn clear(&self, phone: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "DELETE FROM conversations WHERE user_phone = $1 AND expires_at > NOW()"
+        )
+        .bind(phone)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Drop dialogs that timed out
+    pub async fn delete_expired(&self) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM conversations WHERE expires_at <= NOW()")
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversation_words_and_lang() {
+        let conversation = Conversation {
+            command: "SEND".to_string(),
+            args: "10  TXTC".to_string(),
+            lang: "sw".to_string(),
+        };
+        assert_eq!(conversation.words(), ["10", "TXTC"]);
+        assert_eq!(conversation.lang(), Lang::Sw);
+
+        let empty = Conversation { args: String::new(), lang: "xx".to_string(), ..conversation };
+        assert!(empty.words().is_empty());
+        assert_eq!(empty.lang(), Lang::default());
+    }
+}
@@ -0,0 +1,99 @@
+use sqlx::PgPool;
+use chrono::{Duration, Utc};
+
+/// Outcome of taking in a message
+#[derive(Debug)]
+pub enum InboundClaim {
+    /// First time this message is seen: process it
+    New,
+    /// Seen within the window: don't process it again. `reply` is what it
+    /// got, once processed.
+    Duplicate { reply: Option<String> },
+}
+
+/// Inbound message repository.
+///
+/// Gateways retry webhooks they think failed, so each message is recorded
+/// under the gateway's own ID and a retry is recognised instead of running
+/// the command twice.
+#[derive(Clone)]
+pub struct InboundMessageRepository {
+    pool: PgPool,
+}
+
+impl InboundMessageRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record a message, unless the same ID arrived less than `window` ago.
+    ///
+    /// The insert is a single statement, so two deliveries racing each
+    /// other can't both come back `New`.
+    pub async fn claim(
+        &self,
+        gateway: &str,
+        message_id: &str,
+        from_phone: &str,
+        body: &str,
+        window: Duration,
+    ) -> Result<InboundClaim, sqlx::Error> {
+        let claimed = sqlx::query_scalar::<_, String>(
+            r#"
+            INSERT INTO inbound_messages (gateway, message_id, from_phone, body)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (gateway, message_id) DO UPDATE
+            SET from_phone = EXCLUDED.from_phone, body = EXCLUDED.body, reply = NULL,
+                created_at = NOW(), completed_at = NULL
+            WHERE inbound_messages.created_at < $5
+            RETURNING message_id
+            "#
+        )
+        .bind(gateway)
+        .bind(message_id)
+        .bind(from_phone)
+        .bind(body)
+        .bind(Utc::now() - window)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        if claimed.is_some() {
+            return Ok(InboundClaim::New);
+        }
+
+        let reply = sqlx::query_scalar::<_, Option<String>>(
+            "SELECT reply FROM inbound_messages WHERE gateway = $1 AND message_id = $2"
+        )
+        .bind(gateway)
+        .bind(message_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(InboundClaim::Duplicate { reply })
+    }
+
+    /// Store the reply a message got, for retries to be answered with
+    pub async fn complete(&self, gateway: &str, message_id: &str, reply: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE inbound_messages SET reply = $3, completed_at = NOW()
+             WHERE gateway = $1 AND message_id = $2"
+        )
+        .bind(gateway)
+        .bind(message_id)
+        .bind(reply)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Drop messages too old to be retried; returns how many were removed
+    pub async fn delete_older_than(&self, window: Duration) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM inbound_messages WHERE created_at < $1")
+            .bind(Utc::now() - window)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
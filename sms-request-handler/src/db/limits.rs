@@ -0,0 +1,295 @@
+use sqlx::PgPool;
+use chrono::{DateTime, Duration, Utc};
+use crate::i18n::{tr, Lang, Msg};
+use crate::wallet::Amount;
+
+/// What a spending limit caps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitPeriod {
+    /// Any single payment
+    PerTx,
+    /// Total over the last 24 hours
+    Daily,
+    /// Total over the last 7 days
+    Weekly,
+}
+
+impl LimitPeriod {
+    /// Parse the period word of LIMIT: `TX`, `DAY` or `WEEK`
+    pub fn from_input(input: &str) -> Option<LimitPeriod> {
+        match input.to_uppercase().as_str() {
+            "TX" | "PER_TX" => Some(LimitPeriod::PerTx),
+            "DAY" | "DAILY" => Some(LimitPeriod::Daily),
+            "WEEK" | "WEEKLY" => Some(LimitPeriod::Weekly),
+            _ => None,
+        }
+    }
+
+    /// Word users type for this period
+    pub fn keyword(self) -> &'static str {
+        match self {
+            LimitPeriod::PerTx => "TX",
+            LimitPeriod::Daily => "DAY",
+            LimitPeriod::Weekly => "WEEK",
+        }
+    }
+
+    /// Rolling window the ledger is summed over; None for a single payment
+    pub fn window(self) -> Option<Duration> {
+        match self {
+            LimitPeriod::PerTx => None,
+            LimitPeriod::Daily => Some(Duration::hours(24)),
+            LimitPeriod::Weekly => Some(Duration::days(7)),
+        }
+    }
+
+    /// Name of the period in replies
+    pub fn label(self, lang: Lang) -> String {
+        match self {
+            LimitPeriod::PerTx => tr(lang, Msg::LimitPerTxLabel),
+            LimitPeriod::Daily => tr(lang, Msg::LimitDailyLabel),
+            LimitPeriod::Weekly => tr(lang, Msg::LimitWeeklyLabel),
+        }
+    }
+}
+
+impl std::fmt::Display for LimitPeriod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LimitPeriod::PerTx => write!(f, "per_tx"),
+            LimitPeriod::Daily => write!(f, "daily"),
+            LimitPeriod::Weekly => write!(f, "weekly"),
+        }
+    }
+}
+
+/// Per-payment, daily and weekly caps on one token
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limits {
+    pub per_tx: Amount,
+    pub daily: Amount,
+    pub weekly: Amount,
+}
+
+impl Limits {
+    /// System defaults for a token.
+    ///
+    /// `SPEND_LIMIT_<TOKEN>=per_tx/daily/weekly` (e.g.
+    /// `SPEND_LIMIT_TXTC=1000/5000/20000`) overrides the built-in values;
+    /// unknown tokens get the stablecoin defaults.
+    pub fn defaults(token: &str) -> Limits {
+        let token = token.to_uppercase();
+        if let Some(limits) = std::env::var(format!("SPEND_LIMIT_{}", token)).ok().and_then(|v| Limits::parse(&v)) {
+            return limits;
+        }
+
+        let values = match token.as_str() {
+            "TXTC" => "1000/5000/20000",
+            "ETH" | "WETH" => "0.25/1/2",
+            "MATIC" | "POL" => "1000/4000/10000",
+            _ => "500/2000/5000",
+        };
+        Limits::parse(values).expect("built-in limits parse")
+    }
+
+    /// Parse `per_tx/daily/weekly`
+    pub fn parse(input: &str) -> Option<Limits> {
+        let values: Vec<Amount> = input.split('/').map(|v| Amount::parse(v).ok()).collect::<Option<_>>()?;
+        match values.as_slice() {
+            [per_tx, daily, weekly] => Some(Limits { per_tx: *per_tx, daily: *daily, weekly: *weekly }),
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, period: LimitPeriod) -> Amount {
+        match period {
+            LimitPeriod::PerTx => self.per_tx,
+            LimitPeriod::Daily => self.daily,
+            LimitPeriod::Weekly => self.weekly,
+        }
+    }
+
+    fn set(&mut self, period: LimitPeriod, amount: Amount) {
+        match period {
+            LimitPeriod::PerTx => self.per_tx = amount,
+            LimitPeriod::Daily => self.daily = amount,
+            LimitPeriod::Weekly => self.weekly = amount,
+        }
+    }
+}
+
+/// A raise waiting out its cooling-off period
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PendingRaise {
+    pub period: LimitPeriod,
+    pub amount: Amount,
+    pub from: DateTime<Utc>,
+}
+
+/// A limit a user set for themselves, in place of the default
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct SpendingLimit {
+    pub period: String,                        // "per_tx", "daily" or "weekly"
+    pub amount: String,                        // limit in force, e.g. "500"
+    pub pending_amount: Option<String>,        // raise waiting out its cooling-off
+    pub pending_from: Option<DateTime<Utc>>,   // when the raise applies
+}
+
+impl SpendingLimit {
+    pub fn period(&self) -> Option<LimitPeriod> {
+        LimitPeriod::from_input(&self.period)
+    }
+
+    /// Limit in force at `now`: the raise once its cooling-off is over
+    pub fn effective(&self, now: DateTime<Utc>) -> Option<Amount> {
+        match (self.pending_raise(), self.pending_from) {
+            (Some(raise), Some(from)) if from <= now => Some(raise),
+            _ => Amount::parse(&self.amount).ok(),
+        }
+    }
+
+    /// Raise still cooling off at `now`
+    pub fn waiting(&self, now: DateTime<Utc>) -> Option<PendingRaise> {
+        let from = self.pending_from.filter(|from| *from > now)?;
+        Some(PendingRaise { period: self.period()?, amount: self.pending_raise()?, from })
+    }
+
+    fn pending_raise(&self) -> Option<Amount> {
+        self.pending_amount.as_deref().and_then(|a| Amount::parse(a).ok())
+    }
+}
+
+/// A user's limits on `token` at `now`: their own limits over the
+/// defaults, and the raises still cooling off
+pub fn user_limits(token: &str, rows: &[SpendingLimit], now: DateTime<Utc>) -> (Limits, Vec<PendingRaise>) {
+    let mut limits = Limits::defaults(token);
+    let mut waiting = Vec::new();
+    for row in rows {
+        let Some(period) = row.period() else { continue };
+        if let Some(amount) = row.effective(now) {
+            limits.set(period, amount);
+        }
+        waiting.extend(row.waiting(now));
+    }
+    (limits, waiting)
+}
+
+/// Spending limit repository for database operations
+#[derive(Clone)]
+pub struct SpendingLimitRepository {
+    pool: PgPool,
+}
+
+impl SpendingLimitRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// A user's own limits on a token
+    pub async fn find(&self, phone: &str, token: &str) -> Result<Vec<SpendingLimit>, sqlx::Error> {
+        sqlx::query_as::<_, SpendingLimit>(
+            "SELECT period, amount, pending_amount, pending_from
+             FROM spending_limits
+             WHERE user_phone = $1 AND token = $2"
+        )
+        .bind(phone)
+        .bind(token.to_uppercase())
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Set a limit now, dropping any raise still cooling off
+    pub async fn lower(&self, phone: &str, token: &str, period: LimitPeriod, amount: Amount) -> Result<(), sqlx::Error> {
+        self.save(phone, token, period, amount, None).await
+    }
+
+    /// Keep `current` in force and apply `raise` from the given time
+    pub async fn raise(
+        &self,
+        phone: &str,
+        token: &str,
+        period: LimitPeriod,
+        current: Amount,
+        raise: (Amount, DateTime<Utc>),
+    ) -> Result<(), sqlx::Error> {
+        self.save(phone, token, period, current, Some(raise)).await
+    }
+
+    async fn save(
+        &self,
+        phone: &str,
+        token: &str,
+        period: LimitPeriod,
+        amount: Amount,
+        raise: Option<(Amount, DateTime<Utc>)>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO spending_limits (user_phone, token, period, amount, pending_amount, pending_from)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (user_phone, token, period) DO UPDATE
+            SET amount = $4, pending_amount = $5, pending_from = $6, updated_at = NOW()
+            "#
+        )
+        .bind(phone)
+        .bind(token.to_uppercase())
+        .bind(period.to_string())
+        .bind(amount.to_string())
+        .bind(raise.map(|(amount, _)| amount.to_string()))
+        .bind(raise.map(|(_, from)| from))
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn amount(s: &str) -> Amount {
+        Amount::parse(s).unwrap()
+    }
+
+    fn row(period: &str, value: &str, raise: Option<(&str, DateTime<Utc>)>) -> SpendingLimit {
+        SpendingLimit {
+            period: period.to_string(),
+            amount: value.to_string(),
+            pending_amount: raise.map(|(a, _)| a.to_string()),
+            pending_from: raise.map(|(_, from)| from),
+        }
+    }
+
+    #[test]
+    fn test_limits_parse() {
+        let limits = Limits::parse("100/500/1,000").unwrap();
+        assert_eq!(limits.get(LimitPeriod::PerTx), amount("100"));
+        assert_eq!(limits.get(LimitPeriod::Weekly), amount("1000"));
+        assert!(Limits::parse("100/500").is_none());
+        assert!(Limits::parse("100/0/1000").is_none());
+        assert_eq!(Limits::defaults("eth").per_tx, amount("0.25"));
+    }
+
+    #[test]
+    fn test_user_limits() {
+        let now = Utc::now();
+        let rows = [
+            row("per_tx", "50", None),
+            // Raise not yet in force
+            row("daily", "100", Some(("9000", now + Duration::hours(2)))),
+            // Raise whose cooling-off is over
+            row("weekly", "300", Some(("7000", now - Duration::minutes(1)))),
+        ];
+        let (limits, waiting) = user_limits("TXTC", &rows, now);
+        assert_eq!(limits.per_tx, amount("50"));
+        assert_eq!(limits.daily, amount("100"));
+        assert_eq!(limits.weekly, amount("7000"));
+        assert_eq!(waiting.len(), 1);
+        assert_eq!(waiting[0].period, LimitPeriod::Daily);
+        assert_eq!(waiting[0].amount, amount("9000"));
+
+        let (limits, waiting) = user_limits("TXTC", &[], now);
+        assert_eq!(limits, Limits::defaults("TXTC"));
+        assert!(waiting.is_empty());
+    }
+}
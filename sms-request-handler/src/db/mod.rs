@@ -1,11 +1,35 @@
 pub mod address_book;
-pub mod deposits;
+pub mod audit;
+pub mod cashouts;
+pub mod conversations;
+pub mod inbound_messages;
+pub mod limits;
+pub mod outbound_messages;
+pub mod payment_requests;
+pub mod pending;
+pub mod phone_changes;
+pub mod rate_limits;
+pub mod schedules;
+pub mod transactions;
 pub mod users;
+pub mod ussd_sessions;
 pub mod vouchers;
 
 pub use address_book::*;
-pub use deposits::*;
+pub use audit::*;
+pub use cashouts::*;
+pub use conversations::*;
+pub use inbound_messages::*;
+pub use limits::*;
+pub use outbound_messages::*;
+pub use payment_requests::*;
+pub use pending::*;
+pub use phone_changes::*;
+pub use rate_limits::*;
+pub use schedules::*;
+pub use transactions::*;
 pub use users::*;
+pub use ussd_sessions::*;
 pub use vouchers::*;
 
 use sqlx::postgres::PgPoolOptions;
@@ -19,6 +43,17 @@ pub async fn create_pool(database_url: &str) -> Result<PgPool, sqlx::Error> {
         .await
 }
 
+/// Generate a short human-friendly reference (no 0/O or 1/I to avoid misreads)
+pub fn generate_reference() -> String {
+    use rand::Rng;
+    const ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+    let mut rng = rand::thread_rng();
+
+    (0..6)
+        .map(|_| ALPHABET[rng.gen_range(0..ALPHABET.len())] as char)
+        .collect()
+}
+
 /// Run database migrations
 pub async fn run_migrations(pool: &PgPool) -> Result<(), sqlx::Error> {
     tracing::info!("Creating users table...");
@@ -31,13 +66,51 @@ pub async fn run_migrations(pool: &PgPool) -> Result<(), sqlx::Error> {
             encrypted_private_key TEXT NOT NULL,
             pin_hash VARCHAR(255),
             ens_name VARCHAR(255),
-            preferred_chain VARCHAR(20) DEFAULT 'polygon-amoy',
+            preferred_chain VARCHAR(20),
             created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
         )",
     )
     .execute(pool)
     .await?;
 
+    // Reply language, added after the initial schema
+    sqlx::query("ALTER TABLE users ADD COLUMN IF NOT EXISTS language VARCHAR(5)")
+        .execute(pool)
+        .await?;
+
+    // Swap slippage tolerance in basis points, NULL for the default
+    sqlx::query("ALTER TABLE users ADD COLUMN IF NOT EXISTS slippage_bps INTEGER")
+        .execute(pool)
+        .await?;
+
+    // Wrong PINs in a row, counted across PIN commands, and the lockout
+    // they trigger
+    sqlx::query("ALTER TABLE users ADD COLUMN IF NOT EXISTS pin_failures INTEGER NOT NULL DEFAULT 0")
+        .execute(pool)
+        .await?;
+    sqlx::query("ALTER TABLE users ADD COLUMN IF NOT EXISTS pin_locked_until TIMESTAMP WITH TIME ZONE")
+        .execute(pool)
+        .await?;
+
+    // preferred_chain used to default to 'polygon-amoy' without CHAIN ever
+    // writing it. Clear that placeholder once so NULL means the default chain.
+    let legacy_default = sqlx::query_scalar::<_, Option<String>>(
+        "SELECT column_default FROM information_schema.columns
+         WHERE table_name = 'users' AND column_name = 'preferred_chain'"
+    )
+    .fetch_optional(pool)
+    .await?
+    .flatten();
+
+    if legacy_default.is_some() {
+        sqlx::query("UPDATE users SET preferred_chain = NULL WHERE preferred_chain = 'polygon-amoy'")
+            .execute(pool)
+            .await?;
+        sqlx::query("ALTER TABLE users ALTER COLUMN preferred_chain DROP DEFAULT")
+            .execute(pool)
+            .await?;
+    }
+
     tracing::info!("Creating indices for users...");
     sqlx::query("CREATE INDEX IF NOT EXISTS idx_users_phone ON users(phone)")
         .execute(pool)
@@ -128,6 +201,11 @@ pub async fn run_migrations(pool: &PgPool) -> Result<(), sqlx::Error> {
         .execute(pool)
         .await;
 
+    // Contacts saved by ENS name keep the name; wallet_address caches what it resolved to
+    sqlx::query("ALTER TABLE address_book ADD COLUMN IF NOT EXISTS ens_name VARCHAR(255)")
+        .execute(pool)
+        .await?;
+
     sqlx::query("CREATE INDEX IF NOT EXISTS idx_address_book_user ON address_book(user_phone)")
         .execute(pool)
         .await?;
@@ -136,7 +214,366 @@ pub async fn run_migrations(pool: &PgPool) -> Result<(), sqlx::Error> {
         .execute(pool)
         .await?;
 
+    tracing::info!("Creating pending_transactions table...");
+    // Value-moving commands awaiting PIN confirmation
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS pending_transactions (
+            id UUID PRIMARY KEY,
+            reference VARCHAR(12) UNIQUE NOT NULL,
+            user_phone VARCHAR(20) NOT NULL,
+            action TEXT NOT NULL,
+            status VARCHAR(20) NOT NULL DEFAULT 'pending',
+            expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+            created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+        )",
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_pending_user_status ON pending_transactions(user_phone, status)")
+        .execute(pool)
+        .await?;
+
+    tracing::info!("Creating conversations table...");
+    // Commands being filled in one prompt at a time; one dialog per phone
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS conversations (
+            user_phone VARCHAR(20) PRIMARY KEY,
+            command VARCHAR(20) NOT NULL,
+            args TEXT NOT NULL DEFAULT '',
+            lang VARCHAR(5) NOT NULL,
+            expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+            updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+        )",
+    )
+    .execute(pool)
+    .await?;
+
+    tracing::info!("Creating payment_requests table...");
+    // REQUEST: money asked of another user, answered with PAY or DECLINE
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS payment_requests (
+            id UUID PRIMARY KEY,
+            code VARCHAR(12) UNIQUE NOT NULL,
+            requester_phone VARCHAR(20) NOT NULL,
+            payer_phone VARCHAR(20) NOT NULL,
+            amount TEXT NOT NULL,
+            token VARCHAR(10) NOT NULL,
+            chain VARCHAR(20) NOT NULL,
+            status VARCHAR(20) NOT NULL DEFAULT 'pending',
+            expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+            created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+        )",
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_payment_requests_payer ON payment_requests(payer_phone, status)")
+        .execute(pool)
+        .await?;
+
+    tracing::info!("Creating scheduled_payments table...");
+    // SEND ... EVERY/AT, run by the background scheduler
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS scheduled_payments (
+            id UUID PRIMARY KEY,
+            code VARCHAR(12) UNIQUE NOT NULL,
+            user_phone VARCHAR(20) NOT NULL,
+            amount TEXT NOT NULL,
+            token VARCHAR(10) NOT NULL,
+            recipient VARCHAR(255) NOT NULL,
+            recipient_address VARCHAR(42) NOT NULL,
+            chain VARCHAR(20) NOT NULL,
+            recurrence VARCHAR(20) NOT NULL,
+            next_run_at TIMESTAMP WITH TIME ZONE NOT NULL,
+            status VARCHAR(20) NOT NULL DEFAULT 'active',
+            last_run_at TIMESTAMP WITH TIME ZONE,
+            created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+        )",
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_scheduled_payments_due ON scheduled_payments(status, next_run_at)")
+        .execute(pool)
+        .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_scheduled_payments_user ON scheduled_payments(user_phone, status)")
+        .execute(pool)
+        .await?;
+
+    tracing::info!("Creating transactions table...");
+    // Ledger of every value movement; both legs of a transfer share a reference
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS transactions (
+            id UUID PRIMARY KEY,
+            reference VARCHAR(12) NOT NULL,
+            user_phone VARCHAR(20) NOT NULL,
+            kind VARCHAR(20) NOT NULL,
+            direction VARCHAR(3) NOT NULL,
+            counterparty VARCHAR(255),
+            amount TEXT NOT NULL,
+            token VARCHAR(10) NOT NULL,
+            chain VARCHAR(20) NOT NULL,
+            status VARCHAR(20) NOT NULL DEFAULT 'pending',
+            tx_hash VARCHAR(66),
+            created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+            updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+        )",
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_transactions_user ON transactions(user_phone, created_at DESC)")
+        .execute(pool)
+        .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_transactions_reference ON transactions(reference)")
+        .execute(pool)
+        .await?;
+
+    // Set when the watchdog tells the user a transaction is overdue
+    sqlx::query("ALTER TABLE transactions ADD COLUMN IF NOT EXISTS alerted_at TIMESTAMP WITH TIME ZONE")
+        .execute(pool)
+        .await?;
+
+    // Carry deposits recorded before the ledger existed into it; rows keep
+    // the deposit id, so this is a no-op once they are copied
+    sqlx::query(
+        "INSERT INTO transactions
+            (id, reference, user_phone, kind, direction, counterparty, amount, token, chain, status, tx_hash, created_at, updated_at)
+         SELECT id, UPPER(SUBSTRING(MD5(id::TEXT), 1, 6)), user_phone, $1, $2, source,
+                RTRIM(RTRIM((amount::NUMERIC / 1000000)::TEXT, '0'), '.'), 'USDC',
+                COALESCE(chain, $3), $4, source_ref,
+                COALESCE(created_at, NOW()), COALESCE(created_at, NOW())
+         FROM deposits
+         ON CONFLICT (id) DO NOTHING",
+    )
+    .bind(TxKind::Deposit.to_string())
+    .bind(TxDirection::In.to_string())
+    .bind(crate::wallet::Chain::default().key())
+    .bind(TxStatus::Completed.to_string())
+    .execute(pool)
+    .await?;
+
+    tracing::info!("Creating cashouts table...");
+    // CASHOUT payouts at an off-ramp provider; the ledger row shares the reference
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS cashouts (
+            id UUID PRIMARY KEY,
+            reference VARCHAR(12) UNIQUE NOT NULL,
+            user_phone VARCHAR(20) NOT NULL,
+            provider VARCHAR(20) NOT NULL,
+            amount TEXT NOT NULL,
+            token VARCHAR(10) NOT NULL,
+            payout TEXT NOT NULL,
+            currency VARCHAR(10) NOT NULL,
+            payout_id VARCHAR(100),
+            status VARCHAR(20) NOT NULL DEFAULT 'pending',
+            created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+            updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+        )",
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_cashouts_status ON cashouts(status, updated_at)")
+        .execute(pool)
+        .await?;
+
+    tracing::info!("Creating spending_limits table...");
+    // Limits users set for themselves with LIMIT; missing rows use the defaults
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS spending_limits (
+            user_phone VARCHAR(20) NOT NULL,
+            token VARCHAR(10) NOT NULL,
+            period VARCHAR(10) NOT NULL,
+            amount TEXT NOT NULL,
+            pending_amount TEXT,
+            pending_from TIMESTAMP WITH TIME ZONE,
+            updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+            PRIMARY KEY (user_phone, token, period)
+        )",
+    )
+    .execute(pool)
+    .await?;
+
+    tracing::info!("Creating phone_changes table...");
+    // Wallet moves to a new number: code check, then a waiting period
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS phone_changes (
+            id UUID PRIMARY KEY,
+            reference VARCHAR(12) UNIQUE NOT NULL,
+            old_phone VARCHAR(20) NOT NULL,
+            new_phone VARCHAR(20) NOT NULL,
+            code_hash VARCHAR(64) NOT NULL,
+            attempts INTEGER NOT NULL DEFAULT 0,
+            status VARCHAR(20) NOT NULL DEFAULT 'awaiting_code',
+            requested_by VARCHAR(10) NOT NULL DEFAULT 'user',
+            code_expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+            effective_at TIMESTAMP WITH TIME ZONE,
+            created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+            updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+        )",
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_phone_changes_status ON phone_changes(status, effective_at)")
+        .execute(pool)
+        .await?;
+
+    tracing::info!("Creating inbound_messages table...");
+    // Messages taken in, keyed by the gateway's ID so retried webhooks
+    // are answered with the stored reply instead of running again
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS inbound_messages (
+            gateway VARCHAR(30) NOT NULL,
+            message_id VARCHAR(100) NOT NULL,
+            from_phone VARCHAR(20) NOT NULL,
+            body TEXT NOT NULL,
+            reply TEXT,
+            created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+            completed_at TIMESTAMP WITH TIME ZONE,
+            PRIMARY KEY (gateway, message_id)
+        )",
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_inbound_messages_created ON inbound_messages(created_at)")
+        .execute(pool)
+        .await?;
+
+    tracing::info!("Creating outbound_messages table...");
+    // Replies and notifications, retried with backoff until sent or given
+    // up on, then tracked by the gateway's delivery receipts
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS outbound_messages (
+            id UUID PRIMARY KEY,
+            gateway VARCHAR(30),
+            to_phone VARCHAR(20) NOT NULL,
+            body TEXT NOT NULL,
+            status VARCHAR(20) NOT NULL DEFAULT 'pending',
+            attempts INTEGER NOT NULL DEFAULT 0,
+            next_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+            last_error TEXT,
+            provider_message_id VARCHAR(100),
+            delivery_status VARCHAR(20),
+            created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+            updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+        )",
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_outbound_messages_due ON outbound_messages(status, next_attempt_at)")
+        .execute(pool)
+        .await?;
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_outbound_messages_provider ON outbound_messages(gateway, provider_message_id)",
+    )
+    .execute(pool)
+    .await?;
+
+    tracing::info!("Creating rate_limits table...");
+    // Token buckets for inbound messages, per sender, per command class
+    // and global, shared by every handler instance
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS rate_limits (
+            key VARCHAR(100) PRIMARY KEY,
+            tokens DOUBLE PRECISION NOT NULL,
+            updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+            noticed_until TIMESTAMP WITH TIME ZONE
+        )",
+    )
+    .execute(pool)
+    .await?;
+
+    tracing::info!("Creating ussd_sessions table...");
+    // Where each USSD session is in the menu tree, keyed by the operator's
+    // session ID
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS ussd_sessions (
+            session_id VARCHAR(100) PRIMARY KEY,
+            phone VARCHAR(20) NOT NULL,
+            menu VARCHAR(20) NOT NULL,
+            data TEXT NOT NULL DEFAULT '[]',
+            expires_at TIMESTAMP WITH TIME ZONE NOT NULL
+        )",
+    )
+    .execute(pool)
+    .await?;
+
+    tracing::info!("Creating audit_log table...");
+    // Security events, e.g. webhooks rejected for a bad signature
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS audit_log (
+            id UUID PRIMARY KEY,
+            event VARCHAR(50) NOT NULL,
+            subject VARCHAR(100) NOT NULL,
+            detail TEXT NOT NULL,
+            source VARCHAR(100) NOT NULL,
+            created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+        )",
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_audit_log_created ON audit_log(created_at)")
+        .execute(pool)
+        .await?;
+
+    tracing::info!("Normalizing stored phone numbers...");
+    // Numbers saved before everything was kept in E.164 form (local
+    // formats, spaces); rows already in that form are skipped
+    normalize_phones(pool).await?;
+
     tracing::info!("Database migrations completed");
     Ok(())
 }
 
+/// Every column holding a phone number, as (table, column)
+const PHONE_COLUMNS: &[(&str, &str)] = &[
+    ("users", "phone"),
+    ("deposits", "user_phone"),
+    ("address_book", "user_phone"),
+    ("address_book", "contact_phone"),
+    ("pending_transactions", "user_phone"),
+    ("conversations", "user_phone"),
+    ("payment_requests", "requester_phone"),
+    ("payment_requests", "payer_phone"),
+    ("scheduled_payments", "user_phone"),
+    ("transactions", "user_phone"),
+    ("cashouts", "user_phone"),
+    ("spending_limits", "user_phone"),
+    ("phone_changes", "old_phone"),
+    ("phone_changes", "new_phone"),
+];
+
+/// Rewrite stored phone numbers to E.164. A number whose E.164 form is
+/// already taken (e.g. someone who joined under both forms) is left as
+/// it is and logged, to be merged by hand.
+async fn normalize_phones(pool: &PgPool) -> Result<(), sqlx::Error> {
+    for (table, column) in PHONE_COLUMNS {
+        let stored: Vec<String> = sqlx::query_scalar(&format!(
+            "SELECT DISTINCT {column} FROM {table} WHERE {column} IS NOT NULL AND {column} !~ '^\\+[0-9]+$'"
+        ))
+        .fetch_all(pool)
+        .await?;
+
+        let update = format!("UPDATE {table} SET {column} = $2 WHERE {column} = $1");
+        for phone in stored {
+            let normalized = crate::sms::normalize_phone(&phone);
+            if normalized == phone {
+                continue;
+            }
+            if let Err(e) = sqlx::query(&update).bind(&phone).bind(&normalized).execute(pool).await {
+                tracing::warn!(table, from = %phone, to = %normalized, "Could not normalize phone number: {}", e);
+            }
+        }
+    }
+    Ok(())
+}
+
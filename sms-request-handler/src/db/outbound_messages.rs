@@ -0,0 +1,162 @@
+use sqlx::PgPool;
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+/// SMS waiting to be sent, or sent and tracked by delivery receipts
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct OutboundMessage {
+    pub id: Uuid,
+    /// Gateway asked for (e.g. the one the user wrote to), then the one
+    /// that sent it
+    pub gateway: Option<String>,
+    pub to_phone: String,
+    pub body: String,
+    pub status: String,       // "pending", "sent", "dead" (given up on)
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    /// The gateway's reference, which delivery receipts quote
+    pub provider_message_id: Option<String>,
+    /// Latest receipt: queued, sent, delivered, undelivered or failed
+    pub delivery_status: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Outbound message repository.
+///
+/// Rows being sent are leased by pushing `next_attempt_at` ahead, so a
+/// message whose sender died is picked up again once the lease runs out.
+#[derive(Clone)]
+pub struct OutboundMessageRepository {
+    pool: PgPool,
+}
+
+impl OutboundMessageRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Queue a message, leased to the caller for its first attempt
+    pub async fn enqueue(
+        &self,
+        gateway: Option<&str>,
+        to_phone: &str,
+        body: &str,
+        lease: Duration,
+    ) -> Result<OutboundMessage, sqlx::Error> {
+        sqlx::query_as::<_, OutboundMessage>(
+            r#"
+            INSERT INTO outbound_messages (id, gateway, to_phone, body, status, next_attempt_at)
+            VALUES ($1, $2, $3, $4, 'pending', $5)
+            RETURNING id, gateway, to_phone, body, status, attempts, last_error,
+                      provider_message_id, delivery_status, created_at, updated_at
+            "#
+        )
+        .bind(Uuid::new_v4())
+        .bind(gateway)
+        .bind(to_phone)
+        .bind(body)
+        .bind(Utc::now() + lease)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Lease up to `limit` messages due for an attempt
+    pub async fn claim_due(&self, limit: i64, lease: Duration) -> Result<Vec<OutboundMessage>, sqlx::Error> {
+        sqlx::query_as::<_, OutboundMessage>(
+            r#"
+            UPDATE outbound_messages SET next_attempt_at = $2, updated_at = NOW()
+            WHERE id IN (
+                SELECT id FROM outbound_messages
+                WHERE status = 'pending' AND next_attempt_at <= NOW()
+                ORDER BY next_attempt_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, gateway, to_phone, body, status, attempts, last_error,
+                      provider_message_id, delivery_status, created_at, updated_at
+            "#
+        )
+        .bind(limit)
+        .bind(Utc::now() + lease)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// The gateway accepted the message
+    pub async fn mark_sent(&self, id: Uuid, gateway: &str, provider_message_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE outbound_messages
+             SET status = 'sent', gateway = $2, provider_message_id = $3, attempts = attempts + 1,
+                 last_error = NULL, updated_at = NOW()
+             WHERE id = $1"
+        )
+        .bind(id)
+        .bind(gateway)
+        .bind(provider_message_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// An attempt failed; try again at `retry_at`, or never with `None`
+    pub async fn mark_failed(&self, id: Uuid, error: &str, retry_at: Option<DateTime<Utc>>) -> Result<(), sqlx::Error> {
+        let status = if retry_at.is_some() { "pending" } else { "dead" };
+        sqlx::query(
+            "UPDATE outbound_messages
+             SET status = $2, attempts = attempts + 1, last_error = $3,
+                 next_attempt_at = COALESCE($4, next_attempt_at), updated_at = NOW()
+             WHERE id = $1"
+        )
+        .bind(id)
+        .bind(status)
+        .bind(error)
+        .bind(retry_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Store a delivery receipt. A final status (delivered, undelivered,
+    /// failed) isn't overwritten by a late `queued` or `sent`; returns
+    /// whether a message matched.
+    pub async fn record_delivery(
+        &self,
+        gateway: &str,
+        provider_message_id: &str,
+        delivery_status: &str,
+        error: Option<&str>,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE outbound_messages
+             SET delivery_status = $3, last_error = COALESCE($4, last_error), updated_at = NOW()
+             WHERE gateway = $1 AND provider_message_id = $2
+               AND (delivery_status IS NULL OR delivery_status IN ('queued', 'sent'))"
+        )
+        .bind(gateway)
+        .bind(provider_message_id)
+        .bind(delivery_status)
+        .bind(error)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Messages given up on or reported undelivered, newest first
+    pub async fn find_failed(&self, limit: i64) -> Result<Vec<OutboundMessage>, sqlx::Error> {
+        sqlx::query_as::<_, OutboundMessage>(
+            "SELECT id, gateway, to_phone, body, status, attempts, last_error,
+                    provider_message_id, delivery_status, created_at, updated_at
+             FROM outbound_messages
+             WHERE status = 'dead' OR delivery_status IN ('undelivered', 'failed')
+             ORDER BY updated_at DESC
+             LIMIT $1"
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
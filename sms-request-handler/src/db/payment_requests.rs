@@ -0,0 +1,129 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use chrono::{DateTime, Duration, Utc};
+use crate::wallet::{Amount, Chain};
+
+/// Payment request record in database
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct PaymentRequest {
+    pub id: Uuid,
+    pub code: String,
+    pub requester_phone: String,
+    pub amount: String,       // exact decimal, e.g. "20"
+    pub token: String,
+    pub chain: String,        // chain key the payment is sent on
+    pub expires_at: DateTime<Utc>,
+}
+
+impl PaymentRequest {
+    /// Requested amount
+    pub fn amount(&self) -> Option<Amount> {
+        Amount::parse(&self.amount).ok()
+    }
+
+    /// Chain the payment is sent on
+    pub fn chain(&self) -> Chain {
+        Chain::from_input(&self.chain).unwrap_or_default()
+    }
+
+    /// Check if the request can no longer be paid
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= Utc::now()
+    }
+}
+
+/// Payment request repository for database operations
+#[derive(Clone)]
+pub struct PaymentRequestRepository {
+    pool: PgPool,
+}
+
+impl PaymentRequestRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Store a new request from `requester` to `payer`
+    pub async fn create(
+        &self,
+        requester: &str,
+        payer: &str,
+        amount: Amount,
+        token: &str,
+        chain: Chain,
+        ttl: Duration,
+    ) -> Result<PaymentRequest, sqlx::Error> {
+        let id = Uuid::new_v4();
+
+        sqlx::query_as::<_, PaymentRequest>(
+            r#"
+            INSERT INTO payment_requests (id, code, requester_phone, payer_phone, amount, token, chain, status, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, 'pending', $8)
+            RETURNING id, code, requester_phone, amount, token, chain, expires_at
+            "#
+        )
+        .bind(id)
+        .bind(super::generate_reference())
+        .bind(requester)
+        .bind(payer)
+        .bind(amount.to_string())
+        .bind(token)
+        .bind(chain.key())
+        .bind(Utc::now() + ttl)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Get an open request addressed to `payer` by its code
+    pub async fn find_open(&self, payer: &str, code: &str) -> Result<Option<PaymentRequest>, sqlx::Error> {
+        sqlx::query_as::<_, PaymentRequest>(
+            "SELECT id, code, requester_phone, amount, token, chain, expires_at
+             FROM payment_requests
+             WHERE payer_phone = $1 AND code = $2 AND status = 'pending'"
+        )
+        .bind(payer)
+        .bind(code.to_uppercase())
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Move a request to a final status.
+    ///
+    /// Returns false if it was no longer pending, so a request is only
+    /// ever paid or declined once.
+    pub async fn set_status(&self, id: Uuid, status: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE payment_requests SET status = $1 WHERE id = $2 AND status = 'pending'"
+        )
+        .bind(status)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Mark a request paid by its code, returning it if it was still open
+    pub async fn mark_paid(&self, code: &str) -> Result<Option<PaymentRequest>, sqlx::Error> {
+        sqlx::query_as::<_, PaymentRequest>(
+            "UPDATE payment_requests SET status = 'paid'
+             WHERE code = $1 AND status = 'pending'
+             RETURNING id, code, requester_phone, amount, token, chain, expires_at"
+        )
+        .bind(code)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Mark every unanswered request past its expiry as expired
+    pub async fn expire_stale(&self) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE payment_requests SET status = 'expired'
+             WHERE status = 'pending' AND expires_at <= NOW()"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
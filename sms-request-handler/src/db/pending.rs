@@ -0,0 +1,393 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use crate::i18n::{tr_with, Lang, Msg};
+use super::{NewCashout, NewSchedule, NewTransaction, TxDirection, TxKind, TxStatus};
+use crate::wallet::{Amount, Chain};
+
+/// One recipient's part of a SPLIT
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SplitShare {
+    pub recipient: String,
+    pub recipient_address: String,
+    pub amount: Amount,
+}
+
+/// Value-moving action waiting for PIN confirmation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PendingAction {
+    Send {
+        amount: Amount,
+        token: String,
+        recipient: String,
+        recipient_address: String,
+        /// Missing in actions stored before chains were selectable
+        #[serde(default)]
+        chain: Chain,
+        /// Payment request code when this pays a REQUEST
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        request: Option<String>,
+    },
+    Swap {
+        amount: Amount,
+        token: String,
+        /// ETH the user was quoted; missing in swaps staged before quotes
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        quoted_out: Option<Amount>,
+    },
+    Bridge {
+        amount: Amount,
+        token: String,
+        from_chain: String,
+        to_chain: String,
+    },
+    /// Recurring or future SEND; confirming stores it rather than moving funds
+    Schedule(NewSchedule),
+    /// Tokens sent to the treasury and paid out by an off-ramp provider
+    Cashout(NewCashout),
+    /// One payment divided between several recipients; each share is sent
+    /// and recorded on its own
+    Split {
+        amount: Amount,
+        token: String,
+        chain: Chain,
+        shares: Vec<SplitShare>,
+    },
+}
+
+impl PendingAction {
+    /// Short description echoed back to the user
+    pub fn describe(&self, lang: Lang) -> String {
+        match self {
+            PendingAction::Send { amount, token, recipient, chain, .. } => {
+                tr_with(lang, Msg::DescribeSend, &[amount, token, recipient, &chain.short_code()])
+            }
+            PendingAction::Swap { amount, token, quoted_out: Some(out) } => {
+                tr_with(lang, Msg::DescribeSwapQuote, &[amount, token, out, &Chain::default().short_code()])
+            }
+            PendingAction::Swap { amount, token, quoted_out: None } => {
+                tr_with(lang, Msg::DescribeSwap, &[amount, token, &Chain::default().short_code()])
+            }
+            PendingAction::Bridge { amount, token, from_chain, to_chain } => {
+                tr_with(lang, Msg::DescribeBridge, &[amount, token, from_chain, to_chain])
+            }
+            PendingAction::Schedule(schedule) => tr_with(lang, Msg::DescribeSchedule, &[
+                &schedule.amount,
+                &schedule.token,
+                &schedule.recipient,
+                &schedule.recurrence.describe(lang, schedule.first_run),
+                &schedule.chain.short_code(),
+            ]),
+            PendingAction::Cashout(cashout) => tr_with(lang, Msg::DescribeCashout, &[
+                &cashout.amount,
+                &cashout.token,
+                &cashout.payout,
+                &cashout.currency,
+                &cashout.provider,
+            ]),
+            PendingAction::Split { amount, token, chain, shares } => {
+                let list: Vec<String> = shares.iter().map(|s| format!("{} {}", s.recipient, s.amount)).collect();
+                tr_with(lang, Msg::DescribeSplit, &[amount, token, &list.join(", "), &chain.short_code()])
+            }
+        }
+    }
+}
+
+impl PendingAction {
+    /// Ledger row for the confirmed action, before its outcome is known.
+    /// None for actions that don't move funds when confirmed, or that
+    /// record one row per share.
+    pub fn ledger_entry(&self, reference: &str) -> Option<NewTransaction> {
+        let (kind, amount, token, counterparty, chain) = match self {
+            PendingAction::Send { amount, token, recipient, chain, .. } => {
+                (TxKind::Send, *amount, token, recipient.clone(), *chain)
+            }
+            PendingAction::Swap { amount, token, .. } => {
+                (TxKind::Swap, *amount, token, "ETH".to_string(), Chain::default())
+            }
+            PendingAction::Bridge { amount, token, from_chain, to_chain } => (
+                TxKind::Bridge,
+                *amount,
+                token,
+                to_chain.clone(),
+                Chain::from_input(from_chain).unwrap_or_default(),
+            ),
+            PendingAction::Cashout(cashout) => {
+                (TxKind::Cashout, cashout.amount, &cashout.token, cashout.provider.clone(), Chain::default())
+            }
+            PendingAction::Schedule(_) | PendingAction::Split { .. } => return None,
+        };
+
+        Some(NewTransaction {
+            reference: reference.to_string(),
+            kind,
+            direction: TxDirection::Out,
+            counterparty: Some(counterparty),
+            amount,
+            token: token.clone(),
+            chain,
+            status: TxStatus::Pending,
+            tx_hash: None,
+        })
+    }
+
+    /// Amount and token the action takes from the user's wallet, for
+    /// spending limits. None for schedules, which are checked when they run.
+    pub fn spend(&self) -> Option<(Amount, &str)> {
+        match self {
+            PendingAction::Send { amount, token, .. }
+            | PendingAction::Swap { amount, token, .. }
+            | PendingAction::Bridge { amount, token, .. }
+            | PendingAction::Split { amount, token, .. } => Some((*amount, token)),
+            PendingAction::Cashout(cashout) => Some((cashout.amount, &cashout.token)),
+            PendingAction::Schedule(_) => None,
+        }
+    }
+}
+
+/// Pending transaction record in database
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct PendingTransaction {
+    pub id: Uuid,
+    pub reference: String,
+    pub action: String,       // JSON-encoded PendingAction
+    pub expires_at: DateTime<Utc>,
+}
+
+impl PendingTransaction {
+    /// Decode the stored action
+    pub fn action(&self) -> Option<PendingAction> {
+        serde_json::from_str(&self.action).ok()
+    }
+
+    /// Check if the confirmation window has passed
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= Utc::now()
+    }
+}
+
+/// Pending transaction repository for database operations
+#[derive(Clone)]
+pub struct PendingTransactionRepository {
+    pool: PgPool,
+}
+
+impl PendingTransactionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Store a new pending action, cancelling any older one for the same
+    /// user, so there is only ever one open
+    pub async fn create(
+        &self,
+        phone: &str,
+        action: &PendingAction,
+        ttl: Duration,
+    ) -> Result<PendingTransaction, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let payload = serde_json::to_string(action)
+            .map_err(|e| sqlx::Error::Protocol(e.to_string()))?;
+
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            "UPDATE pending_transactions SET status = 'cancelled'
+             WHERE user_phone = $1 AND status = 'pending'"
+        )
+        .bind(phone)
+        .execute(&mut *tx)
+        .await?;
+
+        let pending = sqlx::query_as::<_, PendingTransaction>(
+            r#"
+            INSERT INTO pending_transactions (id, reference, user_phone, action, status, expires_at)
+            VALUES ($1, $2, $3, $4, 'pending', $5)
+            RETURNING id, reference, action, expires_at
+            "#
+        )
+        .bind(id)
+        .bind(super::generate_reference())
+        .bind(phone)
+        .bind(payload)
+        .bind(Utc::now() + ttl)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(pending)
+    }
+
+    /// Get the open pending transaction for a user, if any
+    pub async fn find_open(&self, phone: &str) -> Result<Option<PendingTransaction>, sqlx::Error> {
+        sqlx::query_as::<_, PendingTransaction>(
+            "SELECT id, reference, action, expires_at
+             FROM pending_transactions
+             WHERE user_phone = $1 AND status = 'pending'
+             ORDER BY created_at DESC LIMIT 1"
+        )
+        .bind(phone)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Move a pending transaction to a final status.
+    ///
+    /// Returns false if it was no longer pending, so an action can
+    /// only ever be confirmed once.
+    pub async fn set_status(&self, id: Uuid, status: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE pending_transactions SET status = $1 WHERE id = $2 AND status = 'pending'"
+        )
+        .bind(status)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Mark every unconfirmed transaction past its expiry as expired
+    pub async fn expire_stale(&self) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE pending_transactions SET status = 'expired'
+             WHERE status = 'pending' AND expires_at <= NOW()"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_roundtrip() {
+        let action = PendingAction::Send {
+            amount: Amount::parse("10").unwrap(),
+            token: "TXTC".to_string(),
+            recipient: "alice.ttcip.eth".to_string(),
+            recipient_address: "0x0000000000000000000000000000000000000001".to_string(),
+            chain: Chain::BaseSepolia,
+            request: None,
+        };
+
+        let json = serde_json::to_string(&action).unwrap();
+        assert!(json.contains("\"kind\":\"send\""));
+        assert!(json.contains("\"amount\":\"10\""));
+        assert!(json.contains("\"chain\":\"base-sepolia\""));
+        assert_eq!(serde_json::from_str::<PendingAction>(&json).unwrap(), action);
+    }
+
+    #[test]
+    fn test_action_without_chain_uses_default() {
+        let json = r#"{"kind":"send","amount":"1","token":"ETH","recipient":"bob","recipient_address":"0x0000000000000000000000000000000000000002"}"#;
+        match serde_json::from_str::<PendingAction>(json).unwrap() {
+            PendingAction::Send { chain, .. } => assert_eq!(chain, Chain::default()),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_send_for_request_roundtrip() {
+        let action = PendingAction::Send {
+            amount: Amount::parse("20").unwrap(),
+            token: "TXTC".to_string(),
+            recipient: "bob.ttcip.eth".to_string(),
+            recipient_address: "0x0000000000000000000000000000000000000003".to_string(),
+            chain: Chain::default(),
+            request: Some("ABC234".to_string()),
+        };
+
+        let json = serde_json::to_string(&action).unwrap();
+        assert!(json.contains("\"request\":\"ABC234\""));
+        assert_eq!(serde_json::from_str::<PendingAction>(&json).unwrap(), action);
+    }
+
+    #[test]
+    fn test_ledger_entry() {
+        let action = PendingAction::Bridge {
+            amount: Amount::parse("2.5").unwrap(),
+            token: "USDC".to_string(),
+            from_chain: "base-sepolia".to_string(),
+            to_chain: "polygon".to_string(),
+        };
+        let entry = action.ledger_entry("ABC234").unwrap();
+        assert_eq!(entry.kind, TxKind::Bridge);
+        assert_eq!(entry.direction, TxDirection::Out);
+        assert_eq!(entry.chain, Chain::BaseSepolia);
+        assert_eq!(entry.counterparty.as_deref(), Some("polygon"));
+        assert_eq!(entry.status, TxStatus::Pending);
+    }
+
+    #[test]
+    fn test_cashout_action() {
+        let action = PendingAction::Cashout(NewCashout {
+            amount: Amount::parse("10").unwrap(),
+            token: "TXTC".to_string(),
+            provider: "MPESA".to_string(),
+            payout: Amount::parse("1282").unwrap(),
+            currency: "KES".to_string(),
+        });
+        assert_eq!(action.describe(Lang::En), "Cash out 10 TXTC for 1282 KES via MPESA");
+
+        let json = serde_json::to_string(&action).unwrap();
+        assert!(json.contains("\"kind\":\"cashout\""));
+        assert_eq!(serde_json::from_str::<PendingAction>(&json).unwrap(), action);
+
+        let entry = action.ledger_entry("ABC234").unwrap();
+        assert_eq!(entry.kind, TxKind::Cashout);
+        assert_eq!(entry.counterparty.as_deref(), Some("MPESA"));
+        assert_eq!(entry.chain, Chain::default());
+        assert_eq!(action.spend(), Some((Amount::parse("10").unwrap(), "TXTC")));
+    }
+
+    #[test]
+    fn test_describe() {
+        let action = PendingAction::Swap { amount: Amount::parse("5").unwrap(), token: "TXTC".to_string(), quoted_out: None };
+        assert_eq!(action.describe(Lang::En), "Swap 5 TXTC to ETH (ETH-T)");
+        assert_eq!(action.describe(Lang::Sw), "Badilisha 5 TXTC kuwa ETH (ETH-T)");
+
+        let action = PendingAction::Swap {
+            amount: Amount::parse("5").unwrap(),
+            token: "TXTC".to_string(),
+            quoted_out: Some(Amount::parse("0.01").unwrap()),
+        };
+        assert_eq!(action.describe(Lang::En), "Swap 5 TXTC to ~0.01 ETH (ETH-T)");
+        assert_eq!(serde_json::from_str::<PendingAction>(&serde_json::to_string(&action).unwrap()).unwrap(), action);
+        let legacy: PendingAction = serde_json::from_str(r#"{"kind":"swap","amount":"5","token":"TXTC"}"#).unwrap();
+        assert!(matches!(legacy, PendingAction::Swap { quoted_out: None, .. }));
+
+        let action = PendingAction::Schedule(NewSchedule {
+            amount: Amount::parse("50").unwrap(),
+            token: "TXTC".to_string(),
+            recipient: "landlord".to_string(),
+            recipient_address: "0x0000000000000000000000000000000000000004".to_string(),
+            chain: Chain::default(),
+            recurrence: super::super::Recurrence::Monthly(1),
+            first_run: chrono::NaiveDate::from_ymd_opt(2026, 11, 1).unwrap(),
+        });
+        assert_eq!(action.describe(Lang::En), "Send 50 TXTC to landlord every month on day 1 (ETH-T)");
+        assert_eq!(serde_json::from_str::<PendingAction>(&serde_json::to_string(&action).unwrap()).unwrap(), action);
+
+        let share = |recipient: &str, amount: &str| SplitShare {
+            recipient: recipient.to_string(),
+            recipient_address: "0x0000000000000000000000000000000000000005".to_string(),
+            amount: Amount::parse(amount).unwrap(),
+        };
+        let action = PendingAction::Split {
+            amount: Amount::parse("30").unwrap(),
+            token: "TXTC".to_string(),
+            chain: Chain::default(),
+            shares: vec![share("alice", "20"), share("bob", "10")],
+        };
+        assert_eq!(action.describe(Lang::En), "Split 30 TXTC: alice 20, bob 10 (ETH-T)");
+        assert!(action.ledger_entry("ABC234").is_none());
+        assert_eq!(serde_json::from_str::<PendingAction>(&serde_json::to_string(&action).unwrap()).unwrap(), action);
+    }
+}
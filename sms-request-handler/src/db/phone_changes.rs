@@ -0,0 +1,251 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use chrono::{DateTime, Duration, Utc};
+use super::hash_pin;
+
+/// Generate the one-time code texted to the new number
+pub fn generate_phone_code() -> String {
+    use rand::Rng;
+    format!("{:06}", rand::thread_rng().gen_range(0..1_000_000))
+}
+
+/// Move of a wallet from one phone number to another.
+///
+/// Starts `awaiting_code` until the new number sends back the one-time
+/// code and the PIN, then is `waiting` until `effective_at` so the old
+/// number can still cancel it.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct PhoneChange {
+    pub id: Uuid,
+    pub reference: String,
+    pub old_phone: String,
+    pub new_phone: String,
+    pub code_hash: String,
+    pub status: String,       // "awaiting_code", "waiting", "completed", "cancelled", "expired"
+    pub code_expires_at: DateTime<Utc>,
+}
+
+impl PhoneChange {
+    /// Check a code against the stored hash
+    pub fn verify_code(&self, code: &str) -> bool {
+        hash_pin(code.trim()) == self.code_hash
+    }
+
+    pub fn is_code_expired(&self) -> bool {
+        self.code_expires_at <= Utc::now()
+    }
+
+    pub fn awaiting_code(&self) -> bool {
+        self.status == "awaiting_code"
+    }
+}
+
+/// Phone change repository for database operations
+#[derive(Clone)]
+pub struct PhoneChangeRepository {
+    pool: PgPool,
+}
+
+impl PhoneChangeRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Start a move, cancelling any open one for either number
+    pub async fn create(
+        &self,
+        old_phone: &str,
+        new_phone: &str,
+        code: &str,
+        requested_by: &str,
+        code_ttl: Duration,
+    ) -> Result<PhoneChange, sqlx::Error> {
+        sqlx::query(
+            "UPDATE phone_changes SET status = 'cancelled', updated_at = NOW()
+             WHERE status IN ('awaiting_code', 'waiting')
+               AND (old_phone IN ($1, $2) OR new_phone IN ($1, $2))"
+        )
+        .bind(old_phone)
+        .bind(new_phone)
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query_as::<_, PhoneChange>(
+            r#"
+            INSERT INTO phone_changes (id, reference, old_phone, new_phone, code_hash, status, requested_by, code_expires_at)
+            VALUES ($1, $2, $3, $4, $5, 'awaiting_code', $6, $7)
+            RETURNING id, reference, old_phone, new_phone, code_hash, status, code_expires_at
+            "#
+        )
+        .bind(Uuid::new_v4())
+        .bind(super::generate_reference())
+        .bind(old_phone)
+        .bind(new_phone)
+        .bind(hash_pin(code))
+        .bind(requested_by)
+        .bind(Utc::now() + code_ttl)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Open move to or from a number, if any
+    pub async fn find_open(&self, phone: &str) -> Result<Option<PhoneChange>, sqlx::Error> {
+        sqlx::query_as::<_, PhoneChange>(
+            "SELECT id, reference, old_phone, new_phone, code_hash, status, code_expires_at
+             FROM phone_changes
+             WHERE (old_phone = $1 OR new_phone = $1) AND status IN ('awaiting_code', 'waiting')
+             ORDER BY created_at DESC LIMIT 1"
+        )
+        .bind(phone)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Record a wrong code or PIN, returning the new attempt count
+    pub async fn record_failed_attempt(&self, id: Uuid) -> Result<i32, sqlx::Error> {
+        sqlx::query_scalar::<_, i32>(
+            "UPDATE phone_changes SET attempts = attempts + 1, updated_at = NOW() WHERE id = $1 RETURNING attempts"
+        )
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Start the waiting period once the new number proved itself;
+    /// false if the move was no longer awaiting its code
+    pub async fn verify(&self, id: Uuid, effective_at: DateTime<Utc>) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE phone_changes SET status = 'waiting', effective_at = $1, updated_at = NOW()
+             WHERE id = $2 AND status = 'awaiting_code'"
+        )
+        .bind(effective_at)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Cancel an open move; false if it had already finished
+    pub async fn cancel(&self, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE phone_changes SET status = 'cancelled', updated_at = NOW()
+             WHERE id = $1 AND status IN ('awaiting_code', 'waiting')"
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Moves whose waiting period is over
+    pub async fn find_due(&self, limit: i64) -> Result<Vec<PhoneChange>, sqlx::Error> {
+        sqlx::query_as::<_, PhoneChange>(
+            "SELECT id, reference, old_phone, new_phone, code_hash, status, code_expires_at
+             FROM phone_changes
+             WHERE status = 'waiting' AND effective_at <= NOW()
+             ORDER BY effective_at LIMIT $1"
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Rebind everything keyed by the old number to the new one, in one
+    /// database transaction.
+    ///
+    /// Moves the user row (and with it the ENS name, which points at the
+    /// unchanged wallet address), the user's contacts and contacts others
+    /// saved for the number, pending confirmations, open requests,
+    /// schedules, cashouts, limits and the ledger. Returns false if the
+    /// move was already claimed or the new number has since joined.
+    pub async fn complete(&self, change: &PhoneChange) -> Result<bool, sqlx::Error> {
+        let (old, new) = (change.old_phone.as_str(), change.new_phone.as_str());
+        let mut tx = self.pool.begin().await?;
+
+        let claimed = sqlx::query(
+            "UPDATE phone_changes SET status = 'completed', updated_at = NOW()
+             WHERE id = $1 AND status = 'waiting'"
+        )
+        .bind(change.id)
+        .execute(&mut *tx)
+        .await?;
+        if claimed.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        let taken = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM users WHERE phone = $1")
+            .bind(new)
+            .fetch_one(&mut *tx)
+            .await?;
+        if taken > 0 {
+            tx.rollback().await?;
+            self.cancel(change.id).await?;
+            return Ok(false);
+        }
+
+        let moves = [
+            "UPDATE users SET phone = $2 WHERE phone = $1",
+            "UPDATE address_book SET user_phone = $2 WHERE user_phone = $1",
+            // Skip contacts that would duplicate one already saved for the new number
+            "UPDATE address_book a SET contact_phone = $2 WHERE contact_phone = $1
+               AND NOT EXISTS (SELECT 1 FROM address_book b
+                               WHERE b.user_phone = a.user_phone AND b.contact_phone = $2
+                                 AND COALESCE(b.wallet_address, '') = COALESCE(a.wallet_address, ''))",
+            "UPDATE pending_transactions SET user_phone = $2 WHERE user_phone = $1 AND status = 'pending'",
+            "UPDATE payment_requests SET requester_phone = $2 WHERE requester_phone = $1",
+            "UPDATE payment_requests SET payer_phone = $2 WHERE payer_phone = $1",
+            "UPDATE scheduled_payments SET user_phone = $2 WHERE user_phone = $1",
+            "UPDATE cashouts SET user_phone = $2 WHERE user_phone = $1",
+            "UPDATE spending_limits SET user_phone = $2 WHERE user_phone = $1",
+            "UPDATE transactions SET user_phone = $2 WHERE user_phone = $1",
+            "UPDATE deposits SET user_phone = $2 WHERE user_phone = $1",
+            "DELETE FROM conversations WHERE user_phone IN ($1, $2)",
+        ];
+        for query in moves {
+            sqlx::query(query).bind(old).bind(new).execute(&mut *tx).await?;
+        }
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Expire moves whose code was never sent back
+    pub async fn expire_stale(&self) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE phone_changes SET status = 'expired', updated_at = NOW()
+             WHERE status = 'awaiting_code' AND code_expires_at <= NOW()"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phone_code() {
+        let code = generate_phone_code();
+        assert_eq!(code.len(), 6);
+        assert!(code.chars().all(|c| c.is_ascii_digit()));
+
+        let change = PhoneChange {
+            id: Uuid::new_v4(),
+            reference: "ABC234".to_string(),
+            old_phone: "+254700000000".to_string(),
+            new_phone: "+254711111111".to_string(),
+            code_hash: hash_pin(&code),
+            status: "awaiting_code".to_string(),
+            code_expires_at: Utc::now() + Duration::minutes(15),
+        };
+        assert!(change.verify_code(&format!(" {} ", code)));
+        assert!(!change.verify_code("not-it"));
+        assert!(change.awaiting_code());
+        assert!(!change.is_code_expired());
+    }
+}
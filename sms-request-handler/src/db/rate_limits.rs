@@ -0,0 +1,76 @@
+use sqlx::PgPool;
+use chrono::{DateTime, Duration, Utc};
+
+/// Rate limit repository: token buckets shared by every handler instance.
+///
+/// A bucket holds up to `capacity` tokens and refills continuously; each
+/// message takes one. Buckets are refilled lazily from `updated_at` when
+/// they are next used.
+#[derive(Clone)]
+pub struct RateLimitRepository {
+    pool: PgPool,
+}
+
+impl RateLimitRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Take a token from bucket `key`; false when it is empty
+    pub async fn take(&self, key: &str, capacity: f64, refill_per_sec: f64) -> Result<bool, sqlx::Error> {
+        let taken = sqlx::query_scalar::<_, f64>(
+            r#"
+            INSERT INTO rate_limits (key, tokens, updated_at)
+            VALUES ($1, $2 - 1, NOW())
+            ON CONFLICT (key) DO UPDATE
+            SET tokens = LEAST($2, rate_limits.tokens
+                    + EXTRACT(EPOCH FROM NOW() - rate_limits.updated_at)::DOUBLE PRECISION * $3) - 1,
+                updated_at = NOW()
+            WHERE LEAST($2, rate_limits.tokens
+                    + EXTRACT(EPOCH FROM NOW() - rate_limits.updated_at)::DOUBLE PRECISION * $3) >= 1
+            RETURNING tokens
+            "#
+        )
+        .bind(key)
+        .bind(capacity)
+        .bind(refill_per_sec)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(taken.is_some())
+    }
+
+    /// Claim the right to send the throttling notice for `key` until
+    /// `until`; false when one was already sent in this window
+    pub async fn claim_notice(&self, key: &str, until: DateTime<Utc>) -> Result<bool, sqlx::Error> {
+        let claimed = sqlx::query_scalar::<_, String>(
+            r#"
+            INSERT INTO rate_limits (key, tokens, updated_at, noticed_until)
+            VALUES ($1, 0, NOW(), $2)
+            ON CONFLICT (key) DO UPDATE
+            SET noticed_until = $2
+            WHERE rate_limits.noticed_until IS NULL OR rate_limits.noticed_until <= NOW()
+            RETURNING key
+            "#
+        )
+        .bind(key)
+        .bind(until)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(claimed.is_some())
+    }
+
+    /// Drop buckets unused for `idle`, which are full again by then
+    pub async fn delete_idle(&self, idle: Duration) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            "DELETE FROM rate_limits
+             WHERE updated_at < $1 AND (noticed_until IS NULL OR noticed_until < NOW())"
+        )
+        .bind(Utc::now() - idle)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,347 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use crate::i18n::{tr, tr_with, Lang, Msg};
+use crate::wallet::{Amount, Chain};
+
+/// How often a scheduled payment repeats.
+///
+/// Stored as text: `once`, `daily`, `weekly:fri`, `monthly:1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recurrence {
+    Once,
+    Daily,
+    Weekly(Weekday),
+    /// Day of the month; shorter months use their last day
+    Monthly(u32),
+}
+
+/// Why a schedule clause was rejected
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ScheduleError {
+    #[error("Unrecognised schedule")]
+    Invalid,
+    #[error("Date {0} has passed")]
+    Past(NaiveDate),
+}
+
+impl Recurrence {
+    /// Parse `EVERY DAY`, `EVERY WEEK [ON FRI]`, `EVERY FRIDAY`,
+    /// `EVERY MONTH [ON 1]` or `AT 2026-11-01`, returning the recurrence
+    /// and the first run date (on or after `today`).
+    pub fn parse(clause: &str, today: NaiveDate) -> Result<(Recurrence, NaiveDate), ScheduleError> {
+        let words: Vec<String> = clause.split_whitespace().map(|w| w.to_uppercase()).collect();
+        let words: Vec<&str> = words.iter().map(|w| w.as_str()).collect();
+
+        let recurrence = match words.as_slice() {
+            ["AT" | "ON", date] => {
+                let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| ScheduleError::Invalid)?;
+                if date < today {
+                    return Err(ScheduleError::Past(date));
+                }
+                return Ok((Recurrence::Once, date));
+            }
+            ["EVERY", "DAY"] | ["DAILY"] => Recurrence::Daily,
+            ["EVERY", "WEEK"] | ["WEEKLY"] => Recurrence::Weekly(today.weekday()),
+            ["EVERY", "MONTH"] | ["MONTHLY"] => Recurrence::Monthly(today.day()),
+            ["EVERY", "MONTH", "ON", day] | ["MONTHLY", "ON", day] => Recurrence::Monthly(parse_day(day)?),
+            ["EVERY", "WEEK", "ON", day] | ["WEEKLY", "ON", day] | ["EVERY", day] => {
+                Recurrence::Weekly(day.parse::<Weekday>().map_err(|_| ScheduleError::Invalid)?)
+            }
+            _ => return Err(ScheduleError::Invalid),
+        };
+
+        Ok((recurrence, recurrence.next_on_or_after(today)))
+    }
+
+    /// Read a stored recurrence
+    pub fn from_key(key: &str) -> Option<Recurrence> {
+        match key.split_once(':') {
+            None if key == "once" => Some(Recurrence::Once),
+            None if key == "daily" => Some(Recurrence::Daily),
+            Some(("weekly", day)) => day.parse().ok().map(Recurrence::Weekly),
+            Some(("monthly", day)) => day.parse().ok().filter(|d| (1..=31).contains(d)).map(Recurrence::Monthly),
+            _ => None,
+        }
+    }
+
+    /// First date this recurrence falls on, counting `date` itself
+    pub fn next_on_or_after(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Recurrence::Once | Recurrence::Daily => date,
+            Recurrence::Weekly(day) => {
+                let ahead = (7 + day.num_days_from_monday() - date.weekday().num_days_from_monday()) % 7;
+                date + Duration::days(ahead as i64)
+            }
+            Recurrence::Monthly(day) => {
+                let this_month = clamp_day(date.year(), date.month(), day);
+                if this_month >= date {
+                    this_month
+                } else if date.month() == 12 {
+                    clamp_day(date.year() + 1, 1, day)
+                } else {
+                    clamp_day(date.year(), date.month() + 1, day)
+                }
+            }
+        }
+    }
+
+    /// Run date after one on `last`, or None once a one-off has run
+    pub fn next_after(self, last: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Recurrence::Once => None,
+            _ => Some(self.next_on_or_after(last + Duration::days(1))),
+        }
+    }
+
+    /// Localized phrase, e.g. "every month on day 1"
+    pub fn describe(self, lang: Lang, first_run: NaiveDate) -> String {
+        match self {
+            Recurrence::Once => tr_with(lang, Msg::ScheduleOnce, &[&first_run]),
+            Recurrence::Daily => tr(lang, Msg::ScheduleDaily),
+            Recurrence::Weekly(day) => tr_with(lang, Msg::ScheduleWeekly, &[&day]),
+            Recurrence::Monthly(day) => tr_with(lang, Msg::ScheduleMonthly, &[&day]),
+        }
+    }
+}
+
+impl std::fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Recurrence::Once => write!(f, "once"),
+            Recurrence::Daily => write!(f, "daily"),
+            Recurrence::Weekly(day) => write!(f, "weekly:{}", day.to_string().to_lowercase()),
+            Recurrence::Monthly(day) => write!(f, "monthly:{}", day),
+        }
+    }
+}
+
+impl Serialize for Recurrence {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Recurrence {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let key = String::deserialize(deserializer)?;
+        Recurrence::from_key(&key).ok_or_else(|| serde::de::Error::custom(format!("unknown recurrence: {}", key)))
+    }
+}
+
+/// Day of month as typed: `1`, `1ST`, `15TH`
+fn parse_day(word: &str) -> Result<u32, ScheduleError> {
+    let digits = word.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    digits.parse().ok().filter(|d| (1..=31).contains(d)).ok_or(ScheduleError::Invalid)
+}
+
+/// `day` of the given month, or the month's last day when it is shorter
+fn clamp_day(year: i32, month: u32, day: u32) -> NaiveDate {
+    (1..=day)
+        .rev()
+        .find_map(|d| NaiveDate::from_ymd_opt(year, month, d))
+        .expect("every month has a first day")
+}
+
+/// A confirmed SEND ... EVERY/AT, before it is stored
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewSchedule {
+    pub amount: Amount,
+    pub token: String,
+    pub recipient: String,
+    pub recipient_address: String,
+    pub chain: Chain,
+    pub recurrence: Recurrence,
+    pub first_run: NaiveDate,
+}
+
+/// Scheduled payment record in database
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ScheduledPayment {
+    pub id: Uuid,
+    pub code: String,
+    pub user_phone: String,
+    pub amount: String,       // exact decimal, e.g. "50"
+    pub token: String,
+    pub recipient: String,    // as typed by the user
+    pub recipient_address: String,
+    pub chain: String,        // chain key
+    pub recurrence: String,   // Recurrence key
+    pub next_run_at: DateTime<Utc>,
+}
+
+impl ScheduledPayment {
+    pub fn recurrence(&self) -> Option<Recurrence> {
+        Recurrence::from_key(&self.recurrence)
+    }
+
+    pub fn chain(&self) -> Chain {
+        Chain::from_input(&self.chain).unwrap_or_default()
+    }
+
+    /// One SCHEDULES line, e.g. `K7M2QX: 50 TXTC to landlord, every month on day 1 (01/11)`
+    pub fn to_sms_line(&self, lang: Lang) -> String {
+        let next = self.next_run_at.date_naive();
+        let when = self.recurrence().map(|r| r.describe(lang, next)).unwrap_or_default();
+        tr_with(lang, Msg::ScheduleLine, &[
+            &self.code,
+            &self.amount,
+            &self.token,
+            &self.recipient,
+            &format!("{} ({})", when, next.format("%d/%m")),
+        ])
+    }
+}
+
+/// Midnight UTC on `date`, when a scheduled payment becomes due
+pub fn run_time(date: NaiveDate) -> DateTime<Utc> {
+    date.and_hms_opt(0, 0, 0).expect("midnight exists").and_utc()
+}
+
+/// Scheduled payment repository for database operations
+#[derive(Clone)]
+pub struct ScheduleRepository {
+    pool: PgPool,
+}
+
+impl ScheduleRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Store a confirmed schedule
+    pub async fn create(&self, phone: &str, schedule: &NewSchedule) -> Result<ScheduledPayment, sqlx::Error> {
+        let id = Uuid::new_v4();
+
+        sqlx::query_as::<_, ScheduledPayment>(
+            r#"
+            INSERT INTO scheduled_payments
+                (id, code, user_phone, amount, token, recipient, recipient_address, chain, recurrence, next_run_at, status)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, 'active')
+            RETURNING id, code, user_phone, amount, token, recipient, recipient_address, chain, recurrence,
+                      next_run_at
+            "#
+        )
+        .bind(id)
+        .bind(super::generate_reference())
+        .bind(phone)
+        .bind(schedule.amount.to_string())
+        .bind(&schedule.token)
+        .bind(&schedule.recipient)
+        .bind(&schedule.recipient_address)
+        .bind(schedule.chain.key())
+        .bind(schedule.recurrence.to_string())
+        .bind(run_time(schedule.first_run))
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Active schedules for a user, soonest first
+    pub async fn list_active(&self, phone: &str) -> Result<Vec<ScheduledPayment>, sqlx::Error> {
+        sqlx::query_as::<_, ScheduledPayment>(
+            "SELECT id, code, user_phone, amount, token, recipient, recipient_address, chain, recurrence,
+                    next_run_at
+             FROM scheduled_payments
+             WHERE user_phone = $1 AND status = 'active'
+             ORDER BY next_run_at"
+        )
+        .bind(phone)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Cancel a user's schedule by code, returning false if there was none
+    pub async fn cancel(&self, phone: &str, code: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE scheduled_payments SET status = 'cancelled'
+             WHERE user_phone = $1 AND code = $2 AND status = 'active'"
+        )
+        .bind(phone)
+        .bind(code.to_uppercase())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Active schedules whose next run is due
+    pub async fn find_due(&self, limit: i64) -> Result<Vec<ScheduledPayment>, sqlx::Error> {
+        sqlx::query_as::<_, ScheduledPayment>(
+            "SELECT id, code, user_phone, amount, token, recipient, recipient_address, chain, recurrence,
+                    next_run_at
+             FROM scheduled_payments
+             WHERE status = 'active' AND next_run_at <= NOW()
+             ORDER BY next_run_at LIMIT $1"
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Claim a due run by moving the schedule to its next date (or
+    /// completing it). Returns false if another worker claimed it first.
+    pub async fn claim_run(
+        &self,
+        schedule: &ScheduledPayment,
+        next_run: Option<NaiveDate>,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE scheduled_payments
+             SET next_run_at = COALESCE($1, next_run_at),
+                 status = CASE WHEN $1 IS NULL THEN 'completed' ELSE status END,
+                 last_run_at = NOW()
+             WHERE id = $2 AND status = 'active' AND next_run_at = $3"
+        )
+        .bind(next_run.map(run_time))
+        .bind(schedule.id)
+        .bind(schedule.next_run_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_parse_clauses() {
+        // 2026-10-18 is a Sunday
+        let today = date("2026-10-18");
+        assert_eq!(Recurrence::parse("EVERY MONTH ON 1", today), Ok((Recurrence::Monthly(1), date("2026-11-01"))));
+        assert_eq!(Recurrence::parse("every month on 18th", today), Ok((Recurrence::Monthly(18), today)));
+        assert_eq!(Recurrence::parse("EVERY WEEK ON FRI", today), Ok((Recurrence::Weekly(Weekday::Fri), date("2026-10-23"))));
+        assert_eq!(Recurrence::parse("EVERY MONDAY", today), Ok((Recurrence::Weekly(Weekday::Mon), date("2026-10-19"))));
+        assert_eq!(Recurrence::parse("EVERY DAY", today), Ok((Recurrence::Daily, today)));
+        assert_eq!(Recurrence::parse("AT 2026-11-01", today), Ok((Recurrence::Once, date("2026-11-01"))));
+        assert_eq!(Recurrence::parse("AT 2026-01-01", today), Err(ScheduleError::Past(date("2026-01-01"))));
+        for bad in ["EVERY", "EVERY MONTH ON 32", "AT tomorrow", "EVERY FORTNIGHT", "SOMETIMES"] {
+            assert_eq!(Recurrence::parse(bad, today), Err(ScheduleError::Invalid), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_monthly_clamps_short_months() {
+        let monthly = Recurrence::Monthly(31);
+        assert_eq!(monthly.next_on_or_after(date("2026-02-10")), date("2026-02-28"));
+        assert_eq!(monthly.next_after(date("2026-02-28")), Some(date("2026-03-31")));
+        assert_eq!(Recurrence::Monthly(1).next_after(date("2026-12-01")), Some(date("2027-01-01")));
+        assert_eq!(Recurrence::Once.next_after(date("2026-12-01")), None);
+    }
+
+    #[test]
+    fn test_key_roundtrip() {
+        for r in [Recurrence::Once, Recurrence::Daily, Recurrence::Weekly(Weekday::Fri), Recurrence::Monthly(15)] {
+            assert_eq!(Recurrence::from_key(&r.to_string()), Some(r));
+        }
+        assert_eq!(Recurrence::Weekly(Weekday::Fri).to_string(), "weekly:fri");
+        assert_eq!(Recurrence::from_key("monthly:0"), None);
+    }
+}
@@ -0,0 +1,388 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use chrono::{DateTime, Duration, Utc};
+use crate::i18n::{tr, tr_with, Lang, Msg};
+use crate::wallet::{Amount, Chain};
+
+/// What kind of value movement a ledger row records
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TxKind {
+    Send,
+    Swap,
+    Bridge,
+    Redeem,
+    Deposit,
+    Cashout,
+    /// Tokens returned after a failed cashout
+    Refund,
+}
+
+impl std::fmt::Display for TxKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TxKind::Send => write!(f, "send"),
+            TxKind::Swap => write!(f, "swap"),
+            TxKind::Bridge => write!(f, "bridge"),
+            TxKind::Redeem => write!(f, "redeem"),
+            TxKind::Deposit => write!(f, "deposit"),
+            TxKind::Cashout => write!(f, "cashout"),
+            TxKind::Refund => write!(f, "refund"),
+        }
+    }
+}
+
+/// Whether value left or reached the user's wallet
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TxDirection {
+    Out,
+    In,
+}
+
+impl std::fmt::Display for TxDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TxDirection::Out => write!(f, "out"),
+            TxDirection::In => write!(f, "in"),
+        }
+    }
+}
+
+/// Lifecycle of a ledger row
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TxStatus {
+    Pending,
+    Completed,
+    Failed,
+}
+
+impl TxStatus {
+    /// Parse a status reported by the backend
+    pub fn from_input(input: &str) -> Option<TxStatus> {
+        match input.to_lowercase().as_str() {
+            "pending" | "submitted" => Some(TxStatus::Pending),
+            "completed" | "success" | "confirmed" => Some(TxStatus::Completed),
+            "failed" | "error" | "reverted" => Some(TxStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for TxStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TxStatus::Pending => write!(f, "pending"),
+            TxStatus::Completed => write!(f, "completed"),
+            TxStatus::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+/// Which rows HISTORY shows
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum HistoryFilter {
+    #[default]
+    All,
+    Sent,
+    Received,
+}
+
+impl HistoryFilter {
+    /// Parse the optional HISTORY filter word
+    pub fn from_input(input: &str) -> Option<HistoryFilter> {
+        match input.to_uppercase().as_str() {
+            "ALL" => Some(HistoryFilter::All),
+            "SENT" => Some(HistoryFilter::Sent),
+            "RECEIVED" => Some(HistoryFilter::Received),
+            _ => None,
+        }
+    }
+
+    /// Keyword repeated in the "more" hint
+    pub fn keyword(self) -> Option<&'static str> {
+        match self {
+            HistoryFilter::All => None,
+            HistoryFilter::Sent => Some("SENT"),
+            HistoryFilter::Received => Some("RECEIVED"),
+        }
+    }
+
+    /// Direction to match, if any
+    fn direction(self) -> Option<TxDirection> {
+        match self {
+            HistoryFilter::All => None,
+            HistoryFilter::Sent => Some(TxDirection::Out),
+            HistoryFilter::Received => Some(TxDirection::In),
+        }
+    }
+}
+
+/// Values for a new ledger row
+#[derive(Debug, Clone)]
+pub struct NewTransaction {
+    pub reference: String,
+    pub kind: TxKind,
+    pub direction: TxDirection,
+    pub counterparty: Option<String>,
+    pub amount: Amount,
+    pub token: String,
+    pub chain: Chain,
+    pub status: TxStatus,
+    pub tx_hash: Option<String>,
+}
+
+/// Ledger row in database
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TransactionRecord {
+    pub id: Uuid,
+    pub reference: String,
+    pub user_phone: String,
+    pub kind: String,         // "send", "swap", "bridge", "redeem", "deposit", "cashout", "refund"
+    pub direction: String,    // "out", "in"
+    pub counterparty: Option<String>,  // recipient, sender, target token/chain, voucher code or deposit source
+    pub amount: String,       // exact decimal, e.g. "0.1"
+    pub token: String,
+    pub status: String,       // "pending", "completed", "failed"
+    pub tx_hash: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TransactionRecord {
+    /// Current status, if readable
+    pub fn status(&self) -> Option<TxStatus> {
+        TxStatus::from_input(&self.status)
+    }
+
+    /// One HISTORY line, e.g. `12/03 Sent 10 TXTC to alice ABC234 (pending)`
+    pub fn to_sms_line(&self, lang: Lang) -> String {
+        let line = self.describe(lang);
+        match self.status() {
+            Some(TxStatus::Pending) => format!("{} {}", line, tr(lang, Msg::TxPendingTag)),
+            Some(TxStatus::Failed) => format!("{} {}", line, tr(lang, Msg::TxFailedTag)),
+            _ => line,
+        }
+    }
+
+    /// What happened, without the status, e.g. `12/03 Sent 10 TXTC to alice ABC234`
+    pub fn describe(&self, lang: Lang) -> String {
+        let date = self.created_at.format("%d/%m").to_string();
+        let other = self.counterparty.as_deref().unwrap_or("-");
+        let msg = match (self.kind.as_str(), self.direction.as_str()) {
+            ("swap", _) => Msg::HistorySwap,
+            ("bridge", _) => Msg::HistoryBridge,
+            ("redeem", _) => Msg::HistoryRedeem,
+            ("deposit", _) => Msg::HistoryDeposit,
+            ("cashout", _) => Msg::HistoryCashout,
+            ("refund", _) => Msg::HistoryRefund,
+            (_, "in") => Msg::HistoryReceived,
+            _ => Msg::HistorySent,
+        };
+        tr_with(lang, msg, &[&date, &self.amount, &self.token, &other, &self.reference])
+    }
+}
+
+/// Transaction ledger repository for database operations
+#[derive(Clone)]
+pub struct TransactionRepository {
+    pool: PgPool,
+}
+
+impl TransactionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record a value movement for a user
+    pub async fn record(&self, phone: &str, tx: &NewTransaction) -> Result<TransactionRecord, sqlx::Error> {
+        let id = Uuid::new_v4();
+
+        sqlx::query_as::<_, TransactionRecord>(
+            r#"
+            INSERT INTO transactions
+                (id, reference, user_phone, kind, direction, counterparty, amount, token, chain, status, tx_hash)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING id, reference, user_phone, kind, direction, counterparty, amount, token,
+                      status, tx_hash, created_at
+            "#
+        )
+        .bind(id)
+        .bind(&tx.reference)
+        .bind(phone)
+        .bind(tx.kind.to_string())
+        .bind(tx.direction.to_string())
+        .bind(&tx.counterparty)
+        .bind(tx.amount.to_string())
+        .bind(&tx.token)
+        .bind(tx.chain.key())
+        .bind(tx.status.to_string())
+        .bind(&tx.tx_hash)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Update every row sharing a reference (both legs of a transfer).
+    ///
+    /// Completed and failed rows are final, so a late or repeated callback
+    /// cannot flip them. Returns the number of rows changed.
+    pub async fn update_status(
+        &self,
+        reference: &str,
+        status: TxStatus,
+        tx_hash: Option<&str>,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE transactions
+             SET status = $1, tx_hash = COALESCE($2, tx_hash), updated_at = NOW()
+             WHERE reference = $3 AND status = 'pending'"
+        )
+        .bind(status.to_string())
+        .bind(tx_hash)
+        .bind(reference)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// A user's rows for a reference, including the numbered shares of a
+    /// SPLIT (`ABC234-1`, `ABC234-2`, ...)
+    pub async fn find_by_reference(&self, phone: &str, reference: &str) -> Result<Vec<TransactionRecord>, sqlx::Error> {
+        sqlx::query_as::<_, TransactionRecord>(
+            "SELECT id, reference, user_phone, kind, direction, counterparty, amount, token,
+                    status, tx_hash, created_at
+             FROM transactions
+             WHERE user_phone = $1 AND (reference = $2 OR reference LIKE $2 || '-%')
+             ORDER BY reference, direction DESC"
+        )
+        .bind(phone)
+        .bind(reference.to_uppercase())
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Outgoing rows still pending after `after`, that nobody was told about yet
+    pub async fn find_stalled(&self, after: Duration, limit: i64) -> Result<Vec<TransactionRecord>, sqlx::Error> {
+        sqlx::query_as::<_, TransactionRecord>(
+            "SELECT id, reference, user_phone, kind, direction, counterparty, amount, token,
+                    status, tx_hash, created_at
+             FROM transactions
+             WHERE status = 'pending' AND direction = 'out' AND alerted_at IS NULL AND created_at <= $1
+             ORDER BY created_at LIMIT $2"
+        )
+        .bind(Utc::now() - after)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Note that the user was told a row is overdue, so they are told once
+    pub async fn mark_alerted(&self, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE transactions SET alerted_at = NOW() WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Total of `token` a user sent since `since`, leaving out failed rows
+    pub async fn spent_since(&self, phone: &str, token: &str, since: DateTime<Utc>) -> Result<Amount, sqlx::Error> {
+        let total = sqlx::query_scalar::<_, String>(
+            "SELECT COALESCE(SUM(amount::NUMERIC), 0)::TEXT FROM transactions
+             WHERE user_phone = $1 AND token = $2 AND direction = 'out'
+               AND status <> 'failed' AND created_at >= $3"
+        )
+        .bind(phone)
+        .bind(token)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await?;
+
+        // Zero doesn't parse as an amount, and is the default
+        Ok(Amount::parse(&total).unwrap_or_default())
+    }
+
+    /// One page of a user's ledger, newest first
+    pub async fn page(
+        &self,
+        phone: &str,
+        filter: HistoryFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<TransactionRecord>, sqlx::Error> {
+        sqlx::query_as::<_, TransactionRecord>(
+            "SELECT id, reference, user_phone, kind, direction, counterparty, amount, token,
+                    status, tx_hash, created_at
+             FROM transactions
+             WHERE user_phone = $1 AND ($2::TEXT IS NULL OR direction = $2)
+             ORDER BY created_at DESC LIMIT $3 OFFSET $4"
+        )
+        .bind(phone)
+        .bind(filter.direction().map(|d| d.to_string()))
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Number of ledger rows matching a filter
+    pub async fn count(&self, phone: &str, filter: HistoryFilter) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM transactions
+             WHERE user_phone = $1 AND ($2::TEXT IS NULL OR direction = $2)"
+        )
+        .bind(phone)
+        .bind(filter.direction().map(|d| d.to_string()))
+        .fetch_one(&self.pool)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(kind: &str, direction: &str, status: &str) -> TransactionRecord {
+        TransactionRecord {
+            id: Uuid::nil(),
+            reference: "ABC234".to_string(),
+            user_phone: "+15550001111".to_string(),
+            kind: kind.to_string(),
+            direction: direction.to_string(),
+            counterparty: Some("alice".to_string()),
+            amount: "10.5".to_string(),
+            token: "TXTC".to_string(),
+            status: status.to_string(),
+            tx_hash: None,
+            created_at: "2026-03-12T10:00:00Z".parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_sms_line() {
+        assert_eq!(record("send", "out", "completed").to_sms_line(Lang::En), "12/03 Sent 10.5 TXTC to alice ABC234");
+        assert_eq!(record("send", "in", "completed").to_sms_line(Lang::En), "12/03 Got 10.5 TXTC from alice ABC234");
+        assert_eq!(record("send", "out", "pending").to_sms_line(Lang::En), "12/03 Sent 10.5 TXTC to alice ABC234 (pending)");
+        assert_eq!(record("send", "out", "failed").to_sms_line(Lang::Sw), "12/03 Umetuma 10.5 TXTC kwa alice ABC234 (imeshindwa)");
+    }
+
+    #[test]
+    fn test_describe_without_status() {
+        let pending = record("swap", "out", "pending");
+        assert_eq!(pending.status(), Some(TxStatus::Pending));
+        assert!(!pending.describe(Lang::En).contains("(pending)"));
+        assert!(pending.to_sms_line(Lang::En).ends_with("(pending)"));
+    }
+
+    #[test]
+    fn test_filter_from_input() {
+        assert_eq!(HistoryFilter::from_input("sent"), Some(HistoryFilter::Sent));
+        assert_eq!(HistoryFilter::from_input("RECEIVED"), Some(HistoryFilter::Received));
+        assert_eq!(HistoryFilter::from_input("2"), None);
+    }
+
+    #[test]
+    fn test_status_from_input() {
+        assert_eq!(TxStatus::from_input("success"), Some(TxStatus::Completed));
+        assert_eq!(TxStatus::from_input("FAILED"), Some(TxStatus::Failed));
+        assert_eq!(TxStatus::from_input("maybe"), None);
+    }
+}
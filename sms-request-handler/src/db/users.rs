@@ -1,5 +1,20 @@
+use sha2::Digest;
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use uuid::Uuid;
+use crate::sms::normalize_phone;
+use crate::wallet::{Chain, Slippage};
+
+/// Wrong PINs in a row, across every PIN command, before they are all locked
+pub const MAX_PIN_ATTEMPTS: i32 = 3;
+
+/// How long PIN commands stay locked after MAX_PIN_ATTEMPTS wrong PINs
+pub const PIN_LOCKOUT_MINS: i64 = 30;
+
+/// Hash a PIN for storage (simple hash for demo; use bcrypt in production)
+pub fn hash_pin(pin: &str) -> String {
+    format!("{:x}", sha2::Sha256::digest(pin.as_bytes()))
+}
 
 /// User record in database
 #[derive(Debug, Clone, sqlx::FromRow)]
@@ -10,9 +25,53 @@ pub struct User {
     pub encrypted_private_key: String,
     pub pin_hash: Option<String>,
     pub ens_name: Option<String>,
+    pub language: Option<String>,  // ISO 639-1 code, None until chosen
+    pub preferred_chain: Option<String>,  // Chain::key(), None for the default chain
+    pub slippage_bps: Option<i32>,  // swap tolerance, None for Slippage::DEFAULT
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
+impl User {
+    /// Check a PIN against the stored hash
+    pub fn verify_pin(&self, pin: &str) -> bool {
+        match self.pin_hash {
+            Some(ref stored) => {
+                let candidate = hash_pin(pin);
+                // Compare without short-circuiting on the first differing byte
+                stored.len() == candidate.len()
+                    && stored.bytes().zip(candidate.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+            }
+            None => false,
+        }
+    }
+
+    /// Name shown to other users: the ENS name if set, else the phone number
+    pub fn display_name(&self) -> String {
+        self.ens_name.clone().unwrap_or_else(|| self.phone.clone())
+    }
+
+    /// Chain the user's commands run on
+    pub fn chain(&self) -> Chain {
+        self.preferred_chain
+            .as_deref()
+            .and_then(Chain::from_input)
+            .unwrap_or_default()
+    }
+
+    /// Slippage tolerance applied to the user's swaps
+    pub fn slippage(&self) -> Slippage {
+        self.slippage_bps
+            .and_then(|bps| u32::try_from(bps).ok())
+            .and_then(Slippage::from_bps)
+            .unwrap_or(Slippage::DEFAULT)
+    }
+
+    /// Saved reply language, if the user picked one
+    pub fn lang(&self) -> Option<crate::i18n::Lang> {
+        self.language.as_deref().and_then(crate::i18n::Lang::from_input)
+    }
+}
+
 /// User repository for database operations
 #[derive(Clone)]
 pub struct UserRepository {
@@ -24,13 +83,24 @@ impl UserRepository {
         Self { pool }
     }
 
-    /// Find user by phone number
+    /// Find user by phone number, in any format
     pub async fn find_by_phone(&self, phone: &str) -> Result<Option<User>, sqlx::Error> {
         sqlx::query_as::<_, User>(
-            "SELECT id, phone, wallet_address, encrypted_private_key, pin_hash, ens_name, created_at 
+            "SELECT id, phone, wallet_address, encrypted_private_key, pin_hash, ens_name, language, preferred_chain, slippage_bps, created_at 
              FROM users WHERE phone = $1"
         )
-        .bind(phone)
+        .bind(normalize_phone(phone))
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Find user by wallet address (case-insensitive)
+    pub async fn find_by_wallet(&self, wallet_address: &str) -> Result<Option<User>, sqlx::Error> {
+        sqlx::query_as::<_, User>(
+            "SELECT id, phone, wallet_address, encrypted_private_key, pin_hash, ens_name, language, preferred_chain, slippage_bps, created_at 
+             FROM users WHERE LOWER(wallet_address) = LOWER($1)"
+        )
+        .bind(wallet_address)
         .fetch_optional(&self.pool)
         .await
     }
@@ -48,11 +118,11 @@ impl UserRepository {
             r#"
             INSERT INTO users (id, phone, wallet_address, encrypted_private_key)
             VALUES ($1, $2, $3, $4)
-            RETURNING id, phone, wallet_address, encrypted_private_key, pin_hash, ens_name, created_at
+            RETURNING id, phone, wallet_address, encrypted_private_key, pin_hash, ens_name, language, preferred_chain, slippage_bps, created_at
             "#
         )
         .bind(id)
-        .bind(phone)
+        .bind(normalize_phone(phone))
         .bind(wallet_address)
         .bind(encrypted_private_key)
         .fetch_one(&self.pool)
@@ -63,7 +133,46 @@ impl UserRepository {
     pub async fn update_pin(&self, phone: &str, pin_hash: &str) -> Result<(), sqlx::Error> {
         sqlx::query("UPDATE users SET pin_hash = $1 WHERE phone = $2")
             .bind(pin_hash)
-            .bind(phone)
+            .bind(normalize_phone(phone))
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// When PIN commands unlock for a user locked out after wrong PINs;
+    /// None when they aren't locked
+    pub async fn pin_locked_until(&self, phone: &str) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+        sqlx::query_scalar::<_, DateTime<Utc>>(
+            "SELECT pin_locked_until FROM users WHERE phone = $1 AND pin_locked_until > NOW()"
+        )
+        .bind(normalize_phone(phone))
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Count a wrong PIN, returning the tries left; 0 when this one locked
+    /// PIN commands until `locked_until`
+    pub async fn record_pin_failure(&self, phone: &str, locked_until: DateTime<Utc>) -> Result<i32, sqlx::Error> {
+        sqlx::query_scalar::<_, i32>(
+            r#"
+            UPDATE users SET
+                pin_failures = CASE WHEN pin_failures + 1 >= $2 THEN 0 ELSE pin_failures + 1 END,
+                pin_locked_until = CASE WHEN pin_failures + 1 >= $2 THEN $3 ELSE pin_locked_until END
+            WHERE phone = $1
+            RETURNING CASE WHEN pin_failures = 0 THEN 0 ELSE $2 - pin_failures END
+            "#
+        )
+        .bind(normalize_phone(phone))
+        .bind(MAX_PIN_ATTEMPTS)
+        .bind(locked_until)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Forget earlier wrong PINs once the right one is given
+    pub async fn clear_pin_failures(&self, phone: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE users SET pin_failures = 0 WHERE phone = $1 AND pin_failures > 0")
+            .bind(normalize_phone(phone))
             .execute(&self.pool)
             .await?;
         Ok(())
@@ -73,18 +182,48 @@ impl UserRepository {
     pub async fn update_ens_name(&self, phone: &str, ens_name: &str) -> Result<(), sqlx::Error> {
         sqlx::query("UPDATE users SET ens_name = $1 WHERE phone = $2")
             .bind(ens_name)
-            .bind(phone)
+            .bind(normalize_phone(phone))
             .execute(&self.pool)
             .await?;
         Ok(())
     }
 
+    /// Update user's reply language, returning false if the user doesn't exist
+    pub async fn update_language(&self, phone: &str, language: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("UPDATE users SET language = $1 WHERE phone = $2")
+            .bind(language)
+            .bind(normalize_phone(phone))
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Update user's preferred chain, returning false if the user doesn't exist
+    pub async fn update_preferred_chain(&self, phone: &str, chain: Chain) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("UPDATE users SET preferred_chain = $1 WHERE phone = $2")
+            .bind(chain.key())
+            .bind(normalize_phone(phone))
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Update user's swap slippage tolerance, returning false if the user doesn't exist
+    pub async fn update_slippage(&self, phone: &str, slippage: Slippage) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("UPDATE users SET slippage_bps = $1 WHERE phone = $2")
+            .bind(slippage.bps() as i32)
+            .bind(normalize_phone(phone))
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
     /// Check if user exists
     pub async fn exists(&self, phone: &str) -> Result<bool, sqlx::Error> {
         let result = sqlx::query_scalar::<_, i64>(
             "SELECT COUNT(*) FROM users WHERE phone = $1"
         )
-        .bind(phone)
+        .bind(normalize_phone(phone))
         .fetch_one(&self.pool)
         .await?;
         
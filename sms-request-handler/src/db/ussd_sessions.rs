@@ -0,0 +1,93 @@
+use sqlx::PgPool;
+use chrono::{Duration, Utc};
+
+/// Where a USSD session is in the menu tree
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct UssdSession {
+    pub menu: String,         // e.g. "amount"
+    pub data: String,         // answers collected so far, a JSON array
+}
+
+impl UssdSession {
+    /// Answers collected so far
+    pub fn args(&self) -> Vec<String> {
+        serde_json::from_str(&self.data).unwrap_or_default()
+    }
+}
+
+/// USSD session repository.
+///
+/// Keyed by the operator's session ID, so every handler instance can
+/// answer the next step of a session.
+#[derive(Clone)]
+pub struct UssdSessionRepository {
+    pool: PgPool,
+}
+
+impl UssdSessionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Start or advance a session
+    pub async fn save(
+        &self,
+        session_id: &str,
+        phone: &str,
+        menu: &str,
+        args: &[String],
+        ttl: Duration,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO ussd_sessions (session_id, phone, menu, data, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (session_id) DO UPDATE
+            SET menu = EXCLUDED.menu, data = EXCLUDED.data, expires_at = EXCLUDED.expires_at
+            WHERE ussd_sessions.phone = EXCLUDED.phone
+            "#
+        )
+        .bind(session_id)
+        .bind(phone)
+        .bind(menu)
+        .bind(serde_json::to_string(args).unwrap_or_else(|_| "[]".to_string()))
+        .bind(Utc::now() + ttl)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get an unexpired session, only for the phone that started it
+    pub async fn find_active(&self, session_id: &str, phone: &str) -> Result<Option<UssdSession>, sqlx::Error> {
+        sqlx::query_as::<_, UssdSession>(
+            "SELECT menu, data
+             FROM ussd_sessions
+             WHERE session_id = $1 AND phone = $2 AND expires_at > NOW()"
+        )
+        .bind(session_id)
+        .bind(phone)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// End a session
+    pub async fn delete(&self, session_id: &str, phone: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM ussd_sessions WHERE session_id = $1 AND phone = $2")
+            .bind(session_id)
+            .bind(phone)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Remove sessions the operator has long since closed; returns how many
+    pub async fn delete_expired(&self) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM ussd_sessions WHERE expires_at <= NOW()")
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
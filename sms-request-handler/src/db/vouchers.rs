@@ -1,6 +1,10 @@
 use sqlx::PgPool;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use crate::wallet::Amount;
+
+/// Decimals of the micro-USDC amounts stored in `vouchers`
+pub const USDC_DECIMALS: u8 = 6;
 
 /// Voucher status
 #[derive(Debug, Clone, PartialEq, sqlx::Type)]
@@ -38,9 +42,9 @@ pub struct Voucher {
 }
 
 impl Voucher {
-    /// Get USDC amount as f64
-    pub fn usdc_as_f64(&self) -> f64 {
-        self.usdc_amount as f64 / 1_000_000.0
+    /// Exact USDC amount
+    pub fn usdc(&self) -> Amount {
+        Amount::from_base_units(self.usdc_amount.max(0) as u128, USDC_DECIMALS)
     }
 
     /// Check if voucher is valid for redemption
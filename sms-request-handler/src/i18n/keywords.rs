@@ -0,0 +1,181 @@
+use super::Lang;
+
+/// Localized command keywords mapped to the canonical English keyword.
+/// The first entry for a (language, keyword) pair is the one shown in replies.
+const KEYWORDS: &[(Lang, &str, &str)] = &[
+    // Kiswahili
+    (Lang::Sw, "JIUNGE", "JOIN"),
+    (Lang::Sw, "SALIO", "BALANCE"),
+    (Lang::Sw, "TUMA", "SEND"),
+    (Lang::Sw, "WEKA", "DEPOSIT"),
+    (Lang::Sw, "HISTORIA", "HISTORY"),
+    (Lang::Sw, "HALI", "STATUS"),
+    (Lang::Sw, "KOMBOA", "REDEEM"),
+    (Lang::Sw, "BADILISHA", "SWAP"),
+    (Lang::Sw, "TOA", "CASHOUT"),
+    (Lang::Sw, "KIKOMO", "LIMIT"),
+    (Lang::Sw, "NAMBAMPYA", "NEWNUMBER"),
+    (Lang::Sw, "THIBITISHA", "VERIFY"),
+    (Lang::Sw, "BEI", "QUOTE"),
+    (Lang::Sw, "UTELEZI", "SLIPPAGE"),
+    (Lang::Sw, "VUKA", "BRIDGE"),
+    (Lang::Sw, "HIFADHI", "SAVE"),
+    (Lang::Sw, "ANWANI", "CONTACTS"),
+    (Lang::Sw, "FUTA", "DELETE"),
+    (Lang::Sw, "BADILIJINA", "RENAME"),
+    (Lang::Sw, "MTANDAO", "CHAIN"),
+    (Lang::Sw, "MSAADA", "COMMANDS"),
+    (Lang::Sw, "NDIYO", "YES"),
+    (Lang::Sw, "HAPANA", "NO"),
+    (Lang::Sw, "LUGHA", "LANG"),
+    (Lang::Sw, "OMBA", "REQUEST"),
+    (Lang::Sw, "LIPA", "PAY"),
+    (Lang::Sw, "KATAA", "DECLINE"),
+    (Lang::Sw, "GAWANYA", "SPLIT"),
+    (Lang::Sw, "ZAIDI", "MORE"),
+    (Lang::Sw, "RATIBA", "SCHEDULES"),
+    (Lang::Sw, "SIMAMISHA", "STOP"),
+    // Hindi (romanized)
+    (Lang::Hi, "JUDO", "JOIN"),
+    (Lang::Hi, "BAKAYA", "BALANCE"),
+    (Lang::Hi, "BHEJO", "SEND"),
+    (Lang::Hi, "JAMA", "DEPOSIT"),
+    (Lang::Hi, "ITIHAAS", "HISTORY"),
+    (Lang::Hi, "STHITI", "STATUS"),
+    (Lang::Hi, "BHUNAO", "REDEEM"),
+    (Lang::Hi, "BADLO", "SWAP"),
+    (Lang::Hi, "NIKALO", "CASHOUT"),
+    (Lang::Hi, "SEEMA", "LIMIT"),
+    (Lang::Hi, "NAYANUMBER", "NEWNUMBER"),
+    (Lang::Hi, "PUSHTI", "VERIFY"),
+    (Lang::Hi, "DAAM", "QUOTE"),
+    (Lang::Hi, "FISLAN", "SLIPPAGE"),
+    (Lang::Hi, "PUL", "BRIDGE"),
+    (Lang::Hi, "SAHEJO", "SAVE"),
+    (Lang::Hi, "SAMPARK", "CONTACTS"),
+    (Lang::Hi, "HATAO", "DELETE"),
+    (Lang::Hi, "NAAMBADLO", "RENAME"),
+    (Lang::Hi, "MADAD", "COMMANDS"),
+    (Lang::Hi, "HAAN", "YES"),
+    (Lang::Hi, "NAHI", "NO"),
+    (Lang::Hi, "BHASHA", "LANG"),
+    (Lang::Hi, "MAANGO", "REQUEST"),
+    (Lang::Hi, "CHUKAO", "PAY"),
+    (Lang::Hi, "INKAAR", "DECLINE"),
+    (Lang::Hi, "BAANTO", "SPLIT"),
+    (Lang::Hi, "AUR", "MORE"),
+    (Lang::Hi, "SAMAYSUCHI", "SCHEDULES"),
+    (Lang::Hi, "ROKO", "STOP"),
+    // Spanish
+    (Lang::Es, "UNIRSE", "JOIN"),
+    (Lang::Es, "SALDO", "BALANCE"),
+    (Lang::Es, "ENVIAR", "SEND"),
+    (Lang::Es, "DEPOSITAR", "DEPOSIT"),
+    (Lang::Es, "HISTORIAL", "HISTORY"),
+    (Lang::Es, "ESTADO", "STATUS"),
+    (Lang::Es, "CANJEAR", "REDEEM"),
+    (Lang::Es, "CAMBIAR", "SWAP"),
+    (Lang::Es, "RETIRAR", "CASHOUT"),
+    (Lang::Es, "LIMITE", "LIMIT"),
+    (Lang::Es, "NUEVONUMERO", "NEWNUMBER"),
+    (Lang::Es, "VERIFICAR", "VERIFY"),
+    (Lang::Es, "COTIZAR", "QUOTE"),
+    (Lang::Es, "DESLIZ", "SLIPPAGE"),
+    (Lang::Es, "PUENTE", "BRIDGE"),
+    (Lang::Es, "GUARDAR", "SAVE"),
+    (Lang::Es, "CONTACTOS", "CONTACTS"),
+    (Lang::Es, "BORRAR", "DELETE"),
+    (Lang::Es, "RENOMBRAR", "RENAME"),
+    (Lang::Es, "RED", "CHAIN"),
+    (Lang::Es, "AYUDA", "COMMANDS"),
+    (Lang::Es, "SI", "YES"),
+    (Lang::Es, "IDIOMA", "LANG"),
+    (Lang::Es, "SOLICITAR", "REQUEST"),
+    (Lang::Es, "PAGAR", "PAY"),
+    (Lang::Es, "RECHAZAR", "DECLINE"),
+    (Lang::Es, "DIVIDIR", "SPLIT"),
+    (Lang::Es, "MAS", "MORE"),
+    (Lang::Es, "PROGRAMADOS", "SCHEDULES"),
+    (Lang::Es, "DETENER", "STOP"),
+];
+
+/// Localized filler words (TO, FROM) inside command arguments
+const FILLERS: &[(Lang, &str, &str)] = &[
+    (Lang::Sw, "KWA", "TO"),
+    (Lang::Sw, "KUTOKA", "FROM"),
+    (Lang::Hi, "KO", "TO"),
+    (Lang::Hi, "SE", "FROM"),
+    (Lang::Es, "A", "TO"),
+    (Lang::Es, "DE", "FROM"),
+];
+
+/// Map a localized keyword to its canonical English keyword and language
+pub fn canonical(word: &str) -> Option<(&'static str, Lang)> {
+    KEYWORDS
+        .iter()
+        .find(|(_, local, _)| local.eq_ignore_ascii_case(word))
+        .map(|(lang, _, canonical)| (*canonical, *lang))
+}
+
+/// Every localized keyword as (language, local, canonical)
+pub fn all() -> impl Iterator<Item = (Lang, &'static str, &'static str)> {
+    KEYWORDS.iter().copied()
+}
+
+/// Keyword to show a user for a canonical command in their language
+pub fn localized(lang: Lang, canonical: &'static str) -> &'static str {
+    KEYWORDS
+        .iter()
+        .find(|(l, _, c)| *l == lang && *c == canonical)
+        .map(|(_, local, _)| *local)
+        .unwrap_or(canonical)
+}
+
+/// Check whether a word is the given filler keyword in English or in `lang`
+pub fn is_filler(lang: Lang, canonical: &str, word: &str) -> bool {
+    word.eq_ignore_ascii_case(canonical)
+        || FILLERS
+            .iter()
+            .any(|(l, local, c)| *l == lang && *c == canonical && local.eq_ignore_ascii_case(word))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical() {
+        assert_eq!(canonical("tuma"), Some(("SEND", Lang::Sw)));
+        assert_eq!(canonical("SALIO"), Some(("BALANCE", Lang::Sw)));
+        assert_eq!(canonical("Enviar"), Some(("SEND", Lang::Es)));
+        assert_eq!(canonical("SEND"), None);
+    }
+
+    #[test]
+    fn test_localized() {
+        assert_eq!(localized(Lang::Sw, "SEND"), "TUMA");
+        assert_eq!(localized(Lang::En, "SEND"), "SEND");
+        assert_eq!(localized(Lang::Hi, "PIN"), "PIN");
+    }
+
+    #[test]
+    fn test_fillers() {
+        assert!(is_filler(Lang::Sw, "TO", "kwa"));
+        assert!(is_filler(Lang::Sw, "TO", "TO"));
+        assert!(!is_filler(Lang::En, "TO", "A"));
+    }
+
+    #[test]
+    fn test_localized_keywords_resolve_to_commands() {
+        for (_, local, canonical) in KEYWORDS {
+            assert!(
+                crate::commands::registry::lookup(canonical).is_some(),
+                "{} maps to unknown command {}", local, canonical
+            );
+            assert!(
+                crate::commands::registry::lookup(local).is_none(),
+                "{} clashes with an English keyword", local
+            );
+        }
+    }
+}
@@ -0,0 +1,1556 @@
+use super::Lang;
+
+/// Every reply the SMS handler can send.
+///
+/// Templates use `{0}`, `{1}`, ... placeholders so translations can
+/// reorder arguments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Msg {
+    // General
+    DbOffline,
+    TryLater,
+    Throttled,
+    NoWallet,
+    NetworkError,
+    BadResponse,
+    Welcome,
+    UnknownCommand,
+    DidYouMean,
+    Usage,
+    Example,
+    MorePages,
+    NoMorePages,
+    InvalidAmount,
+    AmountNotPositive,
+    AmountTooPrecise,
+    UnknownToken,
+    // Help
+    HelpTitle,
+    HelpJoin,
+    HelpBalance,
+    HelpPin,
+    HelpSend,
+    HelpDeposit,
+    HelpHistory,
+    HelpStatus,
+    HelpRedeem,
+    HelpSwap,
+    HelpQuote,
+    HelpSlippage,
+    HelpCashout,
+    HelpLimit,
+    HelpNewNumber,
+    HelpRequest,
+    HelpSplit,
+    HelpSchedules,
+    HelpContacts,
+    HelpLang,
+    HelpMenu,
+    // JOIN
+    EnsLength,
+    EnsCharset,
+    NameUnavailable,
+    NameCheckFailed,
+    Registered,
+    NameRegisterFailed,
+    JoinFirst,
+    WelcomeBack,
+    WalletCreateFailed,
+    WalletCreated,
+    WalletSaveFailed,
+    // BALANCE
+    BalanceDbOffline,
+    Balance,
+    ChainBalance,
+    BalanceEmpty,
+    BalanceFailed,
+    // PIN
+    PinPrompt,
+    PinInvalid,
+    PinAlreadySet,
+    PinSet,
+    PinNotSet,
+    PinWrongUnchanged,
+    PinChanged,
+    PinRequired,
+    // Pending transactions
+    ConfirmPrompt,
+    NothingToConfirm,
+    PendingExpired,
+    WrongPinCancelled,
+    WrongPinTriesLeft,
+    PinLocked,
+    Cancelled,
+    NothingToCancel,
+    DescribeSend,
+    DescribeSwap,
+    DescribeSwapQuote,
+    DescribeBridge,
+    DescribeCashout,
+    DescribeSplit,
+    InvalidSplit,
+    SplitTooSmall,
+    SplitSummary,
+    // Dialogs
+    AskAmount,
+    AskToken,
+    AskRecipient,
+    AskPayer,
+    DialogHint,
+    DialogCancelled,
+    // Recipients
+    NotJoined,
+    RecipientLookupFailed,
+    EnsUnresolved,
+    EnsNetworkError,
+    InvalidRecipient,
+    ContactNoWallet,
+    ContactNoAddress,
+    // SEND
+    UnsupportedToken,
+    Sending,
+    SentOnChain,
+    InsufficientBalance,
+    TransferFailed,
+    // DEPOSIT / HISTORY
+    FundWallet,
+    FundWalletChain,
+    HistoryPage,
+    HistoryMore,
+    HistoryNoPage,
+    HistorySent,
+    HistoryReceived,
+    HistorySwap,
+    HistoryBridge,
+    HistoryRedeem,
+    HistoryDeposit,
+    HistoryCashout,
+    HistoryRefund,
+    TxPendingTag,
+    TxFailedTag,
+    NoTransactions,
+    // STATUS
+    TxStatusLine,
+    TxHash,
+    StatusPending,
+    StatusCompleted,
+    StatusFailed,
+    StatusNotFound,
+    TrackHint,
+    TxStalled,
+    // REQUEST / PAY / DECLINE
+    RequestSent,
+    PaymentRequested,
+    RequestNotFound,
+    RequestExpired,
+    RequestSelf,
+    PayerNotJoined,
+    RequestDeclined,
+    RequestDeclinedAck,
+    RequestPaid,
+    // Scheduled payments
+    DescribeSchedule,
+    ScheduleOnce,
+    ScheduleDaily,
+    ScheduleWeekly,
+    ScheduleMonthly,
+    InvalidSchedule,
+    SchedulePast,
+    ScheduleCreated,
+    SchedulesList,
+    ScheduleLine,
+    NoSchedules,
+    ScheduleStopped,
+    ScheduleNotFound,
+    ScheduleRan,
+    ScheduleLowBalance,
+    ScheduleRunFailed,
+    // REDEEM
+    VoucherRedeemed,
+    VoucherUsed,
+    VoucherInvalid,
+    RedeemFailed,
+    // SWAP / BRIDGE
+    Swapping,
+    SwapFailed,
+    SwapUnsupportedChain,
+    Quote,
+    QuoteMin,
+    QuoteFailed,
+    QuoteMoved,
+    SlippageCurrent,
+    SlippageSet,
+    SlippageInvalid,
+    BridgeStarted,
+    BridgeFailed,
+    BridgeInitiated,
+    BridgeUnavailable,
+    // CASHOUT
+    CashoutUnavailable,
+    CashoutUnknownMethod,
+    CashoutQuoteFailed,
+    CashoutUnsupportedToken,
+    CashoutPaid,
+    CashoutStarted,
+    CashoutRefunded,
+    CashoutRefundFailed,
+    CashoutDebitPending,
+    CashoutDebitFailed,
+    // LIMIT
+    LimitPerTxLabel,
+    LimitDailyLabel,
+    LimitWeeklyLabel,
+    LimitsShow,
+    LimitRaiseWaiting,
+    LimitLowered,
+    LimitUnchanged,
+    LimitRaiseNeedsPin,
+    LimitRaiseScheduled,
+    LimitWrongPin,
+    LimitPerTxExceeded,
+    LimitDailyExceeded,
+    LimitWeeklyExceeded,
+    // NEWNUMBER / VERIFY
+    NumberInvalid,
+    NumberSame,
+    NumberTaken,
+    NumberCodeSent,
+    NumberCode,
+    NumberNoChange,
+    NumberCodeExpired,
+    NumberWrongCode,
+    NumberTooManyTries,
+    NumberVerified,
+    NumberChangeWarning,
+    NumberChangeCancelled,
+    NumberChanged,
+    NumberChangedOld,
+    // Address book
+    AddressBookOffline,
+    ContactSaved,
+    ContactSaveFailed,
+    NoContacts,
+    ContactsPage,
+    ContactsNoPage,
+    ContactsLoadFailed,
+    InvalidContact,
+    InvalidContactName,
+    ContactDeleted,
+    ContactNotFound,
+    ContactRenamed,
+    ContactNameTaken,
+    ContactAmbiguous,
+    // CHAIN
+    UnknownChain,
+    ChainSwitched,
+    // LANG
+    LangCurrent,
+    LangSet,
+    LangUnknown,
+    // USSD
+    UssdMenu,
+    UssdRecipient,
+    UssdAmount,
+    UssdPin,
+    UssdCode,
+    UssdContacts,
+    UssdMore,
+    UssdInvalid,
+    UssdWorking,
+}
+
+impl Msg {
+    /// Templates in catalog order: English, Kiswahili, Hindi, Spanish
+    fn texts(self) -> [&'static str; 4] {
+        match self {
+            Msg::DbOffline => [
+                "DB offline. Try later.",
+                "Huduma haipatikani. Jaribu baadaye.",
+                "Seva band hai. Baad mein koshish karein.",
+                "Servicio no disponible. Intenta mas tarde.",
+            ],
+            Msg::TryLater => [
+                "Error. Try later.",
+                "Hitilafu. Jaribu baadaye.",
+                "Galti hui. Baad mein koshish karein.",
+                "Error. Intenta mas tarde.",
+            ],
+            Msg::Throttled => [
+                "Too many messages. Wait {0} min and try again.",
+                "Ujumbe mwingi mno. Subiri dakika {0} kisha ujaribu tena.",
+                "Bahut zyada sandesh. {0} minute ruk kar dobara koshish karein.",
+                "Demasiados mensajes. Espera {0} min e intenta de nuevo.",
+            ],
+            Msg::NoWallet => [
+                "No wallet. Reply JOIN first.",
+                "Huna pochi. Jibu JIUNGE kwanza.",
+                "Wallet nahi hai. Pehle JUDO bhejein.",
+                "Sin billetera. Responde UNIRSE primero.",
+            ],
+            Msg::NetworkError => [
+                "Network error. Try later.",
+                "Hitilafu ya mtandao. Jaribu baadaye.",
+                "Network mein galti. Baad mein koshish karein.",
+                "Error de red. Intenta mas tarde.",
+            ],
+            Msg::BadResponse => [
+                "Error processing response.",
+                "Hitilafu katika kuchakata jibu.",
+                "Jawab samajhne mein galti.",
+                "Error al procesar la respuesta.",
+            ],
+            Msg::Welcome => [
+                "Welcome to TextChain!\n\nReply COMMANDS for help.",
+                "Karibu TextChain!\n\nJibu MSAADA kwa msaada.",
+                "TextChain mein swagat hai!\n\nMadad ke liye MADAD bhejein.",
+                "Bienvenido a TextChain!\n\nResponde AYUDA para ayuda.",
+            ],
+            Msg::UnknownCommand => [
+                "Unknown: {0}\n\nReply COMMANDS for help.",
+                "Haijulikani: {0}\n\nJibu MSAADA kwa msaada.",
+                "Anjaan: {0}\n\nMadad ke liye MADAD bhejein.",
+                "Desconocido: {0}\n\nResponde AYUDA para ayuda.",
+            ],
+            Msg::DidYouMean => [
+                "Unknown: {0}\nDid you mean: {1}\n\nReply COMMANDS for help.",
+                "Haijulikani: {0}\nUlimaanisha: {1}\n\nJibu MSAADA kwa msaada.",
+                "Anjaan: {0}\nKya aapka matlab: {1}\n\nMadad ke liye MADAD bhejein.",
+                "Desconocido: {0}\nQuisiste decir: {1}\n\nResponde AYUDA para ayuda.",
+            ],
+            Msg::Usage => [
+                "Usage: {0}",
+                "Matumizi: {0}",
+                "Upyog: {0}",
+                "Uso: {0}",
+            ],
+            Msg::Example => [
+                "Example: {0}",
+                "Mfano: {0}",
+                "Udaharan: {0}",
+                "Ejemplo: {0}",
+            ],
+            Msg::MorePages => [
+                "({0}/{1}) Reply MORE",
+                "({0}/{1}) Jibu ZAIDI",
+                "({0}/{1}) AUR bhejein",
+                "({0}/{1}) Responde MAS",
+            ],
+            Msg::NoMorePages => [
+                "Nothing more to show.",
+                "Hakuna zaidi ya kuonyesha.",
+                "Dikhane ke liye aur kuch nahi.",
+                "No hay nada mas que mostrar.",
+            ],
+            Msg::InvalidAmount => [
+                "Invalid amount.",
+                "Kiasi si sahihi.",
+                "Galat rakam.",
+                "Cantidad invalida.",
+            ],
+            Msg::AmountNotPositive => [
+                "Amount must be more than 0.",
+                "Kiasi lazima kiwe zaidi ya 0.",
+                "Rakam 0 se zyada honi chahiye.",
+                "La cantidad debe ser mayor que 0.",
+            ],
+            Msg::AmountTooPrecise => [
+                "{1} allows at most {0} decimal places.",
+                "{1} inaruhusu desimali {0} tu.",
+                "{1} mein zyada se zyada {0} decimal ho sakte hain.",
+                "{1} permite como maximo {0} decimales.",
+            ],
+            Msg::UnknownToken => [
+                "Unknown token: {0}",
+                "Tokeni haijulikani: {0}",
+                "Anjaan token: {0}",
+                "Token desconocido: {0}",
+            ],
+            Msg::HelpTitle => [
+                "Text-to-Chain Commands:",
+                "Amri za Text-to-Chain:",
+                "Text-to-Chain Commands:",
+                "Comandos de Text-to-Chain:",
+            ],
+            Msg::HelpJoin => ["Create wallet", "Fungua pochi", "Wallet banayein", "Crear billetera"],
+            Msg::HelpBalance => ["Check balance", "Angalia salio", "Bakaya dekhein", "Ver saldo"],
+            Msg::HelpPin => ["Set PIN", "Weka PIN", "PIN set karein", "Fijar PIN"],
+            Msg::HelpSend => ["Send tokens", "Tuma tokeni", "Token bhejein", "Enviar tokens"],
+            Msg::HelpDeposit => [
+                "Get deposit address",
+                "Pata anwani ya kuweka",
+                "Jama pata lein",
+                "Direccion de deposito",
+            ],
+            Msg::HelpHistory => ["Recent activity", "Shughuli za karibuni", "Haal ki gatividhi", "Actividad reciente"],
+            Msg::HelpStatus => ["Track a transaction", "Fuatilia muamala", "Len-den ki sthiti", "Seguir una transaccion"],
+            Msg::HelpRedeem => ["Redeem voucher", "Komboa vocha", "Voucher bhunayein", "Canjear cupon"],
+            Msg::HelpSwap => ["Swap to ETH", "Badilisha kuwa ETH", "ETH mein badlein", "Cambiar a ETH"],
+            Msg::HelpQuote => ["Price a swap", "Bei ya kubadilisha", "Badalne ka bhaav", "Cotizar un cambio"],
+            Msg::HelpSlippage => ["Swap price tolerance", "Uvumilivu wa bei", "Bhaav ki chhoot", "Tolerancia de precio"],
+            Msg::HelpCashout => ["Cash out to money", "Toa pesa taslimu", "Nakad nikaalein", "Retirar a efectivo"],
+            Msg::HelpLimit => ["Spending limits", "Vikomo vya matumizi", "Kharch ki seema", "Limites de gasto"],
+            Msg::HelpNewNumber => ["Move wallet to a new number", "Hamisha pochi kwa namba mpya", "Wallet naye number par le jayein", "Mover billetera a otro numero"],
+            Msg::HelpRequest => ["Ask for money", "Omba pesa", "Paise maangein", "Pedir dinero"],
+            Msg::HelpSplit => ["Split a payment", "Gawanya malipo", "Bhugtaan baantein", "Dividir un pago"],
+            Msg::HelpSchedules => ["Scheduled payments", "Malipo yaliyopangwa", "Nirdharit bhugtaan", "Pagos programados"],
+            Msg::HelpContacts => ["Saved contacts", "Anwani zilizohifadhiwa", "Sahaje sampark", "Contactos guardados"],
+            Msg::HelpLang => ["Change language", "Badilisha lugha", "Bhasha badlein", "Cambiar idioma"],
+            Msg::HelpMenu => ["Show this help", "Onyesha msaada huu", "Yeh madad dikhayein", "Mostrar esta ayuda"],
+            Msg::EnsLength => [
+                "ENS name must be 3-20 characters.\n\nTry again: JOIN <name>\nExample: JOIN alice",
+                "Jina la ENS liwe na herufi 3-20.\n\nJaribu tena: JIUNGE <jina>\nMfano: JIUNGE alice",
+                "ENS naam 3-20 akshar ka ho.\n\nPhir koshish karein: JUDO <naam>\nUdaharan: JUDO alice",
+                "El nombre ENS debe tener 3-20 caracteres.\n\nIntenta de nuevo: UNIRSE <nombre>\nEjemplo: UNIRSE alice",
+            ],
+            Msg::EnsCharset => [
+                "ENS name can only contain letters and numbers.\n\nTry again: JOIN <name>",
+                "Jina la ENS liwe na herufi na namba tu.\n\nJaribu tena: JIUNGE <jina>",
+                "ENS naam mein sirf akshar aur ank ho.\n\nPhir koshish karein: JUDO <naam>",
+                "El nombre ENS solo puede tener letras y numeros.\n\nIntenta de nuevo: UNIRSE <nombre>",
+            ],
+            Msg::NameUnavailable => [
+                "❌ {0}\n\nTry another name:\nJOIN <name>\n\nExamples: alice, bob123, john",
+                "❌ {0}\n\nJaribu jina jingine:\nJIUNGE <jina>\n\nMifano: alice, bob123, john",
+                "❌ {0}\n\nDoosra naam try karein:\nJUDO <naam>\n\nUdaharan: alice, bob123, john",
+                "❌ {0}\n\nPrueba otro nombre:\nUNIRSE <nombre>\n\nEjemplos: alice, bob123, john",
+            ],
+            Msg::NameCheckFailed => [
+                "Error checking name availability. Try later.",
+                "Hitilafu kukagua jina. Jaribu baadaye.",
+                "Naam jaanchne mein galti. Baad mein koshish karein.",
+                "Error al verificar el nombre. Intenta mas tarde.",
+            ],
+            Msg::Registered => [
+                "Registered!\n{0}\nWallet: {1}\n\nReply DEPOSIT to fund.",
+                "Umesajiliwa!\n{0}\nPochi: {1}\n\nJibu WEKA kuongeza pesa.",
+                "Register ho gaya!\n{0}\nWallet: {1}\n\nPaise daalne ke liye JAMA bhejein.",
+                "Registrado!\n{0}\nBilletera: {1}\n\nResponde DEPOSITAR para fondear.",
+            ],
+            Msg::NameRegisterFailed => [
+                "Error registering ENS name. Try later.",
+                "Hitilafu kusajili jina la ENS. Jaribu baadaye.",
+                "ENS naam register karne mein galti. Baad mein koshish karein.",
+                "Error al registrar el nombre ENS. Intenta mas tarde.",
+            ],
+            Msg::JoinFirst => [
+                "Please use JOIN first to create your wallet.",
+                "Tafadhali tumia JIUNGE kwanza kufungua pochi.",
+                "Wallet banane ke liye pehle JUDO bhejein.",
+                "Usa UNIRSE primero para crear tu billetera.",
+            ],
+            Msg::WelcomeBack => [
+                "Welcome back!\n\nYour wallet:\n{0}\n\nReply BALANCE or DEPOSIT",
+                "Karibu tena!\n\nPochi yako:\n{0}\n\nJibu SALIO au WEKA",
+                "Phir se swagat hai!\n\nAapka wallet:\n{0}\n\nBAKAYA ya JAMA bhejein",
+                "Bienvenido de nuevo!\n\nTu billetera:\n{0}\n\nResponde SALDO o DEPOSITAR",
+            ],
+            Msg::WalletCreateFailed => [
+                "Error creating wallet.",
+                "Hitilafu kufungua pochi.",
+                "Wallet banane mein galti.",
+                "Error al crear la billetera.",
+            ],
+            Msg::WalletCreated => [
+                "Wallet created!\n{0}\n\nNow pick a name:\nJOIN <name>\n\nEx: JOIN alice",
+                "Pochi imefunguliwa!\n{0}\n\nSasa chagua jina:\nJIUNGE <jina>\n\nMf: JIUNGE alice",
+                "Wallet ban gaya!\n{0}\n\nAb naam chunein:\nJUDO <naam>\n\nUd: JUDO alice",
+                "Billetera creada!\n{0}\n\nAhora elige un nombre:\nUNIRSE <nombre>\n\nEj: UNIRSE alice",
+            ],
+            Msg::WalletSaveFailed => [
+                "Error saving wallet.",
+                "Hitilafu kuhifadhi pochi.",
+                "Wallet save karne mein galti.",
+                "Error al guardar la billetera.",
+            ],
+            Msg::BalanceDbOffline => [
+                "Balance: $0.00\nDB offline.",
+                "Salio: $0.00\nHuduma haipatikani.",
+                "Bakaya: $0.00\nSeva band hai.",
+                "Saldo: $0.00\nServicio no disponible.",
+            ],
+            Msg::Balance => [
+                "Balance ({2}):\n{0} TXTC\n{1} ETH",
+                "Salio ({2}):\n{0} TXTC\n{1} ETH",
+                "Bakaya ({2}):\n{0} TXTC\n{1} ETH",
+                "Saldo ({2}):\n{0} TXTC\n{1} ETH",
+            ],
+            Msg::ChainBalance => [
+                "Balance ({0}):\n{1}",
+                "Salio ({0}):\n{1}",
+                "Bakaya ({0}):\n{1}",
+                "Saldo ({0}):\n{1}",
+            ],
+            Msg::BalanceEmpty => [
+                "Balance ({0}): $0.00\n\nReply DEPOSIT to fund wallet.",
+                "Salio ({0}): $0.00\n\nJibu WEKA kuongeza pesa.",
+                "Bakaya ({0}): $0.00\n\nPaise daalne ke liye JAMA bhejein.",
+                "Saldo ({0}): $0.00\n\nResponde DEPOSITAR para fondear.",
+            ],
+            Msg::BalanceFailed => [
+                "Error fetching balance.",
+                "Hitilafu kupata salio.",
+                "Bakaya laane mein galti.",
+                "Error al obtener el saldo.",
+            ],
+            Msg::PinPrompt => [
+                "Reply: PIN <4-6 digits>\nExample: PIN 1234",
+                "Jibu: PIN <tarakimu 4-6>\nMfano: PIN 1234",
+                "Bhejein: PIN <4-6 ank>\nUdaharan: PIN 1234",
+                "Responde: PIN <4-6 digitos>\nEjemplo: PIN 1234",
+            ],
+            Msg::PinInvalid => [
+                "PIN must be 4-6 digits.\nExample: PIN 1234",
+                "PIN iwe tarakimu 4-6.\nMfano: PIN 1234",
+                "PIN 4-6 ank ka ho.\nUdaharan: PIN 1234",
+                "El PIN debe tener 4-6 digitos.\nEjemplo: PIN 1234",
+            ],
+            Msg::PinAlreadySet => [
+                "PIN already set.\nTo change: PIN <old> <new>",
+                "PIN tayari imewekwa.\nKubadilisha: PIN <ya zamani> <mpya>",
+                "PIN pehle se set hai.\nBadalne ke liye: PIN <purana> <naya>",
+                "El PIN ya existe.\nPara cambiarlo: PIN <actual> <nuevo>",
+            ],
+            Msg::PinSet => ["PIN set!", "PIN imewekwa!", "PIN set ho gaya!", "PIN guardado!"],
+            Msg::PinNotSet => [
+                "No PIN set yet.\nReply: PIN <4-6 digits>",
+                "Bado hujaweka PIN.\nJibu: PIN <tarakimu 4-6>",
+                "Abhi PIN set nahi hai.\nBhejein: PIN <4-6 ank>",
+                "Aun no tienes PIN.\nResponde: PIN <4-6 digitos>",
+            ],
+            Msg::PinWrongUnchanged => [
+                "Wrong PIN. PIN not changed.",
+                "PIN si sahihi. PIN haijabadilishwa.",
+                "Galat PIN. PIN nahi badla.",
+                "PIN incorrecto. El PIN no cambio.",
+            ],
+            Msg::PinChanged => ["PIN changed!", "PIN imebadilishwa!", "PIN badal gaya!", "PIN cambiado!"],
+            Msg::PinRequired => [
+                "Set a PIN first to move funds.\nReply: PIN <4-6 digits>",
+                "Weka PIN kwanza ili kutuma pesa.\nJibu: PIN <tarakimu 4-6>",
+                "Paise bhejne se pehle PIN set karein.\nBhejein: PIN <4-6 ank>",
+                "Fija un PIN antes de mover fondos.\nResponde: PIN <4-6 digitos>",
+            ],
+            Msg::ConfirmPrompt => [
+                "{0}?\n\nReply YES <PIN> to confirm or NO to cancel.\nRef {1} expires in {2} min.",
+                "{0}?\n\nJibu NDIYO <PIN> kuthibitisha au HAPANA kughairi.\nRef {1} inaisha baada ya dak {2}.",
+                "{0}?\n\nPushti ke liye HAAN <PIN>, radd ke liye NAHI bhejein.\nRef {1} {2} min mein khatam.",
+                "{0}?\n\nResponde SI <PIN> para confirmar o NO para cancelar.\nRef {1} vence en {2} min.",
+            ],
+            Msg::NothingToConfirm => [
+                "Nothing to confirm.",
+                "Hakuna cha kuthibitisha.",
+                "Pushti ke liye kuch nahi hai.",
+                "Nada que confirmar.",
+            ],
+            Msg::PendingExpired => [
+                "Ref {0} expired. Please send the command again.",
+                "Ref {0} imeisha muda. Tafadhali tuma amri tena.",
+                "Ref {0} ki samay seema khatam. Command phir bhejein.",
+                "Ref {0} vencio. Envia el comando de nuevo.",
+            ],
+            Msg::WrongPinCancelled => [
+                "Wrong PIN. Ref {0} cancelled.",
+                "PIN si sahihi. Ref {0} imeghairiwa.",
+                "Galat PIN. Ref {0} radd.",
+                "PIN incorrecto. Ref {0} cancelada.",
+            ],
+            Msg::WrongPinTriesLeft => [
+                "Wrong PIN. {0} tries left.",
+                "PIN si sahihi. Majaribio {0} yamebaki.",
+                "Galat PIN. {0} koshish baaki.",
+                "PIN incorrecto. Quedan {0} intentos.",
+            ],
+            Msg::PinLocked => [
+                "Too many wrong PINs. PIN commands are locked for {0} min.",
+                "PIN zisizo sahihi nyingi mno. Amri za PIN zimefungwa kwa dakika {0}.",
+                "Bahut zyada galat PIN. PIN commands {0} min ke liye band.",
+                "Demasiados PIN incorrectos. Los comandos con PIN quedan bloqueados {0} min.",
+            ],
+            Msg::Cancelled => [
+                "Cancelled ref {0}.",
+                "Ref {0} imeghairiwa.",
+                "Ref {0} radd kiya gaya.",
+                "Ref {0} cancelada.",
+            ],
+            Msg::NothingToCancel => [
+                "Nothing to cancel.",
+                "Hakuna cha kughairi.",
+                "Radd karne ke liye kuch nahi hai.",
+                "Nada que cancelar.",
+            ],
+            Msg::DescribeSend => [
+                "Send {0} {1} to {2} ({3})",
+                "Tuma {0} {1} kwa {2} ({3})",
+                "{2} ko {0} {1} bhejein ({3})",
+                "Enviar {0} {1} a {2} ({3})",
+            ],
+            Msg::DescribeSwap => [
+                "Swap {0} {1} to ETH ({2})",
+                "Badilisha {0} {1} kuwa ETH ({2})",
+                "{0} {1} ko ETH mein badlein ({2})",
+                "Cambiar {0} {1} a ETH ({2})",
+            ],
+            Msg::DescribeSwapQuote => [
+                "Swap {0} {1} to ~{2} ETH ({3})",
+                "Badilisha {0} {1} kuwa ~{2} ETH ({3})",
+                "{0} {1} ko ~{2} ETH mein badlein ({3})",
+                "Cambiar {0} {1} a ~{2} ETH ({3})",
+            ],
+            Msg::DescribeBridge => [
+                "Bridge {0} {1} from {2} to {3}",
+                "Vusha {0} {1} kutoka {2} hadi {3}",
+                "{0} {1} ko {2} se {3} bhejein",
+                "Puente de {0} {1} de {2} a {3}",
+            ],
+            Msg::DescribeCashout => [
+                "Cash out {0} {1} for {2} {3} via {4}",
+                "Toa {0} {1} upate {2} {3} kupitia {4}",
+                "{0} {1} nikaalein, {4} se {2} {3} milenge",
+                "Retirar {0} {1} por {2} {3} via {4}",
+            ],
+            Msg::DescribeSplit => [
+                "Split {0} {1}: {2} ({3})",
+                "Gawanya {0} {1}: {2} ({3})",
+                "{0} {1} baantein: {2} ({3})",
+                "Dividir {0} {1}: {2} ({3})",
+            ],
+            Msg::InvalidSplit => [
+                "Split between 2 to {0} people, all with or all without shares.\nExample: SPLIT 30 TXTC alice bob\nor: SPLIT 30 TXTC alice:20 bob:10",
+                "Gawanya kati ya watu 2 hadi {0}, wote na au wote bila hisa.\nMfano: GAWANYA 30 TXTC alice bob\nau: GAWANYA 30 TXTC alice:20 bob:10",
+                "2 se {0} logon mein baantein, sabke hisse ke saath ya sabke bina.\nUdaharan: BAANTO 30 TXTC alice bob\nya: BAANTO 30 TXTC alice:20 bob:10",
+                "Divide entre 2 y {0} personas, todas con o todas sin partes.\nEjemplo: DIVIDIR 30 TXTC alice bob\no: DIVIDIR 30 TXTC alice:20 bob:10",
+            ],
+            Msg::SplitTooSmall => [
+                "{0} {1} is too small to split {2} ways.",
+                "{0} {1} ni kidogo mno kugawanywa mara {2}.",
+                "{0} {1} ko {2} hisson mein baantna bahut kam hai.",
+                "{0} {1} es muy poco para dividir en {2}.",
+            ],
+            Msg::SplitSummary => [
+                "Split {0} {1}: {2} of {3} sent.\n{4}",
+                "Gawanya {0} {1}: {2} kati ya {3} zimetumwa.\n{4}",
+                "{0} {1} baanta: {3} mein se {2} bheje gaye.\n{4}",
+                "Division {0} {1}: {2} de {3} enviados.\n{4}",
+            ],
+            Msg::AskAmount => [
+                "How much? Reply with an amount, e.g. 10.",
+                "Kiasi gani? Jibu na kiasi, mf. 10.",
+                "Kitna? Rakam bhejein, jaise 10.",
+                "Cuanto? Responde con una cantidad, ej. 10.",
+            ],
+            Msg::AskToken => [
+                "Which token? e.g. TXTC, ETH or USDC.",
+                "Tokeni gani? mf. TXTC, ETH au USDC.",
+                "Kaunsa token? jaise TXTC, ETH ya USDC.",
+                "Que token? ej. TXTC, ETH o USDC.",
+            ],
+            Msg::AskRecipient => [
+                "Send to whom? Reply with a contact, phone number or ENS name.",
+                "Tuma kwa nani? Jibu na jina la mwasiliani, namba ya simu au jina la ENS.",
+                "Kise bhejna hai? Contact, phone number ya ENS naam bhejein.",
+                "A quien? Responde con un contacto, telefono o nombre ENS.",
+            ],
+            Msg::AskPayer => [
+                "Ask whom? Reply with a contact, phone number or ENS name.",
+                "Omba kutoka kwa nani? Jibu na jina la mwasiliani, namba ya simu au jina la ENS.",
+                "Kisse maangna hai? Contact, phone number ya ENS naam bhejein.",
+                "A quien le pides? Responde con un contacto, telefono o nombre ENS.",
+            ],
+            Msg::DialogHint => [
+                "Reply {0} to stop.",
+                "Jibu {0} kusitisha.",
+                "Rokne ke liye {0} bhejein.",
+                "Responde {0} para parar.",
+            ],
+            Msg::DialogCancelled => [
+                "Stopped. Nothing was sent.",
+                "Imesitishwa. Hakuna kilichotumwa.",
+                "Rok diya. Kuch nahi bheja gaya.",
+                "Detenido. No se envio nada.",
+            ],
+            Msg::NotJoined => [
+                "{0} hasn't joined yet.\nAsk them to text JOIN",
+                "{0} bado hajajiunga.\nMwambie atume JIUNGE",
+                "{0} abhi tak nahi juda.\nUnse JUDO bhejne ko kahein",
+                "{0} aun no se ha unido.\nPidele que envie UNIRSE",
+            ],
+            Msg::RecipientLookupFailed => [
+                "Error looking up recipient.",
+                "Hitilafu kumtafuta mpokeaji.",
+                "Prapt karta dhoondhne mein galti.",
+                "Error al buscar al destinatario.",
+            ],
+            Msg::EnsUnresolved => [
+                "Could not resolve {0}.\nUse wallet address instead.",
+                "Imeshindwa kupata {0}.\nTumia anwani ya pochi badala yake.",
+                "{0} nahi mila.\nWallet address use karein.",
+                "No se pudo resolver {0}.\nUsa la direccion de la billetera.",
+            ],
+            Msg::EnsNetworkError => [
+                "Network error resolving ENS. Try later.",
+                "Hitilafu ya mtandao kupata ENS. Jaribu baadaye.",
+                "ENS dhoondhne mein network galti. Baad mein koshish karein.",
+                "Error de red al resolver ENS. Intenta mas tarde.",
+            ],
+            Msg::InvalidRecipient => [
+                "Invalid recipient.\nUse ENS (name.ttcip.eth), phone (+1...), or address (0x...)",
+                "Mpokeaji si sahihi.\nTumia ENS (jina.ttcip.eth), simu (+254...), au anwani (0x...)",
+                "Galat prapt karta.\nENS (naam.ttcip.eth), phone (+91...), ya address (0x...) use karein",
+                "Destinatario invalido.\nUsa ENS (nombre.ttcip.eth), telefono (+34...) o direccion (0x...)",
+            ],
+            Msg::ContactNoWallet => [
+                "Contact {0} has no wallet.",
+                "Mwasiliani {0} hana pochi.",
+                "Sampark {0} ka wallet nahi hai.",
+                "El contacto {0} no tiene billetera.",
+            ],
+            Msg::ContactNoAddress => [
+                "Contact {0} has no address.",
+                "Mwasiliani {0} hana anwani.",
+                "Sampark {0} ka address nahi hai.",
+                "El contacto {0} no tiene direccion.",
+            ],
+            Msg::UnsupportedToken => [
+                "Supported tokens on {0}: {1}\nExample: SEND 10 {2} swarnim.ttcip.eth",
+                "Tokeni zinazokubalika kwenye {0}: {1}\nMfano: TUMA 10 {2} swarnim.ttcip.eth",
+                "{0} par samarthit token: {1}\nUdaharan: BHEJO 10 {2} swarnim.ttcip.eth",
+                "Tokens admitidos en {0}: {1}\nEjemplo: ENVIAR 10 {2} swarnim.ttcip.eth",
+            ],
+            Msg::Sending => [
+                "Sending {0} {1} to {2} on {3}...\n\nQueued via Yellow Network.\nYou'll get SMS when complete.",
+                "Inatuma {0} {1} kwa {2} kwenye {3}...\n\nImepangwa kupitia Yellow Network.\nUtapata SMS ikikamilika.",
+                "{2} ko {0} {1} {3} par bhej rahe hain...\n\nYellow Network par queue.\nPura hone par SMS milega.",
+                "Enviando {0} {1} a {2} en {3}...\n\nEn cola via Yellow Network.\nRecibiras un SMS al terminar.",
+            ],
+            Msg::SentOnChain => [
+                "Sent {0} {1} to {2} on {3}.\nTx: {4}",
+                "Umetuma {0} {1} kwa {2} kwenye {3}.\nTx: {4}",
+                "{2} ko {0} {1} {3} par bhej diya.\nTx: {4}",
+                "Enviado {0} {1} a {2} en {3}.\nTx: {4}",
+            ],
+            Msg::InsufficientBalance => [
+                "Insufficient balance.",
+                "Salio halitoshi.",
+                "Bakaya kam hai.",
+                "Saldo insuficiente.",
+            ],
+            Msg::TransferFailed => [
+                "Transfer failed. Try later.",
+                "Uhamisho umeshindwa. Jaribu baadaye.",
+                "Transfer fail hua. Baad mein koshish karein.",
+                "La transferencia fallo. Intenta mas tarde.",
+            ],
+            Msg::FundWallet => [
+                "Fund wallet ({1}):\nDial *384*46750#\nOr REDEEM <code>\nOr send to:\n{0}",
+                "Ongeza pesa ({1}):\nPiga *384*46750#\nAu KOMBOA <nambari>\nAu tuma kwa:\n{0}",
+                "Wallet mein paise daalein ({1}):\n*384*46750# dial karein\nYa BHUNAO <code>\nYa yahan bhejein:\n{0}",
+                "Fondear billetera ({1}):\nMarca *384*46750#\nO CANJEAR <codigo>\nO envia a:\n{0}",
+            ],
+            Msg::FundWalletChain => [
+                "Fund wallet ({1}):\nSend to:\n{0}",
+                "Ongeza pesa ({1}):\nTuma kwa:\n{0}",
+                "Wallet mein paise daalein ({1}):\nYahan bhejein:\n{0}",
+                "Fondear billetera ({1}):\nEnvia a:\n{0}",
+            ],
+            Msg::HistoryPage => [
+                "History {0}/{1}:\n{2}",
+                "Historia {0}/{1}:\n{2}",
+                "Itihaas {0}/{1}:\n{2}",
+                "Historial {0}/{1}:\n{2}",
+            ],
+            Msg::HistoryMore => [
+                "Reply {0} for more.",
+                "Jibu {0} kuona zaidi.",
+                "Aur dekhne ke liye {0} bhejein.",
+                "Responde {0} para ver mas.",
+            ],
+            Msg::HistoryNoPage => [
+                "No page {0}. History has {1} page(s).",
+                "Hakuna ukurasa {0}. Historia ina kurasa {1}.",
+                "Page {0} nahi hai. Itihaas mein {1} page hain.",
+                "No hay pagina {0}. El historial tiene {1} pagina(s).",
+            ],
+            Msg::HistorySent => [
+                "{0} Sent {1} {2} to {3} {4}",
+                "{0} Umetuma {1} {2} kwa {3} {4}",
+                "{0} {3} ko {1} {2} bheje {4}",
+                "{0} Enviado {1} {2} a {3} {4}",
+            ],
+            Msg::HistoryReceived => [
+                "{0} Got {1} {2} from {3} {4}",
+                "{0} Umepokea {1} {2} kutoka {3} {4}",
+                "{0} {3} se {1} {2} mile {4}",
+                "{0} Recibido {1} {2} de {3} {4}",
+            ],
+            Msg::HistorySwap => [
+                "{0} Swap {1} {2} to {3} {4}",
+                "{0} Badilisha {1} {2} kuwa {3} {4}",
+                "{0} {1} {2} ko {3} mein badla {4}",
+                "{0} Cambio {1} {2} a {3} {4}",
+            ],
+            Msg::HistoryBridge => [
+                "{0} Bridge {1} {2} to {3} {4}",
+                "{0} Vuka {1} {2} hadi {3} {4}",
+                "{0} {1} {2} {3} par bheje {4}",
+                "{0} Puente {1} {2} a {3} {4}",
+            ],
+            Msg::HistoryRedeem => [
+                "{0} Voucher {3} +{1} {2} {4}",
+                "{0} Vocha {3} +{1} {2} {4}",
+                "{0} Voucher {3} +{1} {2} {4}",
+                "{0} Cupon {3} +{1} {2} {4}",
+            ],
+            Msg::HistoryDeposit => [
+                "{0} Deposit +{1} {2} via {3} {4}",
+                "{0} Amana +{1} {2} kupitia {3} {4}",
+                "{0} Jama +{1} {2} {3} se {4}",
+                "{0} Deposito +{1} {2} via {3} {4}",
+            ],
+            Msg::HistoryCashout => [
+                "{0} Cash out {1} {2} via {3} {4}",
+                "{0} Toa {1} {2} kupitia {3} {4}",
+                "{0} {1} {2} {3} se nikaale {4}",
+                "{0} Retiro {1} {2} via {3} {4}",
+            ],
+            Msg::HistoryRefund => [
+                "{0} Refund +{1} {2} from {3} {4}",
+                "{0} Marejesho +{1} {2} kutoka {3} {4}",
+                "{0} Vaapasi +{1} {2} {3} se {4}",
+                "{0} Reembolso +{1} {2} de {3} {4}",
+            ],
+            Msg::TxPendingTag => ["(pending)", "(inasubiri)", "(lambit)", "(pendiente)"],
+            Msg::TxFailedTag => ["(failed)", "(imeshindwa)", "(vifal)", "(fallido)"],
+            Msg::TxStatusLine => [
+                "Ref {0}: {1}",
+                "Kumb {0}: {1}",
+                "Ref {0}: {1}",
+                "Ref {0}: {1}",
+            ],
+            Msg::TxHash => ["Tx: {0}", "Tx: {0}", "Tx: {0}", "Tx: {0}"],
+            Msg::StatusPending => ["pending", "inasubiri", "lambit", "pendiente"],
+            Msg::StatusCompleted => ["confirmed", "imethibitishwa", "pushti hui", "confirmada"],
+            Msg::StatusFailed => ["failed", "imeshindwa", "vifal", "fallida"],
+            Msg::StatusNotFound => [
+                "No transaction with ref {0}.",
+                "Hakuna muamala wenye kumb {0}.",
+                "Ref {0} ka koi len-den nahi.",
+                "No hay transaccion con ref {0}.",
+            ],
+            Msg::TrackHint => [
+                "Ref {0}. Reply STATUS {0} to check on it.",
+                "Kumb {0}. Jibu HALI {0} kuangalia.",
+                "Ref {0}. Jaanchne ke liye STHITI {0} bhejein.",
+                "Ref {0}. Responde ESTADO {0} para revisarla.",
+            ],
+            Msg::TxStalled => [
+                "Ref {0} is still pending after {1} minutes. It may yet complete; reply STATUS {0} to check.",
+                "Kumb {0} bado inasubiri baada ya dakika {1}. Huenda ikakamilika; jibu HALI {0} kuangalia.",
+                "Ref {0} {1} minute baad bhi lambit hai. Yeh abhi bhi pura ho sakta hai; STHITI {0} bhejein.",
+                "Ref {0} sigue pendiente tras {1} minutos. Aun puede completarse; responde ESTADO {0} para revisarla.",
+            ],
+            Msg::NoTransactions => [
+                "No transactions yet.\nReply REDEEM <code> to add funds.",
+                "Bado hakuna miamala.\nJibu KOMBOA <nambari> kuongeza pesa.",
+                "Abhi koi len-den nahi.\nPaise daalne ke liye BHUNAO <code> bhejein.",
+                "Aun no hay transacciones.\nResponde CANJEAR <codigo> para agregar fondos.",
+            ],
+            Msg::RequestSent => [
+                "Requested {0} {1} from {2}.\nRef: {3}. Expires in {4}h.",
+                "Umeomba {0} {1} kutoka kwa {2}.\nKumb: {3}. Inaisha baada ya saa {4}.",
+                "{2} se {0} {1} maange.\nRef: {3}. {4} ghante mein khatam.",
+                "Solicitaste {0} {1} a {2}.\nRef: {3}. Vence en {4}h.",
+            ],
+            Msg::PaymentRequested => [
+                "{0} requests {1} {2} ({4}).\nReply PAY {3} <PIN> to pay or DECLINE {3}.",
+                "{0} anaomba {1} {2} ({4}).\nJibu LIPA {3} <PIN> kulipa au KATAA {3}.",
+                "{0} {1} {2} maang rahe hain ({4}).\nDene ke liye CHUKAO {3} <PIN>, mana karne ke liye INKAAR {3} bhejein.",
+                "{0} te pide {1} {2} ({4}).\nResponde PAGAR {3} <PIN> para pagar o RECHAZAR {3}.",
+            ],
+            Msg::RequestNotFound => [
+                "No open request {0}.",
+                "Hakuna ombi {0} lililo wazi.",
+                "{0} naam ki koi khuli maang nahi.",
+                "No hay solicitud abierta {0}.",
+            ],
+            Msg::RequestExpired => [
+                "Request {0} has expired.",
+                "Ombi {0} limekwisha muda.",
+                "Maang {0} ki avadhi khatam ho gayi.",
+                "La solicitud {0} ha vencido.",
+            ],
+            Msg::RequestSelf => [
+                "You can't request money from yourself.",
+                "Huwezi kujiomba pesa mwenyewe.",
+                "Aap khud se paise nahi maang sakte.",
+                "No puedes pedirte dinero a ti mismo.",
+            ],
+            Msg::PayerNotJoined => [
+                "{0} has no TextChain wallet yet, so can't be asked to pay.",
+                "{0} hana pochi ya TextChain bado, hawezi kuombwa kulipa.",
+                "{0} ka abhi TextChain wallet nahi hai, unse maang nahi sakte.",
+                "{0} aun no tiene billetera TextChain, no se le puede pedir pago.",
+            ],
+            Msg::RequestDeclined => [
+                "{0} declined your request for {1} {2} ({3}).",
+                "{0} amekataa ombi lako la {1} {2} ({3}).",
+                "{0} ne aapki {1} {2} ki maang thukra di ({3}).",
+                "{0} rechazo tu solicitud de {1} {2} ({3}).",
+            ],
+            Msg::RequestDeclinedAck => [
+                "Request {0} declined.",
+                "Ombi {0} limekataliwa.",
+                "Maang {0} mana kar di gayi.",
+                "Solicitud {0} rechazada.",
+            ],
+            Msg::RequestPaid => [
+                "{0} paid your request for {1} {2} ({3}).",
+                "{0} amelipa ombi lako la {1} {2} ({3}).",
+                "{0} ne aapki {1} {2} ki maang chuka di ({3}).",
+                "{0} pago tu solicitud de {1} {2} ({3}).",
+            ],
+            Msg::DescribeSchedule => [
+                "Send {0} {1} to {2} {3} ({4})",
+                "Tuma {0} {1} kwa {2} {3} ({4})",
+                "{2} ko {0} {1} bhejein {3} ({4})",
+                "Enviar {0} {1} a {2} {3} ({4})",
+            ],
+            Msg::ScheduleOnce => ["on {0}", "tarehe {0}", "{0} ko", "el {0}"],
+            Msg::ScheduleDaily => ["every day", "kila siku", "har din", "cada dia"],
+            Msg::ScheduleWeekly => ["every {0}", "kila {0}", "har {0}", "cada {0}"],
+            Msg::ScheduleMonthly => [
+                "every month on day {0}",
+                "kila mwezi tarehe {0}",
+                "har mahine ki {0} tareekh",
+                "cada mes el dia {0}",
+            ],
+            Msg::InvalidSchedule => [
+                "Unknown schedule. Use EVERY DAY, EVERY WEEK ON FRI, EVERY MONTH ON 1 or AT 2026-11-01.",
+                "Ratiba haijulikani. Tumia EVERY DAY, EVERY WEEK ON FRI, EVERY MONTH ON 1 au AT 2026-11-01.",
+                "Samay samajh nahi aaya. EVERY DAY, EVERY WEEK ON FRI, EVERY MONTH ON 1 ya AT 2026-11-01 likhein.",
+                "Programacion desconocida. Usa EVERY DAY, EVERY WEEK ON FRI, EVERY MONTH ON 1 o AT 2026-11-01.",
+            ],
+            Msg::SchedulePast => [
+                "{0} has already passed.",
+                "{0} imeshapita.",
+                "{0} beet chuka hai.",
+                "{0} ya paso.",
+            ],
+            Msg::ScheduleCreated => [
+                "Scheduled! ID: {0}\nFirst payment: {1}\nReply STOP {0} to cancel.",
+                "Imepangwa! Kitambulisho: {0}\nMalipo ya kwanza: {1}\nJibu SIMAMISHA {0} kusitisha.",
+                "Nirdharit! ID: {0}\nPehla bhugtaan: {1}\nRadd karne ke liye ROKO {0} bhejein.",
+                "Programado! ID: {0}\nPrimer pago: {1}\nResponde DETENER {0} para cancelar.",
+            ],
+            Msg::SchedulesList => [
+                "Scheduled payments:\n{0}\nReply STOP <id> to cancel.",
+                "Malipo yaliyopangwa:\n{0}\nJibu SIMAMISHA <id> kusitisha.",
+                "Nirdharit bhugtaan:\n{0}\nRadd karne ke liye ROKO <id> bhejein.",
+                "Pagos programados:\n{0}\nResponde DETENER <id> para cancelar.",
+            ],
+            Msg::ScheduleLine => [
+                "{0}: {1} {2} to {3}, {4}",
+                "{0}: {1} {2} kwa {3}, {4}",
+                "{0}: {3} ko {1} {2}, {4}",
+                "{0}: {1} {2} a {3}, {4}",
+            ],
+            Msg::NoSchedules => [
+                "No scheduled payments.\nExample: SEND 50 TXTC landlord EVERY MONTH ON 1",
+                "Hakuna malipo yaliyopangwa.\nMfano: TUMA 50 TXTC landlord EVERY MONTH ON 1",
+                "Koi nirdharit bhugtaan nahi.\nUdaharan: BHEJO 50 TXTC landlord EVERY MONTH ON 1",
+                "No hay pagos programados.\nEjemplo: ENVIAR 50 TXTC landlord EVERY MONTH ON 1",
+            ],
+            Msg::ScheduleStopped => [
+                "Schedule {0} stopped.",
+                "Ratiba {0} imesitishwa.",
+                "Bhugtaan {0} rok diya gaya.",
+                "Programacion {0} detenida.",
+            ],
+            Msg::ScheduleNotFound => [
+                "No active schedule {0}. Reply SCHEDULES to list them.",
+                "Hakuna ratiba {0} inayoendelea. Jibu RATIBA kuziona.",
+                "{0} naam ka koi chalu bhugtaan nahi. Suchi ke liye SAMAYSUCHI bhejein.",
+                "No hay programacion activa {0}. Responde PROGRAMADOS para verlas.",
+            ],
+            Msg::ScheduleRan => [
+                "Scheduled payment {0}:\n{1}",
+                "Malipo yaliyopangwa {0}:\n{1}",
+                "Nirdharit bhugtaan {0}:\n{1}",
+                "Pago programado {0}:\n{1}",
+            ],
+            Msg::ScheduleLowBalance => [
+                "Scheduled payment {0} of {1} {2} to {3} failed: insufficient balance.\nTop up before the next run.",
+                "Malipo yaliyopangwa {0} ya {1} {2} kwa {3} yameshindwa: salio halitoshi.\nOngeza pesa kabla ya malipo yajayo.",
+                "{3} ko {1} {2} ka nirdharit bhugtaan {0} vifal: bakaya kam hai.\nAgle bhugtaan se pehle paise daalein.",
+                "El pago programado {0} de {1} {2} a {3} fallo: saldo insuficiente.\nRecarga antes del proximo pago.",
+            ],
+            Msg::ScheduleRunFailed => [
+                "Scheduled payment {0} of {1} {2} to {3} failed. It will be tried again on the next date.",
+                "Malipo yaliyopangwa {0} ya {1} {2} kwa {3} yameshindwa. Yatajaribiwa tena tarehe ijayo.",
+                "{3} ko {1} {2} ka nirdharit bhugtaan {0} vifal. Agli tareekh par phir koshish hogi.",
+                "El pago programado {0} de {1} {2} a {3} fallo. Se intentara de nuevo en la proxima fecha.",
+            ],
+            Msg::VoucherRedeemed => [
+                "Voucher redeemed!\n\n{0} ETH credited.\n\nReply BALANCE to check.",
+                "Vocha imekombolewa!\n\n{0} ETH imeongezwa.\n\nJibu SALIO kuangalia.",
+                "Voucher bhun gaya!\n\n{0} ETH jama hua.\n\nDekhne ke liye BAKAYA bhejein.",
+                "Cupon canjeado!\n\n{0} ETH acreditados.\n\nResponde SALDO para ver.",
+            ],
+            Msg::VoucherUsed => [
+                "Voucher already used.",
+                "Vocha imeshatumika.",
+                "Voucher pehle hi use ho chuka hai.",
+                "Cupon ya usado.",
+            ],
+            Msg::VoucherInvalid => [
+                "Invalid voucher code.",
+                "Nambari ya vocha si sahihi.",
+                "Galat voucher code.",
+                "Codigo de cupon invalido.",
+            ],
+            Msg::RedeemFailed => [
+                "Redemption failed. Try later.",
+                "Ukombozi umeshindwa. Jaribu baadaye.",
+                "Voucher bhunana fail hua. Baad mein koshish karein.",
+                "El canje fallo. Intenta mas tarde.",
+            ],
+            Msg::Swapping => [
+                "Swapping {0} {1} on {2} for at least {3} ETH...\n\nYou'll get an SMS when complete.\n\nThis may take 30 seconds.",
+                "Inabadilisha {0} {1} kwenye {2} kwa angalau {3} ETH...\n\nUtapata SMS ikikamilika.\n\nInaweza kuchukua sekunde 30.",
+                "{2} par {0} {1} kam se kam {3} ETH ke liye badal rahe hain...\n\nPura hone par SMS milega.\n\nIsmein 30 second lag sakte hain.",
+                "Cambiando {0} {1} en {2} por al menos {3} ETH...\n\nRecibiras un SMS al terminar.\n\nPuede tardar 30 segundos.",
+            ],
+            Msg::SwapUnsupportedChain => [
+                "SWAP is only available on {1}.\nYou are on {0}. Reply CHAIN {2}",
+                "BADILISHA inapatikana kwenye {1} tu.\nUko kwenye {0}. Jibu MTANDAO {2}",
+                "BADLO sirf {1} par uplabdh hai.\nAap {0} par hain. CHAIN {2} bhejein",
+                "CAMBIAR solo esta disponible en {1}.\nEstas en {0}. Responde RED {2}",
+            ],
+            Msg::Quote => [
+                "{0} {1} = ~{2} ETH\nPrice impact: {3}",
+                "{0} {1} = ~{2} ETH\nAthari ya bei: {3}",
+                "{0} {1} = ~{2} ETH\nBhaav par asar: {3}",
+                "{0} {1} = ~{2} ETH\nImpacto en precio: {3}",
+            ],
+            Msg::QuoteMin => [
+                "At least {0} ETH with your {1} slippage limit.",
+                "Angalau {0} ETH kwa kikomo chako cha {1}.",
+                "Aapki {1} seema ke saath kam se kam {0} ETH.",
+                "Al menos {0} ETH con tu limite de {1}.",
+            ],
+            Msg::QuoteFailed => [
+                "Couldn't get a swap price. Try again later.",
+                "Imeshindwa kupata bei. Jaribu tena baadaye.",
+                "Bhaav nahi mil saka. Baad mein koshish karein.",
+                "No se pudo obtener el precio. Intenta mas tarde.",
+            ],
+            Msg::QuoteMoved => [
+                "Swap stopped: the price moved. You were quoted {0} ETH, now {1} ETH, beyond your {2} limit. Send QUOTE to check, or SLIPPAGE to allow more.",
+                "Ubadilishaji umesimamishwa: bei imebadilika. Ulinukuliwa {0} ETH, sasa {1} ETH, zaidi ya kikomo chako cha {2}. Tuma BEI kuangalia, au UTELEZI kuruhusu zaidi.",
+                "Badlav roka gaya: bhaav badal gaya. Aapko {0} ETH bataya gaya tha, ab {1} ETH, jo aapki {2} seema se bahar hai. DAAM bhejein, ya FISLAN se seema badhayein.",
+                "Cambio detenido: el precio cambio. Se te cotizo {0} ETH, ahora {1} ETH, fuera de tu limite de {2}. Envia COTIZAR, o DESLIZ para permitir mas.",
+            ],
+            Msg::SlippageCurrent => [
+                "Max slippage for swaps: {0}. Change it with SLIPPAGE 1%",
+                "Kikomo cha bei kwa kubadilisha: {0}. Badilisha kwa UTELEZI 1%",
+                "Swap ke liye adhiktam chhoot: {0}. Badalne ke liye FISLAN 1%",
+                "Deslizamiento maximo: {0}. Cambialo con DESLIZ 1%",
+            ],
+            Msg::SlippageSet => [
+                "Max slippage set to {0}.",
+                "Kikomo cha bei kimewekwa {0}.",
+                "Adhiktam chhoot {0} set ki gayi.",
+                "Deslizamiento maximo fijado en {0}.",
+            ],
+            Msg::SlippageInvalid => [
+                "Slippage must be between 0.01% and 50%, e.g. SLIPPAGE 1%",
+                "Kikomo lazima kiwe kati ya 0.01% na 50%, mf. UTELEZI 1%",
+                "Chhoot 0.01% aur 50% ke beech honi chahiye, jaise FISLAN 1%",
+                "El deslizamiento debe estar entre 0.01% y 50%, ej. DESLIZ 1%",
+            ],
+            Msg::CashoutUnavailable => [
+                "Cash out is not available right now.",
+                "Kutoa pesa hakupatikani kwa sasa.",
+                "Abhi nakad nikaalna uplabdh nahi.",
+                "El retiro no esta disponible ahora.",
+            ],
+            Msg::CashoutUnknownMethod => [
+                "No cash out method {0}. Use {1}.",
+                "Hakuna njia ya kutoa {0}. Tumia {1}.",
+                "{0} naam ka koi tarika nahi. {1} use karein.",
+                "No existe el metodo {0}. Usa {1}.",
+            ],
+            Msg::CashoutQuoteFailed => [
+                "Couldn't get a cash out price. Try again later.",
+                "Imeshindwa kupata bei ya kutoa. Jaribu tena baadaye.",
+                "Nikaalne ka bhaav nahi mil saka. Baad mein koshish karein.",
+                "No se pudo obtener el precio del retiro. Intenta mas tarde.",
+            ],
+            Msg::CashoutUnsupportedToken => [
+                "{0} can't be cashed out. Try TXTC.",
+                "{0} haiwezi kutolewa. Jaribu TXTC.",
+                "{0} nikaala nahi ja sakta. TXTC try karein.",
+                "{0} no se puede retirar. Prueba TXTC.",
+            ],
+            Msg::CashoutPaid => [
+                "✅ {0} {1} paid out via {2}.",
+                "✅ {0} {1} zimelipwa kupitia {2}.",
+                "✅ {2} se {0} {1} bhej diye gaye.",
+                "✅ {0} {1} pagados via {2}.",
+            ],
+            Msg::CashoutStarted => [
+                "Paying out {0} {1} via {2}. You'll get an SMS when it arrives.",
+                "Inalipa {0} {1} kupitia {2}. Utapata SMS zikifika.",
+                "{2} se {0} {1} bhej rahe hain. Pahunchne par SMS milega.",
+                "Pagando {0} {1} via {2}. Recibiras un SMS cuando llegue.",
+            ],
+            Msg::CashoutRefunded => [
+                "❌ Cash out via {2} failed. {0} {1} returned to your wallet.",
+                "❌ Kutoa kupitia {2} kumeshindwa. {0} {1} zimerudishwa kwenye pochi yako.",
+                "❌ {2} se nikaalna fail. {0} {1} aapke wallet mein vaapas.",
+                "❌ El retiro via {2} fallo. {0} {1} devueltos a tu billetera.",
+            ],
+            Msg::CashoutRefundFailed => [
+                "❌ Cash out failed and the refund is delayed. Support will follow up on ref {0}.",
+                "❌ Kutoa kumeshindwa na marejesho yamechelewa. Msaada utafuatilia kumb {0}.",
+                "❌ Nikaalna fail aur vaapasi mein deri. Support ref {0} dekhega.",
+                "❌ El retiro fallo y el reembolso se retrasa. Soporte revisara la ref {0}.",
+            ],
+            Msg::CashoutDebitPending => [
+                "Sending your tokens. {0} {1} will be paid via {2} once they arrive; you'll get an SMS.",
+                "Inatuma tokeni zako. {0} {1} zitalipwa kupitia {2} zikifika; utapata SMS.",
+                "Aapke tokens bhej rahe hain. Pahunchne par {2} se {0} {1} milenge; SMS aayega.",
+                "Enviando tus tokens. {0} {1} se pagaran via {2} cuando lleguen; recibiras un SMS.",
+            ],
+            Msg::CashoutDebitFailed => [
+                "❌ Cash out failed: your {0} {1} couldn't be sent, so nothing was paid out.",
+                "❌ Kutoa kumeshindwa: {0} {1} zako hazikuweza kutumwa, hakuna kilicholipwa.",
+                "❌ Nikaalna fail: aapke {0} {1} bheje nahi ja sake, koi bhugtaan nahi hua.",
+                "❌ El retiro fallo: tus {0} {1} no se pudieron enviar, no se pago nada.",
+            ],
+            Msg::LimitPerTxLabel => ["Per-payment", "kwa malipo", "prati bhugtaan", "por pago"],
+            Msg::LimitDailyLabel => ["Daily", "kwa siku", "rozana", "diario"],
+            Msg::LimitWeeklyLabel => ["Weekly", "kwa wiki", "saptahik", "semanal"],
+            Msg::LimitsShow => [
+                "{0} limits:\nPer payment: {1}\nDaily: {2} ({3} used)\nWeekly: {4} ({5} used)",
+                "Vikomo vya {0}:\nKwa malipo: {1}\nKwa siku: {2} ({3} imetumika)\nKwa wiki: {4} ({5} imetumika)",
+                "{0} ki seema:\nPrati bhugtaan: {1}\nRozana: {2} ({3} kharch)\nSaptahik: {4} ({5} kharch)",
+                "Limites de {0}:\nPor pago: {1}\nDiario: {2} ({3} usado)\nSemanal: {4} ({5} usado)",
+            ],
+            Msg::LimitRaiseWaiting => [
+                "{0} {2} limit rises to {1} at {3}.",
+                "Kikomo cha {2} {0} kitapanda hadi {1} saa {3}.",
+                "{2} ki {0} seema {3} par {1} ho jayegi.",
+                "Limite {0} de {2} sube a {1} el {3}.",
+            ],
+            Msg::LimitLowered => [
+                "✅ {0} {2} limit is now {1}.",
+                "✅ Kikomo cha {2} {0} sasa ni {1}.",
+                "✅ {2} ki {0} seema ab {1} hai.",
+                "✅ Limite {0} de {2} ahora es {1}.",
+            ],
+            Msg::LimitUnchanged => [
+                "{0} {2} limit is already {1}.",
+                "Kikomo cha {2} {0} tayari ni {1}.",
+                "{2} ki {0} seema pehle se {1} hai.",
+                "Limite {0} de {2} ya es {1}.",
+            ],
+            Msg::LimitRaiseNeedsPin => [
+                "Raising a limit needs your PIN, e.g. {0}",
+                "Kupandisha kikomo kunahitaji PIN yako, mf. {0}",
+                "Seema badhane ke liye PIN chahiye, jaise {0}",
+                "Subir un limite requiere tu PIN, ej. {0}",
+            ],
+            Msg::LimitRaiseScheduled => [
+                "⏳ {0} {2} limit rises to {1} at {3}. Set it lower before then to cancel.",
+                "⏳ Kikomo cha {2} {0} kitapanda hadi {1} saa {3}. Kiweke chini kabla ya hapo kusitisha.",
+                "⏳ {2} ki {0} seema {3} par {1} ho jayegi. Radd karne ke liye usse pehle kam karein.",
+                "⏳ Limite {0} de {2} sube a {1} el {3}. Bajalo antes para cancelar.",
+            ],
+            Msg::LimitWrongPin => [
+                "Wrong PIN. Limit not changed.",
+                "PIN si sahihi. Kikomo hakijabadilishwa.",
+                "Galat PIN. Seema nahi badli.",
+                "PIN incorrecto. El limite no cambio.",
+            ],
+            Msg::LimitPerTxExceeded => [
+                "❌ Over your limit of {0} {1} per payment. Reply LIMIT to see your limits.",
+                "❌ Zaidi ya kikomo chako cha {0} {1} kwa malipo. Jibu KIKOMO kuona vikomo vyako.",
+                "❌ Prati bhugtaan {0} {1} ki seema se zyada. Apni seema dekhne ke liye SEEMA bhejein.",
+                "❌ Supera tu limite de {0} {1} por pago. Responde LIMITE para ver tus limites.",
+            ],
+            Msg::LimitDailyExceeded => [
+                "❌ That passes your daily limit of {0} {1} ({2} {1} left). Reply LIMIT to see your limits.",
+                "❌ Hii inazidi kikomo chako cha siku cha {0} {1} (zimebaki {2} {1}). Jibu KIKOMO kuona vikomo vyako.",
+                "❌ Yeh rozana {0} {1} ki seema se zyada hai ({2} {1} baaki). Apni seema dekhne ke liye SEEMA bhejein.",
+                "❌ Supera tu limite diario de {0} {1} (quedan {2} {1}). Responde LIMITE para ver tus limites.",
+            ],
+            Msg::LimitWeeklyExceeded => [
+                "❌ That passes your weekly limit of {0} {1} ({2} {1} left). Reply LIMIT to see your limits.",
+                "❌ Hii inazidi kikomo chako cha wiki cha {0} {1} (zimebaki {2} {1}). Jibu KIKOMO kuona vikomo vyako.",
+                "❌ Yeh saptahik {0} {1} ki seema se zyada hai ({2} {1} baaki). Apni seema dekhne ke liye SEEMA bhejein.",
+                "❌ Supera tu limite semanal de {0} {1} (quedan {2} {1}). Responde LIMITE para ver tus limites.",
+            ],
+            Msg::NumberInvalid => [
+                "Send the new number in full, e.g. NEWNUMBER +254711000000",
+                "Tuma namba mpya kamili, mf. NAMBAMPYA +254711000000",
+                "Naya number poora bhejein, jaise NAYANUMBER +254711000000",
+                "Envia el numero nuevo completo, ej. NUEVONUMERO +254711000000",
+            ],
+            Msg::NumberSame => [
+                "That's already your number.",
+                "Hiyo tayari ni namba yako.",
+                "Yeh pehle se aapka number hai.",
+                "Ese ya es tu numero.",
+            ],
+            Msg::NumberTaken => [
+                "{0} already has a wallet. Pick a number that hasn't joined.",
+                "{0} tayari ina pochi. Chagua namba ambayo haijajiunga.",
+                "{0} ka pehle se wallet hai. Aisa number chunein jo juda nahi hai.",
+                "{0} ya tiene billetera. Elige un numero que no se haya unido.",
+            ],
+            Msg::NumberCodeSent => [
+                "Code sent to {0}. From that phone, reply VERIFY <code> <PIN> within {1} min.",
+                "Msimbo umetumwa kwa {0}. Kutoka simu hiyo, jibu THIBITISHA <msimbo> <PIN> ndani ya dakika {1}.",
+                "{0} par code bheja gaya. Us phone se {1} minute mein PUSHTI <code> <PIN> bhejein.",
+                "Codigo enviado a {0}. Desde ese telefono, responde VERIFICAR <codigo> <PIN> en {1} min.",
+            ],
+            Msg::NumberCode => [
+                "Code {0} moves the wallet of {1} to this number. Reply VERIFY {0} <PIN> within {2} min. Didn't ask? Ignore this.",
+                "Msimbo {0} unahamisha pochi ya {1} kwa namba hii. Jibu THIBITISHA {0} <PIN> ndani ya dakika {2}. Hukuomba? Puuza ujumbe huu.",
+                "Code {0} se {1} ka wallet is number par aayega. {2} minute mein PUSHTI {0} <PIN> bhejein. Aapne nahi maanga? Ise andekha karein.",
+                "El codigo {0} mueve la billetera de {1} a este numero. Responde VERIFICAR {0} <PIN> en {2} min. No lo pediste? Ignora esto.",
+            ],
+            Msg::NumberNoChange => [
+                "No number change in progress.",
+                "Hakuna ubadilishaji wa namba unaoendelea.",
+                "Koi number badlav jaari nahi hai.",
+                "No hay cambio de numero en curso.",
+            ],
+            Msg::NumberCodeExpired => [
+                "That code has expired. Start again with NEWNUMBER from the old number.",
+                "Msimbo huo umeisha muda. Anza upya kwa NAMBAMPYA kutoka namba ya zamani.",
+                "Code ki avadhi khatam. Purane number se NAYANUMBER se dobara shuru karein.",
+                "Ese codigo expiro. Empieza de nuevo con NUEVONUMERO desde el numero anterior.",
+            ],
+            Msg::NumberWrongCode => [
+                "Wrong code or PIN. {0} tries left.",
+                "Msimbo au PIN si sahihi. Majaribio {0} yamebaki.",
+                "Galat code ya PIN. {0} koshish baaki.",
+                "Codigo o PIN incorrecto. Quedan {0} intentos.",
+            ],
+            Msg::NumberTooManyTries => [
+                "Too many wrong tries. Number change cancelled.",
+                "Majaribio mengi yasiyo sahihi. Ubadilishaji wa namba umesitishwa.",
+                "Bahut galat koshishein. Number badlav radd.",
+                "Demasiados intentos fallidos. Cambio de numero cancelado.",
+            ],
+            Msg::NumberVerified => [
+                "✅ Verified. Your wallet moves to this number at {0}.",
+                "✅ Imethibitishwa. Pochi yako itahamia namba hii saa {0}.",
+                "✅ Pushti ho gayi. Aapka wallet {0} par is number par aayega.",
+                "✅ Verificado. Tu billetera pasa a este numero el {0}.",
+            ],
+            Msg::NumberChangeWarning => [
+                "⚠️ Your wallet moves to {0} at {1}. Not you? Reply NEWNUMBER CANCEL now.",
+                "⚠️ Pochi yako itahamia {0} saa {1}. Si wewe? Jibu NAMBAMPYA HAPANA sasa.",
+                "⚠️ Aapka wallet {1} par {0} par chala jayega. Aap nahi? Abhi NAYANUMBER NAHI bhejein.",
+                "⚠️ Tu billetera pasa a {0} el {1}. No fuiste tu? Responde NUEVONUMERO NO ahora.",
+            ],
+            Msg::NumberChangeCancelled => [
+                "Number change cancelled.",
+                "Ubadilishaji wa namba umesitishwa.",
+                "Number badlav radd.",
+                "Cambio de numero cancelado.",
+            ],
+            Msg::NumberChanged => [
+                "✅ Your wallet now answers on {0}.",
+                "✅ Pochi yako sasa inajibu kwenye {0}.",
+                "✅ Aapka wallet ab {0} par hai.",
+                "✅ Tu billetera ahora responde en {0}.",
+            ],
+            Msg::NumberChangedOld => [
+                "Your wallet moved to {0}. This number is no longer linked to it.",
+                "Pochi yako imehamia {0}. Namba hii haihusiani nayo tena.",
+                "Aapka wallet {0} par chala gaya. Yeh number ab usse juda nahi.",
+                "Tu billetera paso a {0}. Este numero ya no esta vinculado.",
+            ],
+            Msg::SwapFailed => [
+                "❌ Swap failed: {0}",
+                "❌ Ubadilishaji umeshindwa: {0}",
+                "❌ Swap fail: {0}",
+                "❌ El cambio fallo: {0}",
+            ],
+            Msg::BridgeStarted => [
+                "Bridge started!\n{0}\nSMS when done.",
+                "Uvushaji umeanza!\n{0}\nSMS ikikamilika.",
+                "Bridge shuru!\n{0}\nPura hone par SMS.",
+                "Puente iniciado!\n{0}\nSMS al terminar.",
+            ],
+            Msg::BridgeFailed => [
+                "❌ Bridge failed: {0}",
+                "❌ Uvushaji umeshindwa: {0}",
+                "❌ Bridge fail: {0}",
+                "❌ El puente fallo: {0}",
+            ],
+            Msg::BridgeInitiated => [
+                "Bridge initiated. You'll get an SMS when complete.",
+                "Uvushaji umeanzishwa. Utapata SMS ikikamilika.",
+                "Bridge shuru hua. Pura hone par SMS milega.",
+                "Puente iniciado. Recibiras un SMS al terminar.",
+            ],
+            Msg::BridgeUnavailable => [
+                "Bridge service unavailable. Try later.",
+                "Huduma ya uvushaji haipatikani. Jaribu baadaye.",
+                "Bridge seva uplabdh nahi. Baad mein koshish karein.",
+                "Servicio de puente no disponible. Intenta mas tarde.",
+            ],
+            Msg::AddressBookOffline => [
+                "Address book offline.",
+                "Kitabu cha anwani hakipatikani.",
+                "Sampark suchi uplabdh nahi.",
+                "Agenda no disponible.",
+            ],
+            Msg::ContactSaved => [
+                "Saved {0} as {1}.",
+                "{0} imehifadhiwa kama {1}.",
+                "{0} ko {1} naam se sahej liya.",
+                "{0} guardado como {1}.",
+            ],
+            Msg::ContactSaveFailed => [
+                "Error saving contact.",
+                "Hitilafu kuhifadhi mwasiliani.",
+                "Sampark sahejne mein galti.",
+                "Error al guardar el contacto.",
+            ],
+            Msg::NoContacts => [
+                "No contacts yet.\n\nSAVE <name> <phone>",
+                "Bado hakuna anwani.\n\nHIFADHI <jina> <simu>",
+                "Abhi koi sampark nahi.\n\nSAHEJO <naam> <phone>",
+                "Aun no hay contactos.\n\nGUARDAR <nombre> <telefono>",
+            ],
+            Msg::ContactsPage => [
+                "Contacts {0}/{1}:\n{2}",
+                "Anwani {0}/{1}:\n{2}",
+                "Sampark {0}/{1}:\n{2}",
+                "Contactos {0}/{1}:\n{2}",
+            ],
+            Msg::ContactsNoPage => [
+                "No page {0}. Contacts have {1} page(s).",
+                "Hakuna ukurasa {0}. Anwani zina kurasa {1}.",
+                "Page {0} nahi hai. Sampark mein {1} page hain.",
+                "No hay pagina {0}. Los contactos tienen {1} pagina(s).",
+            ],
+            Msg::InvalidContact => [
+                "Save a phone (+254...), wallet (0x...) or ENS name (name.ttcip.eth).\nExample: SAVE mom +254700000000",
+                "Hifadhi simu (+254...), pochi (0x...) au jina la ENS (jina.ttcip.eth).\nMfano: HIFADHI mama +254700000000",
+                "Phone (+91...), wallet (0x...) ya ENS naam (naam.ttcip.eth) sahejein.\nUdaharan: SAHEJO maa +919876543210",
+                "Guarda un telefono (+34...), billetera (0x...) o nombre ENS (nombre.ttcip.eth).\nEjemplo: GUARDAR mama +34600000000",
+            ],
+            Msg::InvalidContactName => [
+                "Invalid name {0}. Use up to 30 letters, digits, _ or -.",
+                "Jina {0} si sahihi. Tumia hadi herufi 30, tarakimu, _ au -.",
+                "Naam {0} galat hai. 30 tak akshar, ank, _ ya - use karein.",
+                "Nombre {0} invalido. Usa hasta 30 letras, digitos, _ o -.",
+            ],
+            Msg::ContactDeleted => [
+                "Deleted {0}.",
+                "{0} imefutwa.",
+                "{0} hata diya.",
+                "{0} eliminado.",
+            ],
+            Msg::ContactNotFound => [
+                "No contact named {0}. Reply CONTACTS to list them.",
+                "Hakuna mwasiliani {0}. Jibu ANWANI kuwaona.",
+                "{0} naam ka koi sampark nahi. Suchi ke liye SAMPARK bhejein.",
+                "No hay contacto {0}. Responde CONTACTOS para verlos.",
+            ],
+            Msg::ContactRenamed => [
+                "Renamed {0} to {1}.",
+                "{0} sasa ni {1}.",
+                "{0} ka naam {1} kar diya.",
+                "{0} renombrado a {1}.",
+            ],
+            Msg::ContactNameTaken => [
+                "You already have a contact named {0}.",
+                "Tayari una mwasiliani {0}.",
+                "{0} naam ka sampark pehle se hai.",
+                "Ya tienes un contacto llamado {0}.",
+            ],
+            Msg::ContactAmbiguous => [
+                "{0} matches {1}. Send again with the full name.",
+                "{0} inalingana na {1}. Tuma tena kwa jina kamili.",
+                "{0} kai sampark se milta hai: {1}. Poora naam likh kar dobara bhejein.",
+                "{0} coincide con {1}. Envia de nuevo con el nombre completo.",
+            ],
+            Msg::ContactsLoadFailed => [
+                "Error loading contacts.",
+                "Hitilafu kupakia anwani.",
+                "Sampark laane mein galti.",
+                "Error al cargar contactos.",
+            ],
+            Msg::UnknownChain => [
+                "Unknown chain: {0}\n\nAvailable: sepolia, amoy, base-sepolia, arb-sepolia, polygon, base, eth, arb",
+                "Mtandao haujulikani: {0}\n\nVinavyopatikana: sepolia, amoy, base-sepolia, arb-sepolia, polygon, base, eth, arb",
+                "Anjaan chain: {0}\n\nUplabdh: sepolia, amoy, base-sepolia, arb-sepolia, polygon, base, eth, arb",
+                "Red desconocida: {0}\n\nDisponibles: sepolia, amoy, base-sepolia, arb-sepolia, polygon, base, eth, arb",
+            ],
+            Msg::ChainSwitched => [
+                "Switched to {0} ({3})!\n\nChain ID: {1}\nNative: {2}",
+                "Umebadilisha kwenda {0} ({3})!\n\nChain ID: {1}\nAsili: {2}",
+                "{0} ({3}) par badal gaya!\n\nChain ID: {1}\nNative: {2}",
+                "Cambiado a {0} ({3})!\n\nChain ID: {1}\nNativo: {2}",
+            ],
+            Msg::LangCurrent => [
+                "Language: {0}\nReply LANG <code>\n{1}",
+                "Lugha: {0}\nJibu LUGHA <code>\n{1}",
+                "Bhasha: {0}\nBHASHA <code> bhejein\n{1}",
+                "Idioma: {0}\nResponde IDIOMA <codigo>\n{1}",
+            ],
+            Msg::LangSet => [
+                "Language set to English.",
+                "Lugha imewekwa: Kiswahili.",
+                "Bhasha Hindi set ho gayi.",
+                "Idioma cambiado a Espanol.",
+            ],
+            Msg::LangUnknown => [
+                "Unknown language: {0}\n{1}",
+                "Lugha haijulikani: {0}\n{1}",
+                "Anjaan bhasha: {0}\n{1}",
+                "Idioma desconocido: {0}\n{1}",
+            ],
+            Msg::UssdMenu => [
+                "Text-to-Chain\n1. Balance\n2. Send TXTC\n3. Redeem voucher\n4. Contacts",
+                "Text-to-Chain\n1. Salio\n2. Tuma TXTC\n3. Komboa vocha\n4. Anwani",
+                "Text-to-Chain\n1. Balance\n2. TXTC bhejein\n3. Voucher bhunayein\n4. Sampark",
+                "Text-to-Chain\n1. Saldo\n2. Enviar TXTC\n3. Canjear cupon\n4. Contactos",
+            ],
+            Msg::UssdRecipient => [
+                "Send to (phone number or contact name):",
+                "Tuma kwa (namba ya simu au jina la anwani):",
+                "Kise bhejein (phone number ya sampark ka naam):",
+                "Enviar a (numero de telefono o nombre de contacto):",
+            ],
+            Msg::UssdAmount => [
+                "Amount of TXTC to send to {0}:",
+                "Kiasi cha TXTC cha kutuma kwa {0}:",
+                "{0} ko kitne TXTC bhejne hain:",
+                "Cantidad de TXTC para enviar a {0}:",
+            ],
+            Msg::UssdPin => [
+                "Send {0} TXTC to {1}?\nEnter PIN to confirm:",
+                "Tuma {0} TXTC kwa {1}?\nWeka PIN kuthibitisha:",
+                "{1} ko {0} TXTC bhejein?\nPushti ke liye PIN daalein:",
+                "Enviar {0} TXTC a {1}?\nIngresa tu PIN para confirmar:",
+            ],
+            Msg::UssdCode => [
+                "Enter voucher code:",
+                "Weka nambari ya vocha:",
+                "Voucher code daalein:",
+                "Ingresa el codigo del cupon:",
+            ],
+            Msg::UssdContacts => [
+                "Send to:\n{0}\n0. Back",
+                "Tuma kwa:\n{0}\n0. Rudi",
+                "Kise bhejein:\n{0}\n0. Peeche",
+                "Enviar a:\n{0}\n0. Atras",
+            ],
+            Msg::UssdMore => [
+                "9. More",
+                "9. Zaidi",
+                "9. Aur",
+                "9. Mas",
+            ],
+            Msg::UssdInvalid => [
+                "Invalid choice.",
+                "Chaguo si sahihi.",
+                "Galat vikalp.",
+                "Opcion no valida.",
+            ],
+            Msg::UssdWorking => [
+                "Still working on it. You'll get the result by SMS.",
+                "Bado inashughulikiwa. Utapata jibu kwa SMS.",
+                "Abhi kaam chal raha hai. Parinaam SMS se milega.",
+                "Seguimos procesando. Recibiras el resultado por SMS.",
+            ],
+        }
+    }
+
+    /// Template for a language
+    pub fn template(self, lang: Lang) -> &'static str {
+        self.texts()[lang.index()]
+    }
+}
+
+/// Localized reply without arguments
+pub fn tr(lang: Lang, msg: Msg) -> String {
+    msg.template(lang).to_string()
+}
+
+/// Localized reply with `{0}`, `{1}`, ... filled from `args`
+pub fn tr_with(lang: Lang, msg: Msg, args: &[&dyn std::fmt::Display]) -> String {
+    let mut out = msg.template(lang).to_string();
+    for (i, arg) in args.iter().enumerate() {
+        out = out.replace(&format!("{{{}}}", i), &arg.to_string());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tr_with_reorders_args() {
+        let en = tr_with(Lang::En, Msg::DescribeSend, &[&10, &"TXTC", &"alice", &"ETH-T"]);
+        assert_eq!(en, "Send 10 TXTC to alice (ETH-T)");
+
+        let hi = tr_with(Lang::Hi, Msg::DescribeSend, &[&10, &"TXTC", &"alice", &"ETH-T"]);
+        assert_eq!(hi, "alice ko 10 TXTC bhejein (ETH-T)");
+    }
+
+    #[test]
+    fn test_translations_keep_placeholders() {
+        // Every translation must use the same placeholders as English
+        let placeholders = |s: &str| -> Vec<usize> {
+            (0..6).filter(|i| s.contains(&format!("{{{}}}", i))).collect()
+        };
+
+        for msg in WITH_ARGS {
+            let en = placeholders(msg.template(Lang::En));
+            for lang in Lang::ALL {
+                assert_eq!(placeholders(msg.template(lang)), en, "{:?} in {}", msg, lang);
+            }
+        }
+    }
+
+    const WITH_ARGS: &[Msg] = &[
+        Msg::Throttled, Msg::UnknownCommand, Msg::DidYouMean, Msg::Usage, Msg::MorePages, Msg::AmountTooPrecise, Msg::UnknownToken, Msg::Example, Msg::NameUnavailable, Msg::Registered,
+        Msg::WelcomeBack, Msg::WalletCreated, Msg::Balance, Msg::ChainBalance, Msg::BalanceEmpty,
+        Msg::UnsupportedToken, Msg::SentOnChain, Msg::FundWalletChain, Msg::SwapUnsupportedChain, Msg::Quote, Msg::QuoteMin, Msg::QuoteMoved,
+        Msg::SlippageCurrent, Msg::SlippageSet, Msg::DescribeSwapQuote, Msg::ConfirmPrompt, Msg::PendingExpired,
+        Msg::WrongPinCancelled, Msg::WrongPinTriesLeft, Msg::PinLocked, Msg::Cancelled, Msg::DescribeSend,
+        Msg::DescribeSwap, Msg::DescribeBridge, Msg::DescribeSplit, Msg::InvalidSplit,
+        Msg::SplitTooSmall, Msg::SplitSummary, Msg::DialogHint, Msg::NotJoined, Msg::EnsUnresolved,
+        Msg::ContactNoWallet, Msg::ContactNoAddress, Msg::Sending, Msg::FundWallet,
+        Msg::HistoryPage, Msg::HistoryMore, Msg::HistoryNoPage, Msg::HistorySent,
+        Msg::HistoryReceived, Msg::HistorySwap, Msg::HistoryBridge, Msg::HistoryRedeem,
+        Msg::HistoryDeposit, Msg::TxStatusLine, Msg::TxHash, Msg::StatusNotFound, Msg::TrackHint, Msg::TxStalled, Msg::SwapFailed,
+        Msg::HistoryCashout, Msg::HistoryRefund, Msg::DescribeCashout, Msg::CashoutUnknownMethod, Msg::CashoutUnsupportedToken, Msg::CashoutPaid,
+        Msg::CashoutStarted, Msg::CashoutRefunded, Msg::CashoutRefundFailed, Msg::CashoutDebitPending, Msg::CashoutDebitFailed,
+        Msg::LimitsShow, Msg::LimitRaiseWaiting, Msg::LimitLowered, Msg::LimitUnchanged, Msg::LimitRaiseNeedsPin,
+        Msg::LimitRaiseScheduled, Msg::LimitPerTxExceeded, Msg::LimitDailyExceeded, Msg::LimitWeeklyExceeded,
+        Msg::NumberTaken, Msg::NumberCodeSent, Msg::NumberCode, Msg::NumberWrongCode, Msg::NumberVerified,
+        Msg::NumberChangeWarning, Msg::NumberChanged, Msg::NumberChangedOld,
+        Msg::RequestSent, Msg::PaymentRequested, Msg::RequestNotFound,
+        Msg::RequestExpired, Msg::PayerNotJoined, Msg::RequestDeclined, Msg::RequestDeclinedAck,
+        Msg::RequestPaid, Msg::DescribeSchedule, Msg::ScheduleOnce, Msg::ScheduleWeekly,
+        Msg::ScheduleMonthly, Msg::SchedulePast, Msg::ScheduleCreated, Msg::SchedulesList,
+        Msg::ScheduleLine, Msg::ScheduleStopped, Msg::ScheduleNotFound, Msg::ScheduleRan,
+        Msg::ScheduleLowBalance, Msg::ScheduleRunFailed, Msg::VoucherRedeemed, Msg::Swapping,
+        Msg::BridgeStarted, Msg::BridgeFailed, Msg::ContactSaved, Msg::ContactsPage,
+        Msg::ContactsNoPage, Msg::InvalidContactName, Msg::ContactDeleted, Msg::ContactNotFound,
+        Msg::ContactRenamed, Msg::ContactNameTaken, Msg::ContactAmbiguous,
+        Msg::UnknownChain, Msg::ChainSwitched, Msg::LangCurrent, Msg::LangUnknown,
+        Msg::UssdAmount, Msg::UssdPin, Msg::UssdContacts,
+    ];
+}
@@ -0,0 +1,89 @@
+pub mod keywords;
+pub mod messages;
+
+pub use messages::{tr, tr_with, Msg};
+
+/// Languages supported for commands and replies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Lang {
+    #[default]
+    En,
+    /// Kiswahili (Kenya, Tanzania)
+    Sw,
+    /// Hindi, romanized so replies stay in the GSM-7 alphabet
+    Hi,
+    Es,
+}
+
+impl Lang {
+    /// All supported languages
+    pub const ALL: [Lang; 4] = [Lang::En, Lang::Sw, Lang::Hi, Lang::Es];
+
+    /// ISO 639-1 code stored in `users.language`
+    pub fn code(&self) -> &'static str {
+        match self {
+            Lang::En => "en",
+            Lang::Sw => "sw",
+            Lang::Hi => "hi",
+            Lang::Es => "es",
+        }
+    }
+
+    /// Name of the language in that language
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Lang::En => "English",
+            Lang::Sw => "Kiswahili",
+            Lang::Hi => "Hindi",
+            Lang::Es => "Espanol",
+        }
+    }
+
+    /// Parse a language code or name (case-insensitive)
+    pub fn from_input(input: &str) -> Option<Lang> {
+        match input.to_uppercase().as_str() {
+            "EN" | "ENG" | "ENGLISH" => Some(Lang::En),
+            "SW" | "SWA" | "SWAHILI" | "KISWAHILI" => Some(Lang::Sw),
+            "HI" | "HIN" | "HINDI" => Some(Lang::Hi),
+            "ES" | "SPA" | "SPANISH" | "ESPANOL" | "ESPAÑOL" => Some(Lang::Es),
+            _ => None,
+        }
+    }
+
+    /// Position in the message catalog rows
+    pub(crate) fn index(&self) -> usize {
+        match self {
+            Lang::En => 0,
+            Lang::Sw => 1,
+            Lang::Hi => 2,
+            Lang::Es => 3,
+        }
+    }
+}
+
+impl std::fmt::Display for Lang {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lang_from_input() {
+        assert_eq!(Lang::from_input("sw"), Some(Lang::Sw));
+        assert_eq!(Lang::from_input("Kiswahili"), Some(Lang::Sw));
+        assert_eq!(Lang::from_input("HI"), Some(Lang::Hi));
+        assert_eq!(Lang::from_input("espanol"), Some(Lang::Es));
+        assert_eq!(Lang::from_input("fr"), None);
+    }
+
+    #[test]
+    fn test_code_roundtrip() {
+        for lang in Lang::ALL {
+            assert_eq!(Lang::from_input(lang.code()), Some(lang));
+        }
+    }
+}
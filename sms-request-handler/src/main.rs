@@ -1,18 +1,29 @@
 mod admin;
+mod admin_phone;
+mod admin_sms;
 mod admin_wallet;
+mod callbacks;
+mod cashout;
 mod commands;
 mod config;
 mod db;
+mod i18n;
 mod routes;
 mod sms;
+mod ussd;
 mod wallet;
 mod yellow_client;
 
 use config::Config;
 use commands::CommandProcessor;
-use db::{create_pool, run_migrations, UserRepository, VoucherRepository, DepositRepository, AddressBookRepository};
+use db::{
+    create_pool, run_migrations, UserRepository, VoucherRepository,
+    AddressBookRepository, CashoutRepository, ConversationRepository, InboundMessageRepository, OutboundMessageRepository,
+    PaymentRequestRepository, PendingTransactionRepository, RateLimitRepository, UssdSessionRepository,
+    PhoneChangeRepository, ScheduleRepository, SpendingLimitRepository, TransactionRepository,
+};
 use routes::{create_router, create_router_with_admin};
-use sms::TwilioClient;
+use sms::{inbound_window, Outbox, SmsGateways};
 use wallet::create_shared_provider;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
@@ -36,8 +47,20 @@ async fn main() -> anyhow::Result<()> {
         "Starting TextChain SMS backend"
     );
 
-    // Get admin token from env (defaults to "admin123" for dev)
-    let admin_token = std::env::var("ADMIN_TOKEN").unwrap_or_else(|_| "admin123".to_string());
+    // Get admin token from env; voucher admin falls back to "admin123" for
+    // dev, admin routes that move wallets or show messages aren't mounted
+    // without it
+    let admin_token = std::env::var("ADMIN_TOKEN").ok().filter(|t| !t.is_empty());
+    if admin_token.is_none() {
+        tracing::warn!("ADMIN_TOKEN not set: using the dev token for voucher admin, phone change and SMS admin disabled");
+    }
+
+    // Shared secret for backend completion callbacks; without one the
+    // callback route isn't mounted, since it settles transactions
+    let callback_token = std::env::var("CALLBACK_TOKEN").ok().filter(|t| !t.is_empty());
+    if callback_token.is_none() {
+        tracing::warn!("CALLBACK_TOKEN not set: transaction callbacks are disabled");
+    }
 
     // Initialize database (optional - will work without if DATABASE_URL not set)
     let db_pool = if let Ok(database_url) = std::env::var("DATABASE_URL") {
@@ -55,31 +78,127 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("Connected to Polygon Amoy testnet");
 
     // Initialize services
-    let twilio = TwilioClient::new(&config.twilio);
+    let gateways = SmsGateways::from_env(config.twilio.as_ref())?;
 
     // Build router based on whether database is available
     let app = if let Some(ref pool) = db_pool {
         let user_repo = UserRepository::new(pool.clone());
         let voucher_repo = VoucherRepository::new(pool.clone());
-        let deposit_repo = DepositRepository::new(pool.clone());
         let address_book_repo = AddressBookRepository::new(pool.clone());
+        let pending_repo = PendingTransactionRepository::new(pool.clone());
+        let transaction_repo = TransactionRepository::new(pool.clone());
+        let payment_request_repo = PaymentRequestRepository::new(pool.clone());
+        let schedule_repo = ScheduleRepository::new(pool.clone());
+        let conversation_repo = ConversationRepository::new(pool.clone());
+        let cashout_repo = CashoutRepository::new(pool.clone());
+        let limit_repo = SpendingLimitRepository::new(pool.clone());
+        let phone_change_repo = PhoneChangeRepository::new(pool.clone());
+        let inbound_repo = InboundMessageRepository::new(pool.clone());
+        let rate_limit_repo = RateLimitRepository::new(pool.clone());
+        let ussd_session_repo = UssdSessionRepository::new(pool.clone());
+        let outbox = Outbox::new(gateways.clone()).with_repo(OutboundMessageRepository::new(pool.clone()));
+
+        // Retry replies and notifications that couldn't be sent
+        let sender = outbox.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(5));
+            loop {
+                interval.tick().await;
+                sender.run_due().await;
+            }
+        });
+
+        // Expire unconfirmed transactions, unanswered requests, abandoned
+        // dialogs, number change codes, inbound messages past their retry
+        // window, idle rate limit buckets and closed USSD sessions in the
+        // background
+        let sweeper = pending_repo.clone();
+        let request_sweeper = payment_request_repo.clone();
+        let conversation_sweeper = conversation_repo.clone();
+        let phone_change_sweeper = phone_change_repo.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+            loop {
+                interval.tick().await;
+                match sweeper.expire_stale().await {
+                    Ok(0) => {}
+                    Ok(n) => tracing::info!(count = n, "Expired pending transactions"),
+                    Err(e) => tracing::error!("Failed to expire pending transactions: {}", e),
+                }
+                match request_sweeper.expire_stale().await {
+                    Ok(0) => {}
+                    Ok(n) => tracing::info!(count = n, "Expired payment requests"),
+                    Err(e) => tracing::error!("Failed to expire payment requests: {}", e),
+                }
+                match conversation_sweeper.delete_expired().await {
+                    Ok(0) => {}
+                    Ok(n) => tracing::info!(count = n, "Dropped expired conversations"),
+                    Err(e) => tracing::error!("Failed to drop expired conversations: {}", e),
+                }
+                match phone_change_sweeper.expire_stale().await {
+                    Ok(0) => {}
+                    Ok(n) => tracing::info!(count = n, "Expired number change codes"),
+                    Err(e) => tracing::error!("Failed to expire number change codes: {}", e),
+                }
+                match inbound_repo.delete_older_than(inbound_window()).await {
+                    Ok(0) => {}
+                    Ok(n) => tracing::info!(count = n, "Dropped old inbound messages"),
+                    Err(e) => tracing::error!("Failed to drop old inbound messages: {}", e),
+                }
+                match rate_limit_repo.delete_idle(chrono::Duration::days(1)).await {
+                    Ok(0) => {}
+                    Ok(n) => tracing::info!(count = n, "Dropped idle rate limit buckets"),
+                    Err(e) => tracing::error!("Failed to drop idle rate limit buckets: {}", e),
+                }
+                match ussd_session_repo.delete_expired().await {
+                    Ok(0) => {}
+                    Ok(n) => tracing::info!(count = n, "Dropped expired USSD sessions"),
+                    Err(e) => tracing::error!("Failed to drop expired USSD sessions: {}", e),
+                }
+            }
+        });
 
         let command_processor = CommandProcessor::with_repos(
             Some(user_repo),
             Some(voucher_repo.clone()),
-            Some(deposit_repo),
             Some(address_book_repo),
             provider,
-        );
+        )
+        .with_pending_repo(pending_repo)
+        .with_transaction_repo(transaction_repo)
+        .with_payment_request_repo(payment_request_repo)
+        .with_schedule_repo(schedule_repo)
+        .with_conversation_repo(conversation_repo)
+        .with_cashout_repo(cashout_repo)
+        .with_limit_repo(limit_repo)
+        .with_phone_change_repo(phone_change_repo)
+        .with_notifier(outbox.clone())
+        .with_auto_correct(config.auto_correct_commands);
+
+        // Run scheduled payments as they come due, settle payouts pending
+        // at cashout providers, move wallets whose number change is due,
+        // and alert users about operations the backend never reported back on
+        let scheduler = command_processor.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+            loop {
+                interval.tick().await;
+                scheduler.run_due_schedules().await;
+                scheduler.poll_cashouts().await;
+                scheduler.complete_phone_changes().await;
+                scheduler.alert_stalled_transactions().await;
+            }
+        });
 
         tracing::info!("Admin routes enabled at /admin/*");
-        create_router_with_admin(twilio, command_processor, voucher_repo, admin_token, pool.clone())
+        create_router_with_admin(outbox, command_processor, voucher_repo, admin_token, callback_token, pool.clone())
     } else {
         let command_processor = CommandProcessor::new(
             None, 
             provider,
-        );
-        create_router(twilio, command_processor)
+        )
+        .with_auto_correct(config.auto_correct_commands);
+        create_router(Outbox::new(gateways), command_processor)
     };
 
     // Start server
@@ -1,4 +1,5 @@
 use axum::{
+    middleware,
     routing::{get, post},
     Router,
 };
@@ -6,18 +7,37 @@ use std::sync::Arc;
 use tower_http::trace::TraceLayer;
 
 use crate::admin::{admin_routes, AdminState};
+use crate::admin_phone::{admin_phone_routes, AdminPhoneState};
+use crate::admin_sms::{admin_sms_routes, AdminSmsState};
 use crate::admin_wallet::admin_wallet_routes;
+use crate::callbacks::{callback_routes, CallbackState};
 use crate::commands::CommandProcessor;
-use crate::db::VoucherRepository;
-use crate::sms::{incoming_sms_handler, incoming_sms_json_handler, TwilioClient};
+use crate::db::{
+    AddressBookRepository, AuditRepository, InboundMessageRepository, OutboundMessageRepository, RateLimitRepository,
+    TransactionRepository, UssdSessionRepository, VoucherRepository,
+};
+use crate::sms::{
+    inbound_handler, incoming_sms_handler, incoming_sms_json_handler, status_handler, twilio_status_handler,
+    verify_webhook, Outbox, RateLimiter, WebhookAuth,
+};
 use crate::sms::webhook::AppState;
+use crate::ussd::{ussd_routes, UssdState};
 use sqlx::PgPool;
 
+/// Admin token for voucher admin when ADMIN_TOKEN isn't set, for dev
+const DEV_ADMIN_TOKEN: &str = "admin123";
+
 /// Build the application router with all routes
-pub fn create_router(twilio: TwilioClient, command_processor: CommandProcessor) -> Router {
+pub fn create_router(outbox: Outbox, command_processor: CommandProcessor) -> Router {
+    let auth = WebhookAuth {
+        gateways: outbox.gateways().clone(),
+        audit: None,
+    };
     let state = AppState {
-        twilio: Arc::new(twilio),
+        outbox,
         command_processor: Arc::new(command_processor),
+        inbound: None,
+        limiter: RateLimiter::from_env(None),
     };
 
     Router::new()
@@ -25,6 +45,13 @@ pub fn create_router(twilio: TwilioClient, command_processor: CommandProcessor)
         .route("/sms/incoming", post(incoming_sms_handler))
         // SMS webhook endpoint - SMSCountry/generic JSON webhooks
         .route("/webhook/sms", post(incoming_sms_json_handler))
+        // Twilio delivery receipts (StatusCallback)
+        .route("/sms/status", post(twilio_status_handler))
+        // Incoming messages and delivery receipts for any configured gateway
+        .route("/sms/:gateway/incoming", get(inbound_handler).post(inbound_handler))
+        .route("/sms/:gateway/status", get(status_handler).post(status_handler))
+        // Reject webhooks without a valid gateway signature
+        .route_layer(middleware::from_fn_with_state(auth, verify_webhook))
         // Health check endpoint
         .route("/health", get(health_check))
         // Ready check endpoint
@@ -38,17 +65,48 @@ pub fn create_router(twilio: TwilioClient, command_processor: CommandProcessor)
 
 /// Build router with admin routes (requires voucher repo and db pool)
 pub fn create_router_with_admin(
-    twilio: TwilioClient, 
+    outbox: Outbox,
     command_processor: CommandProcessor,
     voucher_repo: VoucherRepository,
-    admin_token: String,
+    admin_token: Option<String>,
+    callback_token: Option<String>,
     db_pool: PgPool,
 ) -> Router {
+    let auth = WebhookAuth {
+        gateways: outbox.gateways().clone(),
+        audit: Some(AuditRepository::new(db_pool.clone())),
+    };
+    let command_processor = Arc::new(command_processor);
+    let limiter = RateLimiter::from_env(Some(RateLimitRepository::new(db_pool.clone())));
+    // USSD callbacks name the phone they act for, so they are only taken
+    // when Africa's Talking signs them
+    let ussd_signed = outbox.gateways().get("africastalking").is_some_and(|g| g.signs_webhooks());
+    let ussd_state = ussd_signed.then(|| UssdState {
+        command_processor: command_processor.clone(),
+        sessions: UssdSessionRepository::new(db_pool.clone()),
+        address_book: AddressBookRepository::new(db_pool.clone()),
+        outbox: outbox.clone(),
+        limiter: limiter.clone(),
+    });
     let sms_state = AppState {
-        twilio: Arc::new(twilio),
-        command_processor: Arc::new(command_processor),
+        outbox,
+        command_processor: command_processor.clone(),
+        inbound: Some(InboundMessageRepository::new(db_pool.clone())),
+        limiter,
     };
 
+    let phone_state = admin_token.clone().map(|admin_token| AdminPhoneState {
+        command_processor,
+        admin_token,
+    });
+
+    let sms_admin_state = admin_token.clone().map(|admin_token| AdminSmsState {
+        outbound_repo: OutboundMessageRepository::new(db_pool.clone()),
+        admin_token,
+    });
+
+    let admin_token = admin_token.unwrap_or_else(|| DEV_ADMIN_TOKEN.to_string());
+
     let admin_state = AdminState {
         voucher_repo: Arc::new(voucher_repo),
         admin_token,
@@ -58,23 +116,65 @@ pub fn create_router_with_admin(
     let sms_routes = Router::new()
         .route("/sms/incoming", post(incoming_sms_handler))
         .route("/webhook/sms", post(incoming_sms_json_handler))
+        .route("/sms/status", post(twilio_status_handler))
+        .route("/sms/:gateway/incoming", get(inbound_handler).post(inbound_handler))
+        .route("/sms/:gateway/status", get(status_handler).post(status_handler))
+        .route_layer(middleware::from_fn_with_state(auth.clone(), verify_webhook))
         .with_state(sms_state);
 
+    // Create USSD menu routes, only when Africa's Talking signs its webhooks
+    let ussd_router = match ussd_state {
+        Some(ussd_state) => Some(ussd_routes(ussd_state).route_layer(middleware::from_fn_with_state(auth, verify_webhook))),
+        None => {
+            tracing::warn!("USSD disabled: needs the africastalking gateway with AFRICASTALKING_WEBHOOK_SECRET");
+            None
+        }
+    };
 
     // Create admin routes with their state (already has state applied)
     let admin_router = admin_routes(admin_state);
     
+    // Create backend callback routes, only with a callback token of their own
+    let callback_router = callback_token.map(|callback_token| {
+        callback_routes(CallbackState {
+            transaction_repo: Arc::new(TransactionRepository::new(db_pool.clone())),
+            callback_token,
+        })
+    });
+
     // Create admin wallet routes
     let wallet_admin_router = admin_wallet_routes(Arc::new(db_pool));
 
+    // Create admin phone change routes, only with an admin token that was set
+    let phone_admin_router = phone_state.map(admin_phone_routes);
+
+    // Create admin SMS delivery routes, only with an admin token that was set
+    let sms_admin_router = sms_admin_state.map(admin_sms_routes);
+
     // Merge all routes together
-    Router::new()
+    let router = Router::new()
         .merge(sms_routes)
         .nest("/admin", admin_router)
         .nest("/admin", wallet_admin_router)
         .route("/health", get(health_check))
-        .route("/ready", get(ready_check))
-        .layer(TraceLayer::new_for_http())
+        .route("/ready", get(ready_check));
+    let router = match phone_admin_router {
+        Some(phone_admin_router) => router.nest("/admin", phone_admin_router),
+        None => router,
+    };
+    let router = match sms_admin_router {
+        Some(sms_admin_router) => router.nest("/admin", sms_admin_router),
+        None => router,
+    };
+    let router = match ussd_router {
+        Some(ussd_router) => router.merge(ussd_router),
+        None => router,
+    };
+    let router = match callback_router {
+        Some(callback_router) => router.nest("/api", callback_router),
+        None => router,
+    };
+    router.layer(TraceLayer::new_for_http())
 }
 
 /// Health check handler
@@ -88,3 +188,50 @@ async fn ready_check() -> &'static str {
 }
 
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sms::{AfricasTalkingGateway, LoopbackGateway, SmsGateways};
+    use crate::wallet::create_shared_provider;
+    use axum::{
+        body::Body,
+        http::{header, Request, StatusCode},
+    };
+    use tower::Service;
+
+    /// POST a USSD callback for someone else's number to a full router
+    async fn ussd_status(gateways: SmsGateways) -> StatusCode {
+        // Never reached; only recording a rejected call tries it
+        let pool = sqlx::postgres::PgPoolOptions::new()
+            .acquire_timeout(std::time::Duration::from_millis(100))
+            .connect_lazy("postgres://localhost/textchain_test")
+            .unwrap();
+        let mut router = create_router_with_admin(
+            Outbox::new(gateways),
+            CommandProcessor::new(None, create_shared_provider()),
+            VoucherRepository::new(pool.clone()),
+            None,
+            None,
+            pool,
+        );
+        let request = Request::post("/ussd")
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::from("sessionId=S1&phoneNumber=%2B254700000000&text=1"))
+            .unwrap();
+        router.call(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_ussd_needs_signed_gateway() {
+        // Not mounted without Africa's Talking, or with it unsigned
+        let other = SmsGateways::default().with_prefixes(LoopbackGateway::new("+15550000000"), &[]);
+        assert_eq!(ussd_status(other).await, StatusCode::NOT_FOUND);
+        let unsigned = SmsGateways::default().with_prefixes(AfricasTalkingGateway::new("sandbox", "key", "TXTC"), &[]);
+        assert_eq!(ussd_status(unsigned).await, StatusCode::NOT_FOUND);
+
+        // Mounted with a secret, and a call without its signature is refused
+        let signed = AfricasTalkingGateway::new("sandbox", "key", "TXTC").with_webhook_secret("secret");
+        let signed = SmsGateways::default().with_prefixes(signed, &[]);
+        assert_eq!(ussd_status(signed).await, StatusCode::FORBIDDEN);
+    }
+}
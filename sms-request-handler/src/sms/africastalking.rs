@@ -0,0 +1,186 @@
+use futures::future::BoxFuture;
+use futures::FutureExt;
+
+use super::gateway::{field, sender_phone, verify_body_signature};
+use super::{DeliveryReport, DeliveryStatus, GatewayError, InboundSms, SendResult, SmsGateway, WebhookRequest};
+use crate::config::ConfigError;
+
+/// Africa's Talking SMS API.
+///
+/// Messages are form posts to `{api}/version1/messaging` with the
+/// `apiKey` header; the `sandbox` username uses the sandbox host.
+/// Webhooks are forms. Africa's Talking doesn't sign them; with
+/// AFRICASTALKING_WEBHOOK_SECRET set, calls must carry the body's HMAC
+/// in `X-Signature`.
+#[derive(Debug, Clone)]
+pub struct AfricasTalkingGateway {
+    api_url: String,
+    username: String,
+    api_key: String,
+    /// Short code or alphanumeric sender ID messages come from
+    sender: String,
+    webhook_secret: Option<String>,
+}
+
+impl AfricasTalkingGateway {
+    pub fn new(username: &str, api_key: &str, sender: &str) -> Self {
+        let api_url = if username == "sandbox" {
+            "https://api.sandbox.africastalking.com"
+        } else {
+            "https://api.africastalking.com"
+        };
+        Self {
+            api_url: api_url.to_string(),
+            username: username.to_string(),
+            api_key: api_key.to_string(),
+            sender: sender.to_string(),
+            webhook_secret: None,
+        }
+    }
+
+    pub fn with_webhook_secret(mut self, secret: &str) -> Self {
+        self.webhook_secret = Some(secret.to_string());
+        self
+    }
+
+    /// Read AFRICASTALKING_USERNAME, AFRICASTALKING_API_KEY,
+    /// AFRICASTALKING_SENDER and AFRICASTALKING_WEBHOOK_SECRET
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let var = |name: &'static str| std::env::var(name).map_err(|_| ConfigError::Missing(name));
+        let gateway = Self::new(&var("AFRICASTALKING_USERNAME")?, &var("AFRICASTALKING_API_KEY")?, &var("AFRICASTALKING_SENDER")?);
+        Ok(match std::env::var("AFRICASTALKING_WEBHOOK_SECRET") {
+            Ok(secret) => gateway.with_webhook_secret(&secret),
+            Err(_) => {
+                tracing::warn!("AFRICASTALKING_WEBHOOK_SECRET not set: Africa's Talking webhooks are not verified");
+                gateway
+            }
+        })
+    }
+}
+
+/// Read the reply to a send; status codes 100-102 are accepted
+fn send_result(json: &serde_json::Value) -> Result<SendResult, GatewayError> {
+    let data = &json["SMSMessageData"];
+    let Some(recipient) = data["Recipients"].get(0) else {
+        return Err(GatewayError::Api(data["Message"].as_str().unwrap_or("Unknown error").to_string()));
+    };
+    let status = recipient["status"].as_str().unwrap_or_default();
+    match (recipient["statusCode"].as_u64(), recipient["messageId"].as_str()) {
+        (Some(100..=102), Some(id)) => Ok(SendResult { message_id: id.to_string(), status: status.to_string() }),
+        _ => Err(GatewayError::Api(status.to_string())),
+    }
+}
+
+impl SmsGateway for AfricasTalkingGateway {
+    fn key(&self) -> &'static str {
+        "africastalking"
+    }
+
+    fn number(&self) -> &str {
+        &self.sender
+    }
+
+    fn send<'a>(&'a self, to: &'a str, body: &'a str) -> BoxFuture<'a, Result<SendResult, GatewayError>> {
+        async move {
+            let response = reqwest::Client::new()
+                .post(format!("{}/version1/messaging", self.api_url))
+                .header("apiKey", &self.api_key)
+                .header("Accept", "application/json")
+                .form(&[
+                    ("username", self.username.as_str()),
+                    ("to", to),
+                    ("message", body),
+                    ("from", self.sender.as_str()),
+                ])
+                .timeout(std::time::Duration::from_secs(15))
+                .send()
+                .await?;
+            let json: serde_json::Value = response.json().await?;
+            send_result(&json)
+        }
+        .boxed()
+    }
+
+    fn verify(&self, request: &WebhookRequest) -> bool {
+        verify_body_signature(self.webhook_secret.as_deref(), request)
+    }
+
+    fn signs_webhooks(&self) -> bool {
+        self.webhook_secret.is_some()
+    }
+
+    /// `from, to, text, id, date, linkId`
+    fn parse_inbound(&self, request: &WebhookRequest) -> Result<InboundSms, GatewayError> {
+        let fields = request.fields();
+        let from = field(&fields, &["from"]).ok_or_else(|| GatewayError::Payload("missing from".to_string()))?;
+        Ok(InboundSms {
+            from: sender_phone(from),
+            to: field(&fields, &["to"]).unwrap_or_default().to_string(),
+            body: field(&fields, &["text"]).unwrap_or_default().to_string(),
+            message_id: field(&fields, &["id"]).unwrap_or_default().to_string(),
+        })
+    }
+
+    /// `id, status, failureReason`
+    fn parse_status(&self, request: &WebhookRequest) -> Result<DeliveryReport, GatewayError> {
+        let fields = request.fields();
+        let (Some(id), Some(status)) = (field(&fields, &["id"]), field(&fields, &["status"])) else {
+            return Err(GatewayError::Payload("missing id or status".to_string()));
+        };
+        let status = match status {
+            "Success" => DeliveryStatus::Delivered,
+            "Sent" => DeliveryStatus::Sent,
+            "Failed" | "Expired" => DeliveryStatus::Undelivered,
+            "Rejected" => DeliveryStatus::Failed,
+            // Submitted, Buffered
+            _ => DeliveryStatus::Queued,
+        };
+        Ok(DeliveryReport {
+            message_id: id.to_string(),
+            status,
+            error: field(&fields, &["failureReason"]).map(str::to_string),
+        })
+    }
+
+    fn ack(&self) -> (&'static str, String) {
+        ("text/plain", "OK".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_send_result() {
+        let sent = serde_json::json!({"SMSMessageData": {"Message": "Sent to 1/1 Total Cost: KES 0.8", "Recipients": [
+            {"statusCode": 101, "number": "+254712345678", "status": "Success", "cost": "KES 0.8", "messageId": "ATXid_1"}
+        ]}});
+        assert_eq!(send_result(&sent).unwrap().message_id, "ATXid_1");
+
+        let blocked = serde_json::json!({"SMSMessageData": {"Message": "Sent to 0/1", "Recipients": [
+            {"statusCode": 406, "number": "+254712345678", "status": "UserInBlacklist", "messageId": "None"}
+        ]}});
+        assert!(matches!(send_result(&blocked), Err(GatewayError::Api(m)) if m == "UserInBlacklist"));
+        assert!(send_result(&serde_json::json!({"SMSMessageData": {"Message": "InvalidSenderId"}})).is_err());
+    }
+
+    #[test]
+    fn test_parse_webhooks() {
+        let gateway = AfricasTalkingGateway::new("sandbox", "key", "12345");
+        let request = |body: &str| WebhookRequest {
+            url: "https://sms.example.com/sms/africastalking/incoming".to_string(),
+            headers: axum::http::HeaderMap::new(),
+            body: axum::body::Bytes::from(body.to_string()),
+        };
+
+        let sms = gateway.parse_inbound(&request("from=%2B254712345678&to=12345&text=BALANCE&id=abc&linkId=x")).unwrap();
+        assert_eq!(sms.from, "+254712345678");
+        assert_eq!(sms.to, "12345");
+        assert_eq!(sms.body, "BALANCE");
+
+        let report = gateway.parse_status(&request("id=ATXid_1&status=Failed&failureReason=AbsentSubscriber")).unwrap();
+        assert_eq!(report.status, DeliveryStatus::Undelivered);
+        assert_eq!(report.error.as_deref(), Some("AbsentSubscriber"));
+    }
+}
@@ -0,0 +1,346 @@
+use axum::body::Bytes;
+use axum::http::HeaderMap;
+use futures::future::BoxFuture;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::{
+    normalize_phone, AfricasTalkingGateway, LoopbackGateway, SmsCountryGateway, TwilioClient, VonageGateway,
+};
+use crate::config::{ConfigError, TwilioConfig};
+
+/// Result of sending an SMS
+#[derive(Debug, Clone, PartialEq)]
+pub struct SendResult {
+    /// The gateway's own reference for the message
+    pub message_id: String,
+    pub status: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GatewayError {
+    #[error("HTTP request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("API error: {0}")]
+    Api(String),
+    #[error("Unreadable webhook payload: {0}")]
+    Payload(String),
+    #[error("Failed to queue message: {0}")]
+    Queue(#[from] sqlx::Error),
+}
+
+/// An SMS received by a gateway
+#[derive(Debug, Clone, PartialEq)]
+pub struct InboundSms {
+    /// Sender, in E.164 form
+    pub from: String,
+    /// Our number (or short code) it was sent to
+    pub to: String,
+    pub body: String,
+    /// The gateway's ID for the message; empty if it sends none
+    pub message_id: String,
+}
+
+/// Where an outbound message stands, in terms common to every gateway
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryStatus {
+    /// Accepted, not yet handed to the carrier
+    Queued,
+    /// Handed to the carrier
+    Sent,
+    Delivered,
+    /// The carrier couldn't deliver it
+    Undelivered,
+    /// The gateway couldn't send it
+    Failed,
+}
+
+impl std::fmt::Display for DeliveryStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeliveryStatus::Queued => write!(f, "queued"),
+            DeliveryStatus::Sent => write!(f, "sent"),
+            DeliveryStatus::Delivered => write!(f, "delivered"),
+            DeliveryStatus::Undelivered => write!(f, "undelivered"),
+            DeliveryStatus::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+/// Delivery receipt posted back by a gateway
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeliveryReport {
+    pub message_id: String,
+    pub status: DeliveryStatus,
+    /// Error code or reason, when the gateway gives one
+    pub error: Option<String>,
+}
+
+/// A webhook call as received, for gateways to verify and parse
+#[derive(Debug, Clone)]
+pub struct WebhookRequest {
+    /// Full URL the gateway called, including the query string
+    pub url: String,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+impl WebhookRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|v| v.to_str().ok())
+    }
+
+    /// Query string and form-encoded body parameters, the body winning
+    pub fn params(&self) -> HashMap<String, String> {
+        let mut params: HashMap<String, String> = match self.url.split_once('?') {
+            Some((_, query)) => serde_urlencoded::from_str(query).unwrap_or_default(),
+            None => HashMap::new(),
+        };
+        if !self.is_json() {
+            params.extend(serde_urlencoded::from_bytes::<HashMap<String, String>>(&self.body).unwrap_or_default());
+        }
+        params
+    }
+
+    /// Body fields whether sent as JSON or as a form, with JSON numbers
+    /// and booleans turned into strings
+    pub fn fields(&self) -> HashMap<String, String> {
+        if !self.is_json() {
+            return self.params();
+        }
+        let Ok(serde_json::Value::Object(map)) = serde_json::from_slice::<serde_json::Value>(&self.body) else {
+            return HashMap::new();
+        };
+        map.into_iter()
+            .filter_map(|(key, value)| match value {
+                serde_json::Value::String(s) => Some((key, s)),
+                serde_json::Value::Null => None,
+                other => Some((key, other.to_string())),
+            })
+            .collect()
+    }
+
+    fn is_json(&self) -> bool {
+        self.header("content-type").is_some_and(|t| t.starts_with("application/json"))
+            || self.body.first() == Some(&b'{')
+    }
+}
+
+/// Look a field up under any of its spellings, e.g. `From` or `from`
+pub(crate) fn field<'a>(fields: &'a HashMap<String, String>, names: &[&str]) -> Option<&'a str> {
+    names.iter().find_map(|name| fields.get(*name)).map(|v| v.as_str())
+}
+
+/// E.164 form of a sender, adding the `+` gateways like Vonage leave off
+pub(crate) fn sender_phone(raw: &str) -> String {
+    let raw = raw.trim();
+    if !raw.is_empty() && raw.chars().all(|c| c.is_ascii_digit()) && raw.len() > 10 {
+        normalize_phone(&format!("+{}", raw))
+    } else {
+        normalize_phone(raw)
+    }
+}
+
+/// HMAC-SHA256 of a webhook body, for gateways without a signing scheme
+/// of their own
+fn body_mac(secret: &str, body: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC can take key of any size");
+    mac.update(body);
+    mac
+}
+
+/// Hex `X-Signature` for a body, as a relay would send it
+#[cfg(test)]
+pub(crate) fn body_signature(secret: &str, body: &[u8]) -> String {
+    hex::encode(body_mac(secret, body).finalize().into_bytes())
+}
+
+/// Check `X-Signature` against the body when a secret is configured
+pub(crate) fn verify_body_signature(secret: Option<&str>, request: &WebhookRequest) -> bool {
+    let Some(secret) = secret else {
+        return true;
+    };
+    let Some(Ok(signature)) = request.header("x-signature").map(hex::decode) else {
+        return false;
+    };
+    // Constant-time compare, so the signature can't be guessed byte by byte
+    body_mac(secret, &request.body).verify_slice(&signature).is_ok()
+}
+
+/// An SMS provider: sends messages, and reads the webhooks it calls us with.
+///
+/// `send` returns a boxed future so gateways can be picked at runtime.
+pub trait SmsGateway: Send + Sync {
+    /// Name used in config and webhook paths, e.g. `twilio`
+    fn key(&self) -> &'static str;
+
+    /// Our number (or sender ID) on this gateway
+    fn number(&self) -> &str;
+
+    fn send<'a>(&'a self, to: &'a str, body: &'a str) -> BoxFuture<'a, Result<SendResult, GatewayError>>;
+
+    /// Whether a webhook call really came from the gateway
+    fn verify(&self, request: &WebhookRequest) -> bool;
+
+    /// Whether `verify` checks a signature, rather than letting every call
+    /// through for lack of a secret
+    fn signs_webhooks(&self) -> bool {
+        true
+    }
+
+    /// Read an incoming message webhook
+    fn parse_inbound(&self, request: &WebhookRequest) -> Result<InboundSms, GatewayError>;
+
+    /// Read a delivery receipt webhook
+    fn parse_status(&self, request: &WebhookRequest) -> Result<DeliveryReport, GatewayError>;
+
+    /// Content type and body to answer an incoming message webhook with;
+    /// replies are always sent separately through `send`
+    fn ack(&self) -> (&'static str, String) {
+        ("application/json", r#"{"success":true}"#.to_string())
+    }
+}
+
+/// Configured gateways. The first is the default; others take the
+/// messages to and from their own numbers and country prefixes.
+#[derive(Clone, Default)]
+pub struct SmsGateways {
+    gateways: Vec<(Arc<dyn SmsGateway>, Vec<String>)>,
+}
+
+impl SmsGateways {
+    /// Add a gateway that also sends to numbers starting with `prefixes`;
+    /// the first one added is the default
+    pub fn with_prefixes(mut self, gateway: impl SmsGateway + 'static, prefixes: &[&str]) -> Self {
+        let prefixes = prefixes.iter().map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect();
+        self.gateways.push((Arc::new(gateway), prefixes));
+        self
+    }
+
+    /// Read SMS_GATEWAYS (default `twilio`), a comma-separated list of
+    /// `twilio`, `smscountry`, `africastalking`, `vonage` and `loopback`
+    /// in order of preference, and each gateway's settings.
+    ///
+    /// `<KEY>_PREFIXES` (e.g. `AFRICASTALKING_PREFIXES=+254,+255`) sends
+    /// notifications to those numbers through that gateway.
+    pub fn from_env(twilio: Option<&TwilioConfig>) -> Result<Self, ConfigError> {
+        let keys = std::env::var("SMS_GATEWAYS").unwrap_or_else(|_| "twilio".to_string());
+
+        let mut gateways = Self::default();
+        for key in keys.split(',').map(|k| k.trim().to_lowercase()).filter(|k| !k.is_empty()) {
+            let prefixes = std::env::var(format!("{}_PREFIXES", key.to_uppercase())).unwrap_or_default();
+            let prefixes: Vec<&str> = prefixes.split(',').collect();
+            gateways = match key.as_str() {
+                "twilio" => {
+                    let client = TwilioClient::new(twilio.ok_or(ConfigError::Missing("TWILIO_ACCOUNT_SID"))?);
+                    // Delivery receipts need a URL Twilio can reach
+                    let client = match std::env::var("PUBLIC_URL") {
+                        Ok(base) => client.with_status_callback(&format!("{}/sms/status", base.trim_end_matches('/'))),
+                        Err(_) => client,
+                    };
+                    gateways.with_prefixes(client, &prefixes)
+                }
+                "smscountry" => gateways.with_prefixes(SmsCountryGateway::from_env()?, &prefixes),
+                "africastalking" => gateways.with_prefixes(AfricasTalkingGateway::from_env()?, &prefixes),
+                "vonage" => gateways.with_prefixes(VonageGateway::from_env()?, &prefixes),
+                "loopback" => {
+                    tracing::warn!("Loopback SMS gateway enabled: its webhooks are unsigned, use it only locally");
+                    gateways.with_prefixes(LoopbackGateway::from_env(), &prefixes)
+                }
+                _ => return Err(ConfigError::Invalid("SMS_GATEWAYS")),
+            };
+        }
+
+        if gateways.gateways.is_empty() {
+            return Err(ConfigError::Invalid("SMS_GATEWAYS"));
+        }
+        Ok(gateways)
+    }
+
+    /// Gateway by key, for its webhooks
+    pub fn get(&self, key: &str) -> Option<Arc<dyn SmsGateway>> {
+        self.gateways.iter().find(|(g, _)| g.key().eq_ignore_ascii_case(key)).map(|(g, _)| g.clone())
+    }
+
+    /// Gateway that owns the number a message was sent to, so replies
+    /// come from the number the user wrote to
+    pub fn for_inbound(&self, to: &str) -> Option<Arc<dyn SmsGateway>> {
+        let to = normalize_phone(to);
+        self.gateways.iter().find(|(g, _)| normalize_phone(g.number()) == to).map(|(g, _)| g.clone())
+    }
+
+    /// Gateway for a message to `phone`: the one with the longest matching
+    /// prefix, else the default
+    pub fn for_recipient(&self, phone: &str) -> Option<Arc<dyn SmsGateway>> {
+        self.gateways
+            .iter()
+            .filter_map(|(g, prefixes)| {
+                let longest = prefixes.iter().filter(|p| phone.starts_with(p.as_str())).map(|p| p.len()).max()?;
+                Some((longest, g))
+            })
+            .max_by_key(|(len, _)| *len)
+            .map(|(_, g)| g.clone())
+            .or_else(|| self.gateways.first().map(|(g, _)| g.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(content_type: &str, body: &str) -> WebhookRequest {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", content_type.parse().unwrap());
+        WebhookRequest {
+            url: "https://sms.example.com/sms/vonage/incoming?msisdn=447700900001&text=hi".to_string(),
+            headers,
+            body: Bytes::from(body.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_webhook_fields() {
+        let form = request("application/x-www-form-urlencoded", "text=BALANCE&to=12345");
+        let fields = form.fields();
+        assert_eq!(field(&fields, &["Body", "text"]), Some("BALANCE"));
+        assert_eq!(field(&fields, &["msisdn"]), Some("447700900001"));
+        assert_eq!(field(&fields, &["to"]), Some("12345"));
+
+        let json = request("application/json", r#"{"From": "+254712345678", "Count": 2, "Gone": null}"#);
+        let fields = json.fields();
+        assert_eq!(field(&fields, &["from", "From"]), Some("+254712345678"));
+        assert_eq!(field(&fields, &["Count"]), Some("2"));
+        assert_eq!(field(&fields, &["Gone"]), None);
+
+        assert_eq!(sender_phone("447700900001"), "+447700900001");
+        assert_eq!(sender_phone("+254 712 345678"), "+254712345678");
+    }
+
+    #[test]
+    fn test_routing() {
+        let gateways = SmsGateways::default()
+            .with_prefixes(LoopbackGateway::new("+15550000001"), &[])
+            .with_prefixes(LoopbackGateway::named("sandbox", "+254700000001"), &["+254", "+2547"]);
+
+        assert_eq!(gateways.for_recipient("+14155550000").unwrap().number(), "+15550000001");
+        assert_eq!(gateways.for_recipient("+254712345678").unwrap().number(), "+254700000001");
+        assert_eq!(gateways.for_inbound("+254 700 000001").unwrap().key(), "sandbox");
+        assert!(gateways.for_inbound("+19999999999").is_none());
+        assert!(gateways.get("SANDBOX").is_some());
+        assert!(SmsGateways::default().for_recipient("+254712345678").is_none());
+    }
+
+    #[test]
+    fn test_body_signature() {
+        let mut signed = request("application/json", r#"{"From":"+254712345678"}"#);
+        assert!(verify_body_signature(None, &signed));
+        assert!(!verify_body_signature(Some("secret"), &signed));
+
+        let sig = body_signature("secret", &signed.body);
+        signed.headers.insert("x-signature", sig.to_uppercase().parse().unwrap());
+        assert!(verify_body_signature(Some("secret"), &signed));
+        assert!(!verify_body_signature(Some("other"), &signed));
+    }
+}
@@ -0,0 +1,145 @@
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use std::io::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use super::gateway::{field, sender_phone};
+use super::{DeliveryReport, DeliveryStatus, GatewayError, InboundSms, SendResult, SmsGateway, WebhookRequest};
+
+/// Gateway for local development and tests: nothing leaves the machine.
+///
+/// Sent messages are logged and, with SMS_LOOPBACK_FILE set, appended
+/// to that file as JSON lines. Webhooks take `{from, to, body, id}`
+/// as JSON or a form and are never signed, so anyone who can reach the
+/// server can send as any number: don't enable it in production.
+#[derive(Debug, Clone)]
+pub struct LoopbackGateway {
+    key: &'static str,
+    number: String,
+    file: Option<String>,
+    sent: Arc<AtomicUsize>,
+}
+
+impl LoopbackGateway {
+    pub fn new(number: &str) -> Self {
+        Self::named("loopback", number)
+    }
+
+    /// Loopback under another key, to stand in for a second gateway
+    pub fn named(key: &'static str, number: &str) -> Self {
+        Self {
+            key,
+            number: number.to_string(),
+            file: None,
+            sent: Arc::default(),
+        }
+    }
+
+    /// Also append sent messages to `path`
+    pub fn with_file(mut self, path: &str) -> Self {
+        self.file = Some(path.to_string());
+        self
+    }
+
+    /// Read SMS_LOOPBACK_NUMBER (default +15550000000) and SMS_LOOPBACK_FILE
+    pub fn from_env() -> Self {
+        let number = std::env::var("SMS_LOOPBACK_NUMBER").unwrap_or_else(|_| "+15550000000".to_string());
+        let gateway = Self::new(&number);
+        match std::env::var("SMS_LOOPBACK_FILE") {
+            Ok(path) => gateway.with_file(&path),
+            Err(_) => gateway,
+        }
+    }
+
+    fn append(&self, to: &str, body: &str) -> std::io::Result<()> {
+        let Some(ref path) = self.file else {
+            return Ok(());
+        };
+        let line = serde_json::json!({
+            "to": to,
+            "from": self.number,
+            "body": body,
+            "at": chrono::Utc::now().to_rfc3339(),
+        });
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", line)
+    }
+}
+
+impl SmsGateway for LoopbackGateway {
+    fn key(&self) -> &'static str {
+        self.key
+    }
+
+    fn number(&self) -> &str {
+        &self.number
+    }
+
+    fn send<'a>(&'a self, to: &'a str, body: &'a str) -> BoxFuture<'a, Result<SendResult, GatewayError>> {
+        async move {
+            tracing::info!(to = %to, body = %body, "Loopback SMS");
+            self.append(to, body).map_err(|e| GatewayError::Api(e.to_string()))?;
+
+            let sent = self.sent.fetch_add(1, Ordering::Relaxed) + 1;
+            Ok(SendResult {
+                message_id: format!("LB{}", sent),
+                status: DeliveryStatus::Delivered.to_string(),
+            })
+        }
+        .boxed()
+    }
+
+    fn verify(&self, _request: &WebhookRequest) -> bool {
+        true
+    }
+
+    fn signs_webhooks(&self) -> bool {
+        false
+    }
+
+    fn parse_inbound(&self, request: &WebhookRequest) -> Result<InboundSms, GatewayError> {
+        let fields = request.fields();
+        let from = field(&fields, &["from", "From"]).ok_or_else(|| GatewayError::Payload("missing from".to_string()))?;
+        Ok(InboundSms {
+            from: sender_phone(from),
+            to: field(&fields, &["to", "To"]).unwrap_or(&self.number).to_string(),
+            body: field(&fields, &["body", "Body", "text"]).unwrap_or_default().to_string(),
+            message_id: field(&fields, &["id", "MessageSid"]).unwrap_or_default().to_string(),
+        })
+    }
+
+    fn parse_status(&self, request: &WebhookRequest) -> Result<DeliveryReport, GatewayError> {
+        let fields = request.fields();
+        let id = field(&fields, &["id"]).ok_or_else(|| GatewayError::Payload("missing id".to_string()))?;
+        let status = match field(&fields, &["status"]).unwrap_or_default() {
+            "delivered" => DeliveryStatus::Delivered,
+            "sent" => DeliveryStatus::Sent,
+            "undelivered" => DeliveryStatus::Undelivered,
+            "failed" => DeliveryStatus::Failed,
+            _ => DeliveryStatus::Queued,
+        };
+        Ok(DeliveryReport { message_id: id.to_string(), status, error: None })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_loopback_send() {
+        let path = std::env::temp_dir().join(format!("loopback-{}.jsonl", uuid::Uuid::new_v4()));
+        let gateway = LoopbackGateway::new("+15550000000").with_file(path.to_str().unwrap());
+
+        assert_eq!(gateway.send("+254712345678", "Balance: 10 TXTC").await.unwrap().message_id, "LB1");
+        assert_eq!(gateway.send("+254712345678", "Sent").await.unwrap().message_id, "LB2");
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> = written.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["to"], "+254712345678");
+        assert_eq!(lines[0]["body"], "Balance: 10 TXTC");
+        let _ = std::fs::remove_file(path);
+    }
+}
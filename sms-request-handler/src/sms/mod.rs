@@ -1,5 +1,32 @@
+pub mod africastalking;
+pub mod gateway;
+pub mod loopback;
+pub mod outbox;
+pub mod phone;
+pub mod ratelimit;
+pub mod segment;
+pub mod shaper;
+pub mod signature;
+pub mod smscountry;
 pub mod twilio;
+pub mod vonage;
 pub mod webhook;
 
+pub use africastalking::AfricasTalkingGateway;
+pub use gateway::{
+    DeliveryReport, DeliveryStatus, GatewayError, InboundSms, SendResult, SmsGateway, SmsGateways, WebhookRequest,
+};
+pub use loopback::LoopbackGateway;
+pub use outbox::Outbox;
+pub use phone::{normalize_phone, PhoneNumber};
+pub use ratelimit::{RateLimiter, Throttle};
+pub use segment::segment_count;
+pub use shaper::ReplyShaper;
+pub use signature::{header_token_matches, verify_webhook, WebhookAuth};
+pub use smscountry::SmsCountryGateway;
 pub use twilio::TwilioClient;
-pub use webhook::{incoming_sms_handler, incoming_sms_json_handler};
+pub use vonage::VonageGateway;
+pub use webhook::{
+    inbound_handler, inbound_window, incoming_sms_handler, incoming_sms_json_handler, status_handler,
+    twilio_status_handler,
+};
@@ -0,0 +1,156 @@
+use chrono::{Duration, Utc};
+
+use super::{DeliveryReport, GatewayError, SmsGateways};
+use crate::config::env_duration;
+use crate::db::{OutboundMessage, OutboundMessageRepository};
+
+/// How long a message being sent is held before another worker may retry it
+const SEND_LEASE_SECS: i64 = 120;
+
+/// Messages sent per worker tick
+const BATCH_SIZE: i64 = 50;
+
+/// Outgoing SMS: replies and notifications.
+///
+/// With a repository every message is written to `outbound_messages`
+/// before it is sent, and one that fails (or whose process dies) is
+/// retried with exponential backoff until SMS_MAX_ATTEMPTS, then left
+/// dead for an admin to look at. Without one, messages are sent at once.
+#[derive(Clone)]
+pub struct Outbox {
+    gateways: SmsGateways,
+    repo: Option<OutboundMessageRepository>,
+    max_attempts: i32,
+    retry_base: Duration,
+}
+
+impl Outbox {
+    /// Read SMS_MAX_ATTEMPTS (default 6) and SMS_RETRY_BASE_SECS (default 30)
+    pub fn new(gateways: SmsGateways) -> Self {
+        let max_attempts = std::env::var("SMS_MAX_ATTEMPTS").ok().and_then(|v| v.parse().ok()).unwrap_or(6);
+        Self {
+            gateways,
+            repo: None,
+            max_attempts: max_attempts.max(1),
+            retry_base: env_duration("SMS_RETRY_BASE_SECS", Duration::seconds(30)),
+        }
+    }
+
+    pub fn with_repo(mut self, repo: OutboundMessageRepository) -> Self {
+        self.repo = Some(repo);
+        self
+    }
+
+    pub fn gateways(&self) -> &SmsGateways {
+        &self.gateways
+    }
+
+    /// Send `body` to `to` through gateway `via` (the one the user wrote
+    /// to), else the recipient's gateway. Once queued the message is
+    /// tried straight away; only failing to queue it is an error.
+    pub async fn send(&self, via: Option<&str>, to: &str, body: &str) -> Result<(), GatewayError> {
+        let Some(ref repo) = self.repo else {
+            let gateway = via.and_then(|key| self.gateways.get(key)).or_else(|| self.gateways.for_recipient(to));
+            let Some(gateway) = gateway else {
+                return Err(GatewayError::Api("No SMS gateway configured".to_string()));
+            };
+            let sent = gateway.send(to, body).await?;
+            tracing::info!(message_id = %sent.message_id, status = %sent.status, "SMS sent");
+            return Ok(());
+        };
+
+        let message = repo.enqueue(via, to, body, Duration::seconds(SEND_LEASE_SECS)).await?;
+        self.attempt(repo, message).await;
+        Ok(())
+    }
+
+    /// Retry queued messages that are due; run by a background task
+    pub async fn run_due(&self) {
+        let Some(ref repo) = self.repo else {
+            return;
+        };
+
+        let due = match repo.claim_due(BATCH_SIZE, Duration::seconds(SEND_LEASE_SECS)).await {
+            Ok(due) => due,
+            Err(e) => {
+                tracing::error!("Failed to load queued SMS: {}", e);
+                return;
+            }
+        };
+        for message in due {
+            self.attempt(repo, message).await;
+        }
+    }
+
+    /// Store a delivery receipt against the message it is for
+    pub async fn record_delivery(&self, gateway: &str, report: &DeliveryReport) {
+        let Some(ref repo) = self.repo else {
+            return;
+        };
+
+        let status = report.status.to_string();
+        match repo.record_delivery(gateway, &report.message_id, &status, report.error.as_deref()).await {
+            Ok(true) => {}
+            Ok(false) => tracing::debug!(gateway, message_id = %report.message_id, "Delivery report for unknown or settled message"),
+            Err(e) => tracing::error!(message_id = %report.message_id, "Failed to record delivery report: {}", e),
+        }
+    }
+
+    async fn attempt(&self, repo: &OutboundMessageRepository, message: OutboundMessage) {
+        let gateway = message
+            .gateway
+            .as_deref()
+            .and_then(|key| self.gateways.get(key))
+            .or_else(|| self.gateways.for_recipient(&message.to_phone));
+        let result = match gateway {
+            Some(gateway) => gateway.send(&message.to_phone, &message.body).await.map(|sent| (gateway.key(), sent)),
+            None => Err(GatewayError::Api("No SMS gateway configured".to_string())),
+        };
+
+        let stored = match result {
+            Ok((key, sent)) => {
+                tracing::info!(
+                    id = %message.id,
+                    gateway = key,
+                    message_id = %sent.message_id,
+                    status = %sent.status,
+                    "SMS sent"
+                );
+                repo.mark_sent(message.id, key, &sent.message_id).await
+            }
+            Err(e) => {
+                let attempts = message.attempts + 1;
+                let retry_at = (attempts < self.max_attempts).then(|| Utc::now() + retry_delay(self.retry_base, attempts));
+                match retry_at {
+                    Some(at) => tracing::warn!(id = %message.id, to = %message.to_phone, attempts, retry_at = %at, "SMS send failed: {}", e),
+                    None => tracing::error!(id = %message.id, to = %message.to_phone, attempts, "Giving up on SMS: {}", e),
+                }
+                repo.mark_failed(message.id, &e.to_string(), retry_at).await
+            }
+        };
+        if let Err(e) = stored {
+            tracing::error!(id = %message.id, "Failed to update queued SMS: {}", e);
+        }
+    }
+}
+
+/// Wait before attempt `attempts + 1`: `base` doubled per failure, at most an hour
+fn retry_delay(base: Duration, attempts: i32) -> Duration {
+    let factor = 1i32 << (attempts - 1).clamp(0, 16);
+    (base * factor).min(Duration::hours(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay() {
+        let base = Duration::seconds(30);
+        assert_eq!(retry_delay(base, 1), Duration::seconds(30));
+        assert_eq!(retry_delay(base, 2), Duration::seconds(60));
+        assert_eq!(retry_delay(base, 4), Duration::seconds(240));
+        assert_eq!(retry_delay(base, 10), Duration::hours(1));
+        assert_eq!(retry_delay(base, 40), Duration::hours(1));
+    }
+}
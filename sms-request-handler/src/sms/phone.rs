@@ -0,0 +1,105 @@
+use phonenumber::country;
+use phonenumber::Mode;
+
+/// Region assumed for numbers typed without a country code
+const FALLBACK_REGION: country::Id = country::Id::KE;
+
+/// Region local-format numbers are read in: DEFAULT_PHONE_REGION
+/// (ISO 3166 code, e.g. `IN`), else Kenya
+pub fn default_region() -> country::Id {
+    std::env::var("DEFAULT_PHONE_REGION")
+        .ok()
+        .and_then(|v| v.trim().to_uppercase().parse().ok())
+        .unwrap_or(FALLBACK_REGION)
+}
+
+/// A phone number in E.164 form (`+254712345678`), the only form stored
+/// in or compared against the database
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PhoneNumber(String);
+
+impl PhoneNumber {
+    /// Parse international (`+91 98765 43210`) or local (`0712 345678`)
+    /// input, reading local numbers in the deployment's default region
+    pub fn parse(input: &str) -> Option<PhoneNumber> {
+        Self::parse_in(input, default_region())
+    }
+
+    /// Parse, reading local numbers in `region`
+    pub fn parse_in(input: &str, region: country::Id) -> Option<PhoneNumber> {
+        let input = input.trim();
+        // Only digits and the usual separators, so names and vanity
+        // numbers are never taken for a phone
+        let phone_like = input.chars().enumerate().all(|(i, c)| {
+            c.is_ascii_digit() || matches!(c, ' ' | '-' | '(' | ')' | '.') || (i == 0 && c == '+')
+        });
+        if !phone_like || !input.contains(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+
+        let number = phonenumber::parse(Some(region), input).ok()?;
+        // Without a country code, short codes and years would read as
+        // numbers, so local input must be a real number in the region
+        if !input.starts_with('+') && !number.is_valid() {
+            return None;
+        }
+        let e164 = number.format().mode(Mode::E164).to_string();
+        // E.164 allows up to 15 digits; fewer than 7 is never a subscriber
+        let digits = e164.len() - 1;
+        (7..=15).contains(&digits).then_some(PhoneNumber(e164))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for PhoneNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<PhoneNumber> for String {
+    fn from(phone: PhoneNumber) -> String {
+        phone.0
+    }
+}
+
+/// E.164 form of `input`, or `input` trimmed if it isn't a phone number.
+///
+/// For values that are keys rather than user input (webhook senders,
+/// stored rows), where an unreadable number should still match itself.
+pub fn normalize_phone(input: &str) -> String {
+    PhoneNumber::parse(input).map_or_else(|| input.trim().to_string(), String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn e164(input: &str, region: country::Id) -> Option<String> {
+        PhoneNumber::parse_in(input, region).map(String::from)
+    }
+
+    #[test]
+    fn test_parse_phone() {
+        let ke = country::Id::KE;
+        assert_eq!(e164("+254712345678", ke).as_deref(), Some("+254712345678"));
+        assert_eq!(e164("+254 712-345 678", ke).as_deref(), Some("+254712345678"));
+        assert_eq!(e164("0712 345678", ke).as_deref(), Some("+254712345678"));
+        assert_eq!(e164("+91 98765 43210", ke).as_deref(), Some("+919876543210"));
+        assert_eq!(e164("098765 43210", country::Id::IN).as_deref(), Some("+919876543210"));
+        assert_eq!(e164("(650) 253-0000", country::Id::US).as_deref(), Some("+16502530000"));
+
+        for bad in ["", "+12", "alice", "mom2", "2024", "12345678", "+2547abc0000", "1-800-FLOWERS", "0x1234", "12+34"] {
+            assert_eq!(e164(bad, ke), None, "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_normalize_phone() {
+        assert_eq!(normalize_phone(" +254 712 345678 "), "+254712345678");
+        assert_eq!(normalize_phone("+1234"), "+1234");
+    }
+}
@@ -0,0 +1,270 @@
+use chrono::{Duration, Utc};
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use crate::commands::registry;
+use crate::db::RateLimitRepository;
+
+/// Buckets kept in memory before idle ones are dropped
+const MAX_MEMORY_BUCKETS: usize = 10_000;
+
+/// Messages allowed per window, as a token bucket: up to `capacity` at
+/// once, refilled evenly over `window`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limit {
+    pub capacity: u32,
+    pub window: Duration,
+}
+
+impl Limit {
+    /// Read `20/60` (20 messages per 60 seconds); `off` or anything
+    /// unreadable is no limit
+    pub fn parse(value: &str) -> Option<Self> {
+        let (capacity, secs) = value.trim().split_once('/')?;
+        let capacity: u32 = capacity.trim().parse().ok().filter(|c| *c > 0)?;
+        let secs: i64 = secs.trim().parse().ok().filter(|s| *s > 0)?;
+        Some(Self { capacity, window: Duration::seconds(secs) })
+    }
+
+    fn refill_per_sec(&self) -> f64 {
+        self.capacity as f64 / self.window.num_seconds() as f64
+    }
+}
+
+/// Read limit `name`, falling back to `default`
+fn limit_from_env(name: &str, default: &str) -> Option<Limit> {
+    let value = std::env::var(name).unwrap_or_else(|_| default.to_string());
+    let limit = Limit::parse(&value);
+    if limit.is_none() && !value.trim().eq_ignore_ascii_case("off") {
+        tracing::warn!(value = %value, "{} isn't <count>/<seconds> or off, limit disabled", name);
+    }
+    limit
+}
+
+/// Command class a message is limited under, by its keyword: `codes` for
+/// anything that checks a guessable code or PIN, `money` for anything
+/// that moves funds
+fn command_class(body: &str) -> Option<&'static str> {
+    let keyword = body.split_whitespace().next()?;
+    let (spec, _) = registry::lookup_localized(keyword)?;
+    match spec.name() {
+        "REDEEM" | "VERIFY" | "PIN" | "YES" => Some("codes"),
+        "SEND" | "SPLIT" | "PAY" | "REQUEST" | "SWAP" | "BRIDGE" | "CASHOUT" => Some("money"),
+        _ => None,
+    }
+}
+
+/// Where token buckets are kept.
+///
+/// Methods return boxed futures so the store can be picked at runtime.
+/// A store that can't be reached lets messages through rather than
+/// dropping them.
+pub trait RateStore: Send + Sync {
+    /// Take a token from bucket `key`; false when it is empty
+    fn take<'a>(&'a self, key: &'a str, limit: Limit) -> BoxFuture<'a, bool>;
+
+    /// Whether to send the throttling notice for `key`, at most once per `window`
+    fn claim_notice<'a>(&'a self, key: &'a str, window: Duration) -> BoxFuture<'a, bool>;
+}
+
+impl RateStore for RateLimitRepository {
+    fn take<'a>(&'a self, key: &'a str, limit: Limit) -> BoxFuture<'a, bool> {
+        async move {
+            RateLimitRepository::take(self, key, limit.capacity as f64, limit.refill_per_sec())
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::error!(key, "Failed to check rate limit: {}", e);
+                    true
+                })
+        }
+        .boxed()
+    }
+
+    fn claim_notice<'a>(&'a self, key: &'a str, window: Duration) -> BoxFuture<'a, bool> {
+        async move {
+            RateLimitRepository::claim_notice(self, key, Utc::now() + window)
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::error!(key, "Failed to record throttling notice: {}", e);
+                    false
+                })
+        }
+        .boxed()
+    }
+}
+
+/// Buckets in this process only, for running without a database or
+/// with a single instance
+#[derive(Default)]
+pub struct MemoryRateStore {
+    /// Tokens left and when they were counted
+    buckets: Mutex<HashMap<String, (f64, Instant)>>,
+    /// When each key may be sent a notice again
+    notices: Mutex<HashMap<String, Instant>>,
+}
+
+impl RateStore for MemoryRateStore {
+    fn take<'a>(&'a self, key: &'a str, limit: Limit) -> BoxFuture<'a, bool> {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() >= MAX_MEMORY_BUCKETS {
+            // Buckets idle for longer than a window are full again anyway
+            let idle = limit.window.to_std().unwrap_or_default();
+            buckets.retain(|_, (_, at)| now.duration_since(*at) < idle);
+        }
+
+        let capacity = limit.capacity as f64;
+        let (tokens, at) = buckets.entry(key.to_string()).or_insert((capacity, now));
+        *tokens = (*tokens + now.duration_since(*at).as_secs_f64() * limit.refill_per_sec()).min(capacity);
+        *at = now;
+        let allowed = *tokens >= 1.0;
+        if allowed {
+            *tokens -= 1.0;
+        }
+        futures::future::ready(allowed).boxed()
+    }
+
+    fn claim_notice<'a>(&'a self, key: &'a str, window: Duration) -> BoxFuture<'a, bool> {
+        let now = Instant::now();
+        let mut notices = self.notices.lock().unwrap();
+        notices.retain(|_, until| *until > now);
+        let claimed = !notices.contains_key(key);
+        if claimed {
+            notices.insert(key.to_string(), now + window.to_std().unwrap_or_default());
+        }
+        futures::future::ready(claimed).boxed()
+    }
+}
+
+/// Whether an inbound message may be processed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Throttle {
+    Allowed,
+    /// Over a limit whose window is `window`; `notify` is true for the
+    /// first message of the window, which gets a throttling notice
+    Limited { window: Duration, notify: bool },
+}
+
+/// Limits on inbound messages: per sender, per sender and command class,
+/// and across all senders.
+///
+/// Read from RATE_LIMIT_SENDER (default `20/60`), RATE_LIMIT_CODES
+/// (`5/300`, REDEEM, VERIFY, PIN and YES), RATE_LIMIT_MONEY (`10/300`)
+/// and RATE_LIMIT_GLOBAL (`300/60`), each `<count>/<seconds>` or `off`.
+#[derive(Clone)]
+pub struct RateLimiter {
+    store: Arc<dyn RateStore>,
+    sender: Option<Limit>,
+    classes: Vec<(&'static str, Option<Limit>)>,
+    global: Option<Limit>,
+}
+
+impl RateLimiter {
+    pub fn new(store: impl RateStore + 'static) -> Self {
+        Self {
+            store: Arc::new(store),
+            sender: None,
+            classes: Vec::new(),
+            global: None,
+        }
+    }
+
+    pub fn with_sender_limit(mut self, limit: Option<Limit>) -> Self {
+        self.sender = limit;
+        self
+    }
+
+    pub fn with_class_limit(mut self, class: &'static str, limit: Option<Limit>) -> Self {
+        self.classes.push((class, limit));
+        self
+    }
+
+    pub fn with_global_limit(mut self, limit: Option<Limit>) -> Self {
+        self.global = limit;
+        self
+    }
+
+    /// Keep buckets in Postgres when a repository is given, unless
+    /// RATE_LIMIT_STORE=memory
+    pub fn from_env(repo: Option<RateLimitRepository>) -> Self {
+        let memory = std::env::var("RATE_LIMIT_STORE").is_ok_and(|v| v.eq_ignore_ascii_case("memory"));
+        let limiter = match repo {
+            Some(repo) if !memory => Self::new(repo),
+            _ => Self::new(MemoryRateStore::default()),
+        };
+        limiter
+            .with_sender_limit(limit_from_env("RATE_LIMIT_SENDER", "20/60"))
+            .with_class_limit("codes", limit_from_env("RATE_LIMIT_CODES", "5/300"))
+            .with_class_limit("money", limit_from_env("RATE_LIMIT_MONEY", "10/300"))
+            .with_global_limit(limit_from_env("RATE_LIMIT_GLOBAL", "300/60"))
+    }
+
+    /// Take a token from each bucket the message falls in, most specific
+    /// first, so a sender over their own limit doesn't use up the global one
+    pub async fn check(&self, from: &str, body: &str) -> Throttle {
+        let class = command_class(body).and_then(|class| {
+            let (_, limit) = self.classes.iter().find(|(c, _)| *c == class)?;
+            Some((format!("class:{}:{}", class, from), (*limit)?))
+        });
+        let sender = self.sender.map(|limit| (format!("sender:{}", from), limit));
+        let global = self.global.map(|limit| ("global".to_string(), limit));
+
+        for (key, limit) in [class, sender, global].into_iter().flatten() {
+            if !self.store.take(&key, limit).await {
+                tracing::warn!(from = %from, bucket = %key, "Inbound SMS rate limited");
+                let notify = self.store.claim_notice(&format!("notice:{}", from), limit.window).await;
+                return Throttle::Limited { window: limit.window, notify };
+            }
+        }
+        Throttle::Allowed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_limit() {
+        assert_eq!(Limit::parse("20/60"), Some(Limit { capacity: 20, window: Duration::seconds(60) }));
+        assert_eq!(Limit::parse(" 5 / 300 "), Some(Limit { capacity: 5, window: Duration::seconds(300) }));
+        assert_eq!(Limit::parse("off"), None);
+        assert_eq!(Limit::parse("0/60"), None);
+        assert_eq!(Limit::parse("20"), None);
+    }
+
+    #[test]
+    fn test_command_class() {
+        assert_eq!(command_class("REDEEM ABC123"), Some("codes"));
+        assert_eq!(command_class("verify 123456 1234"), Some("codes"));
+        assert_eq!(command_class("SEND 10 TXTC +254712345678"), Some("money"));
+        assert_eq!(command_class("BALANCE"), None);
+        assert_eq!(command_class(""), None);
+    }
+
+    #[tokio::test]
+    async fn test_limiter() {
+        let limiter = RateLimiter::new(MemoryRateStore::default())
+            .with_sender_limit(Limit::parse("3/60"))
+            .with_class_limit("codes", Limit::parse("1/60"))
+            .with_global_limit(Limit::parse("100/60"));
+
+        // One code guess per window, with a single notice
+        assert_eq!(limiter.check("+254700000001", "REDEEM AAA").await, Throttle::Allowed);
+        let window = Duration::seconds(60);
+        assert_eq!(limiter.check("+254700000001", "REDEEM BBB").await, Throttle::Limited { window, notify: true });
+        assert_eq!(limiter.check("+254700000001", "REDEEM CCC").await, Throttle::Limited { window, notify: false });
+
+        // Other commands go on until the sender's own limit, without a second notice
+        assert_eq!(limiter.check("+254700000001", "BALANCE").await, Throttle::Allowed);
+        assert_eq!(limiter.check("+254700000001", "BALANCE").await, Throttle::Allowed);
+        assert_eq!(limiter.check("+254700000001", "BALANCE").await, Throttle::Limited { window, notify: false });
+
+        // Other senders have their own buckets
+        assert_eq!(limiter.check("+254700000002", "REDEEM AAA").await, Throttle::Allowed);
+        assert_eq!(limiter.check("+254700000002", "BALANCE").await, Throttle::Allowed);
+    }
+}
@@ -0,0 +1,244 @@
+//! SMS encoding and segment arithmetic.
+//!
+//! A reply made only of GSM-7 characters fits 160 characters in one SMS
+//! (153 per segment once split); a single character outside that set, such
+//! as an emoji, switches the whole message to UCS-2 at 70 (67) per segment.
+
+/// GSM 03.38 basic character set
+const GSM7_BASIC: &str = "@£$¥èéùìòÇ\nØø\rÅåΔ_ΦΓΛΩΠΨΣΘΞÆæßÉ !\"#¤%&'()*+,-./0123456789:;<=>?\
+¡ABCDEFGHIJKLMNOPQRSTUVWXYZÄÖÑÜ§¿abcdefghijklmnopqrstuvwxyzäöñüà";
+
+/// GSM 03.38 extension table; each costs an escape plus the character
+const GSM7_EXTENDED: &str = "^{}\\[~]|€\u{c}";
+
+/// How a message is encoded on the air
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Encoding {
+    Gsm7,
+    Ucs2,
+}
+
+impl Encoding {
+    /// Cheapest encoding that can carry the text
+    pub fn detect(text: &str) -> Encoding {
+        if text.chars().all(is_gsm7) {
+            Encoding::Gsm7
+        } else {
+            Encoding::Ucs2
+        }
+    }
+
+    /// Units in a single SMS and in each part of a multipart SMS
+    fn limits(self) -> (usize, usize) {
+        match self {
+            Encoding::Gsm7 => (160, 153),
+            Encoding::Ucs2 => (70, 67),
+        }
+    }
+}
+
+fn is_gsm7(c: char) -> bool {
+    GSM7_BASIC.contains(c) || GSM7_EXTENDED.contains(c)
+}
+
+/// Septets (GSM-7) or UTF-16 code units (UCS-2) a character takes
+fn char_units(c: char, encoding: Encoding) -> usize {
+    match encoding {
+        Encoding::Gsm7 if GSM7_EXTENDED.contains(c) => 2,
+        Encoding::Gsm7 => 1,
+        Encoding::Ucs2 => c.len_utf16(),
+    }
+}
+
+/// Length of the text in the encoding's units
+pub fn units(text: &str, encoding: Encoding) -> usize {
+    text.chars().map(|c| char_units(c, encoding)).sum()
+}
+
+/// Number of SMS segments the text is billed as
+pub fn segment_count(text: &str) -> usize {
+    let encoding = Encoding::detect(text);
+    let (single, multi) = encoding.limits();
+    let len = units(text, encoding);
+    if len <= single {
+        1
+    } else {
+        len.div_ceil(multi)
+    }
+}
+
+/// Units that fit in a message of `segments` segments
+pub fn capacity(encoding: Encoding, segments: usize) -> usize {
+    let (single, multi) = encoding.limits();
+    if segments <= 1 {
+        single
+    } else {
+        segments * multi
+    }
+}
+
+/// Replace characters outside GSM-7 with close equivalents so the reply
+/// is sent at 160 characters per segment. Emoji and other symbols with
+/// no equivalent are dropped.
+pub fn transliterate(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut dropped = false;
+
+    for c in text.chars() {
+        if is_gsm7(c) {
+            // Don't leave a stray space where a leading emoji was
+            if !(dropped && c == ' ' && out.chars().last().is_none_or(char::is_whitespace)) {
+                out.push(c);
+            }
+            dropped = false;
+            continue;
+        }
+
+        let replacement = match c {
+            '‘' | '’' | '‚' | '′' | '´' | '`' => "'",
+            '“' | '”' | '„' | '″' => "\"",
+            '–' | '—' | '―' | '−' | '•' | '·' => "-",
+            '…' => "...",
+            '\u{a0}' | '\u{2009}' | '\u{202f}' | '\t' => " ",
+            'á' | 'â' | 'ã' | 'ā' | 'ă' | 'ą' => "a",
+            'Á' | 'À' | 'Â' | 'Ã' | 'Ā' => "A",
+            'ê' | 'ë' | 'ē' | 'ę' | 'ě' => "e",
+            'È' | 'Ê' | 'Ë' | 'Ē' => "E",
+            'í' | 'î' | 'ï' | 'ī' => "i",
+            'Í' | 'Ì' | 'Î' | 'Ï' => "I",
+            'ó' | 'ô' | 'õ' | 'ō' => "o",
+            'Ó' | 'Ò' | 'Ô' | 'Õ' => "O",
+            'ú' | 'û' | 'ū' => "u",
+            'Ú' | 'Ù' | 'Û' => "U",
+            'ç' => "Ç",
+            'ý' | 'ÿ' => "y",
+            'ś' | 'š' => "s",
+            'ź' | 'ż' | 'ž' => "z",
+            'ł' => "l",
+            '₹' => "Rs",
+            _ => "",
+        };
+
+        dropped = replacement.is_empty();
+        out.push_str(replacement);
+    }
+
+    out
+}
+
+/// Cut the text to `max_units`, ending with "..." when anything was removed
+pub fn truncate(text: &str, encoding: Encoding, max_units: usize) -> String {
+    if units(text, encoding) <= max_units {
+        return text.to_string();
+    }
+
+    let budget = max_units.saturating_sub(3);
+    let mut used = 0;
+    let mut out = String::new();
+    for c in text.chars() {
+        used += char_units(c, encoding);
+        if used > budget {
+            break;
+        }
+        out.push(c);
+    }
+    format!("{}...", out.trim_end())
+}
+
+/// Split the text into pages of at most `max_units`, breaking at a line
+/// or word boundary in the second half of a page where there is one.
+pub fn split(text: &str, encoding: Encoding, max_units: usize) -> Vec<String> {
+    let mut pages = Vec::new();
+    let mut rest = text.trim();
+
+    while !rest.is_empty() {
+        if units(rest, encoding) <= max_units {
+            pages.push(rest.to_string());
+            break;
+        }
+
+        let mut used = 0;
+        let mut end = rest.len();
+        for (i, c) in rest.char_indices() {
+            used += char_units(c, encoding);
+            if used > max_units {
+                end = i;
+                break;
+            }
+        }
+        // Always make progress, even if one character is over budget
+        if end == 0 {
+            end = rest.chars().next().map_or(1, char::len_utf8);
+        }
+
+        let head = &rest[..end];
+        let cut = head
+            .rfind('\n')
+            .or_else(|| head.rfind(' '))
+            .filter(|&i| i > 0 && i >= end / 2)
+            .unwrap_or(end);
+
+        let page = rest[..cut].trim_end();
+        if !page.is_empty() {
+            pages.push(page.to_string());
+        }
+        rest = rest[cut..].trim_start();
+    }
+
+    pages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_encoding() {
+        assert_eq!(Encoding::detect("Balance: 10 TXTC"), Encoding::Gsm7);
+        assert_eq!(Encoding::detect("Envío £5 [ok]"), Encoding::Ucs2);
+        assert_eq!(Encoding::detect("Ñandú"), Encoding::Ucs2);
+        assert_eq!(Encoding::detect("❌ Name taken"), Encoding::Ucs2);
+    }
+
+    #[test]
+    fn test_segment_count() {
+        assert_eq!(segment_count(&"a".repeat(160)), 1);
+        assert_eq!(segment_count(&"a".repeat(161)), 2);
+        assert_eq!(segment_count(&"a".repeat(306)), 2);
+        assert_eq!(segment_count(&"a".repeat(307)), 3);
+        // Extension characters take two septets
+        assert_eq!(segment_count(&"€".repeat(80)), 1);
+        assert_eq!(segment_count(&"€".repeat(81)), 2);
+        // One emoji drops the limit to 70
+        assert_eq!(segment_count(&format!("❌{}", "a".repeat(69))), 1);
+        assert_eq!(segment_count(&format!("❌{}", "a".repeat(70))), 2);
+    }
+
+    #[test]
+    fn test_transliterate() {
+        assert_eq!(transliterate("❌ Name taken"), "Name taken");
+        assert_eq!(transliterate("Error:\n⚠️ retry"), "Error:\nretry");
+        assert_eq!(transliterate("“Envío” — 5 días…"), "\"Envio\" - 5 dias...");
+        assert_eq!(transliterate("Ñandú ₹100 é"), "Ñandu Rs100 é");
+        assert_eq!(Encoding::detect(&transliterate("✅ Sent ‘10’ TXTC")), Encoding::Gsm7);
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("short", Encoding::Gsm7, 10), "short");
+        assert_eq!(truncate("a long reply text", Encoding::Gsm7, 10), "a long...");
+    }
+
+    #[test]
+    fn test_split_prefers_boundaries() {
+        let text = "line one\nline two\nline three";
+        assert_eq!(split(text, Encoding::Gsm7, 18), ["line one\nline two", "line three"]);
+        assert_eq!(split("alpha beta gamma", Encoding::Gsm7, 11), ["alpha beta", "gamma"]);
+        assert_eq!(split("abcdefghij", Encoding::Gsm7, 4), ["abcd", "efgh", "ij"]);
+
+        let long = "word ".repeat(100);
+        for page in split(&long, Encoding::Gsm7, 50) {
+            assert!(units(&page, Encoding::Gsm7) <= 50);
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::i18n::{tr_with, Lang, Msg};
+use super::segment::{self, Encoding};
+
+/// How long the rest of a paged reply waits for MORE
+const CONTINUATION_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// Pages of a long reply not yet sent
+struct Continuation {
+    pages: VecDeque<String>,
+    expires_at: Instant,
+}
+
+/// Fits replies into a segment budget before they are sent.
+///
+/// Non-GSM characters are optionally transliterated so replies go out at
+/// 160 characters per segment. Replies longer than one page are split
+/// with "(1/3)" markers; the first page is returned and the rest are held
+/// per phone number for the MORE command.
+#[derive(Clone)]
+pub struct ReplyShaper {
+    transliterate: bool,
+    segments_per_page: usize,
+    max_pages: usize,
+    continuations: Arc<Mutex<HashMap<String, Continuation>>>,
+}
+
+impl ReplyShaper {
+    pub fn new(transliterate: bool, segments_per_page: usize, max_pages: usize) -> Self {
+        Self {
+            transliterate,
+            segments_per_page: segments_per_page.max(1),
+            max_pages: max_pages.max(1),
+            continuations: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Read SMS_TRANSLITERATE, SMS_SEGMENTS_PER_PAGE and SMS_MAX_PAGES
+    pub fn from_env() -> Self {
+        let transliterate = std::env::var("SMS_TRANSLITERATE")
+            .map(|v| !matches!(v.to_lowercase().as_str(), "0" | "false" | "no"))
+            .unwrap_or(true);
+        let number = |name: &str, default: usize| {
+            std::env::var(name).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+        };
+        Self::new(transliterate, number("SMS_SEGMENTS_PER_PAGE", 2), number("SMS_MAX_PAGES", 5))
+    }
+
+    /// Shape a reply to `to`, returning the text to send now
+    pub fn shape(&self, to: &str, lang: Lang, reply: &str) -> String {
+        let text = if self.transliterate {
+            segment::transliterate(reply)
+        } else {
+            reply.to_string()
+        };
+        let encoding = Encoding::detect(&text);
+        let page_units = segment::capacity(encoding, self.segments_per_page);
+
+        if segment::units(&text, encoding) <= page_units {
+            self.store(to, VecDeque::new());
+            return text;
+        }
+        if self.max_pages == 1 {
+            self.store(to, VecDeque::new());
+            return segment::truncate(&text, encoding, page_units);
+        }
+
+        // Leave room on every page for a newline and the widest marker
+        let widest = tr_with(lang, Msg::MorePages, &[&99, &99]);
+        let marker_units = segment::units(&widest, encoding) + 1;
+        let body_units = page_units.saturating_sub(marker_units).max(1);
+
+        let mut pages = segment::split(&text, encoding, body_units);
+        if pages.len() > self.max_pages {
+            let last = self.max_pages - 1;
+            pages[last] = segment::truncate(&format!("{}\n{}", pages[last], pages[last + 1]), encoding, body_units);
+            pages.truncate(self.max_pages);
+        }
+
+        let total = pages.len();
+        let mut pages: VecDeque<String> = pages
+            .into_iter()
+            .enumerate()
+            .map(|(i, page)| format!("{}\n{}", page, self.marker(lang, i + 1, total)))
+            .collect();
+
+        tracing::debug!(to = %to, pages = total, encoding = ?encoding, "Paging long reply");
+        let first = pages.pop_front().unwrap_or_default();
+        self.store(to, pages);
+        first
+    }
+
+    /// Next held page for `to`, if a paged reply is still open
+    pub fn next_page(&self, to: &str) -> Option<String> {
+        let mut continuations = self.continuations.lock().unwrap_or_else(|e| e.into_inner());
+        let continuation = continuations.get_mut(to)?;
+        if continuation.expires_at <= Instant::now() {
+            continuations.remove(to);
+            return None;
+        }
+
+        let page = continuation.pages.pop_front();
+        if continuation.pages.is_empty() {
+            continuations.remove(to);
+        }
+        page
+    }
+
+    /// Replace the held pages for `to`; the newest reply is the one MORE continues
+    fn store(&self, to: &str, pages: VecDeque<String>) {
+        let mut continuations = self.continuations.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        continuations.retain(|_, c| c.expires_at > now);
+
+        if pages.is_empty() {
+            continuations.remove(to);
+        } else {
+            continuations.insert(to.to_string(), Continuation { pages, expires_at: now + CONTINUATION_TTL });
+        }
+    }
+
+    /// Page marker; every page but the last points at MORE
+    fn marker(&self, lang: Lang, page: usize, total: usize) -> String {
+        if page < total {
+            tr_with(lang, Msg::MorePages, &[&page, &total])
+        } else {
+            format!("({}/{})", page, total)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sms::segment_count;
+
+    const PHONE: &str = "+15550001111";
+
+    #[test]
+    fn test_short_reply_unchanged() {
+        let shaper = ReplyShaper::new(true, 1, 5);
+        assert_eq!(shaper.shape(PHONE, Lang::En, "❌ Name taken"), "Name taken");
+        assert_eq!(shaper.next_page(PHONE), None);
+
+        let shaper = ReplyShaper::new(false, 1, 5);
+        assert_eq!(shaper.shape(PHONE, Lang::En, "❌ Name taken"), "❌ Name taken");
+    }
+
+    #[test]
+    fn test_long_reply_paged() {
+        let shaper = ReplyShaper::new(true, 1, 5);
+        let lines: Vec<String> = (1..=20).map(|i| format!("contact{}: +2547000000{:02}", i, i)).collect();
+        let reply = lines.join("\n");
+
+        let first = shaper.shape(PHONE, Lang::En, &reply);
+        assert!(first.starts_with("contact1: "));
+        assert!(first.ends_with("Reply MORE"));
+        assert_eq!(segment_count(&first), 1);
+
+        let mut pages = vec![first];
+        while let Some(page) = shaper.next_page(PHONE) {
+            assert_eq!(segment_count(&page), 1);
+            pages.push(page);
+        }
+        let total = pages.len();
+        assert!(total > 1);
+        assert!(pages[0].contains(&format!("(1/{})", total)));
+        assert!(pages[total - 1].ends_with(&format!("({}/{})", total, total)));
+        assert!(pages[total - 1].contains("contact20"));
+    }
+
+    #[test]
+    fn test_new_reply_replaces_pages() {
+        let shaper = ReplyShaper::new(true, 1, 5);
+        shaper.shape(PHONE, Lang::En, &"word ".repeat(100));
+        shaper.shape(PHONE, Lang::En, "Balance: 10 TXTC");
+        assert_eq!(shaper.next_page(PHONE), None);
+    }
+
+    #[test]
+    fn test_truncates_past_max_pages() {
+        let shaper = ReplyShaper::new(true, 1, 2);
+        let first = shaper.shape(PHONE, Lang::Sw, &"word ".repeat(200));
+        assert!(first.ends_with("(1/2) Jibu ZAIDI"));
+
+        let last = shaper.next_page(PHONE).unwrap();
+        assert!(last.ends_with("...\n(2/2)"));
+        assert_eq!(shaper.next_page(PHONE), None);
+
+        let shaper = ReplyShaper::new(true, 1, 1);
+        let only = shaper.shape(PHONE, Lang::En, &"word ".repeat(200));
+        assert!(only.ends_with("..."));
+        assert_eq!(segment_count(&only), 1);
+    }
+}
@@ -0,0 +1,152 @@
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{header, HeaderMap, StatusCode, Uri},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use subtle::ConstantTimeEq;
+
+use super::{SmsGateways, WebhookRequest};
+use crate::db::AuditRepository;
+
+/// Largest webhook body read for verification; SMS payloads are far smaller
+const MAX_WEBHOOK_BODY: usize = 64 * 1024;
+
+/// State for `verify_webhook`
+#[derive(Clone)]
+pub struct WebhookAuth {
+    pub gateways: SmsGateways,
+    /// Where rejected calls are recorded, when the database is up
+    pub audit: Option<AuditRepository>,
+}
+
+/// Whether the shared secret in header `name` is `expected`, compared in
+/// constant time so it can't be guessed byte by byte
+pub fn header_token_matches(headers: &HeaderMap, name: &str, expected: &str) -> bool {
+    let given = headers.get(name).map_or(&[][..], |v| v.as_bytes());
+    bool::from(given.ct_eq(expected.as_bytes()))
+}
+
+/// Rebuild the URL a gateway called, which is what Twilio signs.
+///
+/// PUBLIC_URL (e.g. `https://sms.example.com`) wins when set. Otherwise
+/// the scheme and host come from `X-Forwarded-Proto`/`X-Forwarded-Host`
+/// set by a proxy or tunnel, then from the `Host` header over http.
+pub fn public_url(headers: &HeaderMap, uri: &Uri) -> String {
+    let path = uri.path_and_query().map_or("/", |p| p.as_str());
+    if let Ok(base) = std::env::var("PUBLIC_URL") {
+        return format!("{}{}", base.trim_end_matches('/'), path);
+    }
+
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split(',').next())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+    };
+    let scheme = header("x-forwarded-proto").unwrap_or("http");
+    let host = header("x-forwarded-host")
+        .or_else(|| header(header::HOST.as_str()))
+        .unwrap_or("localhost");
+    format!("{}://{}{}", scheme, host, path)
+}
+
+/// Gateway whose webhook a path is
+fn gateway_key(path: &str) -> Option<&str> {
+    match path {
+        "/sms/incoming" | "/sms/status" => Some("twilio"),
+        "/webhook/sms" => Some("smscountry"),
+        "/ussd" => Some("africastalking"),
+        _ => {
+            let (key, rest) = path.strip_prefix("/sms/")?.split_once('/')?;
+            matches!(rest, "incoming" | "status").then_some(key)
+        }
+    }
+}
+
+/// Reject webhook calls the gateway didn't sign with 403, and record them.
+///
+/// Paths that aren't a configured gateway's webhook pass through for the
+/// handler to answer.
+pub async fn verify_webhook(State(auth): State<WebhookAuth>, request: Request, next: Next) -> Response {
+    let Some(gateway) = gateway_key(request.uri().path()).and_then(|key| auth.gateways.get(key)) else {
+        return next.run(request).await;
+    };
+
+    let (parts, body) = request.into_parts();
+    let Ok(body) = axum::body::to_bytes(body, MAX_WEBHOOK_BODY).await else {
+        return StatusCode::PAYLOAD_TOO_LARGE.into_response();
+    };
+
+    let webhook = WebhookRequest {
+        url: public_url(&parts.headers, &parts.uri),
+        headers: parts.headers.clone(),
+        body: body.clone(),
+    };
+    if !gateway.verify(&webhook) {
+        let source = parts
+            .headers
+            .get("x-forwarded-for")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("-");
+        tracing::warn!(
+            target: "audit",
+            gateway = gateway.key(),
+            url = %webhook.url,
+            source = %source,
+            "Rejected webhook with a bad signature"
+        );
+        if let Some(ref audit) = auth.audit {
+            if let Err(e) = audit.record("webhook_rejected", gateway.key(), &webhook.url, source).await {
+                tracing::error!("Failed to record rejected webhook: {}", e);
+            }
+        }
+        return StatusCode::FORBIDDEN.into_response();
+    }
+
+    next.run(Request::from_parts(parts, Body::from(body))).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_public_url() {
+        let uri: Uri = "/sms/incoming?x=1".parse().unwrap();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::HOST, "localhost:8080".parse().unwrap());
+        assert_eq!(public_url(&headers, &uri), "http://localhost:8080/sms/incoming?x=1");
+
+        // Behind a tunnel the public scheme and host are forwarded
+        headers.insert("x-forwarded-proto", "https".parse().unwrap());
+        headers.insert("x-forwarded-host", "sms.example.com, internal:8080".parse().unwrap());
+        assert_eq!(public_url(&headers, &uri), "https://sms.example.com/sms/incoming?x=1");
+    }
+
+    #[test]
+    fn test_header_token_matches() {
+        let mut headers = HeaderMap::new();
+        assert!(!header_token_matches(&headers, "x-callback-token", "secret"));
+        headers.insert("x-callback-token", "secret".parse().unwrap());
+        assert!(header_token_matches(&headers, "x-callback-token", "secret"));
+        assert!(!header_token_matches(&headers, "x-callback-token", "secret2"));
+        assert!(!header_token_matches(&headers, "x-callback-token", "secreT"));
+    }
+
+    #[test]
+    fn test_gateway_key() {
+        assert_eq!(gateway_key("/sms/incoming"), Some("twilio"));
+        assert_eq!(gateway_key("/sms/status"), Some("twilio"));
+        assert_eq!(gateway_key("/webhook/sms"), Some("smscountry"));
+        assert_eq!(gateway_key("/ussd"), Some("africastalking"));
+        assert_eq!(gateway_key("/sms/vonage/incoming"), Some("vonage"));
+        assert_eq!(gateway_key("/sms/africastalking/status"), Some("africastalking"));
+        assert_eq!(gateway_key("/sms/vonage/other"), None);
+        assert_eq!(gateway_key("/health"), None);
+    }
+}
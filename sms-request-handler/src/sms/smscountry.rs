@@ -0,0 +1,165 @@
+use futures::future::BoxFuture;
+use futures::FutureExt;
+
+use super::gateway::{field, sender_phone, verify_body_signature};
+use super::{DeliveryReport, DeliveryStatus, GatewayError, InboundSms, SendResult, SmsGateway, WebhookRequest};
+use crate::config::ConfigError;
+
+/// SMSCountry REST API.
+///
+/// Messages are `POST {api}/Accounts/{auth_key}/SMSes/` with basic auth.
+/// SMSCountry doesn't sign its webhooks, so whatever relays them must
+/// send the body's HMAC-SHA256 under SMSCOUNTRY_WEBHOOK_SECRET as hex in
+/// `X-Signature`; unsigned calls are rejected.
+#[derive(Debug, Clone)]
+pub struct SmsCountryGateway {
+    api_url: String,
+    auth_key: String,
+    auth_token: String,
+    sender_id: String,
+    webhook_secret: String,
+}
+
+impl SmsCountryGateway {
+    pub fn new(api_url: &str, auth_key: &str, auth_token: &str, sender_id: &str, webhook_secret: &str) -> Self {
+        Self {
+            api_url: api_url.trim_end_matches('/').to_string(),
+            auth_key: auth_key.to_string(),
+            auth_token: auth_token.to_string(),
+            sender_id: sender_id.to_string(),
+            webhook_secret: webhook_secret.to_string(),
+        }
+    }
+
+    /// Read SMSCOUNTRY_AUTH_KEY, SMSCOUNTRY_AUTH_TOKEN, SMSCOUNTRY_SENDER_ID,
+    /// SMSCOUNTRY_API_URL and SMSCOUNTRY_WEBHOOK_SECRET
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let var = |name: &'static str| std::env::var(name).map_err(|_| ConfigError::Missing(name));
+        let api_url = std::env::var("SMSCOUNTRY_API_URL")
+            .unwrap_or_else(|_| "https://restapi.smscountry.com/v0.1".to_string());
+        Ok(Self::new(
+            &api_url,
+            &var("SMSCOUNTRY_AUTH_KEY")?,
+            &var("SMSCOUNTRY_AUTH_TOKEN")?,
+            &var("SMSCOUNTRY_SENDER_ID")?,
+            &var("SMSCOUNTRY_WEBHOOK_SECRET")?,
+        ))
+    }
+}
+
+/// Read the reply to a send: `{"Success": "True", "MessageUUID", "Message"}`
+fn send_result(json: &serde_json::Value) -> Result<SendResult, GatewayError> {
+    let success = match &json["Success"] {
+        serde_json::Value::Bool(b) => *b,
+        serde_json::Value::String(s) => s.eq_ignore_ascii_case("true"),
+        _ => false,
+    };
+    match json["MessageUUID"].as_str() {
+        Some(id) if success => Ok(SendResult { message_id: id.to_string(), status: DeliveryStatus::Queued.to_string() }),
+        _ => Err(GatewayError::Api(json["Message"].as_str().unwrap_or("Unknown error").to_string())),
+    }
+}
+
+impl SmsGateway for SmsCountryGateway {
+    fn key(&self) -> &'static str {
+        "smscountry"
+    }
+
+    fn number(&self) -> &str {
+        &self.sender_id
+    }
+
+    fn send<'a>(&'a self, to: &'a str, body: &'a str) -> BoxFuture<'a, Result<SendResult, GatewayError>> {
+        async move {
+            let response = reqwest::Client::new()
+                .post(format!("{}/Accounts/{}/SMSes/", self.api_url, self.auth_key))
+                .basic_auth(&self.auth_key, Some(&self.auth_token))
+                .json(&serde_json::json!({
+                    "Text": body,
+                    "Number": to.trim_start_matches('+'),
+                    "SenderId": self.sender_id,
+                    "Tool": "API"
+                }))
+                .timeout(std::time::Duration::from_secs(15))
+                .send()
+                .await?;
+            let json: serde_json::Value = response.json().await?;
+            send_result(&json)
+        }
+        .boxed()
+    }
+
+    fn verify(&self, request: &WebhookRequest) -> bool {
+        verify_body_signature(Some(&self.webhook_secret), request)
+    }
+
+    /// `{From, To, Body, MessageSid}` as JSON or a form
+    fn parse_inbound(&self, request: &WebhookRequest) -> Result<InboundSms, GatewayError> {
+        let fields = request.fields();
+        let from = field(&fields, &["From", "from", "Number"]).ok_or_else(|| GatewayError::Payload("missing From".to_string()))?;
+        Ok(InboundSms {
+            from: sender_phone(from),
+            to: field(&fields, &["To", "to"]).unwrap_or_default().to_string(),
+            body: field(&fields, &["Body", "body", "Text", "text"]).unwrap_or_default().to_string(),
+            message_id: field(&fields, &["MessageSid", "MessageUUID", "id"]).unwrap_or_default().to_string(),
+        })
+    }
+
+    /// `{MessageUUID, Status, StatusCode}`
+    fn parse_status(&self, request: &WebhookRequest) -> Result<DeliveryReport, GatewayError> {
+        let fields = request.fields();
+        let (Some(id), Some(status)) = (field(&fields, &["MessageUUID"]), field(&fields, &["Status"])) else {
+            return Err(GatewayError::Payload("missing MessageUUID or Status".to_string()));
+        };
+        let status = match status.to_lowercase().as_str() {
+            "delivered" => DeliveryStatus::Delivered,
+            "sent" => DeliveryStatus::Sent,
+            "undelivered" | "expired" => DeliveryStatus::Undelivered,
+            "failed" | "rejected" => DeliveryStatus::Failed,
+            _ => DeliveryStatus::Queued,
+        };
+        let error = (status == DeliveryStatus::Undelivered || status == DeliveryStatus::Failed)
+            .then(|| field(&fields, &["StatusCode"]).map(str::to_string))
+            .flatten();
+        Ok(DeliveryReport { message_id: id.to_string(), status, error })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sms::gateway::body_signature;
+
+    #[test]
+    fn test_send_result() {
+        let ok = serde_json::json!({"Success": "True", "MessageUUID": "abc-123", "Message": "SMS queued"});
+        assert_eq!(send_result(&ok).unwrap().message_id, "abc-123");
+
+        let refused = serde_json::json!({"Success": false, "Message": "Invalid SenderId"});
+        assert!(matches!(send_result(&refused), Err(GatewayError::Api(m)) if m == "Invalid SenderId"));
+    }
+
+    #[test]
+    fn test_parse_webhooks() {
+        let gateway = SmsCountryGateway::new("https://api.example.com", "key", "token", "TXTCHN", "s3cret");
+        let request = |body: &str| WebhookRequest {
+            url: "https://sms.example.com/webhook/sms".to_string(),
+            headers: axum::http::HeaderMap::new(),
+            body: axum::body::Bytes::from(body.to_string()),
+        };
+
+        let sms = gateway.parse_inbound(&request(r#"{"From": "919876543210", "To": "TXTCHN", "Body": "BALANCE"}"#)).unwrap();
+        assert_eq!(sms.from, "+919876543210");
+        assert_eq!(sms.body, "BALANCE");
+
+        let report = gateway.parse_status(&request(r#"{"MessageUUID": "abc-123", "Status": "Failed", "StatusCode": 4}"#)).unwrap();
+        assert_eq!(report.status, DeliveryStatus::Failed);
+        assert_eq!(report.error.as_deref(), Some("4"));
+
+        // Unsigned calls are refused
+        let mut signed = request("{}");
+        assert!(!gateway.verify(&signed));
+        signed.headers.insert("x-signature", body_signature("s3cret", b"{}").parse().unwrap());
+        assert!(gateway.verify(&signed));
+    }
+}
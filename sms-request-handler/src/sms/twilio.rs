@@ -1,9 +1,13 @@
 use base64::Engine;
+use futures::future::BoxFuture;
+use futures::FutureExt;
 use hmac::{Hmac, Mac};
 use reqwest::Client;
 use sha1::Sha1;
 use std::collections::HashMap;
 
+use super::gateway::{field, sender_phone};
+use super::{DeliveryReport, DeliveryStatus, GatewayError, InboundSms, SendResult, SmsGateway, WebhookRequest};
 use crate::config::TwilioConfig;
 
 type HmacSha1 = Hmac<Sha1>;
@@ -15,23 +19,8 @@ pub struct TwilioClient {
     account_sid: String,
     auth_token: String,
     phone_number: String,
-}
-
-/// Result of sending an SMS
-#[derive(Debug)]
-pub struct SendResult {
-    pub message_sid: String,
-    pub status: String,
-}
-
-#[derive(Debug, thiserror::Error)]
-pub enum TwilioError {
-    #[error("HTTP request failed: {0}")]
-    Request(#[from] reqwest::Error),
-    #[error("API error: {0}")]
-    Api(String),
-    #[error("Invalid signature")]
-    InvalidSignature,
+    /// Where Twilio posts delivery receipts
+    status_callback: Option<String>,
 }
 
 impl TwilioClient {
@@ -42,11 +31,18 @@ impl TwilioClient {
             account_sid: config.account_sid.clone(),
             auth_token: config.auth_token.clone(),
             phone_number: config.phone_number.clone(),
+            status_callback: None,
         }
     }
 
+    /// Ask for delivery receipts at `url` for every message sent
+    pub fn with_status_callback(mut self, url: &str) -> Self {
+        self.status_callback = Some(url.to_string());
+        self
+    }
+
     /// Send an SMS message
-    pub async fn send_sms(&self, to: &str, body: &str) -> Result<SendResult, TwilioError> {
+    pub async fn send_sms(&self, to: &str, body: &str) -> Result<SendResult, GatewayError> {
         let url = format!(
             "https://api.twilio.com/2010-04-01/Accounts/{}/Messages.json",
             self.account_sid
@@ -56,6 +52,9 @@ impl TwilioClient {
         params.insert("To", to);
         params.insert("From", &self.phone_number);
         params.insert("Body", body);
+        if let Some(ref url) = self.status_callback {
+            params.insert("StatusCallback", url);
+        }
 
         let response = self
             .client
@@ -67,13 +66,13 @@ impl TwilioClient {
 
         if !response.status().is_success() {
             let error_text = response.text().await.unwrap_or_default();
-            return Err(TwilioError::Api(error_text));
+            return Err(GatewayError::Api(error_text));
         }
 
         let json: serde_json::Value = response.json().await?;
 
         Ok(SendResult {
-            message_sid: json["sid"].as_str().unwrap_or("").to_string(),
+            message_id: json["sid"].as_str().unwrap_or("").to_string(),
             status: json["status"].as_str().unwrap_or("").to_string(),
         })
     }
@@ -98,23 +97,82 @@ impl TwilioClient {
             data.push_str(value);
         }
 
-        // Calculate HMAC-SHA1
+        let Ok(signature) = base64::engine::general_purpose::STANDARD.decode(signature) else {
+            return false;
+        };
+
+        // Calculate HMAC-SHA1 and compare in constant time
         let mut mac = HmacSha1::new_from_slice(self.auth_token.as_bytes())
             .expect("HMAC can take key of any size");
         mac.update(data.as_bytes());
-        let result = mac.finalize();
-        
-        // Base64 encode
-        let calculated = base64::engine::general_purpose::STANDARD.encode(result.into_bytes());
-        
-        // Compare
-        calculated == signature
+        mac.verify_slice(&signature).is_ok()
+    }
+
+}
+
+/// Twilio's message status words, from `MessageStatus`
+fn delivery_status(status: &str) -> DeliveryStatus {
+    match status {
+        "delivered" | "read" => DeliveryStatus::Delivered,
+        "sent" => DeliveryStatus::Sent,
+        "undelivered" => DeliveryStatus::Undelivered,
+        "failed" | "canceled" => DeliveryStatus::Failed,
+        // accepted, scheduled, queued, sending
+        _ => DeliveryStatus::Queued,
+    }
+}
+
+impl SmsGateway for TwilioClient {
+    fn key(&self) -> &'static str {
+        "twilio"
     }
 
-    /// Get the Twilio phone number
-    pub fn phone_number(&self) -> &str {
+    fn number(&self) -> &str {
         &self.phone_number
     }
+
+    fn send<'a>(&'a self, to: &'a str, body: &'a str) -> BoxFuture<'a, Result<SendResult, GatewayError>> {
+        self.send_sms(to, body).boxed()
+    }
+
+    fn verify(&self, request: &WebhookRequest) -> bool {
+        let Some(signature) = request.header("x-twilio-signature") else {
+            return false;
+        };
+        // Twilio signs the URL it called plus the POSTed form fields
+        let url = request.url.as_str();
+        let params = serde_urlencoded::from_bytes(&request.body).unwrap_or_default();
+        self.validate_signature(signature, url, &params)
+    }
+
+    fn parse_inbound(&self, request: &WebhookRequest) -> Result<InboundSms, GatewayError> {
+        let params = request.params();
+        let from = field(&params, &["From"]).ok_or_else(|| GatewayError::Payload("missing From".to_string()))?;
+        Ok(InboundSms {
+            from: sender_phone(from),
+            to: field(&params, &["To"]).unwrap_or_default().to_string(),
+            body: field(&params, &["Body"]).unwrap_or_default().to_string(),
+            message_id: field(&params, &["MessageSid", "SmsSid"]).unwrap_or_default().to_string(),
+        })
+    }
+
+    fn parse_status(&self, request: &WebhookRequest) -> Result<DeliveryReport, GatewayError> {
+        let params = request.params();
+        let (Some(id), Some(status)) = (field(&params, &["MessageSid", "SmsSid"]), field(&params, &["MessageStatus", "SmsStatus"])) else {
+            return Err(GatewayError::Payload("missing MessageSid or MessageStatus".to_string()));
+        };
+        Ok(DeliveryReport {
+            message_id: id.to_string(),
+            status: delivery_status(status),
+            error: field(&params, &["ErrorCode"]).map(str::to_string),
+        })
+    }
+
+    /// Empty TwiML, so Twilio sends nothing itself
+    fn ack(&self) -> (&'static str, String) {
+        ("text/xml", r#"<?xml version="1.0" encoding="UTF-8"?>
+<Response></Response>"#.to_string())
+    }
 }
 
 #[cfg(test)]
@@ -139,4 +197,67 @@ mod tests {
         // The signature validation logic is correct; actual testing would need real Twilio data
         assert!(!client.validate_signature("invalid", "https://example.com", &params));
     }
+
+    #[test]
+    fn test_signature_vectors() {
+        // Example from Twilio's webhook security docs
+        let client = TwilioClient::new(&TwilioConfig {
+            account_sid: "test_sid".to_string(),
+            auth_token: "12345".to_string(),
+            phone_number: "+18005551212".to_string(),
+        });
+        let params: HashMap<String, String> = [
+            ("CallSid", "CA1234567890ABCDE"),
+            ("Caller", "+12349013030"),
+            ("Digits", "1234"),
+            ("From", "+12349013030"),
+            ("To", "+18005551212"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let url = "https://mycompany.com/myapp.php?foo=1&bar=2";
+
+        assert!(client.validate_signature("0/KCTR6DLpKmkAf8muzZqo1nDgQ=", url, &params));
+        assert!(!client.validate_signature("0/KCTR6DLpKmkAf8muzZqo1nDgQ=", "https://mycompany.com/myapp.php?foo=1", &params));
+        assert!(!client.validate_signature("1/KCTR6DLpKmkAf8muzZqo1nDgQ=", url, &params));
+
+        let mut forged = params.clone();
+        forged.insert("From".to_string(), "+254712345678".to_string());
+        assert!(!client.validate_signature("0/KCTR6DLpKmkAf8muzZqo1nDgQ=", url, &forged));
+
+        // The same request as a webhook, signed header and form body
+        let body = serde_urlencoded::to_string(&params).unwrap();
+        let mut headers = axum::http::HeaderMap::new();
+        headers.insert("x-twilio-signature", "0/KCTR6DLpKmkAf8muzZqo1nDgQ=".parse().unwrap());
+        let request = WebhookRequest { url: url.to_string(), headers, body: axum::body::Bytes::from(body) };
+        assert!(client.verify(&request));
+    }
+
+    #[test]
+    fn test_parse_webhooks() {
+        let client = TwilioClient::new(&TwilioConfig {
+            account_sid: "test_sid".to_string(),
+            auth_token: "12345".to_string(),
+            phone_number: "+15005550006".to_string(),
+        });
+        let request = |body: &str| WebhookRequest {
+            url: "https://sms.example.com/sms/incoming".to_string(),
+            headers: axum::http::HeaderMap::new(),
+            body: axum::body::Bytes::from(body.to_string()),
+        };
+
+        let sms = client
+            .parse_inbound(&request("MessageSid=SM123&From=%2B254712345678&To=%2B15005550006&Body=SEND+10+TXTC+mom"))
+            .unwrap();
+        assert_eq!(sms.from, "+254712345678");
+        assert_eq!(sms.body, "SEND 10 TXTC mom");
+        assert_eq!(sms.message_id, "SM123");
+        assert!(client.parse_inbound(&request("Body=hi")).is_err());
+
+        let report = client.parse_status(&request("MessageSid=SM123&MessageStatus=undelivered&ErrorCode=30003")).unwrap();
+        assert_eq!(report.status, DeliveryStatus::Undelivered);
+        assert_eq!(report.error.as_deref(), Some("30003"));
+        assert!(!client.verify(&request("Body=hi")));
+    }
 }
@@ -0,0 +1,205 @@
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::collections::HashMap;
+
+use super::gateway::{field, sender_phone};
+use super::{DeliveryReport, DeliveryStatus, GatewayError, InboundSms, SendResult, SmsGateway, WebhookRequest};
+use crate::config::ConfigError;
+
+/// Vonage (Nexmo) SMS API.
+///
+/// Messages are posted to `{api}/sms/json`. Inbound messages and delivery
+/// receipts arrive as query parameters, forms or JSON; with
+/// VONAGE_SIGNATURE_SECRET set they must carry a valid `sig` (the
+/// HMAC-SHA256 signing method in the Vonage dashboard).
+#[derive(Debug, Clone)]
+pub struct VonageGateway {
+    api_url: String,
+    api_key: String,
+    api_secret: String,
+    number: String,
+    signature_secret: Option<String>,
+}
+
+impl VonageGateway {
+    pub fn new(api_key: &str, api_secret: &str, number: &str) -> Self {
+        Self {
+            api_url: "https://rest.nexmo.com".to_string(),
+            api_key: api_key.to_string(),
+            api_secret: api_secret.to_string(),
+            number: number.to_string(),
+            signature_secret: None,
+        }
+    }
+
+    pub fn with_signature_secret(mut self, secret: &str) -> Self {
+        self.signature_secret = Some(secret.to_string());
+        self
+    }
+
+    /// Read VONAGE_API_KEY, VONAGE_API_SECRET, VONAGE_NUMBER and
+    /// VONAGE_SIGNATURE_SECRET
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let var = |name: &'static str| std::env::var(name).map_err(|_| ConfigError::Missing(name));
+        let gateway = Self::new(&var("VONAGE_API_KEY")?, &var("VONAGE_API_SECRET")?, &var("VONAGE_NUMBER")?);
+        Ok(match std::env::var("VONAGE_SIGNATURE_SECRET") {
+            Ok(secret) => gateway.with_signature_secret(&secret),
+            Err(_) => {
+                tracing::warn!("VONAGE_SIGNATURE_SECRET not set: Vonage webhooks are not verified");
+                gateway
+            }
+        })
+    }
+}
+
+/// Vonage's `sig`: HMAC-SHA256 over `&key=value` for every other
+/// parameter in key order, with `&` and `=` in values replaced by `_`
+fn signature_mac(secret: &str, params: &HashMap<String, String>) -> Hmac<Sha256> {
+    let mut keys: Vec<&String> = params.keys().filter(|k| k.as_str() != "sig").collect();
+    keys.sort();
+    let signed: String = keys
+        .into_iter()
+        .map(|k| format!("&{}={}", k, params[k].replace(['&', '='], "_")))
+        .collect();
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC can take key of any size");
+    mac.update(signed.as_bytes());
+    mac
+}
+
+/// Read the reply to a send; message status `"0"` is accepted
+fn send_result(json: &serde_json::Value) -> Result<SendResult, GatewayError> {
+    let message = &json["messages"][0];
+    match (message["status"].as_str(), message["message-id"].as_str()) {
+        (Some("0"), Some(id)) => Ok(SendResult { message_id: id.to_string(), status: DeliveryStatus::Queued.to_string() }),
+        _ => Err(GatewayError::Api(message["error-text"].as_str().unwrap_or("Unknown error").to_string())),
+    }
+}
+
+impl SmsGateway for VonageGateway {
+    fn key(&self) -> &'static str {
+        "vonage"
+    }
+
+    fn number(&self) -> &str {
+        &self.number
+    }
+
+    fn send<'a>(&'a self, to: &'a str, body: &'a str) -> BoxFuture<'a, Result<SendResult, GatewayError>> {
+        async move {
+            let response = reqwest::Client::new()
+                .post(format!("{}/sms/json", self.api_url))
+                .form(&[
+                    ("api_key", self.api_key.as_str()),
+                    ("api_secret", self.api_secret.as_str()),
+                    ("from", self.number.trim_start_matches('+')),
+                    ("to", to.trim_start_matches('+')),
+                    ("text", body),
+                    ("type", "unicode"),
+                ])
+                .timeout(std::time::Duration::from_secs(15))
+                .send()
+                .await?;
+            let json: serde_json::Value = response.json().await?;
+            send_result(&json)
+        }
+        .boxed()
+    }
+
+    fn verify(&self, request: &WebhookRequest) -> bool {
+        let Some(ref secret) = self.signature_secret else {
+            return true;
+        };
+        let fields = request.fields();
+        let Some(Ok(sig)) = field(&fields, &["sig"]).map(hex::decode) else {
+            return false;
+        };
+        signature_mac(secret, &fields).verify_slice(&sig).is_ok()
+    }
+
+    /// `msisdn, to, messageId, text`
+    fn parse_inbound(&self, request: &WebhookRequest) -> Result<InboundSms, GatewayError> {
+        let fields = request.fields();
+        let from = field(&fields, &["msisdn"]).ok_or_else(|| GatewayError::Payload("missing msisdn".to_string()))?;
+        Ok(InboundSms {
+            from: sender_phone(from),
+            to: sender_phone(field(&fields, &["to"]).unwrap_or_default()),
+            body: field(&fields, &["text"]).unwrap_or_default().to_string(),
+            message_id: field(&fields, &["messageId"]).unwrap_or_default().to_string(),
+        })
+    }
+
+    /// `messageId, status, err-code`
+    fn parse_status(&self, request: &WebhookRequest) -> Result<DeliveryReport, GatewayError> {
+        let fields = request.fields();
+        let (Some(id), Some(status)) = (field(&fields, &["messageId"]), field(&fields, &["status"])) else {
+            return Err(GatewayError::Payload("missing messageId or status".to_string()));
+        };
+        let status = match status {
+            "delivered" => DeliveryStatus::Delivered,
+            "accepted" => DeliveryStatus::Sent,
+            "expired" | "unknown" => DeliveryStatus::Undelivered,
+            "failed" | "rejected" => DeliveryStatus::Failed,
+            // buffered
+            _ => DeliveryStatus::Queued,
+        };
+        Ok(DeliveryReport {
+            message_id: id.to_string(),
+            status,
+            error: field(&fields, &["err-code"]).filter(|code| *code != "0").map(str::to_string),
+        })
+    }
+
+    /// Vonage retries anything that isn't a 2xx; the body is ignored
+    fn ack(&self) -> (&'static str, String) {
+        ("text/plain", String::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(url: &str) -> WebhookRequest {
+        WebhookRequest {
+            url: url.to_string(),
+            headers: axum::http::HeaderMap::new(),
+            body: axum::body::Bytes::new(),
+        }
+    }
+
+    #[test]
+    fn test_send_result() {
+        let sent = serde_json::json!({"message-count": "1", "messages": [{"to": "447700900000", "message-id": "0A00", "status": "0"}]});
+        assert_eq!(send_result(&sent).unwrap().message_id, "0A00");
+
+        let throttled = serde_json::json!({"message-count": "1", "messages": [{"status": "1", "error-text": "Throttled"}]});
+        assert!(matches!(send_result(&throttled), Err(GatewayError::Api(m)) if m == "Throttled"));
+    }
+
+    #[test]
+    fn test_parse_and_verify() {
+        let gateway = VonageGateway::new("key", "secret", "447700900000").with_signature_secret("sig-secret");
+        let query = "msisdn=447700900001&to=447700900000&messageId=0B00&text=BALANCE&timestamp=1700000000";
+
+        let sms = gateway.parse_inbound(&request(&format!("https://sms.example.com/sms/vonage/incoming?{}", query))).unwrap();
+        assert_eq!(sms.from, "+447700900001");
+        assert_eq!(sms.to, "+447700900000");
+        assert_eq!(sms.body, "BALANCE");
+
+        let params: HashMap<String, String> = serde_urlencoded::from_str(query).unwrap();
+        let sig = hex::encode(signature_mac("sig-secret", &params).finalize().into_bytes());
+        let signed = format!("https://sms.example.com/sms/vonage/incoming?{}&sig={}", query, sig.to_uppercase());
+        assert!(gateway.verify(&request(&signed)));
+        assert!(!gateway.verify(&request(&signed.replace("BALANCE", "SEND"))));
+        assert!(!gateway.verify(&request(&format!("https://sms.example.com/sms/vonage/incoming?{}", query))));
+
+        let report = gateway
+            .parse_status(&request("https://sms.example.com/sms/vonage/status?messageId=0A00&status=delivered&err-code=0"))
+            .unwrap();
+        assert_eq!(report.status, DeliveryStatus::Delivered);
+        assert_eq!(report.error, None);
+    }
+}
@@ -1,153 +1,208 @@
 use axum::{
-    extract::State,
-    http::StatusCode,
+    body::Bytes,
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode, Uri},
     response::{IntoResponse, Response},
-    Form,
 };
-use serde::Deserialize;
 use std::sync::Arc;
 
 use crate::commands::CommandProcessor;
-use crate::sms::TwilioClient;
-
-/// Incoming SMS webhook payload from Twilio
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "PascalCase")]
-pub struct IncomingSms {
-    /// The phone number that sent the message
-    pub from: String,
-    /// The phone number the message was sent to (your Twilio number)
-    #[serde(default)]
-    pub to: String,
-    /// The body of the SMS message
-    pub body: String,
-    /// Twilio's unique ID for this message
-    #[serde(default)]
-    pub message_sid: String,
-    /// Number of media items attached (MMS)
-    #[serde(default)]
-    pub num_media: String,
-}
+use crate::config::env_duration;
+use crate::db::{InboundClaim, InboundMessageRepository};
+use crate::sms::signature::public_url;
+use crate::sms::{segment_count, InboundSms, Outbox, RateLimiter, SmsGateway, Throttle, WebhookRequest};
 
 /// Application state shared across handlers
 #[derive(Clone)]
 pub struct AppState {
-    pub twilio: Arc<TwilioClient>,
+    /// Sends replies, and holds the gateways webhooks are read with
+    pub outbox: Outbox,
     pub command_processor: Arc<CommandProcessor>,
+    /// Messages already taken in, so retried webhooks don't run twice
+    pub inbound: Option<InboundMessageRepository>,
+    /// Limits on how fast senders, and everyone together, can send
+    pub limiter: RateLimiter,
+}
+
+/// How long a gateway may retry a message and still be recognised
+pub fn inbound_window() -> chrono::Duration {
+    env_duration("INBOUND_DEDUP_HOURS", chrono::Duration::hours(24))
 }
 
-/// TwiML response for Twilio
-struct TwimlResponse(String);
-
-impl IntoResponse for TwimlResponse {
-    fn into_response(self) -> Response {
-        (
-            StatusCode::OK,
-            [("Content-Type", "text/xml")],
-            self.0,
-        )
-            .into_response()
+/// Webhook call for a gateway to parse; `verify_webhook` already checked it
+fn webhook_request(uri: &Uri, headers: HeaderMap, body: Bytes) -> WebhookRequest {
+    WebhookRequest {
+        url: public_url(&headers, uri),
+        headers,
+        body,
     }
 }
 
-/// JSON response for SMSCountry
-struct JsonResponse(String);
-
-impl IntoResponse for JsonResponse {
-    fn into_response(self) -> Response {
-        (
-            StatusCode::OK,
-            [("Content-Type", "application/json")],
-            self.0,
-        )
-            .into_response()
+/// Handler for incoming SMS on any configured gateway (`/sms/:gateway/incoming`)
+pub async fn inbound_handler(
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    match state.outbox.gateways().get(&key) {
+        Some(gateway) => handle_inbound(state, gateway, webhook_request(&uri, headers, body)),
+        None => StatusCode::NOT_FOUND.into_response(),
     }
 }
 
 /// Handler for incoming SMS messages from Twilio (Form-encoded)
-///
-/// Responds immediately with empty TwiML to avoid Twilio's 15s timeout,
-/// then processes the command and sends the reply via Twilio REST API.
-pub async fn incoming_sms_handler(
-    State(state): State<AppState>,
-    Form(sms): Form<IncomingSms>,
-) -> impl IntoResponse {
+pub async fn incoming_sms_handler(State(state): State<AppState>, uri: Uri, headers: HeaderMap, body: Bytes) -> Response {
+    match state.outbox.gateways().get("twilio") {
+        Some(gateway) => handle_inbound(state, gateway, webhook_request(&uri, headers, body)),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Handler for incoming SMS messages from SMSCountry (JSON format)
+pub async fn incoming_sms_json_handler(State(state): State<AppState>, uri: Uri, headers: HeaderMap, body: Bytes) -> Response {
+    match state.outbox.gateways().get("smscountry") {
+        Some(gateway) => handle_inbound(state, gateway, webhook_request(&uri, headers, body)),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Acknowledge at once so the gateway doesn't time out, then process the
+/// command and send the reply from the number the user wrote to
+fn handle_inbound(state: AppState, gateway: Arc<dyn SmsGateway>, request: WebhookRequest) -> Response {
+    let sms = match gateway.parse_inbound(&request) {
+        Ok(sms) => sms,
+        Err(e) => {
+            tracing::warn!(gateway = gateway.key(), "Rejected inbound SMS: {}", e);
+            return StatusCode::BAD_REQUEST.into_response();
+        }
+    };
+
     tracing::info!(
+        gateway = gateway.key(),
         from = %sms.from,
         body = %sms.body,
-        "Received SMS (Twilio format)"
+        "Received SMS"
     );
 
-    let from = sms.from.clone();
-    let body = sms.body.clone();
-    let processor = state.command_processor.clone();
-    let twilio = state.twilio.clone();
+    let replier = state.outbox.gateways().for_inbound(&sms.to).map_or(gateway.key(), |g| g.key());
 
-    // Process command in background and send reply via Twilio API
-    tokio::spawn(async move {
-        let response_text = processor.process(&from, &body).await;
+    // Process command in background and send reply via the gateway's API
+    tokio::spawn(process_inbound(state, gateway.key(), replier, sms));
 
-        tracing::info!(
-            to = %from,
-            response = %response_text,
-            "Sending SMS response via Twilio API"
-        );
+    let (content_type, body) = gateway.ack();
+    (StatusCode::OK, [(header::CONTENT_TYPE, content_type)], body).into_response()
+}
 
-        match twilio.send_sms(&from, &response_text).await {
-            Ok(result) => {
+/// Run the command in a message once. A retry of a message already
+/// answered gets the stored reply again; one still being processed is
+/// dropped. A sender over a rate limit gets one notice per window and
+/// their messages are dropped.
+async fn process_inbound(state: AppState, gateway: &'static str, replier: &'static str, sms: InboundSms) {
+    let inbound = state.inbound.filter(|_| !sms.message_id.is_empty());
+
+    if let Some(ref inbound) = inbound {
+        match inbound
+            .claim(gateway, &sms.message_id, &sms.from, &sms.body, inbound_window())
+            .await
+        {
+            Ok(InboundClaim::New) => {}
+            Ok(InboundClaim::Duplicate { reply }) => {
                 tracing::info!(
-                    message_sid = %result.message_sid,
-                    status = %result.status,
-                    "SMS reply sent successfully"
-                );
-            }
-            Err(e) => {
-                tracing::error!(
-                    to = %from,
-                    error = %e,
-                    "Failed to send SMS reply"
+                    gateway,
+                    message_id = %sms.message_id,
+                    answered = reply.is_some(),
+                    "Duplicate inbound SMS, not processing again"
                 );
+                if let Some(reply) = reply {
+                    send_reply(&state.outbox, replier, &sms.from, &reply).await;
+                }
+                return;
             }
+            // Better to answer than to drop the message
+            Err(e) => tracing::error!("Failed to record inbound SMS: {}", e),
         }
-    });
+    }
 
-    // Respond immediately with empty TwiML so Twilio doesn't timeout
-    let twiml = r#"<?xml version="1.0" encoding="UTF-8"?>
-<Response></Response>"#.to_string();
+    if let Throttle::Limited { window, notify } = state.limiter.check(&sms.from, &sms.body).await {
+        if notify {
+            let notice = state.command_processor.throttled_response(&sms.from, &sms.body, window).await;
+            send_reply(&state.outbox, replier, &sms.from, &notice).await;
+        }
+        return;
+    }
 
-    TwimlResponse(twiml)
-}
+    let response_text = state.command_processor.process(&sms.from, &sms.body).await;
 
-/// Handler for incoming SMS messages from SMSCountry (JSON format)
-pub async fn incoming_sms_json_handler(
-    State(state): State<AppState>,
-    axum::extract::Json(sms): axum::extract::Json<IncomingSms>,
-) -> impl IntoResponse {
-    tracing::info!(
-        from = %sms.from,
-        body = %sms.body,
-        "Received SMS (JSON format)"
-    );
+    if let Some(ref inbound) = inbound {
+        if let Err(e) = inbound.complete(gateway, &sms.message_id, &response_text).await {
+            tracing::error!("Failed to store reply to inbound SMS: {}", e);
+        }
+    }
 
-    // Process the command
-    let response_text = state
-        .command_processor
-        .process(&sms.from, &sms.body)
-        .await;
+    send_reply(&state.outbox, replier, &sms.from, &response_text).await;
+}
 
+async fn send_reply(outbox: &Outbox, replier: &'static str, to: &str, response_text: &str) {
     tracing::info!(
-        to = %sms.from,
+        to = %to,
+        gateway = replier,
         response = %response_text,
+        segments = segment_count(response_text),
         "Sending SMS response"
     );
 
-    // Return JSON response
-    let json_response = serde_json::json!({
-        "success": true,
-        "response": response_text
-    });
+    if let Err(e) = outbox.send(Some(replier), to, response_text).await {
+        tracing::error!(
+            to = %to,
+            error = %e,
+            "Failed to send SMS reply"
+        );
+    }
+}
+
+/// Handler for delivery receipts (`/sms/:gateway/status`)
+pub async fn status_handler(
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> StatusCode {
+    match state.outbox.gateways().get(&key) {
+        Some(gateway) => handle_status(&state, gateway.as_ref(), webhook_request(&uri, headers, body)).await,
+        None => StatusCode::NOT_FOUND,
+    }
+}
 
-    JsonResponse(json_response.to_string())
+/// Handler for Twilio delivery receipts (`/sms/status`, the StatusCallback)
+pub async fn twilio_status_handler(State(state): State<AppState>, uri: Uri, headers: HeaderMap, body: Bytes) -> StatusCode {
+    match state.outbox.gateways().get("twilio") {
+        Some(gateway) => handle_status(&state, gateway.as_ref(), webhook_request(&uri, headers, body)).await,
+        None => StatusCode::NOT_FOUND,
+    }
+}
+
+/// Record a delivery receipt against the message it is for
+async fn handle_status(state: &AppState, gateway: &dyn SmsGateway, request: WebhookRequest) -> StatusCode {
+    match gateway.parse_status(&request) {
+        Ok(report) => {
+            tracing::info!(
+                gateway = gateway.key(),
+                message_id = %report.message_id,
+                status = %report.status,
+                error = ?report.error,
+                "SMS delivery report"
+            );
+            state.outbox.record_delivery(gateway.key(), &report).await;
+            StatusCode::OK
+        }
+        Err(e) => {
+            tracing::warn!(gateway = gateway.key(), "Rejected delivery report: {}", e);
+            StatusCode::BAD_REQUEST
+        }
+    }
 }
 
 
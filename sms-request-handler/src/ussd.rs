@@ -0,0 +1,374 @@
+use axum::{
+    extract::State,
+    http::header,
+    response::IntoResponse,
+    routing::post,
+    Form, Router,
+};
+use serde::Deserialize;
+use std::future::Future;
+use std::sync::Arc;
+
+use crate::commands::CommandProcessor;
+use crate::config::env_duration;
+use crate::db::{AddressBookRepository, UssdSessionRepository};
+use crate::i18n::{tr, tr_with, Lang, Msg};
+use crate::sms::{normalize_phone, Outbox, PhoneNumber, RateLimiter, Throttle};
+use crate::wallet::Amount;
+
+/// Contacts listed per screen
+const CONTACTS_PER_SCREEN: i64 = 5;
+
+/// How long a session is kept after its last screen; operators close
+/// them well before this
+const SESSION_TTL_SECS: i64 = 300;
+
+/// Longest screen operators reliably display
+const MAX_SCREEN_CHARS: usize = 182;
+
+/// USSD routes state
+#[derive(Clone)]
+pub struct UssdState {
+    pub command_processor: Arc<CommandProcessor>,
+    pub sessions: UssdSessionRepository,
+    pub address_book: AddressBookRepository,
+    /// Sends results that weren't ready before the operator timed out
+    pub outbox: Outbox,
+    pub limiter: RateLimiter,
+}
+
+/// Africa's Talking USSD callback (form-encoded)
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UssdRequest {
+    pub session_id: String,
+    #[serde(default)]
+    pub service_code: String,
+    pub phone_number: String,
+    /// Everything entered this session, joined by `*`; empty when it starts
+    #[serde(default)]
+    pub text: String,
+}
+
+/// Where a session is in the menu tree
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    Main,
+    Recipient,
+    Amount { recipient: String },
+    /// `reference` is the transaction this send staged
+    Pin { recipient: String, amount: String, reference: String },
+    Code,
+    Contacts { page: i64 },
+}
+
+impl Step {
+    /// Menu name and answers, as stored in `ussd_sessions`
+    fn to_parts(&self) -> (&'static str, Vec<String>) {
+        match self {
+            Step::Main => ("main", vec![]),
+            Step::Recipient => ("recipient", vec![]),
+            Step::Amount { recipient } => ("amount", vec![recipient.clone()]),
+            Step::Pin { recipient, amount, reference } => ("pin", vec![recipient.clone(), amount.clone(), reference.clone()]),
+            Step::Code => ("code", vec![]),
+            Step::Contacts { page } => ("contacts", vec![page.to_string()]),
+        }
+    }
+
+    /// Read a stored step; anything unreadable goes back to the main menu
+    fn from_parts(menu: &str, args: &[String]) -> Step {
+        match (menu, args) {
+            ("recipient", _) => Step::Recipient,
+            ("amount", [recipient]) => Step::Amount { recipient: recipient.clone() },
+            ("pin", [recipient, amount, reference]) => Step::Pin {
+                recipient: recipient.clone(),
+                amount: amount.clone(),
+                reference: reference.clone(),
+            },
+            ("code", _) => Step::Code,
+            ("contacts", [page]) => page.parse().map(|page| Step::Contacts { page }).unwrap_or(Step::Main),
+            _ => Step::Main,
+        }
+    }
+
+    /// What the step asks for; contact lists are built by `UssdState::contacts`
+    fn prompt(&self, lang: Lang) -> String {
+        match self {
+            Step::Main | Step::Contacts { .. } => tr(lang, Msg::UssdMenu),
+            Step::Recipient => tr(lang, Msg::UssdRecipient),
+            Step::Amount { recipient } => tr_with(lang, Msg::UssdAmount, &[recipient]),
+            Step::Pin { recipient, amount, .. } => tr_with(lang, Msg::UssdPin, &[amount, recipient]),
+            Step::Code => tr(lang, Msg::UssdCode),
+        }
+    }
+}
+
+/// Next screen: `CON` keeps the session open at a step, `END` closes it
+#[derive(Debug, PartialEq)]
+enum Screen {
+    Con(String, Step),
+    End(String),
+}
+
+impl Screen {
+    /// The same screen with `notice` above it
+    fn after(self, notice: &str) -> Screen {
+        match self {
+            Screen::Con(text, step) => Screen::Con(format!("{}\n{}", notice, text), step),
+            Screen::End(text) => Screen::End(format!("{}\n{}", notice, text)),
+        }
+    }
+}
+
+/// The newest answer: the part of `text` after the last `*`
+fn last_input(text: &str) -> &str {
+    text.rsplit('*').next().unwrap_or_default().trim()
+}
+
+/// Cut a screen down to what operators display
+fn fit(text: &str) -> String {
+    match text.char_indices().nth(MAX_SCREEN_CHARS) {
+        Some((end, _)) => text[..end].to_string(),
+        None => text.to_string(),
+    }
+}
+
+/// Create USSD routes
+pub fn ussd_routes(state: UssdState) -> Router {
+    Router::new()
+        .route("/ussd", post(ussd_handler))
+        .with_state(state)
+}
+
+/// Answer a USSD callback with the next screen
+async fn ussd_handler(State(state): State<UssdState>, Form(request): Form<UssdRequest>) -> impl IntoResponse {
+    let phone = normalize_phone(&request.phone_number);
+    let lang = state.command_processor.user_language(&phone).await;
+    tracing::debug!(session_id = %request.session_id, service_code = %request.service_code, "USSD request");
+
+    // A session that has expired starts again from the main menu
+    let step = if request.text.is_empty() {
+        None
+    } else {
+        match state.sessions.find_active(&request.session_id, &phone).await {
+            Ok(session) => session.map(|s| Step::from_parts(&s.menu, &s.args())),
+            Err(e) => {
+                tracing::error!(session_id = %request.session_id, "Failed to load USSD session: {}", e);
+                return text_response(format!("END {}", tr(lang, Msg::TryLater)));
+            }
+        }
+    };
+    let screen = match step {
+        Some(step) => state.advance(&phone, lang, step, last_input(&request.text)).await,
+        None => Screen::Con(tr(lang, Msg::UssdMenu), Step::Main),
+    };
+
+    let body = match screen {
+        Screen::Con(text, step) => {
+            let (menu, args) = step.to_parts();
+            let ttl = chrono::Duration::seconds(SESSION_TTL_SECS);
+            match state.sessions.save(&request.session_id, &phone, menu, &args, ttl).await {
+                Ok(()) => format!("CON {}", fit(&text)),
+                Err(e) => {
+                    tracing::error!(session_id = %request.session_id, "Failed to save USSD session: {}", e);
+                    format!("END {}", tr(lang, Msg::TryLater))
+                }
+            }
+        }
+        Screen::End(text) => {
+            if let Err(e) = state.sessions.delete(&request.session_id, &phone).await {
+                tracing::error!(session_id = %request.session_id, "Failed to end USSD session: {}", e);
+            }
+            format!("END {}", fit(&text))
+        }
+    };
+    text_response(body)
+}
+
+fn text_response(body: String) -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "text/plain; charset=utf-8")], body)
+}
+
+impl UssdState {
+    /// Take `input` as the answer to `step`
+    async fn advance(&self, phone: &str, lang: Lang, step: Step, input: &str) -> Screen {
+        let reprompt = |step: Step| {
+            let text = format!("{}\n{}", tr(lang, Msg::UssdInvalid), step.prompt(lang));
+            Screen::Con(text, step)
+        };
+
+        match step {
+            Step::Main => match input {
+                "1" => Screen::End(self.command(phone, lang, "BALANCE").await),
+                "2" => Screen::Con(Step::Recipient.prompt(lang), Step::Recipient),
+                "3" => Screen::Con(Step::Code.prompt(lang), Step::Code),
+                "4" => self.contacts(phone, lang, 1).await,
+                _ => reprompt(Step::Main),
+            },
+            Step::Recipient if input.is_empty() => reprompt(Step::Recipient),
+            Step::Recipient => {
+                let recipient = PhoneNumber::parse(input)
+                    .map(|p| p.as_str().to_string())
+                    .unwrap_or_else(|| input.to_string());
+                let step = Step::Amount { recipient };
+                Screen::Con(step.prompt(lang), step)
+            }
+            Step::Amount { recipient } => {
+                let Ok(amount) = Amount::parse(input) else {
+                    let step = Step::Amount { recipient };
+                    return Screen::Con(format!("{}\n{}", tr(lang, Msg::InvalidAmount), step.prompt(lang)), step);
+                };
+                let before = self.command_processor.pending_reference(phone).await;
+                let reply = self.command(phone, lang, &format!("SEND {} TXTC {}", amount, recipient)).await;
+                // Transfers wait for YES <PIN>, which is asked for here rather
+                // than by SMS; only for one this SEND staged, not an older one
+                match self.command_processor.pending_reference(phone).await {
+                    Some(reference) if before.as_ref() != Some(&reference) => {
+                        let step = Step::Pin { recipient, amount: amount.to_string(), reference };
+                        Screen::Con(step.prompt(lang), step)
+                    }
+                    _ => Screen::End(reply),
+                }
+            }
+            Step::Pin { .. } | Step::Code if input.is_empty() => reprompt(step),
+            Step::Pin { reference, .. } => {
+                let processor = self.command_processor.clone();
+                let (from, pin) = (phone.to_string(), input.to_string());
+                let confirm = async move { processor.confirm_reference(&from, &pin, &reference).await };
+                Screen::End(self.run(phone, lang, &format!("YES {}", input), confirm).await)
+            }
+            Step::Code => Screen::End(self.command(phone, lang, &format!("REDEEM {}", input)).await),
+            Step::Contacts { page } => match input {
+                "0" => Screen::Con(Step::Main.prompt(lang), Step::Main),
+                "9" => self.contacts(phone, lang, page + 1).await,
+                _ => match self.pick_contact(phone, page, input).await {
+                    Some(name) => {
+                        let step = Step::Amount { recipient: name };
+                        Screen::Con(step.prompt(lang), step)
+                    }
+                    None => self.contacts(phone, lang, page).await.after(&tr(lang, Msg::UssdInvalid)),
+                },
+            },
+        }
+    }
+
+    /// Numbered screen `page` of the user's contacts, with 9 for the next
+    /// one when there is more
+    async fn contacts(&self, phone: &str, lang: Lang, page: i64) -> Screen {
+        let offset = (page - 1) * CONTACTS_PER_SCREEN;
+        // One extra to tell whether there's another screen
+        let contacts = match self.address_book.page(phone, CONTACTS_PER_SCREEN + 1, offset).await {
+            Ok(contacts) => contacts,
+            Err(e) => {
+                tracing::error!("Failed to load contacts for USSD: {}", e);
+                return Screen::End(tr(lang, Msg::TryLater));
+            }
+        };
+        if contacts.is_empty() {
+            return Screen::End(tr(lang, Msg::NoContacts));
+        }
+
+        let mut lines: Vec<String> = contacts
+            .iter()
+            .take(CONTACTS_PER_SCREEN as usize)
+            .enumerate()
+            .map(|(i, c)| format!("{}. {}", i + 1, c.name))
+            .collect();
+        if contacts.len() as i64 > CONTACTS_PER_SCREEN {
+            lines.push(tr(lang, Msg::UssdMore));
+        }
+        Screen::Con(tr_with(lang, Msg::UssdContacts, &[&lines.join("\n")]), Step::Contacts { page })
+    }
+
+    /// Name of contact number `input` on screen `page`
+    async fn pick_contact(&self, phone: &str, page: i64, input: &str) -> Option<String> {
+        let n: i64 = input.parse().ok().filter(|n| (1..=CONTACTS_PER_SCREEN).contains(n))?;
+        let offset = (page - 1) * CONTACTS_PER_SCREEN + n - 1;
+        let mut contacts = self.address_book.page(phone, 1, offset).await.ok()?;
+        contacts.pop().map(|c| c.name)
+    }
+
+    /// Run a command as if it had been texted in
+    async fn command(&self, phone: &str, lang: Lang, text: &str) -> String {
+        let processor = self.command_processor.clone();
+        let (from, command) = (phone.to_string(), text.to_string());
+        self.run(phone, lang, text, async move { processor.run_command(&from, &command).await }).await
+    }
+
+    /// Run `job` under the rate limits the SMS `text` would fall under. A
+    /// job still running when the operator's timeout nears finishes in the
+    /// background and its reply is sent by SMS.
+    async fn run(
+        &self,
+        phone: &str,
+        lang: Lang,
+        text: &str,
+        job: impl Future<Output = String> + Send + 'static,
+    ) -> String {
+        if let Throttle::Limited { window, .. } = self.limiter.check(phone, text).await {
+            return self.command_processor.throttled_response(phone, text, window).await;
+        }
+
+        // Operators drop sessions that wait much longer than this
+        let timeout = env_duration("USSD_TIMEOUT_SECS", chrono::Duration::seconds(5)).to_std().unwrap_or_default();
+        let mut task = tokio::spawn(job);
+        match tokio::time::timeout(timeout, &mut task).await {
+            Ok(Ok(reply)) => reply,
+            Ok(Err(e)) => {
+                tracing::error!(phone = %phone, "USSD command failed: {}", e);
+                tr(lang, Msg::TryLater)
+            }
+            Err(_) => {
+                tracing::warn!(phone = %phone, "USSD command timed out, replying by SMS");
+                let outbox = self.outbox.clone();
+                let to = phone.to_string();
+                tokio::spawn(async move {
+                    let Ok(reply) = task.await else {
+                        return;
+                    };
+                    if let Err(e) = outbox.send(None, &to, &reply).await {
+                        tracing::error!(to = %to, "Failed to send USSD result by SMS: {}", e);
+                    }
+                });
+                tr(lang, Msg::UssdWorking)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_last_input() {
+        assert_eq!(last_input(""), "");
+        assert_eq!(last_input("2"), "2");
+        assert_eq!(last_input("2*0712345678*10"), "10");
+        assert_eq!(last_input("4*"), "");
+    }
+
+    #[test]
+    fn test_step_round_trip() {
+        let steps = [
+            Step::Main,
+            Step::Recipient,
+            Step::Amount { recipient: "+254712345678".to_string() },
+            Step::Pin { recipient: "alice".to_string(), amount: "10".to_string(), reference: "ABC234".to_string() },
+            Step::Code,
+            Step::Contacts { page: 2 },
+        ];
+        for step in steps {
+            let (menu, args) = step.to_parts();
+            assert_eq!(Step::from_parts(menu, &args), step);
+        }
+        assert_eq!(Step::from_parts("amount", &[]), Step::Main);
+        assert_eq!(Step::from_parts("unknown", &[]), Step::Main);
+    }
+
+    #[test]
+    fn test_fit() {
+        assert_eq!(fit("Balance: 10 TXTC"), "Balance: 10 TXTC");
+        assert_eq!(fit(&"é".repeat(200)).chars().count(), MAX_SCREEN_CHARS);
+    }
+}
@@ -0,0 +1,400 @@
+use ethers::types::U256;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// Most decimal places any supported token uses (ETH, TXTC)
+pub const MAX_DECIMALS: u8 = 18;
+
+/// Exact decimal amount, stored as `units / 10^scale`.
+///
+/// Amounts are never held as floats: what the user typed is what gets
+/// converted to base units, so `0.1` stays `0.1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Amount {
+    units: u128,
+    scale: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AmountError {
+    #[error("Invalid amount")]
+    Invalid,
+    #[error("Amount must be positive")]
+    Negative,
+    #[error("Amount must be greater than zero")]
+    Zero,
+    #[error("Too many decimal places (max {0})")]
+    TooPrecise(u8),
+    #[error("Amount too large")]
+    TooLarge,
+}
+
+impl Amount {
+    /// Parse a user-supplied amount.
+    ///
+    /// Accepts plain decimals (`10`, `0.5`, `.5`), a leading `$`,
+    /// thousands separators (`1,000`) and `k`/`m` suffixes (`10k`, `1.5m`).
+    /// Rejects negatives, zero, exponents, `NaN` and `inf`.
+    pub fn parse(input: &str) -> Result<Amount, AmountError> {
+        let s = input.trim();
+        let s = s.strip_prefix('$').unwrap_or(s);
+        if s.starts_with('-') {
+            return Err(AmountError::Negative);
+        }
+
+        let (s, shift) = match s.as_bytes().last() {
+            Some(b'k' | b'K') => (&s[..s.len() - 1], 3u8),
+            Some(b'm' | b'M') => (&s[..s.len() - 1], 6u8),
+            _ => (s, 0u8),
+        };
+
+        let (int_part, frac_part) = s.split_once('.').unwrap_or((s, ""));
+        let int_digits = strip_thousands(int_part)?;
+        if int_digits.is_empty() && frac_part.is_empty() {
+            return Err(AmountError::Invalid);
+        }
+        if !int_digits.bytes().chain(frac_part.bytes()).all(|b| b.is_ascii_digit()) {
+            return Err(AmountError::Invalid);
+        }
+        if frac_part.len() > (MAX_DECIMALS + shift) as usize {
+            return Err(AmountError::TooPrecise(MAX_DECIMALS));
+        }
+
+        let mut units: u128 = 0;
+        for b in int_digits.bytes().chain(frac_part.bytes()) {
+            units = units
+                .checked_mul(10)
+                .and_then(|u| u.checked_add((b - b'0') as u128))
+                .ok_or(AmountError::TooLarge)?;
+        }
+
+        // Apply the k/m suffix by moving the decimal point
+        let mut scale = frac_part.len() as u8;
+        if scale >= shift {
+            scale -= shift;
+        } else {
+            units = units
+                .checked_mul(10u128.pow((shift - scale) as u32))
+                .ok_or(AmountError::TooLarge)?;
+            scale = 0;
+        }
+
+        if units == 0 {
+            return Err(AmountError::Zero);
+        }
+        if scale > MAX_DECIMALS {
+            return Err(AmountError::TooPrecise(MAX_DECIMALS));
+        }
+
+        Ok(Amount { units, scale }.normalized())
+    }
+
+    /// Build an amount from integer base units of a token with `decimals`
+    pub fn from_base_units(units: u128, decimals: u8) -> Amount {
+        Amount { units, scale: decimals }.normalized()
+    }
+
+    /// Convert to integer base units, rejecting more precision than the token has
+    pub fn to_base_units(self, decimals: u8) -> Result<U256, AmountError> {
+        if self.scale > decimals {
+            return Err(AmountError::TooPrecise(decimals));
+        }
+        U256::from(self.units)
+            .checked_mul(U256::exp10((decimals - self.scale) as usize))
+            .ok_or(AmountError::TooLarge)
+    }
+
+    /// Base units as an `i64`, for the micro-USDC columns in the database
+    pub fn to_base_units_i64(self, decimals: u8) -> Result<i64, AmountError> {
+        let base = self.to_base_units(decimals)?;
+        u64::try_from(base)
+            .ok()
+            .and_then(|v| i64::try_from(v).ok())
+            .ok_or(AmountError::TooLarge)
+    }
+
+    /// Format with exactly `places` decimals, truncating extra digits
+    pub fn to_fixed(self, places: u8) -> String {
+        let amount = if self.scale > places {
+            Amount {
+                units: self.units / 10u128.pow((self.scale - places) as u32),
+                scale: places,
+            }
+        } else {
+            self
+        };
+        let digits = format!("{:0>width$}", amount.units, width = amount.scale as usize + 1);
+        let (int_part, frac_part) = digits.split_at(digits.len() - amount.scale as usize);
+        let padding = "0".repeat((places - amount.scale) as usize);
+        if places == 0 {
+            int_part.to_string()
+        } else {
+            format!("{}.{}{}", int_part, frac_part, padding)
+        }
+    }
+
+    /// Divide into shares proportional to `weights`.
+    ///
+    /// Shares are whole cents, or finer when the total itself is, up to
+    /// the token's `decimals`. Steps left over from rounding go to the
+    /// first shares, so the shares always add up to exactly the total.
+    pub fn split(self, weights: &[Amount], decimals: u8) -> Result<Vec<Amount>, AmountError> {
+        if self.scale > decimals {
+            return Err(AmountError::TooPrecise(decimals));
+        }
+        let scale = self.scale.max(2).min(decimals);
+        let steps = self
+            .units
+            .checked_mul(10u128.pow((scale - self.scale) as u32))
+            .ok_or(AmountError::TooLarge)?;
+
+        // Bring the weights to a common scale
+        let weight_scale = weights.iter().map(|w| w.scale).max().unwrap_or(0);
+        let weights: Vec<U256> = weights
+            .iter()
+            .map(|w| U256::from(w.units) * U256::exp10((weight_scale - w.scale) as usize))
+            .collect();
+        let weight_sum = weights.iter().fold(U256::zero(), |sum, w| sum + w);
+        if weight_sum.is_zero() {
+            return Err(AmountError::Zero);
+        }
+
+        let mut shares: Vec<u128> = weights
+            .iter()
+            .map(|w| (U256::from(steps) * w / weight_sum).as_u128())
+            .collect();
+        let leftover = steps - shares.iter().sum::<u128>();
+        for share in shares.iter_mut().take(leftover as usize) {
+            *share += 1;
+        }
+
+        if shares.contains(&0) {
+            return Err(AmountError::Zero);
+        }
+        Ok(shares.into_iter().map(|units| Amount { units, scale }.normalized()).collect())
+    }
+
+    /// Sum of two amounts, None on overflow
+    pub fn checked_add(self, other: Amount) -> Option<Amount> {
+        let (a, b, scale) = self.aligned(other)?;
+        Some(Amount { units: a.checked_add(b)?, scale }.normalized())
+    }
+
+    /// Difference of two amounts, zero when `other` is larger
+    pub fn saturating_sub(self, other: Amount) -> Amount {
+        match self.aligned(other) {
+            Some((a, b, scale)) => Amount { units: a.saturating_sub(b), scale }.normalized(),
+            None => Amount::default(),
+        }
+    }
+
+    /// Both amounts in units of the finer scale
+    fn aligned(self, other: Amount) -> Option<(u128, u128, u8)> {
+        let scale = self.scale.max(other.scale);
+        let a = self.units.checked_mul(10u128.pow((scale - self.scale) as u32))?;
+        let b = other.units.checked_mul(10u128.pow((scale - other.scale) as u32))?;
+        Some((a, b, scale))
+    }
+
+    /// Drop trailing fractional zeros so equal values compare equal
+    fn normalized(mut self) -> Amount {
+        while self.scale > 0 && self.units.is_multiple_of(10) {
+            self.units /= 10;
+            self.scale -= 1;
+        }
+        if self.units == 0 {
+            self.scale = 0;
+        }
+        self
+    }
+}
+
+/// Remove `,` thousands separators, checking they are in the right places
+fn strip_thousands(int_part: &str) -> Result<String, AmountError> {
+    if !int_part.contains(',') {
+        return Ok(int_part.to_string());
+    }
+
+    let groups: Vec<&str> = int_part.split(',').collect();
+    let first_ok = (1..=3).contains(&groups[0].len());
+    let rest_ok = groups[1..].iter().all(|g| g.len() == 3);
+    if !first_ok || !rest_ok {
+        return Err(AmountError::Invalid);
+    }
+    Ok(groups.concat())
+}
+
+impl Ord for Amount {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        let scale = self.scale.max(other.scale);
+        let scaled = |a: &Amount| U256::from(a.units) * U256::exp10((scale - a.scale) as usize);
+        scaled(self).cmp(&scaled(other))
+    }
+}
+
+impl PartialOrd for Amount {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_fixed(self.scale))
+    }
+}
+
+impl std::str::FromStr for Amount {
+    type Err = AmountError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Amount::parse(s)
+    }
+}
+
+// Serialized as a decimal string so JSON never rounds it
+impl Serialize for Amount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Amount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct AmountVisitor;
+
+        impl de::Visitor<'_> for AmountVisitor {
+            type Value = Amount;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a positive decimal amount")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Amount, E> {
+                Amount::parse(v).map_err(E::custom)
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Amount, E> {
+                Amount::parse(&v.to_string()).map_err(E::custom)
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Amount, E> {
+                Amount::parse(&v.to_string()).map_err(E::custom)
+            }
+
+            // JSON numbers: Display gives the shortest exact decimal (0.1 -> "0.1")
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<Amount, E> {
+                Amount::parse(&v.to_string()).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(AmountVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn amt(s: &str) -> Amount {
+        Amount::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse_plain() {
+        assert_eq!(amt("10").to_string(), "10");
+        assert_eq!(amt("0.5").to_string(), "0.5");
+        assert_eq!(amt(".5").to_string(), "0.5");
+        assert_eq!(amt("10.50").to_string(), "10.5");
+        assert_eq!(amt("0.000000000000000001").to_string(), "0.000000000000000001");
+    }
+
+    #[test]
+    fn test_parse_friendly_forms() {
+        assert_eq!(amt("10k"), amt("10000"));
+        assert_eq!(amt("1.5K"), amt("1500"));
+        assert_eq!(amt("2m"), amt("2000000"));
+        assert_eq!(amt("$5"), amt("5"));
+        assert_eq!(amt("$2.5k"), amt("2500"));
+        assert_eq!(amt("1,000"), amt("1000"));
+        assert_eq!(amt("1,234,567.89"), amt("1234567.89"));
+    }
+
+    #[test]
+    fn test_parse_rejects() {
+        assert_eq!(Amount::parse("-5"), Err(AmountError::Negative));
+        assert_eq!(Amount::parse("$-5"), Err(AmountError::Negative));
+        assert_eq!(Amount::parse("-inf"), Err(AmountError::Negative));
+        assert_eq!(Amount::parse("0"), Err(AmountError::Zero));
+        assert_eq!(Amount::parse("0.00"), Err(AmountError::Zero));
+        assert_eq!(Amount::parse("0k"), Err(AmountError::Zero));
+        for bad in ["1e3", "NaN", "inf", "", ".", "k", "abc", "1.2.3", "+5", "1,00", "10,0000", ",100", "1 000"] {
+            assert_eq!(Amount::parse(bad), Err(AmountError::Invalid), "{:?}", bad);
+        }
+        assert_eq!(
+            Amount::parse("0.0000000000000000001"),
+            Err(AmountError::TooPrecise(MAX_DECIMALS))
+        );
+        assert_eq!(Amount::parse("999999999999999999999999999999999999999"), Err(AmountError::TooLarge));
+    }
+
+    #[test]
+    fn test_base_units() {
+        assert_eq!(amt("10").to_base_units(6).unwrap(), U256::from(10_000_000u64));
+        assert_eq!(amt("0.1").to_base_units(18).unwrap(), U256::exp10(17));
+        assert_eq!(amt("1.234567").to_base_units(6).unwrap(), U256::from(1_234_567u64));
+        assert_eq!(amt("1.2345678").to_base_units(6), Err(AmountError::TooPrecise(6)));
+        assert_eq!(amt("10.25").to_base_units_i64(6).unwrap(), 10_250_000);
+    }
+
+    #[test]
+    fn test_from_base_units() {
+        assert_eq!(Amount::from_base_units(10_500_000, 6).to_string(), "10.5");
+        assert_eq!(Amount::from_base_units(0, 6).to_string(), "0");
+    }
+
+    #[test]
+    fn test_split() {
+        let ones = [amt("1"), amt("1"), amt("1")];
+        let shares: Vec<String> = amt("10").split(&ones, 18).unwrap().iter().map(|a| a.to_string()).collect();
+        assert_eq!(shares, ["3.34", "3.33", "3.33"]);
+        assert_eq!(amt("30").split(&ones, 18).unwrap(), [amt("10"), amt("10"), amt("10")]);
+        assert_eq!(amt("30").split(&[amt("20"), amt("10")], 18).unwrap(), [amt("20"), amt("10")]);
+        assert_eq!(amt("1").split(&[amt("0.5"), amt("1.5")], 6).unwrap(), [amt("0.25"), amt("0.75")]);
+        // Finer totals keep their precision
+        assert_eq!(amt("0.003").split(&ones, 18).unwrap(), [amt("0.001"), amt("0.001"), amt("0.001")]);
+        // Too small to give everyone something
+        assert_eq!(amt("0.01").split(&ones, 2), Err(AmountError::Zero));
+        assert_eq!(amt("1.5").split(&ones, 0), Err(AmountError::TooPrecise(0)));
+    }
+
+    #[test]
+    fn test_compare_and_arithmetic() {
+        assert!(amt("10") > amt("9.99"));
+        assert!(amt("0.5") < amt("1"));
+        assert_eq!(amt("1.50").cmp(&amt("1.5")), std::cmp::Ordering::Equal);
+        assert_eq!(amt("1.5").checked_add(amt("0.25")), Some(amt("1.75")));
+        assert_eq!(amt("1.5").checked_add(Amount::default()), Some(amt("1.5")));
+        assert_eq!(amt("10").saturating_sub(amt("2.5")), amt("7.5"));
+        assert_eq!(amt("1").saturating_sub(amt("2")), Amount::default());
+    }
+
+    #[test]
+    fn test_to_fixed() {
+        assert_eq!(amt("10.5").to_fixed(2), "10.50");
+        assert_eq!(amt("10.567").to_fixed(2), "10.56");
+        assert_eq!(amt("0.005").to_fixed(2), "0.00");
+        assert_eq!(amt("7").to_fixed(0), "7");
+    }
+
+    #[test]
+    fn test_serde_roundtrip() {
+        let json = serde_json::to_string(&amt("0.1")).unwrap();
+        assert_eq!(json, "\"0.1\"");
+        assert_eq!(serde_json::from_str::<Amount>(&json).unwrap(), amt("0.1"));
+        // Numbers are accepted too, e.g. older JSON or admin requests
+        assert_eq!(serde_json::from_str::<Amount>("0.1").unwrap(), amt("0.1"));
+        assert_eq!(serde_json::from_str::<Amount>("25").unwrap(), amt("25"));
+        assert!(serde_json::from_str::<Amount>("-1").is_err());
+    }
+}
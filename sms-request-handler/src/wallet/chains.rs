@@ -1,5 +1,6 @@
 use ethers::providers::{Http, Provider};
 use ethers::types::Address;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -67,6 +68,20 @@ impl Chain {
         }
     }
 
+    /// Stable key stored in `users.preferred_chain`; parsed back by `from_input`
+    pub fn key(&self) -> &'static str {
+        match self {
+            Chain::PolygonAmoy => "amoy",
+            Chain::PolygonMainnet => "polygon",
+            Chain::BaseSepolia => "base-sepolia",
+            Chain::BaseMainnet => "base",
+            Chain::EthereumSepolia => "sepolia",
+            Chain::EthereumMainnet => "eth",
+            Chain::ArbitrumSepolia => "arb-sepolia",
+            Chain::ArbitrumOne => "arb",
+        }
+    }
+
     /// Get short code for SMS display
     pub fn short_code(&self) -> &'static str {
         match self {
@@ -153,6 +168,28 @@ impl Chain {
     }
 }
 
+/// Ethereum Sepolia, where the backend's TXTC token and swap pool live.
+/// Users who never sent CHAIN are on this chain.
+impl Default for Chain {
+    fn default() -> Self {
+        Chain::EthereumSepolia
+    }
+}
+
+// Serialized by key so stored JSON stays readable
+impl Serialize for Chain {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.key())
+    }
+}
+
+impl<'de> Deserialize<'de> for Chain {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let key = String::deserialize(deserializer)?;
+        Chain::from_input(&key).ok_or_else(|| de::Error::custom(format!("unknown chain: {}", key)))
+    }
+}
+
 impl std::fmt::Display for Chain {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.name())
@@ -245,6 +282,16 @@ mod tests {
         assert_eq!(Chain::from_input("unknown"), None);
     }
 
+    #[test]
+    fn test_chain_key_roundtrip() {
+        for chain in Chain::testnets().into_iter().chain(Chain::mainnets()) {
+            assert_eq!(Chain::from_input(chain.key()), Some(chain));
+        }
+        // Value of the old column default
+        assert_eq!(Chain::from_input("polygon-amoy"), Some(Chain::PolygonAmoy));
+        assert_eq!(serde_json::to_string(&Chain::BaseSepolia).unwrap(), "\"base-sepolia\"");
+    }
+
     #[test]
     fn test_usdc_addresses() {
         assert!(Chain::PolygonMainnet.usdc_address().is_some());
@@ -1,12 +1,18 @@
 pub mod aa;
+pub mod amount;
 pub mod chains;
 pub mod provider;
+pub mod quote;
 pub mod tokens;
+pub mod transfer;
 pub mod wallet;
 
 pub use aa::*;
+pub use amount::*;
 pub use chains::*;
 pub use provider::*;
+pub use quote::*;
 pub use tokens::*;
+pub use transfer::*;
 pub use wallet::*;
 
@@ -0,0 +1,140 @@
+use ethers::types::U256;
+use std::fmt;
+
+use super::amount::{Amount, MAX_DECIMALS};
+
+/// Basis points in 100%
+const BPS: u32 = 10_000;
+
+/// Most slippage a user can accept (50%)
+const MAX_SLIPPAGE_BPS: u32 = 5_000;
+
+/// Slippage tolerance for swaps, in basis points (100 = 1%)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Slippage(u32);
+
+impl Slippage {
+    /// Tolerance for users who haven't set one
+    pub const DEFAULT: Slippage = Slippage(100);
+
+    /// Build from basis points, if within 0.01% - 50%
+    pub fn from_bps(bps: u32) -> Option<Slippage> {
+        (1..=MAX_SLIPPAGE_BPS).contains(&bps).then_some(Slippage(bps))
+    }
+
+    /// Parse `1%`, `0.5%` or `0.5`
+    pub fn parse(input: &str) -> Option<Slippage> {
+        let s = input.trim();
+        let s = s.strip_suffix('%').unwrap_or(s);
+        let bps = Amount::parse(s).ok()?.to_base_units(2).ok()?;
+        Slippage::from_bps(u32::try_from(bps).ok()?)
+    }
+
+    pub fn bps(self) -> u32 {
+        self.0
+    }
+
+    /// Least output accepted when `quote` is expected
+    pub fn min_out(self, quote: Amount) -> Amount {
+        let units = quote.to_base_units(MAX_DECIMALS).unwrap_or_default();
+        let min = units * U256::from(BPS - self.0) / U256::from(BPS);
+        Amount::from_base_units(u128::try_from(min).unwrap_or_default(), MAX_DECIMALS)
+    }
+
+    /// Whether a fresh quote is still within tolerance of the one shown
+    pub fn accepts(self, shown: Amount, fresh: Amount) -> bool {
+        base_units(fresh) >= base_units(self.min_out(shown))
+    }
+}
+
+impl fmt::Display for Slippage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Percent(self.0))
+    }
+}
+
+/// Basis points shown as a percentage, e.g. `1.25%`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Percent(pub u32);
+
+impl fmt::Display for Percent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 == 0 {
+            return write!(f, "0%");
+        }
+        write!(f, "{}%", Amount::from_base_units(self.0 as u128, 2))
+    }
+}
+
+/// Expected output of a swap
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SwapQuote {
+    pub amount_in: Amount,
+    pub amount_out: Amount,
+    /// How much worse the rate is than for a tiny trade, in basis points
+    pub price_impact: Percent,
+}
+
+impl SwapQuote {
+    /// Quote for `amount_in`, with price impact measured against the rate
+    /// for the much smaller `reference_in`
+    pub fn new(amount_in: Amount, amount_out: Amount, reference_in: Amount, reference_out: Amount) -> SwapQuote {
+        // rate / reference rate = (out * ref_in) / (in * ref_out)
+        let actual = base_units(amount_out) * base_units(reference_in);
+        let spot = base_units(amount_in) * base_units(reference_out);
+        let impact = if spot.is_zero() || actual >= spot {
+            0
+        } else {
+            ((spot - actual) * U256::from(BPS) / spot).as_u32()
+        };
+
+        SwapQuote { amount_in, amount_out, price_impact: Percent(impact) }
+    }
+}
+
+fn base_units(amount: Amount) -> U256 {
+    amount.to_base_units(MAX_DECIMALS).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn amount(s: &str) -> Amount {
+        Amount::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse_slippage() {
+        assert_eq!(Slippage::parse("1%"), Some(Slippage(100)));
+        assert_eq!(Slippage::parse("0.5"), Some(Slippage(50)));
+        assert_eq!(Slippage::parse("50%"), Some(Slippage(5_000)));
+        assert_eq!(Slippage::parse("51%"), None);
+        assert_eq!(Slippage::parse("0.001%"), None);
+        assert_eq!(Slippage::parse("0%"), None);
+        assert_eq!(Slippage::parse("lots"), None);
+        assert_eq!(Slippage(50).to_string(), "0.5%");
+        assert_eq!(Slippage::DEFAULT.to_string(), "1%");
+    }
+
+    #[test]
+    fn test_min_out_and_tolerance() {
+        let slippage = Slippage::DEFAULT;
+        assert_eq!(slippage.min_out(amount("0.02")), amount("0.0198"));
+
+        assert!(slippage.accepts(amount("0.02"), amount("0.021")));
+        assert!(slippage.accepts(amount("0.02"), amount("0.0198")));
+        assert!(!slippage.accepts(amount("0.02"), amount("0.0197")));
+    }
+
+    #[test]
+    fn test_price_impact() {
+        // 0.01 TXTC gets 0.00002 ETH; 10 TXTC gets 0.0195 instead of 0.02
+        let quote = SwapQuote::new(amount("10"), amount("0.0195"), amount("0.01"), amount("0.00002"));
+        assert_eq!(quote.price_impact, Percent(250));
+        assert_eq!(quote.price_impact.to_string(), "2.5%");
+
+        let better = SwapQuote::new(amount("10"), amount("0.03"), amount("0.01"), amount("0.00002"));
+        assert_eq!(better.price_impact.to_string(), "0%");
+    }
+}
@@ -31,6 +31,15 @@ impl TokenBalance {
     }
 }
 
+/// Decimals for the tokens users can name in commands
+pub fn token_decimals(symbol: &str) -> Option<u8> {
+    match symbol.to_uppercase().as_str() {
+        "USDC" | "USDT" => Some(6),
+        "TXTC" | "ETH" | "WETH" | "MATIC" | "POL" | "DAI" => Some(18),
+        _ => None,
+    }
+}
+
 /// Format token balance with proper decimals
 pub fn format_token_balance(balance: U256, decimals: u8) -> String {
     if balance.is_zero() {
@@ -0,0 +1,67 @@
+use ethers::prelude::*;
+use std::sync::Arc;
+
+use super::chains::{Chain, ChainProvider};
+use super::tokens::IERC20;
+
+/// Tokens that can be sent directly on a chain: its native token and USDC
+pub fn transferable_tokens(chain: Chain) -> Vec<&'static str> {
+    let mut tokens = vec![chain.native_token()];
+    if chain.usdc_address().is_some() {
+        tokens.push("USDC");
+    }
+    tokens
+}
+
+/// Sign and broadcast a native or USDC transfer from a user's key.
+///
+/// Returns as soon as the node accepts the transaction; it is not
+/// waited on.
+pub async fn transfer(
+    provider: Arc<ChainProvider>,
+    chain: Chain,
+    private_key_hex: &str,
+    to: Address,
+    token: &str,
+    amount: U256,
+) -> Result<TxHash, String> {
+    let key = hex::decode(private_key_hex.trim_start_matches("0x"))
+        .map_err(|e| format!("Invalid key: {}", e))?;
+    let wallet = LocalWallet::from_bytes(&key)
+        .map_err(|e| format!("Invalid key: {}", e))?
+        .with_chain_id(chain.chain_id());
+    let client = Arc::new(SignerMiddleware::new(provider, wallet));
+
+    if token.eq_ignore_ascii_case(chain.native_token()) {
+        let pending = client
+            .send_transaction(TransactionRequest::pay(to, amount), None)
+            .await
+            .map_err(|e| format!("Transfer failed: {}", e))?;
+        Ok(pending.tx_hash())
+    } else if token.eq_ignore_ascii_case("USDC") {
+        let usdc_address = chain
+            .usdc_address()
+            .ok_or_else(|| format!("USDC not available on {}", chain.name()))?;
+        let contract = IERC20::new(usdc_address, client);
+        let call = contract.transfer(to, amount);
+        let pending = call
+            .send()
+            .await
+            .map_err(|e| format!("Transfer failed: {}", e))?;
+        Ok(pending.tx_hash())
+    } else {
+        Err(format!("{} not supported on {}", token, chain.name()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transferable_tokens() {
+        assert_eq!(transferable_tokens(Chain::BaseSepolia), vec!["ETH", "USDC"]);
+        assert_eq!(transferable_tokens(Chain::PolygonAmoy), vec!["MATIC", "USDC"]);
+        assert_eq!(transferable_tokens(Chain::ArbitrumSepolia), vec!["ETH"]);
+    }
+}